@@ -14,18 +14,59 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
 
 use crate::camera::CameraLockMode;
+use crate::diagnostics::DiagnosticsReadout;
+use crate::moire::MoireReport;
 use crate::simulation_core_state::ScalingMethod;
+use crate::ui_controller::message_verbosity::MessageVerbosityOptions;
 use app_error::AppResult;
 use std::fmt::Display;
 
+/// Hum and Static are loops that keep playing at the last volume received (0 stops them),
+/// while Degauss is played once every time it gets dispatched.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SoundEffect {
+    Hum,
+    Degauss,
+    Static,
+}
+
+impl std::fmt::Display for SoundEffect {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            SoundEffect::Hum => write!(f, "hum"),
+            SoundEffect::Degauss => write!(f, "degauss"),
+            SoundEffect::Static => write!(f, "static"),
+        }
+    }
+}
+
+/// The kinds of extra messages that can be silenced separately, because moving the camera
+/// around produces a lot more of them than changing filters.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MessageCategory {
+    Camera,
+    Filters,
+}
+
+/// A value that was asked to go past one of its limits and got clamped to it. `field` is the event kind
+/// of the input holding the value, so frontends can point at it.
+pub struct ClampedValue<'a> {
+    pub field: &'static str,
+    pub attempted: &'a dyn Display,
+    pub bound: &'a dyn Display,
+}
+
 pub trait AppEventDispatcher {
     fn enable_extra_messages(&self, extra_messages_enabled: bool);
-    fn are_extra_messages_enabled(&self) -> bool;
+    fn set_message_verbosity(&self, verbosity: MessageVerbosityOptions);
+    fn are_extra_messages_enabled(&self, category: MessageCategory) -> bool;
     fn dispatch_log(&self, msg: String);
     fn dispatch_string_event(&self, event_id: &'static str, message: &str);
     fn dispatch_camera_update(&self, position: &glm::Vec3, direction: &glm::Vec3, axis_up: &glm::Vec3);
+    fn dispatch_recovery_state(&self, preset: &str, position: &glm::Vec3, direction: &glm::Vec3, axis_up: &glm::Vec3);
     fn dispatch_change_pixel_width(&self, size: f32);
     fn dispatch_change_camera_zoom(&self, zoom: f32);
+    fn dispatch_change_camera_focal_length(&self, focal_length: f32);
     fn dispatch_change_pixel_speed(&self, speed: f32);
     fn dispatch_change_turning_speed(&self, speed: f32);
     fn dispatch_change_movement_speed(&self, speed: f32);
@@ -38,14 +79,21 @@ pub trait AppEventDispatcher {
     fn dispatch_exiting_session(&self);
     fn dispatch_toggle_info_panel(&self);
     fn dispatch_fps(&self, fps: f32);
+    fn dispatch_frame_times(&self, frame_times: &[f32]);
     fn dispatch_request_fullscreen(&self);
     fn dispatch_request_pointer_lock(&self);
     fn dispatch_exit_pointer_lock(&self);
-    fn dispatch_screenshot(&self, width: i32, height: i32, pixels: &mut [u8]) -> AppResult<()>;
+    fn dispatch_play_sound(&self, sound: SoundEffect, volume: f32);
+    fn dispatch_light_gun_shot(&self, hit: Option<(u32, u32)>);
+    fn dispatch_diagnostics(&self, readout: &DiagnosticsReadout);
+    fn dispatch_moire_report(&self, report: &MoireReport);
+    fn request_screenshot_readback(&self, width: i32, height: i32) -> AppResult<()>;
+    fn dispatch_screenshot(&self, width: i32, height: i32, pixels: &mut [u8], tag: Option<&str>) -> AppResult<()>;
+    fn dispatch_turntable_recording(&self, recording: bool) -> AppResult<()>;
     fn dispatch_change_camera_movement_mode(&self, locked_mode: CameraLockMode);
     fn dispatch_top_message(&self, message: &str);
-    fn dispatch_minimum_value(&self, value: &dyn Display);
-    fn dispatch_maximum_value(&self, value: &dyn Display);
+    fn dispatch_minimum_value(&self, clamped: &ClampedValue);
+    fn dispatch_maximum_value(&self, clamped: &ClampedValue);
 }
 
 #[derive(Default)]
@@ -53,14 +101,17 @@ pub struct FakeEventDispatcher {}
 
 impl AppEventDispatcher for FakeEventDispatcher {
     fn enable_extra_messages(&self, _: bool) {}
-    fn are_extra_messages_enabled(&self) -> bool {
+    fn set_message_verbosity(&self, _: MessageVerbosityOptions) {}
+    fn are_extra_messages_enabled(&self, _: MessageCategory) -> bool {
         true
     }
     fn dispatch_log(&self, _: String) {}
     fn dispatch_string_event(&self, _: &'static str, _: &str) {}
     fn dispatch_camera_update(&self, _: &glm::Vec3, _: &glm::Vec3, _: &glm::Vec3) {}
+    fn dispatch_recovery_state(&self, _: &str, _: &glm::Vec3, _: &glm::Vec3, _: &glm::Vec3) {}
     fn dispatch_change_pixel_width(&self, _: f32) {}
     fn dispatch_change_camera_zoom(&self, _: f32) {}
+    fn dispatch_change_camera_focal_length(&self, _: f32) {}
     fn dispatch_change_pixel_speed(&self, _: f32) {}
     fn dispatch_change_turning_speed(&self, _: f32) {}
     fn dispatch_change_movement_speed(&self, _: f32) {}
@@ -75,14 +126,25 @@ impl AppEventDispatcher for FakeEventDispatcher {
     fn dispatch_fps(&self, fps: f32) {
         println!("frames in 20 seconds: {}", fps);
     }
-    fn dispatch_screenshot(&self, _: i32, _: i32, _: &mut [u8]) -> AppResult<()> {
+    fn dispatch_frame_times(&self, _: &[f32]) {}
+    fn request_screenshot_readback(&self, _: i32, _: i32) -> AppResult<()> {
+        Ok(())
+    }
+    fn dispatch_screenshot(&self, _: i32, _: i32, _: &mut [u8], _: Option<&str>) -> AppResult<()> {
+        Ok(())
+    }
+    fn dispatch_turntable_recording(&self, _: bool) -> AppResult<()> {
         Ok(())
     }
     fn dispatch_request_fullscreen(&self) {}
     fn dispatch_request_pointer_lock(&self) {}
     fn dispatch_exit_pointer_lock(&self) {}
+    fn dispatch_play_sound(&self, _: SoundEffect, _: f32) {}
+    fn dispatch_light_gun_shot(&self, _: Option<(u32, u32)>) {}
+    fn dispatch_diagnostics(&self, _: &DiagnosticsReadout) {}
+    fn dispatch_moire_report(&self, _: &MoireReport) {}
     fn dispatch_change_camera_movement_mode(&self, _: CameraLockMode) {}
     fn dispatch_top_message(&self, _: &str) {}
-    fn dispatch_minimum_value(&self, _: &dyn Display) {}
-    fn dispatch_maximum_value(&self, _: &dyn Display) {}
+    fn dispatch_minimum_value(&self, _: &ClampedValue) {}
+    fn dispatch_maximum_value(&self, _: &ClampedValue) {}
 }
@@ -0,0 +1,72 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+/// Faint scratches on the glass of every virtual monitor.
+pub const BLEMISH_SCRATCHES: usize = 3;
+
+/// What the scratch lines are built from. The same settings always give the same scratches.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ScratchSettings {
+    pub seed: usize,
+    pub strength: f32,
+    pub half_size: [f32; 2],
+}
+
+// SplitMix64, so a seed gives the same monitor on every platform.
+pub(crate) fn seeded_unit(seed: usize, index: u64) -> f32 {
+    let mut z = (seed as u64)
+        .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        .wrapping_add(index.wrapping_mul(0xBF58_476D_1CE4_E5B9));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 40) as f32 / (1u64 << 24) as f32
+}
+
+/// Scratches of the monitor with the given seed, over a screen of the given half size. They are flattened
+/// as center x, center y, angle and half length for each scratch, in the same units as the half size.
+pub fn scratch_lines(seed: usize, half_size: [f32; 2]) -> [f32; BLEMISH_SCRATCHES * 4] {
+    let mut lines = [0.0; BLEMISH_SCRATCHES * 4];
+    for (i, line) in lines.chunks_mut(4).enumerate() {
+        let unit = |n: u64| seeded_unit(seed, i as u64 * 4 + n);
+        line[0] = (unit(0) * 2.0 - 1.0) * half_size[0];
+        line[1] = (unit(1) * 2.0 - 1.0) * half_size[1];
+        line[2] = unit(2) * std::f32::consts::PI;
+        line[3] = (0.1 + 0.3 * unit(3)) * half_size[0].min(half_size[1]);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    #[test]
+    fn test_scratch_lines__same_seed__same_monitor() {
+        assert_eq!(scratch_lines(7, [100.0, 50.0]), scratch_lines(7, [100.0, 50.0]));
+        assert_ne!(scratch_lines(7, [100.0, 50.0]), scratch_lines(8, [100.0, 50.0]));
+    }
+
+    #[test]
+    fn test_scratch_lines__stay_on_the_screen() {
+        for seed in 0..100 {
+            for line in scratch_lines(seed, [100.0, 50.0]).chunks(4) {
+                assert!(line[0].abs() <= 100.0 && line[1].abs() <= 50.0);
+                assert!(line[3] >= 5.0 && line[3] <= 20.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+
+use crate::ui_controller::filter_preset::FilterPresetOptions;
+use std::fmt::{Display, Error, Formatter};
+
+/// Presets that can take part in a blind test. The demo moves the camera and custom may not exist yet.
+const BLIND_TEST_CANDIDATES: [FilterPresetOptions; 4] = [
+    FilterPresetOptions::Sharp1,
+    FilterPresetOptions::CrtApertureGrille1,
+    FilterPresetOptions::CrtShadowMask1,
+    FilterPresetOptions::CrtShadowMask2,
+];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BlindTestSide {
+    A,
+    B,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BlindTestResult {
+    pub preferred: FilterPresetOptions,
+    pub rejected: FilterPresetOptions,
+    pub flips: u32,
+}
+
+impl Display for BlindTestResult {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "You preferred {} over {} after {} flips.", self.preferred, self.rejected, self.flips)
+    }
+}
+
+/// Keeps the presets behind keys 1 and 2 hidden until the user votes for the one on screen.
+/// Voting is only accepted once both sides have been seen.
+#[derive(Default)]
+pub struct BlindTestState {
+    pair: Option<(FilterPresetOptions, FilterPresetOptions)>,
+    showing: Option<BlindTestSide>,
+    seen_b: bool,
+    flips: u32,
+    pub results: Vec<BlindTestResult>,
+}
+
+impl BlindTestState {
+    pub fn is_running(&self) -> bool {
+        self.pair.is_some()
+    }
+
+    /// Draws two different presets from two random numbers in [0, 1) and returns the one to show first, behind key 1.
+    pub(crate) fn start(&mut self, first: f32, second: f32) -> FilterPresetOptions {
+        let len = BLIND_TEST_CANDIDATES.len();
+        let a = pick(first, len);
+        let b = (a + 1 + pick(second, len - 1)) % len;
+        self.pair = Some((BLIND_TEST_CANDIDATES[a], BLIND_TEST_CANDIDATES[b]));
+        self.showing = Some(BlindTestSide::A);
+        self.seen_b = false;
+        self.flips = 0;
+        BLIND_TEST_CANDIDATES[a]
+    }
+
+    /// Returns the preset to apply, or None when that side is already on screen.
+    pub(crate) fn show(&mut self, side: BlindTestSide) -> Option<FilterPresetOptions> {
+        let (a, b) = self.pair?;
+        if self.showing == Some(side) {
+            return None;
+        }
+        self.showing = Some(side);
+        self.flips += 1;
+        match side {
+            BlindTestSide::A => Some(a),
+            BlindTestSide::B => {
+                self.seen_b = true;
+                Some(b)
+            }
+        }
+    }
+
+    pub(crate) fn can_vote(&self) -> bool {
+        self.seen_b
+    }
+
+    /// Records the side on screen as the preferred one and ends the test.
+    pub(crate) fn vote(&mut self) -> Option<BlindTestResult> {
+        if !self.can_vote() {
+            return None;
+        }
+        let (a, b) = self.pair.take()?;
+        let (preferred, rejected) = match self.showing.take()? {
+            BlindTestSide::A => (a, b),
+            BlindTestSide::B => (b, a),
+        };
+        let result = BlindTestResult {
+            preferred,
+            rejected,
+            flips: self.flips,
+        };
+        self.results.push(result);
+        Some(result)
+    }
+}
+
+fn pick(random: f32, len: usize) -> usize {
+    ((random * len as f32) as usize).min(len - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    #[test]
+    fn test_blind_test__always_draws_two_different_presets() {
+        for &(first, second) in &[(0.0, 0.0), (0.99, 0.99), (0.5, 0.0), (0.3, 0.7)] {
+            let mut state = BlindTestState::default();
+            let a = state.start(first, second);
+            let b = state.show(BlindTestSide::B).unwrap();
+            assert_ne!(a, b);
+        }
+    }
+
+    #[test]
+    fn test_blind_test__reveals_only_after_both_sides_were_seen() {
+        let mut state = BlindTestState::default();
+        let a = state.start(0.0, 0.0);
+        assert_eq!(state.vote(), None);
+        assert_eq!(state.show(BlindTestSide::A), None);
+        let b = state.show(BlindTestSide::B).unwrap();
+        assert_eq!(state.show(BlindTestSide::A), Some(a));
+        assert_eq!(
+            state.vote(),
+            Some(BlindTestResult {
+                preferred: a,
+                rejected: b,
+                flips: 2
+            })
+        );
+        assert!(!state.is_running());
+        assert_eq!(state.results.len(), 1);
+    }
+}
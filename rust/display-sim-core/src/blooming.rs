@@ -0,0 +1,95 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+const MAX_LUMINANCE_SAMPLES: usize = 4096;
+
+/// Average Rec. 709 luminance of an RGBA frame, between 0 and 1. Big frames are sampled with a stride,
+/// the raster breathing doesn't need more precision than that.
+pub fn average_luminance(frame: &[u8]) -> f32 {
+    let pixels = frame.len() / 4;
+    if pixels == 0 {
+        return 0.0;
+    }
+    let stride = pixels.div_ceil(MAX_LUMINANCE_SAMPLES);
+    let mut sum = 0.0;
+    let mut samples = 0;
+    for pixel in (0..pixels).step_by(stride) {
+        let i = pixel * 4;
+        sum += 0.2126 * frame[i] as f32 + 0.7152 * frame[i + 1] as f32 + 0.0722 * frame[i + 2] as f32;
+        samples += 1;
+    }
+    sum / (samples as f32 * 255.0)
+}
+
+/// Real tubes with poor high voltage regulation grow a bit on bright scenes. The renderer measures the
+/// luminance of every uploaded frame, and the raster scale follows it with a first order lag.
+pub struct BloomingState {
+    luminance: f32,
+    pub raster_scale: f32,
+}
+
+impl Default for BloomingState {
+    fn default() -> Self {
+        BloomingState {
+            luminance: 0.0,
+            raster_scale: 1.0,
+        }
+    }
+}
+
+impl BloomingState {
+    pub fn record_luminance(&mut self, luminance: f32) {
+        self.luminance = luminance.clamp(0.0, 1.0);
+    }
+
+    /// `amount` is the growth in percent at full white, `time_constant` in seconds.
+    pub(crate) fn update(&mut self, amount: f32, time_constant: f32, dt: f32) -> f32 {
+        let target = 1.0 + amount * 0.01 * self.luminance;
+        if time_constant <= 0.0 {
+            self.raster_scale = target;
+        } else {
+            self.raster_scale += (target - self.raster_scale) * (1.0 - (-dt / time_constant).exp());
+        }
+        self.raster_scale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    #[test]
+    fn test_average_luminance__white_and_black__are_the_extremes() {
+        assert_eq!(average_luminance(&[255; 64]), 1.0);
+        assert_eq!(average_luminance(&[0; 64]), 0.0);
+        assert_eq!(average_luminance(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_update__bright_frame__grows_towards_target_over_time() {
+        let mut state = BloomingState::default();
+        state.record_luminance(1.0);
+        let first = state.update(2.0, 0.5, 0.5);
+        assert!(first > 1.0 && first < 1.02);
+        for _ in 0..100 {
+            state.update(2.0, 0.5, 0.5);
+        }
+        assert!((state.raster_scale - 1.02).abs() < 1e-5);
+
+        state.record_luminance(0.0);
+        assert_eq!(state.update(2.0, 0.0, 0.016), 1.0);
+    }
+}
@@ -17,6 +17,24 @@ use crate::input_types::{Boolean2DAction, BooleanAction, Input, KeyCodeBooleanAc
 use crate::simulation_core_state::{KeyEventKind, Resources};
 
 pub(crate) fn trigger_hotkey_action(input: &mut Input, res: &mut Resources, keycode: &str, pressed: Pressed) -> ActionUsed {
+    // While the settings menu is open, arrows and enter drive it instead of the camera.
+    if let Some(settings_menu_key) = settings_menu_key(keycode) {
+        if res.settings_menu.open {
+            return trigger_hotkey_action_intern(input, settings_menu_key, pressed);
+        } else if pressed == Pressed::No {
+            // The menu may have been closed while the key was held.
+            trigger_hotkey_action_intern(input, settings_menu_key, pressed);
+        }
+    }
+    // While a blind test runs, keys 1 and 2 flip between its presets instead of their usual actions.
+    if let Some(blind_test_key) = blind_test_key(keycode) {
+        if res.blind_test.is_running() {
+            return trigger_hotkey_action_intern(input, blind_test_key, pressed);
+        } else if pressed == Pressed::No {
+            // The test may have ended while the key was held.
+            trigger_hotkey_action_intern(input, blind_test_key, pressed);
+        }
+    }
     match trigger_hotkey_action_2(input, res, keycode, pressed) {
         ActionUsed::Yes => ActionUsed::Yes,
         #[cfg(debug_assertions)]
@@ -64,6 +82,24 @@ pub(crate) fn trigger_hotkey_action_2(input: &mut Input, res: &mut Resources, ke
     }
 }
 
+fn settings_menu_key(keycode: &str) -> Option<&'static str> {
+    match keycode {
+        "arrowup" | "up" | "↑" | "▲" => Some("settings-menu-up"),
+        "arrowdown" | "down" | "↓" | "▼" => Some("settings-menu-down"),
+        "arrowleft" | "left" | "←" | "◀" => Some("settings-menu-dec"),
+        "arrowright" | "right" | "→" | "▶" | "enter" | "return" => Some("settings-menu-inc"),
+        _ => None,
+    }
+}
+
+fn blind_test_key(keycode: &str) -> Option<&'static str> {
+    match keycode {
+        "1" | "key1" => Some("blind-test-a"),
+        "2" | "key2" => Some("blind-test-b"),
+        _ => None,
+    }
+}
+
 fn remove_action_2(input: &mut Input, keycode: &str) {
     let mut index = None;
     for (i, active_action) in input.active_pressed_actions_2.iter().enumerate() {
@@ -350,6 +386,19 @@ fn handle_action(input: &mut Input, action: BooleanAction, pressed: Pressed) {
         BooleanAction::Control => input.control = pressed,
         BooleanAction::Alt => input.alt = pressed,
         BooleanAction::Screenshot => input.screenshot.input = pressed,
+        BooleanAction::MoireAssistant => input.moire_assistant.input = pressed,
+        BooleanAction::ScreenshotBracket => input.screenshot_bracket.input = pressed,
+        BooleanAction::Turntable => input.turntable.input = pressed,
+        BooleanAction::RoomLighting => input.room_lighting.input = pressed,
+        BooleanAction::BlackPedestalSetup => input.black_pedestal_setup.input = pressed,
+        BooleanAction::BlindTest => input.blind_test.input = pressed,
+        BooleanAction::BlindTestA => input.blind_test_a.input = pressed,
+        BooleanAction::BlindTestB => input.blind_test_b.input = pressed,
+        BooleanAction::SettingsMenu => input.settings_menu.input = pressed,
+        BooleanAction::SettingsMenuUp => input.settings_menu_up.input = pressed,
+        BooleanAction::SettingsMenuDown => input.settings_menu_down.input = pressed,
+        BooleanAction::SettingsMenuInc => input.settings_menu_inc = pressed,
+        BooleanAction::SettingsMenuDec => input.settings_menu_dec = pressed,
         BooleanAction::ResetPosition => input.reset_position = pressed,
         BooleanAction::ResetFilters => input.reset_filters = pressed,
         BooleanAction::InputFocused => input.input_focused = pressed,
@@ -389,9 +438,12 @@ fn handle_action(input: &mut Input, action: BooleanAction, pressed: Pressed) {
         BooleanAction::PixelWidth(Boolean2DAction::Decrease) => input.pixel_width.decrease = pressed,
         BooleanAction::NextCameraMovementMode(Boolean2DAction::Increase) => input.next_camera_movement_mode.increase.input = pressed,
         BooleanAction::NextCameraMovementMode(Boolean2DAction::Decrease) => input.next_camera_movement_mode.decrease.input = pressed,
+        BooleanAction::FocalLengthPreset(Boolean2DAction::Increase) => input.focal_length_preset.increase.input = pressed,
+        BooleanAction::FocalLengthPreset(Boolean2DAction::Decrease) => input.focal_length_preset.decrease.input = pressed,
         BooleanAction::TurnSpeed(Boolean2DAction::Increase) => input.turn_speed.increase.input = pressed,
         BooleanAction::TurnSpeed(Boolean2DAction::Decrease) => input.turn_speed.decrease.input = pressed,
         BooleanAction::MouseClick => input.mouse_click.input = pressed,
+        BooleanAction::Magnet => input.magnet = pressed,
     }
 }
 
@@ -402,6 +454,19 @@ fn to_boolean_action(boolean_action: &str) -> Option<BooleanAction> {
         "control" => Some(BooleanAction::Control),
         "alt" => Some(BooleanAction::Alt),
         "f4" | "capture-framebuffer" => Some(BooleanAction::Screenshot),
+        "moire-assistant-run" => Some(BooleanAction::MoireAssistant),
+        "screenshot-bracket-run" => Some(BooleanAction::ScreenshotBracket),
+        "f6" | "turntable-run" => Some(BooleanAction::Turntable),
+        "f7" | "room-lighting-toggle" => Some(BooleanAction::RoomLighting),
+        "black-pedestal-setup" => Some(BooleanAction::BlackPedestalSetup),
+        "blind-test-run" => Some(BooleanAction::BlindTest),
+        "blind-test-a" => Some(BooleanAction::BlindTestA),
+        "blind-test-b" => Some(BooleanAction::BlindTestB),
+        "f1" | "settings-menu" => Some(BooleanAction::SettingsMenu),
+        "settings-menu-up" => Some(BooleanAction::SettingsMenuUp),
+        "settings-menu-down" => Some(BooleanAction::SettingsMenuDown),
+        "settings-menu-inc" => Some(BooleanAction::SettingsMenuInc),
+        "settings-menu-dec" => Some(BooleanAction::SettingsMenuDec),
         "reset-camera" => Some(BooleanAction::ResetPosition),
         "reset-filters" => Some(BooleanAction::ResetFilters),
         "input_focused" => Some(BooleanAction::InputFocused),
@@ -420,6 +485,7 @@ fn to_boolean_action(boolean_action: &str) -> Option<BooleanAction> {
         "s" => Some(BooleanAction::WalkBackward),
         "q" => Some(BooleanAction::WalkUp),
         "e" => Some(BooleanAction::WalkDown),
+        "1" | "key1" | "magnet" => Some(BooleanAction::Magnet),
         "scaling-method-inc" => Some(BooleanAction::ScalingMethod(Boolean2DAction::Increase)),
         "scaling-method-dec" => Some(BooleanAction::ScalingMethod(Boolean2DAction::Decrease)),
         "custom-scaling-resolution-width-inc" => Some(BooleanAction::ScalingResolutionWidth(Boolean2DAction::Increase)),
@@ -443,6 +509,8 @@ fn to_boolean_action(boolean_action: &str) -> Option<BooleanAction> {
         "shift+o" | "pixel-width-dec" => Some(BooleanAction::PixelWidth(Boolean2DAction::Decrease)),
         "g" | "camera-movement-mode-inc" => Some(BooleanAction::NextCameraMovementMode(Boolean2DAction::Increase)),
         "shift+g" | "camera-movement-mode-dec" => Some(BooleanAction::NextCameraMovementMode(Boolean2DAction::Decrease)),
+        "f5" | "focal-length-preset-inc" => Some(BooleanAction::FocalLengthPreset(Boolean2DAction::Increase)),
+        "shift+f5" | "focal-length-preset-dec" => Some(BooleanAction::FocalLengthPreset(Boolean2DAction::Decrease)),
         _ => None,
     }
 }
@@ -13,7 +13,30 @@
  * You should have received a copy of the GNU Affero General Public License
  * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
 
-use crate::app_events::AppEventDispatcher;
+use crate::app_events::{AppEventDispatcher, ClampedValue};
+
+// Same distance the 2D mode keeps from the screen.
+const SCREEN_COLLISION_MARGIN: f32 = 0.8;
+const MIN_NEAR_PLANE: f32 = 0.001;
+// Height of a 35mm full frame, so the focal lengths read like the ones of a photographic camera.
+const FULL_FRAME_HEIGHT_MM: f32 = 24.0;
+pub const FOCAL_LENGTH_PRESETS: [f32; 3] = [24.0, 50.0, 85.0];
+const MIN_ZOOM: f32 = 0.1;
+const MAX_ZOOM: f32 = 90.0;
+
+/// Zoom is the vertical field of view in degrees.
+pub fn zoom_to_focal_length(zoom: f32) -> f32 {
+    FULL_FRAME_HEIGHT_MM * 0.5 / (crate::math::radians(zoom) * 0.5).tan()
+}
+
+pub fn focal_length_to_zoom(focal_length: f32) -> f32 {
+    (2.0 * (FULL_FRAME_HEIGHT_MM * 0.5 / focal_length).atan()).to_degrees()
+}
+
+pub(crate) fn dispatch_zoom(dispatcher: &dyn AppEventDispatcher, zoom: f32) {
+    dispatcher.dispatch_change_camera_zoom(zoom);
+    dispatcher.dispatch_change_camera_focal_length(zoom_to_focal_length(zoom));
+}
 
 pub(crate) enum CameraDirection {
     Down,
@@ -36,6 +59,9 @@ pub enum CameraChange {
     DirectionX(f32),
     DirectionY(f32),
     DirectionZ(f32),
+    NearPlane(f32),
+    FarPlane(f32),
+    FocalLength(f32),
 }
 
 #[derive(Copy, Clone)]
@@ -70,6 +96,9 @@ impl CameraChange {
             CameraChange::DirectionX(n) => n,
             CameraChange::DirectionY(n) => n,
             CameraChange::DirectionZ(n) => n,
+            CameraChange::NearPlane(n) => n,
+            CameraChange::FarPlane(n) => n,
+            CameraChange::FocalLength(n) => n,
         }
     }
 }
@@ -90,6 +119,11 @@ pub struct CameraData {
     pub sending_camera_update_event: bool,
     pub locked_mode: CameraLockMode,
     pub position_changed: bool,
+    /// Manual clip planes, 0 means that they are computed from the camera distance and the scene radius.
+    pub near_plane: f32,
+    pub far_plane: f32,
+    /// Radius around the origin that contains everything that is drawn.
+    pub scene_radius: f32,
 }
 
 impl CameraData {
@@ -109,6 +143,9 @@ impl CameraData {
             position_changed: true,
             sending_camera_update_event: true,
             locked_mode: CameraLockMode::TwoDimensional,
+            near_plane: 0.0,
+            far_plane: 0.0,
+            scene_radius: 0.0,
         }
     }
 
@@ -127,7 +164,24 @@ impl CameraData {
     }
 
     pub fn get_projection(&self, width: f32, height: f32) -> glm::TMat4<f32> {
-        glm::perspective::<f32>(width / height, crate::math::radians(self.zoom), 0.01, 10000.0)
+        let (near, far) = self.get_clip_planes();
+        glm::perspective::<f32>(width / height, crate::math::radians(self.zoom), near, far)
+    }
+
+    // The planes hug the scene, so macro shots don't clip and far away shots keep enough depth precision to not z-fight.
+    pub fn get_clip_planes(&self) -> (f32, f32) {
+        let distance = glm::length(&self.position_eye);
+        let near = if self.near_plane > 0.0 {
+            self.near_plane
+        } else {
+            ((distance - self.scene_radius) * 0.9).max(MIN_NEAR_PLANE)
+        };
+        let far = if self.far_plane > 0.0 {
+            self.far_plane
+        } else {
+            (distance + self.scene_radius) * 1.1
+        };
+        (near, far.max(near + 1.0))
     }
 }
 
@@ -239,13 +293,24 @@ impl<'a> CameraSystem<'a> {
             CameraChange::PosX(x) => self.data.position_eye.x = x,
             CameraChange::PosY(y) => self.data.position_eye.y = y,
             CameraChange::PosZ(z) => self.data.position_eye.z = z,
-            CameraChange::Zoom(zoom) => self.data.zoom = zoom,
+            CameraChange::Zoom(zoom) => {
+                self.data.zoom = zoom;
+                dispatch_zoom(self.dispatcher, self.data.zoom);
+            }
             CameraChange::AxisUpX(x) => self.data.axis_up.x = x,
             CameraChange::AxisUpY(y) => self.data.axis_up.y = y,
             CameraChange::AxisUpZ(z) => self.data.axis_up.z = z,
             CameraChange::DirectionX(x) => self.data.direction.x = x,
             CameraChange::DirectionY(y) => self.data.direction.y = y,
             CameraChange::DirectionZ(z) => self.data.direction.z = z,
+            CameraChange::NearPlane(near) => self.data.near_plane = near.max(0.0),
+            CameraChange::FarPlane(far) => self.data.far_plane = far.max(0.0),
+            CameraChange::FocalLength(focal_length) => {
+                if focal_length > 0.0 {
+                    self.data.zoom = focal_length_to_zoom(focal_length).clamp(MIN_ZOOM, MAX_ZOOM);
+                }
+                dispatch_zoom(self.dispatcher, self.data.zoom);
+            }
         }
         self.data.position_changed = true;
         self.data.position_destiny = self.data.position_eye;
@@ -253,19 +318,59 @@ impl<'a> CameraSystem<'a> {
 
     pub(crate) fn change_zoom(&mut self, change: f32, dispatcher: &dyn AppEventDispatcher) {
         let last_zoom = self.data.zoom;
-        if self.data.zoom >= 0.1 && self.data.zoom <= 90.0 {
+        if self.data.zoom >= MIN_ZOOM && self.data.zoom <= MAX_ZOOM {
             self.data.zoom -= change * 0.1;
         }
-        if self.data.zoom <= 0.1 {
-            self.data.zoom = 0.1;
-            dispatcher.dispatch_top_message("Minimum value is 0.1");
+        let attempted = self.data.zoom;
+        if self.data.zoom <= MIN_ZOOM {
+            self.data.zoom = MIN_ZOOM;
+            dispatcher.dispatch_minimum_value(&ClampedValue {
+                field: "camera_zoom",
+                attempted: &attempted,
+                bound: &MIN_ZOOM,
+            });
         }
-        if self.data.zoom >= 90.0 {
-            self.data.zoom = 90.0;
-            dispatcher.dispatch_top_message("Maximum value is 90.0");
+        if self.data.zoom >= MAX_ZOOM {
+            self.data.zoom = MAX_ZOOM;
+            dispatcher.dispatch_maximum_value(&ClampedValue {
+                field: "camera_zoom",
+                attempted: &attempted,
+                bound: &MAX_ZOOM,
+            });
         }
         if (self.data.zoom - last_zoom).abs() > std::f32::EPSILON {
-            dispatcher.dispatch_change_camera_zoom(self.data.zoom);
+            dispatch_zoom(dispatcher, self.data.zoom);
+        }
+    }
+
+    /// Jumps to the next longer (or shorter) focal length preset, wrapping around. Returns the new focal length.
+    pub(crate) fn next_focal_length_preset(&mut self, increase: bool) -> f32 {
+        let current = zoom_to_focal_length(self.data.zoom).round();
+        let next = if increase {
+            FOCAL_LENGTH_PRESETS
+                .iter()
+                .find(|preset| **preset > current)
+                .unwrap_or(&FOCAL_LENGTH_PRESETS[0])
+        } else {
+            FOCAL_LENGTH_PRESETS
+                .iter()
+                .rev()
+                .find(|preset| **preset < current)
+                .unwrap_or(&FOCAL_LENGTH_PRESETS[FOCAL_LENGTH_PRESETS.len() - 1])
+        };
+        self.data.zoom = focal_length_to_zoom(*next);
+        dispatch_zoom(self.dispatcher, self.data.zoom);
+        *next
+    }
+
+    /// Stops the camera right before crossing the screen surface from the front.
+    /// A camera that is already behind the screen keeps flying freely, so it can always come back.
+    pub(crate) fn collide_with_screen(&mut self, previous_z: f32, surface_z: impl Fn(f32, f32) -> Option<f32>) {
+        let destiny = &mut self.data.position_destiny;
+        if let Some(limit) = surface_z(destiny.x, destiny.y).map(|z| z + SCREEN_COLLISION_MARGIN) {
+            if previous_z >= limit && destiny.z < limit {
+                destiny.z = limit;
+            }
         }
     }
 
@@ -330,3 +435,59 @@ impl<'a> CameraSystem<'a> {
             .dispatch_camera_update(&self.data.position_eye, &self.data.direction, &self.data.axis_up);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    fn make_camera(z: f32, scene_radius: f32) -> CameraData {
+        let mut camera = CameraData::new(1.0, 1.0);
+        camera.set_position(glm::vec3(0.0, 0.0, z));
+        camera.scene_radius = scene_radius;
+        camera
+    }
+
+    #[test]
+    fn test_get_clip_planes__far_camera__planes_hug_the_scene() {
+        let (near, far) = make_camera(1000.0, 100.0).get_clip_planes();
+        assert!((near - 810.0).abs() < 0.01);
+        assert!((far - 1210.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_get_clip_planes__camera_inside_the_scene__near_plane_is_minimum() {
+        assert_eq!(make_camera(1.0, 100.0).get_clip_planes().0, MIN_NEAR_PLANE);
+    }
+
+    #[test]
+    fn test_get_clip_planes__manual_override__is_used() {
+        let mut camera = make_camera(1000.0, 100.0);
+        camera.near_plane = 0.5;
+        camera.far_plane = 20000.0;
+        assert_eq!(camera.get_clip_planes(), (0.5, 20000.0));
+    }
+
+    #[test]
+    fn test_focal_length__round_trip__gives_the_same_zoom() {
+        assert!((focal_length_to_zoom(zoom_to_focal_length(45.0)) - 45.0).abs() < 0.001);
+        assert!((zoom_to_focal_length(focal_length_to_zoom(50.0)) - 50.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_focal_length__full_frame_normal_lens__is_about_27_degrees() {
+        assert!((focal_length_to_zoom(50.0) - 26.99).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_next_focal_length_preset__wraps_around() {
+        let dispatcher = crate::app_events::FakeEventDispatcher {};
+        let mut data = CameraData::new(1.0, 1.0);
+        let mut camera = CameraSystem::new(&mut data, &dispatcher);
+        assert_eq!(camera.next_focal_length_preset(true), 50.0);
+        assert_eq!(camera.next_focal_length_preset(true), 85.0);
+        assert_eq!(camera.next_focal_length_preset(true), 24.0);
+        assert_eq!(camera.next_focal_length_preset(false), 85.0);
+    }
+}
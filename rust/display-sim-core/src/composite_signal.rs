@@ -0,0 +1,172 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::general_types::Size2D;
+use crate::ui_controller::signal_kind::SignalKindOptions;
+
+/// NTSC fits around 188 chroma subcarrier cycles in the active part of a line.
+pub const SUBCARRIER_CYCLES_PER_LINE: usize = 188;
+/// The signal is sampled at four times the subcarrier, so every sample is a quarter cycle apart.
+const SAMPLES_PER_CYCLE: usize = 4;
+const CARRIER_COS: [f32; SAMPLES_PER_CYCLE] = [1.0, 0.0, -1.0, 0.0];
+const CARRIER_SIN: [f32; SAMPLES_PER_CYCLE] = [0.0, 1.0, 0.0, -1.0];
+/// Samples averaged to demodulate the chroma, two subcarrier cycles.
+const CHROMA_WINDOW: usize = 2 * SAMPLES_PER_CYCLE;
+
+/// The signal the source frames go through before reaching the tube. `field` counts the frames
+/// shown, because the subcarrier phase flips on every one of them.
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub struct CompositeSignal {
+    pub kind: SignalKindOptions,
+    pub field: u32,
+}
+
+/// Encodes every row of an RGBA frame as the selected video signal and decodes it back, like a TV would. Alpha is left untouched.
+/// S-Video only loses chroma bandwidth. Composite also mixes luma and chroma in one signal, so fine luma detail
+/// near the subcarrier frequency gets decoded as artifact colors, and the subcarrier itself leaks into the luma.
+pub fn encode_signal(frame: &mut [u8], size: Size2D<u32>, signal: CompositeSignal) {
+    if signal.kind == SignalKindOptions::Rgb {
+        return;
+    }
+    let width = size.width as usize;
+    let samples = SUBCARRIER_CYCLES_PER_LINE * SAMPLES_PER_CYCLE;
+    let mut luma = vec![0.0f32; samples];
+    let mut chroma = vec![0.0f32; samples];
+    for y in 0..size.height as usize {
+        let start = y * width * 4;
+        // Lines and fields hold a half number of subcarrier cycles, so the phase flips from one to the next.
+        let phase = (y + signal.field as usize) % 2 * SAMPLES_PER_CYCLE / 2;
+        for n in 0..samples {
+            let pixel = start + n * width / samples * 4;
+            let [lum, i, q] = rgb_to_yiq(&frame[pixel..pixel + 3]);
+            let carrier = (n + phase) % SAMPLES_PER_CYCLE;
+            luma[n] = lum;
+            chroma[n] = i * CARRIER_COS[carrier] + q * CARRIER_SIN[carrier];
+        }
+        for x in 0..width {
+            let center = ((x as f32 + 0.5) * samples as f32 / width as f32) as usize;
+            let sample = |n: usize| match signal.kind {
+                SignalKindOptions::Composite => luma[n] + chroma[n],
+                _ => chroma[n],
+            };
+            let lum = match signal.kind {
+                // Averaging a whole cycle cancels the subcarrier, which is how a simple notch filter separates the luma.
+                SignalKindOptions::Composite => window(center, SAMPLES_PER_CYCLE, samples).map(|n| luma[n] + chroma[n]).sum::<f32>() / SAMPLES_PER_CYCLE as f32,
+                _ => luma[center.min(samples - 1)],
+            };
+            let mut i = 0.0;
+            let mut q = 0.0;
+            for n in window(center, CHROMA_WINDOW, samples) {
+                let carrier = (n + phase) % SAMPLES_PER_CYCLE;
+                i += sample(n) * CARRIER_COS[carrier];
+                q += sample(n) * CARRIER_SIN[carrier];
+            }
+            // Only half of the window samples carry each component, and its value is multiplied by one on them.
+            let scale = 2.0 / CHROMA_WINDOW as f32;
+            let rgb = yiq_to_rgb([lum, i * scale, q * scale]);
+            for (channel, value) in rgb.iter().enumerate() {
+                frame[start + x * 4 + channel] = (value * 255.0).round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+}
+
+fn window(center: usize, length: usize, samples: usize) -> impl Iterator<Item = usize> {
+    let first = center.saturating_sub(length / 2).min(samples - length);
+    first..first + length
+}
+
+fn rgb_to_yiq(rgb: &[u8]) -> [f32; 3] {
+    let [r, g, b] = [rgb[0] as f32 / 255.0, rgb[1] as f32 / 255.0, rgb[2] as f32 / 255.0];
+    [
+        0.299 * r + 0.587 * g + 0.114 * b,
+        0.596 * r - 0.274 * g - 0.322 * b,
+        0.211 * r - 0.523 * g + 0.312 * b,
+    ]
+}
+
+fn yiq_to_rgb([y, i, q]: [f32; 3]) -> [f32; 3] {
+    [y + 0.956 * i + 0.621 * q, y - 0.272 * i - 0.647 * q, y - 1.106 * i + 1.703 * q]
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    fn solid_frame(size: Size2D<u32>, color: [u8; 4]) -> Vec<u8> {
+        color.iter().copied().cycle().take((size.width * size.height * 4) as usize).collect()
+    }
+
+    #[test]
+    fn test_encode_signal__flat_color__survives_the_round_trip() {
+        let size = Size2D { width: 64, height: 4 };
+        for kind in [SignalKindOptions::SVideo, SignalKindOptions::Composite] {
+            let mut frame = solid_frame(size, [200, 100, 50, 255]);
+            encode_signal(&mut frame, size, CompositeSignal { kind, field: 0 });
+            for pixel in frame.chunks(4) {
+                assert!((pixel[0] as i32 - 200).abs() <= 3, "{:?}", pixel);
+                assert!((pixel[1] as i32 - 100).abs() <= 3, "{:?}", pixel);
+                assert!((pixel[2] as i32 - 50).abs() <= 3, "{:?}", pixel);
+                assert_eq!(pixel[3], 255);
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_signal__composite_fine_stripes__decode_as_artifact_colors_that_crawl() {
+        // Grey stripes one sample wide at the subcarrier frequency, like the dithering of old consoles.
+        let size = Size2D {
+            width: (SUBCARRIER_CYCLES_PER_LINE * 2) as u32,
+            height: 2,
+        };
+        let stripes: Vec<u8> = (0..size.width * size.height)
+            .flat_map(|x| if x % 2 == 0 { [255, 255, 255, 255] } else { [0, 0, 0, 255] })
+            .collect();
+
+        let mut svideo = stripes.clone();
+        encode_signal(
+            &mut svideo,
+            size,
+            CompositeSignal {
+                kind: SignalKindOptions::SVideo,
+                field: 0,
+            },
+        );
+        assert!(svideo.chunks(4).all(|pixel| pixel[0] == pixel[1] && pixel[1] == pixel[2]));
+
+        let mut even = stripes.clone();
+        encode_signal(
+            &mut even,
+            size,
+            CompositeSignal {
+                kind: SignalKindOptions::Composite,
+                field: 0,
+            },
+        );
+        assert!(even.chunks(4).any(|pixel| pixel[0] != pixel[2]));
+
+        let mut odd = stripes;
+        encode_signal(
+            &mut odd,
+            size,
+            CompositeSignal {
+                kind: SignalKindOptions::Composite,
+                field: 1,
+            },
+        );
+        assert_ne!(even, odd);
+    }
+}
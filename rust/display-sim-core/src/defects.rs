@@ -0,0 +1,105 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::blemishes::seeded_unit;
+
+/// Which defects a monitor shows. The same settings always give the same mask.
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub struct DefectSettings {
+    pub seed: usize,
+    pub dead_subpixels: usize,
+    pub stuck_pixels: usize,
+    pub dead_scanlines: usize,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl DefectSettings {
+    pub fn is_flawless(&self) -> bool {
+        self.dead_subpixels == 0 && self.stuck_pixels == 0 && self.dead_scanlines == 0
+    }
+}
+
+// Every kind of defect draws its positions from its own stream, so adding one kind doesn't move the others.
+const DEAD_SUBPIXEL_STREAM: u64 = 1 << 32;
+const STUCK_PIXEL_STREAM: u64 = 2 << 32;
+const DEAD_SCANLINE_STREAM: u64 = 3 << 32;
+
+/// Builds an RGBA texel per source pixel, in the same order as the frame: the color channels multiply
+/// the subpixels, so a dead one is zero, and the alpha marks the pixels stuck at full brightness.
+pub fn build_defect_mask(settings: &DefectSettings) -> Vec<u8> {
+    let width = settings.width as usize;
+    let height = settings.height as usize;
+    let mut mask = [255, 255, 255, 0].repeat(width * height);
+    if mask.is_empty() {
+        return mask;
+    }
+    let pick = |stream: u64, n: u64, range: usize| ((seeded_unit(settings.seed, stream + n) * range as f32) as usize).min(range - 1);
+    for i in 0..settings.dead_subpixels as u64 {
+        let pixel = pick(DEAD_SUBPIXEL_STREAM, i * 2, width * height);
+        let channel = pick(DEAD_SUBPIXEL_STREAM, i * 2 + 1, 3);
+        mask[pixel * 4 + channel] = 0;
+    }
+    for i in 0..settings.stuck_pixels as u64 {
+        let pixel = pick(STUCK_PIXEL_STREAM, i, width * height);
+        mask[pixel * 4 + 3] = 255;
+    }
+    for i in 0..settings.dead_scanlines as u64 {
+        let row = pick(DEAD_SCANLINE_STREAM, i, height);
+        for texel in mask[row * width * 4..(row + 1) * width * 4].chunks_mut(4) {
+            texel.copy_from_slice(&[0, 0, 0, 0]);
+        }
+    }
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    fn settings(dead_subpixels: usize, stuck_pixels: usize, dead_scanlines: usize) -> DefectSettings {
+        DefectSettings {
+            seed: 1,
+            dead_subpixels,
+            stuck_pixels,
+            dead_scanlines,
+            width: 32,
+            height: 24,
+        }
+    }
+
+    #[test]
+    fn test_build_defect_mask__counts__land_on_the_mask() {
+        let mask = build_defect_mask(&settings(5, 3, 0));
+        assert_eq!(mask.len(), 32 * 24 * 4);
+        let dead = mask
+            .chunks(4)
+            .map(|texel| texel[..3].iter().filter(|&&channel| channel == 0).count())
+            .sum::<usize>();
+        let stuck = mask.chunks(4).filter(|texel| texel[3] == 255).count();
+        assert!((1..=5).contains(&dead));
+        assert!((1..=3).contains(&stuck));
+        assert_eq!(mask, build_defect_mask(&settings(5, 3, 0)));
+    }
+
+    #[test]
+    fn test_build_defect_mask__dead_scanline__darkens_a_whole_row() {
+        let mask = build_defect_mask(&settings(0, 0, 1));
+        let dead_rows = mask.chunks(32 * 4).filter(|row| row.iter().all(|&value| value == 0)).count();
+        assert_eq!(dead_rows, 1);
+        assert_eq!(mask.iter().filter(|&&value| value == 0).count(), 32 * 4 + 32 * 23);
+    }
+}
@@ -0,0 +1,121 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::simulation_core_state::ViewModel;
+
+/// Measurements in viewport pixels, so different configurations can be compared quantitatively.
+/// Corners are ordered top-left, top-right, bottom-left, bottom-right, and convergence is relative to the green channel.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiagnosticsReadout {
+    pub scanline_pitch: f32,
+    pub convergence_red: [[f32; 2]; 4],
+    pub convergence_blue: [[f32; 2]; 4],
+}
+
+pub fn measure_diagnostics(view_projection: &glm::Mat4, viewport: (f32, f32), image_size: (u32, u32), output: &ViewModel) -> DiagnosticsReadout {
+    let project = |position: glm::Vec3| -> glm::Vec2 {
+        let clip = view_projection * glm::vec4(position.x, position.y, position.z, 1.0);
+        glm::vec2((clip.x / clip.w + 1.0) * 0.5 * viewport.0, (1.0 - clip.y / clip.w) * 0.5 * viewport.1)
+    };
+    let spread = output.pixel_spread;
+    let scanline_pitch = glm::distance(&project(glm::vec3(0.0, 0.0, 0.0)), &project(glm::vec3(0.0, spread[1], 0.0)));
+
+    let channel_offset = |channel: usize| -> glm::Vec3 {
+        let beam = glm::vec3(output.convergence[channel][0] * spread[0], output.convergence[channel][1] * spread[1], 0.0);
+        if output.color_splits < 3 {
+            return beam;
+        }
+        match output.pixel_offset_foreground.first() {
+            Some(offsets) => beam + glm::vec3(offsets[channel][0], offsets[channel][1], offsets[channel][2]),
+            None => beam,
+        }
+    };
+    // At the corners the curvature displacement of the pixels shader reaches its maximum.
+    let half_width = image_size.0 as f32 * 0.5 * spread[0];
+    let half_height = image_size.1 as f32 * 0.5 * spread[1];
+    let axes = output.screen_curvature_axes;
+    let (half_columns, half_rows) = (image_size.0 as f32 * 0.5, image_size.1 as f32 * 0.5);
+    let normalized = (half_columns * axes[0]).hypot(half_rows * axes[1]) / half_columns.hypot(half_rows);
+    let corner_z = -normalized.sin() * output.screen_curvature_factor * 100.0;
+    let corners = [
+        glm::vec3(-half_width, half_height, corner_z),
+        glm::vec3(half_width, half_height, corner_z),
+        glm::vec3(-half_width, -half_height, corner_z),
+        glm::vec3(half_width, -half_height, corner_z),
+    ];
+    let mut readout = DiagnosticsReadout {
+        scanline_pitch,
+        ..Default::default()
+    };
+    for (i, corner) in corners.iter().enumerate() {
+        let green = project(corner + channel_offset(1));
+        let red = project(corner + channel_offset(0)) - green;
+        let blue = project(corner + channel_offset(2)) - green;
+        readout.convergence_red[i] = [red.x, red.y];
+        readout.convergence_blue[i] = [blue.x, blue.y];
+    }
+    readout
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+    use crate::math::radians;
+
+    fn view_projection(distance: f32) -> glm::Mat4 {
+        let projection = glm::perspective::<f32>(1.0, radians(90.0), 0.01, 10000.0);
+        let view = glm::look_at(&glm::vec3(0.0, 0.0, distance), &glm::vec3(0.0, 0.0, 0.0), &glm::vec3(0.0, 1.0, 0.0));
+        projection * view
+    }
+
+    #[test]
+    fn test_measure_diagnostics__flat_screen__scanline_pitch_matches_the_projection() {
+        let output = ViewModel {
+            pixel_spread: [1.0, 1.0],
+            color_splits: 1,
+            ..Default::default()
+        };
+        let readout = measure_diagnostics(&view_projection(1.0), (100.0, 100.0), (2, 2), &output);
+        assert!((readout.scanline_pitch - 50.0).abs() < 0.001);
+        assert_eq!(readout.convergence_red, [[0.0, 0.0]; 4]);
+    }
+
+    #[test]
+    fn test_measure_diagnostics__shifted_red_channel__reports_convergence_offset() {
+        let output = ViewModel {
+            pixel_spread: [1.0, 1.0],
+            color_splits: 3,
+            pixel_offset_foreground: vec![[[0.1, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0]]],
+            ..Default::default()
+        };
+        let readout = measure_diagnostics(&view_projection(10.0), (100.0, 100.0), (2, 2), &output);
+        assert!((readout.convergence_red[0][0] - 0.5).abs() < 0.001);
+        assert_eq!(readout.convergence_blue, [[0.0, 0.0]; 4]);
+    }
+
+    #[test]
+    fn test_measure_diagnostics__misconverged_blue_beam__reports_convergence_offset_with_combined_channels() {
+        let output = ViewModel {
+            pixel_spread: [1.0, 1.0],
+            color_splits: 1,
+            convergence: [[0.0, 0.0], [0.0, 0.0], [0.1, 0.0]],
+            ..Default::default()
+        };
+        let readout = measure_diagnostics(&view_projection(10.0), (100.0, 100.0), (2, 2), &output);
+        assert!((readout.convergence_blue[0][0] - 0.5).abs() < 0.001);
+        assert_eq!(readout.convergence_red, [[0.0, 0.0]; 4]);
+    }
+}
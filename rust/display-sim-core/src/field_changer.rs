@@ -13,6 +13,7 @@
  * You should have received a copy of the GNU Affero General Public License
  * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
 
+use crate::app_events::ClampedValue;
 use crate::general_types::{IncDec, OptionCursor};
 use crate::simulation_context::SimulationContext;
 use std::cmp::{PartialEq, PartialOrd};
@@ -21,6 +22,7 @@ use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
 
 pub(crate) struct FieldChanger<'a, T, U, TriggerHandler: FnOnce(U)> {
     ctx: &'a dyn SimulationContext,
+    field: &'static str,
     var: &'a mut T,
     incdec: IncDec<bool>,
     trigger_handler: Option<TriggerHandler>,
@@ -32,9 +34,10 @@ pub(crate) struct FieldChanger<'a, T, U, TriggerHandler: FnOnce(U)> {
 }
 
 impl<'a, T, U, TriggerHandler: FnOnce(U)> FieldChanger<'a, T, U, TriggerHandler> {
-    pub(crate) fn new(ctx: &'a dyn SimulationContext, var: &'a mut T, incdec: IncDec<bool>) -> Self {
+    pub(crate) fn new(ctx: &'a dyn SimulationContext, field: &'static str, var: &'a mut T, incdec: IncDec<bool>) -> Self {
         FieldChanger {
             ctx,
+            field,
             var,
             incdec,
             trigger_handler: None,
@@ -91,10 +94,15 @@ where
             changed = true;
         }
         if changed {
+            let clamped = ClampedValue {
+                field: self.field,
+                attempted: self.var,
+                bound: self.var,
+            };
             if self.var.has_reached_minimum_limit() {
-                self.ctx.dispatcher().dispatch_minimum_value(self.var);
+                self.ctx.dispatcher().dispatch_minimum_value(&clamped);
             } else if self.var.has_reached_maximum_limit() {
-                self.ctx.dispatcher().dispatch_maximum_value(self.var);
+                self.ctx.dispatcher().dispatch_maximum_value(&clamped);
             } else if let Some(handler) = self.trigger_handler {
                 handler(self.var);
                 return true;
@@ -124,7 +132,7 @@ where
     }
 }
 
-fn operate_filter<T, TriggerHandler>(params: FieldChanger<T, T, TriggerHandler>, inc_op: impl FnOnce(&mut T, T), dec_op: impl FnOnce(&mut T, T)) -> bool
+fn operate_filter<T, TriggerHandler>(params: FieldChanger<T, T, TriggerHandler>, inc_op: impl Fn(&mut T, T), dec_op: impl Fn(&mut T, T)) -> bool
 where
     T: Display + PartialOrd + PartialEq + Copy + Default,
     TriggerHandler: FnOnce(T),
@@ -142,16 +150,32 @@ where
     if let Some(val) = params.event_value {
         *params.var = val;
     }
+    // Going further from a limit the value is already at doesn't change it, so then it's the step that got clamped.
+    let mut attempted = *params.var;
+    if is_min && params.incdec.decrease {
+        dec_op(&mut attempted, velocity);
+    }
+    if is_max && params.incdec.increase {
+        inc_op(&mut attempted, velocity);
+    }
     if let Some(min) = params.min {
         if *params.var < min || (is_min && params.incdec.decrease) {
             *params.var = min;
-            params.ctx.dispatcher().dispatch_minimum_value(&min);
+            params.ctx.dispatcher().dispatch_minimum_value(&ClampedValue {
+                field: params.field,
+                attempted: &attempted,
+                bound: &min,
+            });
         }
     }
     if let Some(max) = params.max {
         if *params.var > max || (is_max && params.incdec.increase) {
             *params.var = max;
-            params.ctx.dispatcher().dispatch_maximum_value(&max);
+            params.ctx.dispatcher().dispatch_maximum_value(&ClampedValue {
+                field: params.field,
+                attempted: &attempted,
+                bound: &max,
+            });
         }
     }
     if last_value != *params.var {
@@ -239,7 +263,7 @@ mod tests {
         fn trigger_handler__on_change__triggers() {
             let mut actual = OptionKind::A;
             let mut triggered = false;
-            FieldChanger::new(&CTX, &mut actual, INCDEC_DOWN)
+            FieldChanger::new(&CTX, "test", &mut actual, INCDEC_DOWN)
                 .set_trigger_handler(|_: &OptionKind| triggered = true)
                 .process_options();
             assert_eq!(triggered, true);
@@ -322,7 +346,7 @@ mod tests {
         fn trigger_handler__on_change__triggers() {
             let mut actual = 0;
             let mut triggered = false;
-            FieldChanger::new(&CTX, &mut actual, INCDEC_DOWN)
+            FieldChanger::new(&CTX, "test", &mut actual, INCDEC_DOWN)
                 .set_trigger_handler(|_| triggered = true)
                 .set_progression(1)
                 .process_with_sums();
@@ -333,7 +357,7 @@ mod tests {
         fn trigger_handler__on_blocked_change__doesnt_trigger() {
             let mut actual = 0;
             let mut triggered = false;
-            FieldChanger::new(&CTX, &mut actual, INCDEC_DOWN)
+            FieldChanger::new(&CTX, "test", &mut actual, INCDEC_DOWN)
                 .set_trigger_handler(|_| triggered = true)
                 .set_progression(1)
                 .set_min(0)
@@ -363,10 +387,10 @@ mod tests {
     static CTX: ConcreteSimulationContext<FakeEventDispatcher, FakeRngGenerator> = make_fake_simulation_context();
 
     fn sut<'a, T>(parameter: &'a mut T, incdec: IncDec<bool>) -> FieldChanger<'a, T, T, impl FnOnce(T)> {
-        FieldChanger::new(&CTX, parameter, incdec).set_trigger_handler(|_| {})
+        FieldChanger::new(&CTX, "test", parameter, incdec).set_trigger_handler(|_| {})
     }
 
     fn sut_ref<'a, T>(parameter: &'a mut T, incdec: IncDec<bool>) -> FieldChanger<'a, T, &'a T, impl FnOnce(&'a T)> {
-        FieldChanger::new(&CTX, parameter, incdec).set_trigger_handler(|_| {})
+        FieldChanger::new(&CTX, "test", parameter, incdec).set_trigger_handler(|_| {})
     }
 }
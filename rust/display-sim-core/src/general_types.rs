@@ -163,6 +163,65 @@ pub fn get_int_from_3_f32color(color: &[f32; 3]) -> i32 {
     ((r << 16) + (g << 8) + b) as i32
 }
 
+pub fn get_3_f32color_from_kelvin(kelvin: f32) -> [f32; 3] {
+    let temperature = kelvin / 100.0;
+    let red = if temperature <= 66.0 {
+        255.0
+    } else {
+        329.698_73 * (temperature - 60.0).powf(-0.133_204_76)
+    };
+    let green = if temperature <= 66.0 {
+        99.470_8 * temperature.ln() - 161.119_57
+    } else {
+        288.122_17 * (temperature - 60.0).powf(-0.075_514_85)
+    };
+    let blue = if temperature >= 66.0 {
+        255.0
+    } else if temperature <= 19.0 {
+        0.0
+    } else {
+        138.517_73 * (temperature - 10.0).ln() - 305.044_8
+    };
+    [red.clamp(0.0, 255.0) / 255.0, green.clamp(0.0, 255.0) / 255.0, blue.clamp(0.0, 255.0) / 255.0]
+}
+
+/// The white point every picture is assumed to be mastered for.
+pub const NEUTRAL_WHITE_POINT_KELVIN: f32 = 6500.0;
+
+/// Per channel gains that move the neutral white point to the given one. The strongest channel stays at 1,
+/// so the white point only tints the picture and never clips it.
+pub fn get_white_point_gains(kelvin: f32) -> [f32; 3] {
+    let neutral = get_3_f32color_from_kelvin(NEUTRAL_WHITE_POINT_KELVIN);
+    let target = get_3_f32color_from_kelvin(kelvin);
+    let gains = [target[0] / neutral[0], target[1] / neutral[1], target[2] / neutral[2]];
+    let max = gains[0].max(gains[1]).max(gains[2]);
+    [gains[0] / max, gains[1] / max, gains[2] / max]
+}
+
+/// Chromaticities (x, y) of the red, green and blue primaries of a color space.
+pub type Primaries = [[f32; 2]; 3];
+
+pub const SRGB_PRIMARIES: Primaries = [[0.64, 0.33], [0.30, 0.60], [0.15, 0.06]];
+
+// Every color space handled here has the D65 white.
+const D65_WHITE: [f32; 2] = [0.3127, 0.3290];
+
+fn get_rgb_to_xyz_matrix(primaries: &Primaries) -> glm::Mat3 {
+    let xyz = |[x, y]: [f32; 2]| glm::vec3(x / y, 1.0, (1.0 - x - y) / y);
+    let columns = glm::Mat3::from_columns(&[xyz(primaries[0]), xyz(primaries[1]), xyz(primaries[2])]);
+    let scale = columns.try_inverse().unwrap_or_else(glm::Mat3::identity) * xyz(D65_WHITE);
+    columns * glm::Mat3::from_diagonal(&scale)
+}
+
+/// Linear RGB matrix that shows the colors made with the `from` primaries on a display with the `to` ones,
+/// column-major as GL takes it.
+pub fn get_primaries_conversion_matrix(from: &Primaries, to: &Primaries) -> [f32; 9] {
+    let matrix = get_rgb_to_xyz_matrix(to).try_inverse().unwrap_or_else(glm::Mat3::identity) * get_rgb_to_xyz_matrix(from);
+    let mut result = [0.0; 9];
+    result.copy_from_slice(matrix.as_slice());
+    result
+}
+
 #[cfg(test)]
 mod tests {
     mod get_3_f32color_from_int {
@@ -200,4 +259,39 @@ mod tests {
         let actual = get_int_from_3_f32color(&get_3_f32color_from_int(expected));
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_get_3_f32color_from_kelvin() {
+        let daylight = get_3_f32color_from_kelvin(6500.0);
+        assert!(daylight.iter().all(|c| *c > 0.97));
+        let cold = get_3_f32color_from_kelvin(9300.0);
+        assert!(cold[2] > cold[1] && cold[1] > cold[0]);
+        let warm = get_3_f32color_from_kelvin(3000.0);
+        assert!(warm[0] > warm[1] && warm[1] > warm[2]);
+    }
+
+    #[test]
+    fn test_get_primaries_conversion_matrix() {
+        let identity = get_primaries_conversion_matrix(&SRGB_PRIMARIES, &SRGB_PRIMARIES);
+        for (i, value) in identity.iter().enumerate() {
+            let expected = if i % 4 == 0 { 1.0 } else { 0.0 };
+            assert!((value - expected).abs() < 0.0001, "{:?}", identity);
+        }
+        let ebu = [[0.64, 0.33], [0.29, 0.60], [0.15, 0.06]];
+        let matrix = get_primaries_conversion_matrix(&ebu, &SRGB_PRIMARIES);
+        for row in 0..3 {
+            let white = matrix[row] + matrix[row + 3] + matrix[row + 6];
+            assert!((white - 1.0).abs() < 0.0001, "row {} turns white into {}", row, white);
+        }
+        assert!(matrix[3] < 0.0, "the EBU green falls out of the sRGB gamut, it needs negative red");
+    }
+
+    #[test]
+    fn test_get_white_point_gains() {
+        assert_eq!(get_white_point_gains(NEUTRAL_WHITE_POINT_KELVIN), [1.0, 1.0, 1.0]);
+        let cold = get_white_point_gains(9300.0);
+        assert!(cold[2] == 1.0 && cold[0] < cold[1]);
+        let warm = get_white_point_gains(5000.0);
+        assert!(warm[0] == 1.0 && warm[2] < warm[1]);
+    }
 }
@@ -0,0 +1,219 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::input_types::Pressed;
+use std::str::FromStr;
+
+/// Milliseconds between the frames of a replay, whatever the pace of the session that was recorded.
+pub const REPLAY_FRAME_MS: f64 = 1000.0 / 60.0;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct LoggedKey {
+    pub frame: u32,
+    pub key: String,
+    pub pressed: Pressed,
+}
+
+/// Keys pressed and released during a session, one "<frame> <down|up> <key>" per line. The space bar is written
+/// as "space" so the line doesn't end in blank, and empty lines and lines starting with '#' are skipped.
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct InputLog(pub Vec<LoggedKey>);
+
+impl std::fmt::Display for InputLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for logged in self.0.iter() {
+            let key = if logged.key == " " { "space" } else { &logged.key };
+            let pressed = if logged.pressed == Pressed::Yes { "down" } else { "up" };
+            writeln!(f, "{} {} {}", logged.frame, pressed, key)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for InputLog {
+    type Err = String;
+    fn from_str(encoded: &str) -> Result<Self, Self::Err> {
+        let mut log = Vec::new();
+        for line in encoded.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let parts: Vec<&str> = line.splitn(3, ' ').collect();
+            if parts.len() != 3 {
+                return Err(format!("Bad input log line '{}'", line));
+            }
+            let frame = u32::from_str(parts[0]).map_err(|e| format!("Bad input log frame '{}': {}", parts[0], e))?;
+            let pressed = match parts[1] {
+                "down" => Pressed::Yes,
+                "up" => Pressed::No,
+                other => return Err(format!("Bad input log press '{}'", other)),
+            };
+            let key = if parts[2] == "space" { " ".into() } else { parts[2].into() };
+            log.push(LoggedKey { frame, key, pressed });
+        }
+        // The steps go through the log in order, so the frames can't go back.
+        if log.windows(2).any(|pair| pair[1].frame < pair[0].frame) {
+            return Err("The frames of the input log have to be in order".into());
+        }
+        Ok(InputLog(log))
+    }
+}
+
+/// Records the keys of a session, and replays them later one frame per step with its own clock, so a reported
+/// glitch can be looked at frame by frame.
+#[derive(Default)]
+pub struct InputReplayState {
+    recording: Option<InputLog>,
+    recorded_frames: u32,
+    replaying: Option<InputLog>,
+    next: usize,
+    frame: u32,
+    clock: f64,
+    held: Vec<String>,
+    external_clock_before: bool,
+}
+
+impl InputReplayState {
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.replaying.is_some()
+    }
+
+    pub(crate) fn start_recording(&mut self) {
+        self.recording = Some(InputLog::default());
+        self.recorded_frames = 0;
+    }
+
+    pub(crate) fn stop_recording(&mut self) -> Option<InputLog> {
+        self.recording.take()
+    }
+
+    pub(crate) fn record(&mut self, key: &str, pressed: Pressed) {
+        if let Some(log) = self.recording.as_mut() {
+            log.0.push(LoggedKey {
+                frame: self.recorded_frames,
+                key: key.into(),
+                pressed,
+            });
+        }
+    }
+
+    /// Called once per simulated frame, so the recorded keys know when they happened.
+    pub(crate) fn count_recorded_frame(&mut self) {
+        if self.recording.is_some() {
+            self.recorded_frames += 1;
+        }
+    }
+
+    /// Starts at `now`, `external_clock` being how the source was advanced before, so it can be given back.
+    pub(crate) fn start_replay(&mut self, log: InputLog, now: f64, external_clock: bool) {
+        *self = InputReplayState {
+            replaying: Some(log),
+            clock: now,
+            external_clock_before: external_clock,
+            ..Default::default()
+        };
+    }
+
+    /// Returns whether the source was advanced by an external clock before the replay.
+    pub(crate) fn stop_replay(&mut self) -> bool {
+        let external_clock = self.external_clock_before;
+        *self = InputReplayState::default();
+        external_clock
+    }
+
+    /// Moves to the next frame and returns its keys in the order they were logged, with the time of that frame.
+    pub(crate) fn step(&mut self) -> (Vec<(String, Pressed)>, f64) {
+        let log = match self.replaying.as_ref() {
+            Some(log) => log,
+            None => return (vec![], self.clock),
+        };
+        let mut keys = vec![];
+        while let Some(logged) = log.0.get(self.next).filter(|logged| logged.frame == self.frame) {
+            self.held.retain(|key| *key != logged.key);
+            if logged.pressed == Pressed::Yes {
+                self.held.push(logged.key.clone());
+            }
+            keys.push((logged.key.clone(), logged.pressed));
+            self.next += 1;
+        }
+        self.frame += 1;
+        self.clock += REPLAY_FRAME_MS;
+        (keys, self.clock)
+    }
+
+    /// Time of the last replayed frame, the simulation stays there between steps.
+    pub(crate) fn clock(&self) -> f64 {
+        self.clock
+    }
+
+    /// Line for the HUD with the last replayed frame and the keys held on it.
+    pub fn status_line(&self) -> Option<String> {
+        let log = self.replaying.as_ref()?;
+        let last_frame = log.0.last().map_or(0, |logged| logged.frame);
+        let held: Vec<&str> = self.held.iter().map(|key| if key == " " { "space" } else { key.as_str() }).collect();
+        Some(format!(
+            "Replay frame {}/{}: {}",
+            self.frame,
+            last_frame + 1,
+            if held.is_empty() { "-".into() } else { held.join(" ") }
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    #[test]
+    fn test_input_log__roundtrips_through_its_encoding() -> Result<(), String> {
+        let log = InputLog::from_str("# session\n0 down arrowleft\n\n2 down space\n3 up arrowleft\n")?;
+        assert_eq!(log.0.len(), 3);
+        assert_eq!(log.0[1].key, " ");
+        assert_eq!(log.to_string(), "0 down arrowleft\n2 down space\n3 up arrowleft\n");
+        assert!(InputLog::from_str("1 down a\n0 up a").is_err());
+        assert!(InputLog::from_str("1 held a").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_input_replay_state__step__gives_the_keys_of_each_frame() -> Result<(), String> {
+        let mut state = InputReplayState::default();
+        state.start_replay(InputLog::from_str("0 down a\n0 down b\n2 up a")?, 1000.0, false);
+        let (keys, now) = state.step();
+        assert_eq!(keys, vec![("a".into(), Pressed::Yes), ("b".into(), Pressed::Yes)]);
+        assert_eq!(now, 1000.0 + REPLAY_FRAME_MS);
+        assert_eq!(state.status_line(), Some("Replay frame 1/3: a b".into()));
+        assert!(state.step().0.is_empty());
+        assert_eq!(state.step().0, vec![("a".into(), Pressed::No)]);
+        assert_eq!(state.status_line(), Some("Replay frame 3/3: b".into()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_input_replay_state__recording__logs_the_frame_of_each_key() {
+        let mut state = InputReplayState::default();
+        state.record("a", Pressed::Yes);
+        state.start_recording();
+        state.record("a", Pressed::Yes);
+        state.count_recorded_frame();
+        state.count_recorded_frame();
+        state.record("a", Pressed::No);
+        let log = state.stop_recording().expect("It was recording");
+        assert_eq!(log.to_string(), "0 down a\n2 up a\n");
+        assert!(!state.is_recording());
+    }
+}
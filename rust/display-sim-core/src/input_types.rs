@@ -39,9 +39,19 @@ impl Pressed {
 pub enum InputEventValue {
     None,
 
-    Keyboard { pressed: Pressed, key: String },
+    Keyboard {
+        pressed: Pressed,
+        key: String,
+    },
     MouseClick(Pressed),
-    MouseMove { x: i32, y: i32 },
+    MouseMove {
+        x: i32,
+        y: i32,
+    },
+    PointerPosition {
+        x: i32,
+        y: i32,
+    },
     MouseWheel(f32),
     BlurredWindow,
 
@@ -53,6 +63,9 @@ pub enum InputEventValue {
     CustomScalingAspectRatioY(f32),
     CustomScalingStretchNearest(bool),
     ViewportResize(u32, u32),
+    /// Input log to replay frame by frame, empty to stop the replay.
+    InputReplay(String),
+    InputRecording(bool),
 }
 
 pub(crate) struct CustomInputEvent {
@@ -131,13 +144,18 @@ pub struct Input {
     pub(crate) mouse_position_x: i32,
     pub(crate) mouse_position_y: i32,
     pub(crate) mouse_scroll_y: f32,
+    pub(crate) pointer_position: (i32, i32),
+    pub(crate) magnet: bool,
     pub(crate) pixel_width: IncDec<bool>,
+    pub(crate) settings_menu_inc: bool,
+    pub(crate) settings_menu_dec: bool,
 
     pub(crate) active_pressed_actions: Vec<KeyCodeBooleanAction>,
     pub(crate) active_pressed_actions_2: Vec<String>,
 
     // get_tracked_buttons
     pub(crate) next_camera_movement_mode: IncDec<BooleanButton>,
+    pub(crate) focal_length_preset: IncDec<BooleanButton>,
     pub(crate) translation_speed: IncDec<BooleanButton>,
     pub(crate) turn_speed: IncDec<BooleanButton>,
     pub(crate) filter_speed: IncDec<BooleanButton>,
@@ -151,6 +169,17 @@ pub struct Input {
     pub(crate) esc: BooleanButton,
     pub(crate) space: BooleanButton,
     pub(crate) screenshot: BooleanButton,
+    pub(crate) moire_assistant: BooleanButton,
+    pub(crate) screenshot_bracket: BooleanButton,
+    pub(crate) turntable: BooleanButton,
+    pub(crate) room_lighting: BooleanButton,
+    pub(crate) black_pedestal_setup: BooleanButton,
+    pub(crate) blind_test: BooleanButton,
+    pub(crate) blind_test_a: BooleanButton,
+    pub(crate) blind_test_b: BooleanButton,
+    pub(crate) settings_menu: BooleanButton,
+    pub(crate) settings_menu_up: BooleanButton,
+    pub(crate) settings_menu_down: BooleanButton,
 
     // get_options_to_be_noned
     pub(crate) event_scaling_resolution_width: Option<f32>,
@@ -206,13 +235,28 @@ pub(crate) enum BooleanAction {
     Esc,
     Space,
     Screenshot,
+    MoireAssistant,
+    ScreenshotBracket,
+    Turntable,
+    RoomLighting,
+    BlackPedestalSetup,
+    BlindTest,
+    BlindTestA,
+    BlindTestB,
+    SettingsMenu,
+    SettingsMenuUp,
+    SettingsMenuDown,
+    SettingsMenuInc,
+    SettingsMenuDec,
     InputFocused,
     CanvasFocused,
     MouseClick,
+    Magnet,
 
     CameraZoom(Boolean2DAction),
     PixelWidth(Boolean2DAction),
     NextCameraMovementMode(Boolean2DAction),
+    FocalLengthPreset(Boolean2DAction),
     TranslationSpeed(Boolean2DAction),
     TurnSpeed(Boolean2DAction),
     FilterSpeed(Boolean2DAction),
@@ -0,0 +1,101 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+/// Slow pan and zoom of the camera over a still picture, so a single screenshot doesn't look frozen.
+/// The offset is kept apart from the camera, so it can be taken back and the camera can still be moved by hand.
+pub struct KenBurnsState {
+    phase: f32,
+    applied: glm::Vec3,
+}
+
+impl Default for KenBurnsState {
+    fn default() -> Self {
+        KenBurnsState {
+            phase: 0.0,
+            applied: glm::vec3(0.0, 0.0, 0.0),
+        }
+    }
+}
+
+impl KenBurnsState {
+    /// Advances the drift by `cycles` and returns how much the camera has to move since the previous call,
+    /// along its right, up and forward axes.
+    pub(crate) fn advance(&mut self, cycles: f32, margin: f32, distance: f32, half_view: glm::Vec2) -> glm::Vec3 {
+        // The pan goes at half the rate of the zoom, so it takes two zoom cycles to come back to the start.
+        self.phase = (self.phase + cycles * std::f32::consts::TAU) % (2.0 * std::f32::consts::TAU);
+        let offset = ken_burns_offset(self.phase, margin, distance, half_view);
+        let delta = offset - self.applied;
+        self.applied = offset;
+        delta
+    }
+
+    /// Returns the movement that takes the camera back to where it would be without the drift.
+    pub(crate) fn stop(&mut self) -> glm::Vec3 {
+        let delta = -self.applied;
+        *self = KenBurnsState::default();
+        delta
+    }
+
+    /// Forgets the drift without moving the camera back, for when the camera has been placed again.
+    pub(crate) fn forget(&mut self) {
+        *self = KenBurnsState::default();
+    }
+}
+
+/// Offset along the right, up and forward axes of a camera at `distance` from the screen that sees `half_view`
+/// (half width and height) of it. The zoom goes in and out once per turn of `phase`, getting at most `margin` of the
+/// distance closer, and the pan never goes further than what the zoom has cropped, so nothing outside of the view at
+/// rest comes into it.
+pub fn ken_burns_offset(phase: f32, margin: f32, distance: f32, half_view: glm::Vec2) -> glm::Vec3 {
+    let zoom = margin * 0.5 * (1.0 - phase.cos());
+    glm::vec3(
+        zoom * half_view.x * (phase * 0.5).sin(),
+        zoom * half_view.y * (phase * 0.5 + 1.0).sin(),
+        zoom * distance,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    #[test]
+    fn test_ken_burns_offset__stays_inside_the_view_at_rest() {
+        let half_view = glm::vec2(160.0, 120.0);
+        assert_eq!(ken_burns_offset(0.0, 0.2, 500.0, half_view), glm::vec3(0.0, 0.0, 0.0));
+        for step in 0..200 {
+            let phase = step as f32 * 0.05;
+            let offset = ken_burns_offset(phase, 0.2, 500.0, half_view);
+            let zoom = offset.z / 500.0;
+            assert!((0.0..=0.2).contains(&zoom));
+            // What the camera sees shrinks with the zoom, and it has to fit in the view at rest wherever it pans to.
+            assert!(offset.x.abs() + (1.0 - zoom) * half_view.x <= half_view.x + 0.001);
+            assert!(offset.y.abs() + (1.0 - zoom) * half_view.y <= half_view.y + 0.001);
+        }
+    }
+
+    #[test]
+    fn test_ken_burns_state__stop__takes_the_camera_back() {
+        let mut state = KenBurnsState::default();
+        let mut position = glm::vec3(0.0, 0.0, 0.0);
+        for _ in 0..10 {
+            position += state.advance(0.07, 0.1, 500.0, glm::vec2(160.0, 120.0));
+        }
+        assert!(position.norm() > 0.0);
+        position += state.stop();
+        assert!(position.norm() < 0.001);
+    }
+}
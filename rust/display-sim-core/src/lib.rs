@@ -20,14 +20,34 @@
 extern crate derive_new;
 
 pub mod app_events;
+pub mod blemishes;
+pub mod blind_test;
+pub mod blooming;
 mod boolean_actions;
 mod boolean_button;
 pub mod camera;
+pub mod composite_signal;
+pub mod defects;
+pub mod diagnostics;
 mod field_changer;
 pub mod general_types;
+pub mod input_replay;
 pub mod input_types;
+pub mod ken_burns;
 mod math;
+pub mod moire;
+pub mod monitor_case;
+pub mod palettes;
+pub mod procedural_sources;
+pub mod response_time;
+pub mod room;
+pub mod screenshot_bracket;
+pub mod settings_menu;
 pub mod simulation_context;
 pub mod simulation_core_state;
 pub mod simulation_core_ticker;
+pub mod subtitles;
+pub mod transitions;
+pub mod turntable;
+pub mod tv_lines;
 pub mod ui_controller;
@@ -46,3 +46,163 @@ mod test_radians {
         assert_eq!(0.0, radians(0.0));
     }
 }
+
+pub fn calculate_physical_distance(dpi: f32, dot_pitch_mm: f32, world_pitch: f32, viewport_height: f32, fov_degrees: f32) -> f32 {
+    let target_screen_pixels = dot_pitch_mm / 25.4 * dpi;
+    viewport_height * world_pitch / (2.0 * (radians(fov_degrees) * 0.5).tan() * target_screen_pixels)
+}
+
+#[cfg(test)]
+mod test_calculate_physical_distance {
+    use super::calculate_physical_distance;
+    #[test]
+    fn test_calculate_physical_distance_gives_good_results() {
+        assert!((calculate_physical_distance(254.0, 1.0, 1.0, 1000.0, 90.0) - 50.0).abs() < 0.001);
+        assert!((calculate_physical_distance(254.0, 2.0, 1.0, 1000.0, 90.0) - 25.0).abs() < 0.001);
+        assert!((calculate_physical_distance(254.0, 1.0, 2.0, 1000.0, 90.0) - 100.0).abs() < 0.001);
+    }
+}
+
+/// Intersects the ray going through the pointer with the flat screen plane (z = 0).
+pub fn calculate_screen_plane_hit(pointer: (i32, i32), viewport: (f32, f32), view_projection: &glm::Mat4) -> Option<glm::Vec3> {
+    let ndc_x = 2.0 * pointer.0 as f32 / viewport.0 - 1.0;
+    let ndc_y = 1.0 - 2.0 * pointer.1 as f32 / viewport.1;
+    let inverse = glm::inverse(view_projection);
+    let near = inverse * glm::vec4(ndc_x, ndc_y, -1.0, 1.0);
+    let far = inverse * glm::vec4(ndc_x, ndc_y, 1.0, 1.0);
+    let near = near.xyz() / near.w;
+    let far = far.xyz() / far.w;
+    let direction = far - near;
+    if direction.z.abs() < f32::EPSILON {
+        return None;
+    }
+    let t = -near.z / direction.z;
+    if t < 0.0 {
+        return None;
+    }
+    Some(near + direction * t)
+}
+
+/// Returns the source pixel (column, row from the top) under the pointer.
+pub fn calculate_light_gun_hit(
+    pointer: (i32, i32),
+    viewport: (f32, f32),
+    view_projection: &glm::Mat4,
+    image_size: (u32, u32),
+    pixel_spread: [f32; 2],
+) -> Option<(u32, u32)> {
+    let hit = calculate_screen_plane_hit(pointer, viewport, view_projection)?;
+    let (width, height) = image_size;
+    let column = (hit.x / pixel_spread[0] + width as f32 / 2.0 + if width % 2 == 0 { 0.0 } else { 0.5 }).floor();
+    let row = (hit.y / pixel_spread[1] + height as f32 / 2.0 + if height % 2 == 0 { 0.0 } else { 0.5 }).floor();
+    if column < 0.0 || row < 0.0 || column >= width as f32 || row >= height as f32 {
+        return None;
+    }
+    Some((column as u32, height - 1 - row as u32))
+}
+
+#[cfg(test)]
+mod test_calculate_light_gun_hit {
+    use super::{calculate_light_gun_hit, radians};
+
+    fn view_projection(distance: f32) -> glm::Mat4 {
+        let projection = glm::perspective::<f32>(1.0, radians(90.0), 0.01, 10000.0);
+        let view = glm::look_at(&glm::vec3(0.0, 0.0, distance), &glm::vec3(0.0, 0.0, 0.0), &glm::vec3(0.0, 1.0, 0.0));
+        projection * view
+    }
+
+    #[test]
+    fn test_calculate_light_gun_hit_gives_source_pixels_from_the_top() {
+        assert_eq!(
+            Some((0, 0)),
+            calculate_light_gun_hit((25, 25), (100.0, 100.0), &view_projection(1.0), (2, 2), [1.0, 1.0])
+        );
+        assert_eq!(
+            Some((1, 1)),
+            calculate_light_gun_hit((75, 75), (100.0, 100.0), &view_projection(1.0), (2, 2), [1.0, 1.0])
+        );
+    }
+
+    #[test]
+    fn test_calculate_light_gun_hit_outside_the_image_misses() {
+        assert_eq!(None, calculate_light_gun_hit((0, 0), (100.0, 100.0), &view_projection(2.0), (2, 2), [1.0, 1.0]));
+    }
+}
+
+pub fn calculate_degauss_wobble(seconds: f32, duration: f32, frequency: f32) -> f32 {
+    if seconds >= duration {
+        return 0.0;
+    }
+    (1.0 - seconds / duration) * (seconds * frequency * 2.0 * glm::pi::<f32>()).cos()
+}
+
+#[cfg(test)]
+mod test_calculate_degauss_wobble {
+    use super::calculate_degauss_wobble;
+    #[test]
+    fn test_calculate_degauss_wobble_fades_out() {
+        assert_eq!(1.0, calculate_degauss_wobble(0.0, 2.0, 6.0));
+        assert!((calculate_degauss_wobble(1.0 / 12.0, 2.0, 6.0) + 0.958).abs() < 0.001);
+        assert_eq!(0.0, calculate_degauss_wobble(2.0, 2.0, 6.0));
+    }
+}
+
+/// Returns the depth of the front of the screen surface at the given point, or None when the point is outside of the picture.
+/// It mirrors the curvature applied in the pixels vertex shader, so curved screens recede towards the edges. The axes weight
+/// how much each direction curves, [1, 0] bends the screen only horizontally like a cylinder.
+pub fn calculate_screen_surface_z(
+    point: (f32, f32),
+    image_size: (u32, u32),
+    pixel_spread: [f32; 2],
+    screen_curvature: f32,
+    curvature_axes: [f32; 2],
+    front_depth: f32,
+) -> Option<f32> {
+    let half_width = image_size.0 as f32 * 0.5;
+    let half_height = image_size.1 as f32 * 0.5;
+    let offset_x = point.0 / pixel_spread[0];
+    let offset_y = point.1 / pixel_spread[1];
+    if offset_x.abs() > half_width || offset_y.abs() > half_height {
+        return None;
+    }
+    let (curved_x, curved_y) = (offset_x * curvature_axes[0], offset_y * curvature_axes[1]);
+    let normalized = (curved_x * curved_x + curved_y * curved_y).sqrt() / (half_width * half_width + half_height * half_height).sqrt();
+    Some(front_depth - normalized.sin() * screen_curvature * 100.0)
+}
+
+#[cfg(test)]
+mod test_calculate_screen_surface_z {
+    use super::calculate_screen_surface_z;
+
+    #[test]
+    fn test_calculate_screen_surface_z_on_flat_screen_is_the_front_depth() {
+        assert_eq!(
+            Some(0.5),
+            calculate_screen_surface_z((10.0, -10.0), (100, 100), [1.0, 1.0], 0.0, [1.0, 1.0], 0.5)
+        );
+    }
+
+    #[test]
+    fn test_calculate_screen_surface_z_recedes_towards_the_edges_when_curved() {
+        let center = calculate_screen_surface_z((0.0, 0.0), (100, 100), [1.0, 1.0], 0.3, [1.0, 1.0], 0.0).unwrap();
+        let corner = calculate_screen_surface_z((50.0, 50.0), (100, 100), [1.0, 1.0], 0.3, [1.0, 1.0], 0.0).unwrap();
+        assert_eq!(0.0, center);
+        assert!((corner + 1.0f32.sin() * 30.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_calculate_screen_surface_z_only_recedes_horizontally_when_cylindrical() {
+        let top = calculate_screen_surface_z((0.0, 50.0), (100, 100), [1.0, 1.0], 0.3, [1.0, 0.0], 0.0).unwrap();
+        let side = calculate_screen_surface_z((50.0, 0.0), (100, 100), [1.0, 1.0], 0.3, [1.0, 0.0], 0.0).unwrap();
+        let corner = calculate_screen_surface_z((50.0, 50.0), (100, 100), [1.0, 1.0], 0.3, [1.0, 0.0], 0.0).unwrap();
+        assert_eq!(0.0, top);
+        assert!(side < 0.0);
+        assert_eq!(side, corner);
+    }
+
+    #[test]
+    fn test_calculate_screen_surface_z_outside_the_picture_is_none() {
+        assert_eq!(None, calculate_screen_surface_z((60.0, 0.0), (100, 100), [1.0, 1.0], 0.0, [1.0, 1.0], 0.0));
+        assert_eq!(None, calculate_screen_surface_z((60.0, 0.0), (50, 100), [2.0, 1.0], 0.0, [1.0, 1.0], 0.0));
+    }
+}
@@ -0,0 +1,297 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use std::fmt::{Display, Error, Formatter};
+
+/// Frames to wait after applying a candidate, so its capture doesn't contain the previous configuration.
+const MOIRE_SETTLE_FRAMES: u32 = 2;
+/// Multipliers over the current pixel width, sorted by how far they move away from it.
+const MOIRE_PIXEL_WIDTH_STEPS: [f32; 5] = [1.0, 0.95, 1.05, 0.9, 1.1];
+/// Scores under this threshold don't show visible beating patterns.
+pub const MOIRE_FREE_THRESHOLD: f32 = 0.01;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MoireCandidate {
+    pub internal_resolution: i32,
+    pub pixel_width: f32,
+}
+
+impl Display for MoireCandidate {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{}p, pixel width {:.2}", self.internal_resolution, self.pixel_width)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MoireMeasurement {
+    pub candidate: MoireCandidate,
+    pub score: f32,
+}
+
+/// Sent to the frontend once the sweep is over. `applied` tells if `recommended` is now active.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoireReport {
+    pub measurements: Vec<MoireMeasurement>,
+    pub recommended: MoireCandidate,
+    pub applied: bool,
+}
+
+pub(crate) enum MoireAdvance {
+    Wait,
+    Apply(MoireCandidate),
+    Finished(Vec<MoireMeasurement>),
+}
+
+/// Drives the sweep one frame at a time. The renderer reads back the frame when `capture_requested`
+/// is set, and the score of that capture comes back through `record_score`.
+#[derive(Default)]
+pub struct MoireAssistantState {
+    pub capture_requested: bool,
+    sweep: Vec<MoireCandidate>,
+    measurements: Vec<MoireMeasurement>,
+    settle_frames: u32,
+    awaiting_score: bool,
+    received_score: Option<f32>,
+}
+
+impl MoireAssistantState {
+    pub fn is_running(&self) -> bool {
+        !self.sweep.is_empty()
+    }
+
+    pub fn record_score(&mut self, score: f32) {
+        if self.awaiting_score {
+            self.received_score = Some(score);
+        }
+    }
+
+    pub(crate) fn start(&mut self, sweep: Vec<MoireCandidate>) -> Option<MoireCandidate> {
+        *self = MoireAssistantState {
+            settle_frames: MOIRE_SETTLE_FRAMES,
+            sweep,
+            ..Default::default()
+        };
+        self.sweep.first().copied()
+    }
+
+    pub(crate) fn advance(&mut self) -> MoireAdvance {
+        self.capture_requested = false;
+        if self.sweep.is_empty() {
+            return MoireAdvance::Wait;
+        }
+        if let Some(score) = self.received_score.take() {
+            self.awaiting_score = false;
+            let candidate = self.sweep[self.measurements.len()];
+            self.measurements.push(MoireMeasurement { candidate, score });
+            if self.measurements.len() == self.sweep.len() {
+                self.sweep.clear();
+                return MoireAdvance::Finished(std::mem::take(&mut self.measurements));
+            }
+            self.settle_frames = MOIRE_SETTLE_FRAMES;
+            return MoireAdvance::Apply(self.sweep[self.measurements.len()]);
+        }
+        if self.awaiting_score {
+            return MoireAdvance::Wait;
+        }
+        if self.settle_frames > 0 {
+            self.settle_frames -= 1;
+        } else {
+            self.capture_requested = true;
+            self.awaiting_score = true;
+        }
+        MoireAdvance::Wait
+    }
+}
+
+/// The current configuration goes first, then the same resolutions with other pixel widths, then the neighbour resolutions.
+pub fn build_moire_sweep(current: MoireCandidate, neighbour_resolutions: &[i32], vary_pixel_width: bool) -> Vec<MoireCandidate> {
+    let pixel_width_steps: &[f32] = if vary_pixel_width {
+        &MOIRE_PIXEL_WIDTH_STEPS
+    } else {
+        &MOIRE_PIXEL_WIDTH_STEPS[0..1]
+    };
+    let mut resolutions = vec![current.internal_resolution];
+    for resolution in neighbour_resolutions {
+        if !resolutions.contains(resolution) {
+            resolutions.push(*resolution);
+        }
+    }
+    let mut sweep = Vec::with_capacity(resolutions.len() * pixel_width_steps.len());
+    for internal_resolution in resolutions {
+        for step in pixel_width_steps {
+            sweep.push(MoireCandidate {
+                internal_resolution,
+                pixel_width: current.pixel_width * step,
+            });
+        }
+    }
+    sweep
+}
+
+/// The nearest candidate under the threshold in sweep order, or the lowest scoring one when none is moiré-free.
+pub fn recommend_moire_candidate(measurements: &[MoireMeasurement]) -> Option<MoireCandidate> {
+    measurements
+        .iter()
+        .find(|measurement| measurement.score < MOIRE_FREE_THRESHOLD)
+        .or_else(|| {
+            measurements
+                .iter()
+                .min_by(|a, b| a.score.partial_cmp(&b.score).unwrap_or(std::cmp::Ordering::Equal))
+        })
+        .map(|measurement| measurement.candidate)
+}
+
+/// Variance heuristic over the central half of an RGBA capture. The luminance is averaged into a row and a column
+/// profile, and each profile is band-passed between a fine window, that hides the pixel and scanline structure,
+/// and a coarse one, that keeps the lighting and curvature gradients. What survives are the beating patterns.
+pub fn measure_moire(pixels: &[u8], width: usize, height: usize) -> f32 {
+    let (x0, x1) = (width / 4, width - width / 4);
+    let (y0, y1) = (height / 4, height - height / 4);
+    if x1 <= x0 || y1 <= y0 || pixels.len() < width * height * 4 {
+        return 0.0;
+    }
+    let mut columns = vec![0.0; x1 - x0];
+    let mut rows = vec![0.0; y1 - y0];
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let i = (y * width + x) * 4;
+            let luminance = (0.2126 * pixels[i] as f32 + 0.7152 * pixels[i + 1] as f32 + 0.0722 * pixels[i + 2] as f32) / 255.0;
+            columns[x - x0] += luminance;
+            rows[y - y0] += luminance;
+        }
+    }
+    band_energy(&columns).max(band_energy(&rows))
+}
+
+fn band_energy(profile: &[f32]) -> f32 {
+    let mean = profile.iter().sum::<f32>() / profile.len() as f32;
+    if mean <= f32::EPSILON {
+        return 0.0;
+    }
+    let fine = box_blur(profile, (profile.len() / 64).max(2));
+    let coarse = box_blur(profile, (profile.len() / 8).max(4));
+    let energy = fine.iter().zip(coarse.iter()).map(|(a, b)| (a - b) * (a - b)).sum::<f32>() / profile.len() as f32;
+    energy.sqrt() / mean
+}
+
+fn box_blur(profile: &[f32], window: usize) -> Vec<f32> {
+    let mut sums = Vec::with_capacity(profile.len() + 1);
+    sums.push(0.0);
+    for value in profile {
+        sums.push(sums[sums.len() - 1] + value);
+    }
+    (0..profile.len())
+        .map(|i| {
+            let start = i.saturating_sub(window / 2).min(profile.len().saturating_sub(window));
+            let end = (start + window).min(profile.len());
+            (sums[end] - sums[start]) / (end - start) as f32
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    fn stripes(width: usize, height: usize, intensity: impl Fn(usize) -> f32) -> Vec<u8> {
+        let mut pixels = vec![0; width * height * 4];
+        for (i, pixel) in pixels.chunks_mut(4).enumerate() {
+            let x = i % width;
+            let value = if x % 2 == 1 { 0 } else { (intensity(x) * 255.0) as u8 };
+            pixel.copy_from_slice(&[value, value, value, 255]);
+        }
+        pixels
+    }
+
+    #[test]
+    fn test_measure_moire__regular_stripes__no_moire() {
+        let pixels = stripes(512, 64, |_| 1.0);
+        assert!(measure_moire(&pixels, 512, 64) < MOIRE_FREE_THRESHOLD);
+    }
+
+    #[test]
+    fn test_measure_moire__beating_stripes__moire_detected() {
+        let pixels = stripes(512, 64, |x| 0.75 + 0.25 * (x as f32 * std::f32::consts::PI * 2.0 / 24.0).sin());
+        assert!(measure_moire(&pixels, 512, 64) > MOIRE_FREE_THRESHOLD);
+    }
+
+    #[test]
+    fn test_build_moire_sweep__fixed_pixel_width__current_resolution_goes_first() {
+        let current = MoireCandidate {
+            internal_resolution: 720,
+            pixel_width: 1.0,
+        };
+        let sweep = build_moire_sweep(current, &[720, 1080], false);
+        assert_eq!(sweep.iter().map(|c| c.internal_resolution).collect::<Vec<_>>(), vec![720, 1080]);
+        assert_eq!(build_moire_sweep(current, &[486, 1080], true).len(), 15);
+    }
+
+    #[test]
+    fn test_recommend_moire_candidate__prefers_nearest_moire_free() {
+        let candidate = |internal_resolution| MoireCandidate {
+            internal_resolution,
+            pixel_width: 1.0,
+        };
+        let measurements = [
+            MoireMeasurement {
+                candidate: candidate(720),
+                score: 0.2,
+            },
+            MoireMeasurement {
+                candidate: candidate(1080),
+                score: 0.005,
+            },
+            MoireMeasurement {
+                candidate: candidate(486),
+                score: 0.001,
+            },
+        ];
+        assert_eq!(recommend_moire_candidate(&measurements), Some(candidate(1080)));
+        assert_eq!(recommend_moire_candidate(&measurements[0..1]), Some(candidate(720)));
+    }
+
+    #[test]
+    fn test_moire_assistant_state__sweep__captures_each_candidate() {
+        let sweep = build_moire_sweep(
+            MoireCandidate {
+                internal_resolution: 720,
+                pixel_width: 1.0,
+            },
+            &[1080],
+            false,
+        );
+        let mut state = MoireAssistantState::default();
+        assert_eq!(state.start(sweep.clone()), Some(sweep[0]));
+        let mut captures = 0;
+        for _ in 0..20 {
+            match state.advance() {
+                MoireAdvance::Finished(measurements) => {
+                    assert_eq!(measurements.len(), 2);
+                    assert_eq!(captures, 2);
+                    assert!(!state.is_running());
+                    return;
+                }
+                MoireAdvance::Apply(candidate) => assert_eq!(candidate, sweep[1]),
+                MoireAdvance::Wait => {}
+            }
+            if state.capture_requested {
+                captures += 1;
+                state.record_score(0.5);
+            }
+        }
+        panic!("The sweep didn't finish.");
+    }
+}
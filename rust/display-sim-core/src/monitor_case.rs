@@ -0,0 +1,156 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+// Proportions of the shell, relative to the height of the picture.
+const CASE_BORDER: f32 = 0.08;
+const CASE_BEZEL_DEPTH: f32 = 0.03;
+// Relative to the width of the picture, tubes are about as deep as they are wide.
+const CASE_DEPTH: f32 = 0.9;
+
+/// Floats per vertex in the case mesh: position and normal.
+pub const CASE_VERTEX_SIZE: usize = 6;
+
+/// What the case mesh is built from. The same settings always give the same mesh.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CaseSettings {
+    pub width: u32,
+    pub height: u32,
+    pub pixel_spread: [f32; 2],
+    pub quarter_turned: bool,
+}
+
+/// Builds the monitor shell around a picture of the given half size, centered in the origin and facing +z.
+/// The stand goes down to the floor when the floor is below the case. The mesh is a list of triangles
+/// wound counter-clockwise from the outside, and the opening of the bezel is left empty for the pixels.
+pub fn monitor_case_mesh(half_size: [f32; 2], floor_y: f32) -> Vec<f32> {
+    let [hw, hh] = half_size;
+    let border = CASE_BORDER * 2.0 * hh;
+    let (w, h) = (hw + border, hh + border);
+    let front = CASE_BEZEL_DEPTH * 2.0 * hh;
+    let back = -CASE_DEPTH * 2.0 * hw;
+    let mut mesh = Vec::new();
+
+    let x = glm::vec3(1.0, 0.0, 0.0);
+    let y = glm::vec3(0.0, 1.0, 0.0);
+    let z = glm::vec3(0.0, 0.0, 1.0);
+
+    // Bezel, as a frame around the opening.
+    push_quad(&mut mesh, glm::vec3(-w, hh, front), x * 2.0 * w, y * border);
+    push_quad(&mut mesh, glm::vec3(-w, -h, front), x * 2.0 * w, y * border);
+    push_quad(&mut mesh, glm::vec3(-w, -hh, front), x * border, y * 2.0 * hh);
+    push_quad(&mut mesh, glm::vec3(hw, -hh, front), x * border, y * 2.0 * hh);
+
+    // Inner walls of the opening, from the bezel to the glass. They face the picture.
+    push_quad(&mut mesh, glm::vec3(-hw, hh, 0.0), x * 2.0 * hw, z * front);
+    push_quad(&mut mesh, glm::vec3(-hw, -hh, 0.0), z * front, x * 2.0 * hw);
+    push_quad(&mut mesh, glm::vec3(-hw, -hh, 0.0), y * 2.0 * hh, z * front);
+    push_quad(&mut mesh, glm::vec3(hw, -hh, 0.0), z * front, y * 2.0 * hh);
+
+    push_box_sides(&mut mesh, glm::vec3(-w, -h, back), glm::vec3(w, h, front));
+
+    if floor_y < -h {
+        push_box_sides(&mut mesh, glm::vec3(-0.5 * w, floor_y, 0.8 * back), glm::vec3(0.5 * w, -h, 0.2 * back));
+    }
+    mesh
+}
+
+// Every face but the front, the bezel already covers it.
+fn push_box_sides(mesh: &mut Vec<f32>, min: glm::Vec3, max: glm::Vec3) {
+    let size = max - min;
+    let x = glm::vec3(size.x, 0.0, 0.0);
+    let y = glm::vec3(0.0, size.y, 0.0);
+    let z = glm::vec3(0.0, 0.0, size.z);
+    push_quad(mesh, min, y, x);
+    push_quad(mesh, min, z, y);
+    push_quad(mesh, min + x, y, z);
+    push_quad(mesh, min, x, z);
+    push_quad(mesh, min + y, z, x);
+}
+
+// The normal is `u` x `v`, so the quad faces where that cross product points.
+fn push_quad(mesh: &mut Vec<f32>, origin: glm::Vec3, u: glm::Vec3, v: glm::Vec3) {
+    let normal = glm::cross(&u, &v).normalize();
+    for corner in [origin, origin + u, origin + u + v, origin, origin + u + v, origin + v].iter() {
+        mesh.extend_from_slice(&[corner.x, corner.y, corner.z, normal.x, normal.y, normal.z]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    fn faces(mesh: &[f32]) -> Vec<(glm::Vec3, glm::Vec3)> {
+        mesh.chunks(CASE_VERTEX_SIZE * 3)
+            .map(|triangle| {
+                let vertex = |i: usize| {
+                    glm::vec3(
+                        triangle[i * CASE_VERTEX_SIZE],
+                        triangle[i * CASE_VERTEX_SIZE + 1],
+                        triangle[i * CASE_VERTEX_SIZE + 2],
+                    )
+                };
+                let center = (vertex(0) + vertex(1) + vertex(2)) / 3.0;
+                let normal = glm::vec3(triangle[3], triangle[4], triangle[5]);
+                (center, normal)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_monitor_case_mesh__winds_every_triangle_as_its_normal() {
+        let mesh = monitor_case_mesh([160.0, 120.0], -200.0);
+        for triangle in mesh.chunks(CASE_VERTEX_SIZE * 3) {
+            let vertex = |i: usize| {
+                glm::vec3(
+                    triangle[i * CASE_VERTEX_SIZE],
+                    triangle[i * CASE_VERTEX_SIZE + 1],
+                    triangle[i * CASE_VERTEX_SIZE + 2],
+                )
+            };
+            let winding = glm::cross(&(vertex(1) - vertex(0)), &(vertex(2) - vertex(0))).normalize();
+            assert!((winding - glm::vec3(triangle[3], triangle[4], triangle[5])).norm() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_monitor_case_mesh__faces_point_away_from_the_center() {
+        for (center, normal) in faces(&monitor_case_mesh([160.0, 120.0], -100.0)).iter().filter(|(_, normal)| normal.z == 0.0) {
+            let outwards = glm::dot(&glm::vec3(center.x, center.y, 0.0), normal);
+            if center.x.abs() <= 160.0 && center.y.abs() <= 120.0 {
+                assert!(outwards < 0.0, "the inner walls face the picture");
+            } else {
+                assert!(outwards > 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_monitor_case_mesh__leaves_the_picture_uncovered() {
+        let mesh = monitor_case_mesh([160.0, 120.0], -200.0);
+        assert!(faces(&mesh)
+            .iter()
+            .filter(|(_, normal)| normal.z > 0.5)
+            .all(|(center, _)| center.x.abs() > 160.0 || center.y.abs() > 120.0));
+    }
+
+    #[test]
+    fn test_monitor_case_mesh__stand_only_when_the_floor_is_below() {
+        let with_stand = monitor_case_mesh([160.0, 120.0], -200.0);
+        let without_stand = monitor_case_mesh([160.0, 120.0], -100.0);
+        assert_eq!(with_stand.len(), without_stand.len() + 5 * 6 * CASE_VERTEX_SIZE);
+        assert!(faces(&with_stand).iter().any(|(center, _)| center.y < -150.0));
+    }
+}
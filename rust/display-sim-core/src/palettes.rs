@@ -0,0 +1,126 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use std::str::FromStr;
+
+/// Colors of the NES PPU (2C02) as most emulators show them, indexed like the hardware does.
+pub const NES_PALETTE: [u32; 64] = [
+    0x7C7C7C, 0x0000FC, 0x0000BC, 0x4428BC, 0x940084, 0xA80020, 0xA81000, 0x881400, 0x503000, 0x007800, 0x006800, 0x005800, 0x004058, 0x000000, 0x000000,
+    0x000000, 0xBCBCBC, 0x0078F8, 0x0058F8, 0x6844FC, 0xD800CC, 0xE40058, 0xF83800, 0xE45C10, 0xAC7C00, 0x00B800, 0x00A800, 0x00A844, 0x008888, 0x000000,
+    0x000000, 0x000000, 0xF8F8F8, 0x3CBCFC, 0x6888FC, 0x9878F8, 0xF878F8, 0xF85898, 0xF87858, 0xFCA044, 0xF8B800, 0xB8F818, 0x58D854, 0x58F898, 0x00E8D8,
+    0x787878, 0x000000, 0x000000, 0xFCFCFC, 0xA4E4FC, 0xB8B8F8, 0xD8B8F8, 0xF8B8F8, 0xF8A4C0, 0xF0D0B0, 0xFCE0A8, 0xF8D878, 0xD8F878, 0xB8F8B8, 0xB8F8D8,
+    0x00FCFC, 0xF8D8F8, 0x000000, 0x000000,
+];
+
+/// The 16 colors of the Commodore 64 VIC-II, in register order.
+pub const C64_PALETTE: [u32; 16] = [
+    0x000000, 0xFFFFFF, 0x880000, 0xAAFFEE, 0xCC44CC, 0x00CC55, 0x0000AA, 0xEEEE77, 0xDD8855, 0x664400, 0xFF7777, 0x333333, 0x777777, 0xAAFF66, 0x0088FF,
+    0xBBBBBB,
+];
+
+/// The default 16 colors of an EGA card, with the brown that the monitor produces for color 6.
+pub const EGA_PALETTE: [u32; 16] = [
+    0x000000, 0x0000AA, 0x00AA00, 0x00AAAA, 0xAA0000, 0xAA00AA, 0xAA5500, 0xAAAAAA, 0x555555, 0x5555FF, 0x55FF55, 0x55FFFF, 0xFF5555, 0xFF55FF, 0xFFFF55,
+    0xFFFFFF,
+];
+
+pub const MAX_PALETTE_COLORS: usize = 256;
+
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct PaletteColors(pub Vec<[u8; 3]>);
+
+impl PaletteColors {
+    pub fn from_hex(colors: &[u32]) -> Self {
+        PaletteColors(colors.iter().map(|color| [(color >> 16) as u8, (color >> 8) as u8, *color as u8]).collect())
+    }
+}
+
+impl std::fmt::Display for PaletteColors {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let encoded: Vec<String> = self.0.iter().map(|[r, g, b]| format!("#{:02x}{:02x}{:02x}", r, g, b)).collect();
+        write!(f, "{}", encoded.join(","))
+    }
+}
+
+// The encoding is a list of "#rrggbb" colors separated by commas, the '#' being optional.
+impl FromStr for PaletteColors {
+    type Err = String;
+    fn from_str(encoded: &str) -> Result<Self, Self::Err> {
+        let mut colors = Vec::new();
+        for entry in encoded.split(',') {
+            let hex = entry.trim().trim_start_matches('#');
+            if hex.len() != 6 {
+                return Err(format!("Bad palette color '{}'", entry));
+            }
+            let color = u32::from_str_radix(hex, 16).map_err(|e| format!("Bad palette color '{}': {}", entry, e))?;
+            colors.push([(color >> 16) as u8, (color >> 8) as u8, color as u8]);
+        }
+        if colors.len() > MAX_PALETTE_COLORS {
+            return Err(format!("A palette can't have more than {} colors", MAX_PALETTE_COLORS));
+        }
+        Ok(PaletteColors(colors))
+    }
+}
+
+/// Replaces every pixel of the RGBA `frame` with the closest color of the palette, leaving the alpha as it is.
+/// The distance weights green over red and red over blue, roughly like the eye does. An empty palette does nothing.
+pub fn quantize_to_palette(frame: &mut [u8], palette: &[[u8; 3]]) {
+    if palette.is_empty() {
+        return;
+    }
+    let mut last_source = None;
+    let mut last_match = palette[0];
+    for pixel in frame.chunks_exact_mut(4) {
+        let source = [pixel[0], pixel[1], pixel[2]];
+        // Retro sources repeat the same color along whole runs of pixels, so the previous match is reused.
+        if last_source != Some(source) {
+            last_match = *palette
+                .iter()
+                .min_by_key(|color| {
+                    let dr = color[0] as i32 - source[0] as i32;
+                    let dg = color[1] as i32 - source[1] as i32;
+                    let db = color[2] as i32 - source[2] as i32;
+                    2 * dr * dr + 4 * dg * dg + 3 * db * db
+                })
+                .expect("PaletteColors is not empty");
+            last_source = Some(source);
+        }
+        pixel[..3].copy_from_slice(&last_match);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    #[test]
+    fn test_quantize_to_palette__ega__snaps_to_the_closest_color() {
+        let palette = PaletteColors::from_hex(&EGA_PALETTE);
+        let mut frame = vec![0xAA, 0x55, 0x00, 0xFF, 0xF0, 0xF0, 0x60, 0x80, 0x10, 0x10, 0x10, 0xFF];
+        quantize_to_palette(&mut frame, &palette.0);
+        assert_eq!(frame, vec![0xAA, 0x55, 0x00, 0xFF, 0xFF, 0xFF, 0x55, 0x80, 0x00, 0x00, 0x00, 0xFF]);
+    }
+
+    #[test]
+    fn test_palette__roundtrips_through_its_encoding() -> Result<(), String> {
+        let palette = PaletteColors::from_str("#102030, a0b0c0")?;
+        assert_eq!(palette.0, vec![[0x10, 0x20, 0x30], [0xA0, 0xB0, 0xC0]]);
+        assert_eq!(palette.to_string(), "#102030,#a0b0c0");
+        assert!(PaletteColors::from_str("#12345").is_err());
+        assert!(PaletteColors::from_str("#zzzzzz").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,314 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::general_types::Size2D;
+use crate::ui_controller::procedural_source::ProceduralSourceOptions;
+
+pub const PROCEDURAL_SOURCE_SIZE: Size2D<u32> = Size2D { width: 320, height: 240 };
+
+const LOGO_SIZE: Size2D<u32> = Size2D { width: 64, height: 32 };
+const LOGO_SPEED: f32 = 60.0;
+const LOGO_COLORS: [[u8; 3]; 6] = [[255, 255, 255], [255, 64, 64], [64, 255, 64], [64, 64, 255], [255, 255, 64], [255, 64, 255]];
+const STARS_COUNT: usize = 200;
+const GRAYSCALE_STEPS: u32 = 11;
+const GRID_SPACING: u32 = 16;
+const MULTIBURST_PERIODS: [u32; 6] = [8, 6, 4, 3, 2, 1];
+const WEDGE_SECTORS: f32 = 48.0;
+const STARS_SPEED: f32 = 0.5;
+
+// 75% bars: white, yellow, cyan, green, magenta, red, blue
+const SMPTE_BARS: [[u8; 3]; 7] = [
+    [191, 191, 191],
+    [191, 191, 0],
+    [0, 191, 191],
+    [0, 191, 0],
+    [191, 0, 191],
+    [191, 0, 0],
+    [0, 0, 191],
+];
+const SMPTE_REVERSE_BARS: [[u8; 3]; 7] = [
+    [0, 0, 191],
+    [19, 19, 19],
+    [191, 0, 191],
+    [19, 19, 19],
+    [0, 191, 191],
+    [19, 19, 19],
+    [191, 191, 191],
+];
+const SMPTE_PLUGE: [[u8; 3]; 6] = [[0, 33, 76], [255, 255, 255], [50, 0, 106], [19, 19, 19], [9, 9, 9], [29, 29, 29]];
+const COLOR_BARS: [[u8; 3]; 8] = [
+    [255, 255, 255],
+    [255, 255, 0],
+    [0, 255, 255],
+    [0, 255, 0],
+    [255, 0, 255],
+    [255, 0, 0],
+    [0, 0, 255],
+    [0, 0, 0],
+];
+
+/// Frames generated in the core, so they can replace the loaded video without any image.
+/// `frame` holds RGBA bytes with the same layout as the video buffers, rows from the top.
+#[derive(Default)]
+pub struct ProceduralSourceState {
+    pub frame: Vec<u8>,
+    pub original_image_size: Option<Size2D<u32>>,
+    pub current: Option<ProceduralSourceOptions>,
+    logo_position: [f32; 2],
+    logo_velocity: [f32; 2],
+    logo_color: usize,
+    stars: Vec<[f32; 3]>,
+    seed: u32,
+}
+
+impl ProceduralSourceState {
+    pub fn active_frame(&self) -> Option<&[u8]> {
+        if self.current.is_some() && !self.frame.is_empty() {
+            Some(&self.frame)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true when the frame has changed and needs to be uploaded again.
+    pub fn generate(&mut self, kind: ProceduralSourceOptions, dt: f32) -> bool {
+        let size = PROCEDURAL_SOURCE_SIZE;
+        let changed_kind = self.current != Some(kind);
+        if changed_kind {
+            self.current = Some(kind);
+            self.frame = vec![0; (size.width * size.height * 4) as usize];
+            self.logo_position = [0.0, 0.0];
+            self.logo_velocity = [LOGO_SPEED, LOGO_SPEED];
+            self.stars.clear();
+            self.seed = 0x2545_F491;
+        }
+        match kind {
+            ProceduralSourceOptions::Off => return false,
+            ProceduralSourceOptions::ColorBars if changed_kind => draw_color_bars(&mut self.frame, size),
+            ProceduralSourceOptions::SmpteBars if changed_kind => draw_smpte_bars(&mut self.frame, size),
+            ProceduralSourceOptions::GrayscaleRamp if changed_kind => draw_grayscale_ramp(&mut self.frame, size),
+            ProceduralSourceOptions::ConvergenceGrid if changed_kind => draw_convergence_grid(&mut self.frame, size),
+            ProceduralSourceOptions::OverscanMarkers if changed_kind => draw_overscan_markers(&mut self.frame, size),
+            ProceduralSourceOptions::SharpnessWedges if changed_kind => draw_sharpness_wedges(&mut self.frame, size),
+            ProceduralSourceOptions::ColorBars
+            | ProceduralSourceOptions::SmpteBars
+            | ProceduralSourceOptions::GrayscaleRamp
+            | ProceduralSourceOptions::ConvergenceGrid
+            | ProceduralSourceOptions::OverscanMarkers
+            | ProceduralSourceOptions::SharpnessWedges => return false,
+            ProceduralSourceOptions::BouncingLogo => self.draw_bouncing_logo(size, dt),
+            ProceduralSourceOptions::Starfield => self.draw_starfield(size, dt),
+        }
+        true
+    }
+
+    fn draw_bouncing_logo(&mut self, size: Size2D<u32>, dt: f32) {
+        let max = [(size.width - LOGO_SIZE.width) as f32, (size.height - LOGO_SIZE.height) as f32];
+        for (axis, max) in max.iter().enumerate() {
+            self.logo_position[axis] += self.logo_velocity[axis] * dt;
+            if self.logo_position[axis] < 0.0 || self.logo_position[axis] > *max {
+                self.logo_position[axis] = self.logo_position[axis].max(0.0).min(*max);
+                self.logo_velocity[axis] = -self.logo_velocity[axis];
+                self.logo_color = (self.logo_color + 1) % LOGO_COLORS.len();
+            }
+        }
+        fill(&mut self.frame, size, 0, 0, size.width, size.height, [0, 0, 0]);
+        let x = self.logo_position[0] as u32;
+        let y = self.logo_position[1] as u32;
+        let color = LOGO_COLORS[self.logo_color];
+        fill(&mut self.frame, size, x, y, LOGO_SIZE.width, LOGO_SIZE.height, color);
+        fill(&mut self.frame, size, x + 8, y + 12, LOGO_SIZE.width - 16, 8, [0, 0, 0]);
+    }
+
+    fn draw_starfield(&mut self, size: Size2D<u32>, dt: f32) {
+        while self.stars.len() < STARS_COUNT {
+            let star = [self.next_random() * 2.0 - 1.0, self.next_random() * 2.0 - 1.0, self.next_random()];
+            self.stars.push(star);
+        }
+        fill(&mut self.frame, size, 0, 0, size.width, size.height, [0, 0, 0]);
+        for i in 0..self.stars.len() {
+            self.stars[i][2] -= STARS_SPEED * dt;
+            if self.stars[i][2] <= 0.01 {
+                self.stars[i] = [self.next_random() * 2.0 - 1.0, self.next_random() * 2.0 - 1.0, 1.0];
+            }
+            let [sx, sy, sz] = self.stars[i];
+            let x = (sx / sz * 0.5 + 0.5) * size.width as f32;
+            let y = (sy / sz * 0.5 + 0.5) * size.height as f32;
+            if x < 0.0 || y < 0.0 || x >= size.width as f32 || y >= size.height as f32 {
+                continue;
+            }
+            let brightness = ((1.0 - sz) * 255.0) as u8;
+            fill(&mut self.frame, size, x as u32, y as u32, 1, 1, [brightness, brightness, brightness]);
+        }
+    }
+
+    fn next_random(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        (self.seed % 10_000) as f32 / 10_000.0
+    }
+}
+
+fn draw_color_bars(frame: &mut [u8], size: Size2D<u32>) {
+    draw_bars(frame, size, 0, size.height, &COLOR_BARS);
+}
+
+fn draw_smpte_bars(frame: &mut [u8], size: Size2D<u32>) {
+    let top = size.height * 2 / 3;
+    let middle = size.height / 12;
+    draw_bars(frame, size, 0, top, &SMPTE_BARS);
+    draw_bars(frame, size, top, middle, &SMPTE_REVERSE_BARS);
+    draw_bars(frame, size, top + middle, size.height - top - middle, &SMPTE_PLUGE);
+}
+
+fn draw_grayscale_ramp(frame: &mut [u8], size: Size2D<u32>) {
+    let half = size.height / 2;
+    for step in 0..GRAYSCALE_STEPS {
+        let x0 = size.width * step / GRAYSCALE_STEPS;
+        let x1 = size.width * (step + 1) / GRAYSCALE_STEPS;
+        let level = (255 * step / (GRAYSCALE_STEPS - 1)) as u8;
+        fill(frame, size, x0, 0, x1 - x0, half, [level, level, level]);
+    }
+    for x in 0..size.width {
+        let level = (255 * x / (size.width - 1)) as u8;
+        fill(frame, size, x, half, 1, size.height - half, [level, level, level]);
+    }
+}
+
+fn draw_convergence_grid(frame: &mut [u8], size: Size2D<u32>) {
+    fill(frame, size, 0, 0, size.width, size.height, [0, 0, 0]);
+    for x in (0..size.width).step_by(GRID_SPACING as usize) {
+        fill(frame, size, x, 0, 1, size.height, [255, 255, 255]);
+    }
+    for y in (0..size.height).step_by(GRID_SPACING as usize) {
+        fill(frame, size, 0, y, size.width, 1, [255, 255, 255]);
+    }
+    fill(frame, size, size.width - 1, 0, 1, size.height, [255, 255, 255]);
+    fill(frame, size, 0, size.height - 1, size.width, 1, [255, 255, 255]);
+    for x in (GRID_SPACING / 2..size.width).step_by(GRID_SPACING as usize) {
+        for y in (GRID_SPACING / 2..size.height).step_by(GRID_SPACING as usize) {
+            fill(frame, size, x, y, 1, 1, [255, 255, 255]);
+        }
+    }
+}
+
+fn draw_overscan_markers(frame: &mut [u8], size: Size2D<u32>) {
+    fill(frame, size, 0, 0, size.width, size.height, [64, 64, 64]);
+    // Full frame, action safe (5%) and title safe (10%) areas.
+    for (percent, color) in [(0, [255, 255, 255]), (5, [255, 255, 0]), (10, [0, 255, 255])].iter() {
+        let dx = size.width * percent / 100;
+        let dy = size.height * percent / 100;
+        outline(frame, size, dx, dy, size.width - 2 * dx, size.height - 2 * dy, *color);
+    }
+    fill(frame, size, size.width / 2, size.height / 2 - 8, 1, 16, [255, 255, 255]);
+    fill(frame, size, size.width / 2 - 8, size.height / 2, 16, 1, [255, 255, 255]);
+}
+
+fn draw_sharpness_wedges(frame: &mut [u8], size: Size2D<u32>) {
+    fill(frame, size, 0, 0, size.width, size.height, [0, 0, 0]);
+    let half = size.height / 2;
+    let count = MULTIBURST_PERIODS.len() as u32;
+    for (i, period) in MULTIBURST_PERIODS.iter().enumerate() {
+        let x0 = size.width * i as u32 / count;
+        let x1 = size.width * (i as u32 + 1) / count;
+        for x in (x0..x1).filter(|x| (x - x0) / period % 2 == 1) {
+            fill(frame, size, x, 0, 1, half, [255, 255, 255]);
+        }
+    }
+    let center = [size.width as f32 / 2.0, size.height as f32];
+    for y in half..size.height {
+        for x in 0..size.width {
+            let angle = (center[1] - y as f32).atan2(x as f32 - center[0]);
+            if (angle / std::f32::consts::PI * WEDGE_SECTORS) as u32 % 2 == 1 {
+                fill(frame, size, x, y, 1, 1, [255, 255, 255]);
+            }
+        }
+    }
+}
+
+fn draw_bars(frame: &mut [u8], size: Size2D<u32>, y: u32, height: u32, colors: &[[u8; 3]]) {
+    let count = colors.len() as u32;
+    for (i, color) in colors.iter().enumerate() {
+        let x0 = size.width * i as u32 / count;
+        let x1 = size.width * (i as u32 + 1) / count;
+        fill(frame, size, x0, y, x1 - x0, height, *color);
+    }
+}
+
+fn fill(frame: &mut [u8], size: Size2D<u32>, x: u32, y: u32, width: u32, height: u32, color: [u8; 3]) {
+    for row in y..(y + height).min(size.height) {
+        for column in x..(x + width).min(size.width) {
+            let index = ((row * size.width + column) * 4) as usize;
+            frame[index..index + 4].copy_from_slice(&[color[0], color[1], color[2], 255]);
+        }
+    }
+}
+
+fn outline(frame: &mut [u8], size: Size2D<u32>, x: u32, y: u32, width: u32, height: u32, color: [u8; 3]) {
+    fill(frame, size, x, y, width, 1, color);
+    fill(frame, size, x, y + height - 1, width, 1, color);
+    fill(frame, size, x, y, 1, height, color);
+    fill(frame, size, x + width - 1, y, 1, height, color);
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    fn pixel(state: &ProceduralSourceState, x: u32, y: u32) -> [u8; 4] {
+        let index = ((y * PROCEDURAL_SOURCE_SIZE.width + x) * 4) as usize;
+        [state.frame[index], state.frame[index + 1], state.frame[index + 2], state.frame[index + 3]]
+    }
+
+    #[test]
+    fn test_generate__color_bars__go_from_white_to_black_and_only_draw_once() {
+        let mut state = ProceduralSourceState::default();
+        assert!(state.generate(ProceduralSourceOptions::ColorBars, 0.016));
+        assert_eq!(pixel(&state, 0, 0), [255, 255, 255, 255]);
+        assert_eq!(pixel(&state, PROCEDURAL_SOURCE_SIZE.width - 1, 0), [0, 0, 0, 255]);
+        assert!(!state.generate(ProceduralSourceOptions::ColorBars, 0.016));
+    }
+
+    #[test]
+    fn test_generate__bouncing_logo__bounces_on_the_edges() {
+        let mut state = ProceduralSourceState::default();
+        state.generate(ProceduralSourceOptions::BouncingLogo, 0.0);
+        state.generate(ProceduralSourceOptions::BouncingLogo, 10.0);
+        assert_eq!(state.logo_velocity, [-LOGO_SPEED, -LOGO_SPEED]);
+        assert_eq!(state.logo_color, 2);
+    }
+
+    #[test]
+    fn test_generate__grayscale_ramp__goes_from_black_to_white() {
+        let mut state = ProceduralSourceState::default();
+        state.generate(ProceduralSourceOptions::GrayscaleRamp, 0.016);
+        assert_eq!(pixel(&state, 0, 0), [0, 0, 0, 255]);
+        assert_eq!(
+            pixel(&state, PROCEDURAL_SOURCE_SIZE.width - 1, PROCEDURAL_SOURCE_SIZE.height - 1),
+            [255, 255, 255, 255]
+        );
+    }
+
+    #[test]
+    fn test_generate__overscan_markers__outline_the_title_safe_area() {
+        let mut state = ProceduralSourceState::default();
+        state.generate(ProceduralSourceOptions::OverscanMarkers, 0.016);
+        assert_eq!(pixel(&state, 0, 0), [255, 255, 255, 255]);
+        assert_eq!(pixel(&state, 32, 24), [0, 255, 255, 255]);
+        assert_eq!(pixel(&state, 33, 25), [64, 64, 64, 255]);
+    }
+}
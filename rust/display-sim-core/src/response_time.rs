@@ -0,0 +1,73 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+/// Blend factor of a first order lag with time constant `milliseconds` over a frame of `dt` seconds.
+/// A non positive time constant means an instant response.
+pub fn response_blend_factor(milliseconds: f32, dt: f32) -> f32 {
+    if milliseconds <= 0.0 {
+        1.0
+    } else {
+        1.0 - (-dt * 1000.0 / milliseconds).exp()
+    }
+}
+
+/// Moves every color channel of `history` towards the RGBA `frame` and writes the result back into the frame.
+/// Channels getting brighter use the rise factor and the ones getting darker use the fall factor, so phosphors
+/// or LCD panels with asymmetric response times can be reproduced. An empty or stale history starts from the frame.
+pub fn smear_frame(history: &mut Vec<f32>, frame: &mut [u8], blend_factors: [f32; 2]) {
+    if history.len() != frame.len() {
+        history.clear();
+        history.extend(frame.iter().map(|value| *value as f32));
+        return;
+    }
+    for (i, (value, previous)) in frame.iter_mut().zip(history.iter_mut()).enumerate() {
+        if i % 4 == 3 {
+            continue;
+        }
+        let current = *value as f32;
+        let factor = if current > *previous { blend_factors[0] } else { blend_factors[1] };
+        *previous += (current - *previous) * factor;
+        *value = previous.round().clamp(0.0, 255.0) as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    #[test]
+    fn test_response_blend_factor__zero_time__is_instant() {
+        assert_eq!(response_blend_factor(0.0, 0.016), 1.0);
+        let factor = response_blend_factor(16.0, 0.016);
+        assert!((factor - (1.0 - (-1.0f32).exp())).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_smear_frame__slow_fall__leaves_a_trail() {
+        let mut history = vec![];
+        let mut frame = vec![255, 255, 255, 255];
+        smear_frame(&mut history, &mut frame, [1.0, 0.5]);
+        assert_eq!(frame, vec![255, 255, 255, 255]);
+
+        let mut frame = vec![0, 0, 0, 255];
+        smear_frame(&mut history, &mut frame, [1.0, 0.5]);
+        assert_eq!(frame, vec![128, 128, 128, 255]);
+
+        let mut frame = vec![255, 0, 0, 255];
+        smear_frame(&mut history, &mut frame, [1.0, 0.5]);
+        assert_eq!(frame, vec![255, 64, 64, 255]);
+    }
+}
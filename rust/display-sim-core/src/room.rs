@@ -0,0 +1,117 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::ui_controller::room_environment::RoomEnvironmentOptions;
+
+/// Floats per vertex in the room mesh: position and color.
+pub const ROOM_VERTEX_SIZE: usize = 6;
+
+/// What the room mesh is built from. The same settings always give the same mesh.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RoomSettings {
+    pub environment: RoomEnvironmentOptions,
+    pub width: u32,
+    pub height: u32,
+    pub pixel_spread: [f32; 2],
+    pub quarter_turned: bool,
+}
+
+// Relative to the half width of the picture.
+const ROOM_HALF_WIDTH: f32 = 8.0;
+const ROOM_BACK: f32 = -3.0;
+const ROOM_FRONT: f32 = 12.0;
+// Relative to the half height of the picture, above the floor.
+const ROOM_HEIGHT: f32 = 6.0;
+
+struct RoomPalette {
+    floor_near: [f32; 3],
+    floor_far: [f32; 3],
+    wall_bottom: [f32; 3],
+    wall_top: [f32; 3],
+}
+
+fn palette(environment: RoomEnvironmentOptions) -> Option<RoomPalette> {
+    match environment {
+        RoomEnvironmentOptions::Off => None,
+        // A dark cyclorama, lit from below so it fades out upwards.
+        RoomEnvironmentOptions::Studio => Some(RoomPalette {
+            floor_near: [0.10, 0.10, 0.11],
+            floor_far: [0.16, 0.16, 0.17],
+            wall_bottom: [0.18, 0.18, 0.19],
+            wall_top: [0.05, 0.05, 0.06],
+        }),
+        RoomEnvironmentOptions::LivingRoom => Some(RoomPalette {
+            floor_near: [0.22, 0.13, 0.07],
+            floor_far: [0.30, 0.19, 0.10],
+            wall_bottom: [0.42, 0.36, 0.28],
+            wall_top: [0.20, 0.17, 0.13],
+        }),
+    }
+}
+
+/// Builds the floor and the wall behind a picture of the given half size, centered in the origin and facing +z.
+/// The colors fade between the corners, which gives the room its ambient light gradient. Empty when there is no room.
+pub fn room_mesh(environment: RoomEnvironmentOptions, half_size: [f32; 2], floor_y: f32) -> Vec<f32> {
+    let palette = match palette(environment) {
+        Some(palette) => palette,
+        None => return Vec::new(),
+    };
+    let [hw, hh] = half_size;
+    let (left, right) = (-ROOM_HALF_WIDTH * hw, ROOM_HALF_WIDTH * hw);
+    let (back, front) = (ROOM_BACK * hw, ROOM_FRONT * hw);
+    let top = floor_y + ROOM_HEIGHT * hh;
+    let mut mesh = Vec::with_capacity(12 * ROOM_VERTEX_SIZE);
+    push_quad(
+        &mut mesh,
+        [[left, floor_y, front], [right, floor_y, front], [right, floor_y, back], [left, floor_y, back]],
+        [palette.floor_near, palette.floor_near, palette.floor_far, palette.floor_far],
+    );
+    push_quad(
+        &mut mesh,
+        [[left, floor_y, back], [right, floor_y, back], [right, top, back], [left, top, back]],
+        [palette.wall_bottom, palette.wall_bottom, palette.wall_top, palette.wall_top],
+    );
+    mesh
+}
+
+fn push_quad(mesh: &mut Vec<f32>, corners: [[f32; 3]; 4], colors: [[f32; 3]; 4]) {
+    for &i in &[0, 1, 2, 0, 2, 3] {
+        mesh.extend_from_slice(&corners[i]);
+        mesh.extend_from_slice(&colors[i]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    #[test]
+    fn test_room_mesh__off__is_empty() {
+        assert!(room_mesh(RoomEnvironmentOptions::Off, [128.0, 120.0], -144.0).is_empty());
+    }
+
+    #[test]
+    fn test_room_mesh__stays_behind_the_screen_and_over_the_floor() {
+        let mesh = room_mesh(RoomEnvironmentOptions::Studio, [128.0, 120.0], -144.0);
+        assert_eq!(mesh.len(), 12 * ROOM_VERTEX_SIZE);
+        for vertex in mesh.chunks(ROOM_VERTEX_SIZE) {
+            assert!(vertex[1] >= -144.0);
+            let on_floor = vertex[1] == -144.0;
+            let on_wall = vertex[2] == ROOM_BACK * 128.0;
+            assert!(on_floor || on_wall);
+        }
+    }
+}
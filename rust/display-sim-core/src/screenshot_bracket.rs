@@ -0,0 +1,167 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::simulation_core_state::Controllers;
+use crate::ui_controller::crt_model::CrtMaskKind;
+use crate::ui_controller::screen_curvature_kind::ScreenCurvatureKindOptions;
+use crate::ui_controller::screenshot_bracket::ScreenshotBracketOptions;
+
+/// Frames to wait after applying a step, so its screenshot doesn't contain the previous configuration.
+const BRACKET_SETTLE_FRAMES: u32 = 3;
+
+#[derive(Clone, Copy)]
+pub enum BracketStep {
+    Curvature(ScreenCurvatureKindOptions),
+    Mask(CrtMaskKind),
+}
+
+impl BracketStep {
+    /// Goes in the screenshot file name, so the captures of a bracket can be told apart.
+    pub fn tag(&self) -> String {
+        let value = match self {
+            BracketStep::Curvature(curvature) => format!("curvature-{}", curvature),
+            BracketStep::Mask(CrtMaskKind::ApertureGrille) => "mask-aperture-grille".into(),
+            BracketStep::Mask(CrtMaskKind::ShadowMask) => "mask-shadow-mask".into(),
+            BracketStep::Mask(CrtMaskKind::SlotMask) => "mask-slot-mask".into(),
+        };
+        value.to_lowercase().replace(' ', "-")
+    }
+
+    pub fn apply(&self, controllers: &mut Controllers) {
+        match *self {
+            BracketStep::Curvature(curvature) => controllers.screen_curvature_kind = curvature.into(),
+            BracketStep::Mask(mask) => controllers.apply_crt_mask(mask),
+        }
+    }
+}
+
+pub(crate) enum BracketAdvance {
+    Wait,
+    Apply(BracketStep),
+    Capture(String),
+    Finished(Box<Controllers>),
+}
+
+/// Drives the bracket one frame at a time: each step is applied, left to settle, and captured.
+/// The controllers from before the bracket are given back at the end, so the sweep leaves no trace.
+#[derive(Default)]
+pub struct ScreenshotBracketState {
+    sweep: Vec<BracketStep>,
+    captured: usize,
+    settle_frames: Option<u32>,
+    original: Option<Box<Controllers>>,
+}
+
+impl ScreenshotBracketState {
+    pub fn is_running(&self) -> bool {
+        self.original.is_some()
+    }
+
+    pub(crate) fn start(&mut self, sweep: Vec<BracketStep>, original: Controllers) {
+        *self = ScreenshotBracketState {
+            sweep,
+            original: Some(Box::new(original)),
+            ..Default::default()
+        };
+    }
+
+    pub(crate) fn advance(&mut self) -> BracketAdvance {
+        let original = match self.original {
+            Some(_) => &mut self.original,
+            None => return BracketAdvance::Wait,
+        };
+        match self.settle_frames {
+            None if self.captured == self.sweep.len() => BracketAdvance::Finished(original.take().expect("Bracket is running")),
+            None => {
+                self.settle_frames = Some(BRACKET_SETTLE_FRAMES);
+                BracketAdvance::Apply(self.sweep[self.captured])
+            }
+            Some(0) => {
+                self.settle_frames = None;
+                self.captured += 1;
+                BracketAdvance::Capture(self.sweep[self.captured - 1].tag())
+            }
+            Some(frames) => {
+                self.settle_frames = Some(frames - 1);
+                BracketAdvance::Wait
+            }
+        }
+    }
+}
+
+pub fn build_bracket_sweep(parameter: ScreenshotBracketOptions) -> Vec<BracketStep> {
+    match parameter {
+        ScreenshotBracketOptions::Curvature => [
+            ScreenCurvatureKindOptions::Flat,
+            ScreenCurvatureKindOptions::Curved1,
+            ScreenCurvatureKindOptions::Curved2,
+            ScreenCurvatureKindOptions::Curved3,
+            ScreenCurvatureKindOptions::Cylindrical,
+            ScreenCurvatureKindOptions::Pulse,
+            ScreenCurvatureKindOptions::Barrel,
+        ]
+        .iter()
+        .map(|&curvature| BracketStep::Curvature(curvature))
+        .collect(),
+        ScreenshotBracketOptions::MaskType => [CrtMaskKind::ApertureGrille, CrtMaskKind::ShadowMask, CrtMaskKind::SlotMask]
+            .iter()
+            .map(|&mask| BracketStep::Mask(mask))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    #[test]
+    fn test_bracket__applies_settles_and_captures_every_step_then_restores() {
+        let mut state = ScreenshotBracketState::default();
+        assert!(matches!(state.advance(), BracketAdvance::Wait));
+
+        state.start(build_bracket_sweep(ScreenshotBracketOptions::MaskType), Controllers::default());
+        let mut tags = vec![];
+        let mut applied = 0;
+        let mut frames = 0;
+        loop {
+            frames += 1;
+            match state.advance() {
+                BracketAdvance::Wait => {}
+                BracketAdvance::Apply(_) => applied += 1,
+                BracketAdvance::Capture(tag) => tags.push(tag),
+                BracketAdvance::Finished(_) => break,
+            }
+        }
+        assert_eq!(applied, 3);
+        assert_eq!(tags, vec!["mask-aperture-grille", "mask-shadow-mask", "mask-slot-mask"]);
+        assert_eq!(frames, 3 * (BRACKET_SETTLE_FRAMES as usize + 2) + 1);
+        assert!(!state.is_running());
+    }
+
+    #[test]
+    fn test_bracket_tags__name_the_parameter_and_value() {
+        assert_eq!(BracketStep::Curvature(ScreenCurvatureKindOptions::Curved2).tag(), "curvature-curved-2");
+    }
+
+    #[test]
+    fn test_sweep_parameter__parses_and_applies_to_the_controllers() {
+        let sweep = build_bracket_sweep("curvature".parse().unwrap());
+        let mut controllers = Controllers::default();
+        sweep[2].apply(&mut controllers);
+        assert!(matches!(controllers.screen_curvature_kind.value, ScreenCurvatureKindOptions::Curved2));
+        assert!("pixel-size".parse::<ScreenshotBracketOptions>().is_err());
+    }
+}
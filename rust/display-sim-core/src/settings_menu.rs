@@ -0,0 +1,185 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+
+use crate::app_events::{AppEventDispatcher, ClampedValue, MessageCategory, SoundEffect};
+use crate::camera::CameraLockMode;
+use crate::diagnostics::DiagnosticsReadout;
+use crate::moire::MoireReport;
+use crate::simulation_core_state::{Controllers, ScalingMethod};
+use crate::ui_controller::message_verbosity::MessageVerbosityOptions;
+use app_error::AppResult;
+use std::cell::RefCell;
+
+/// Lines of settings visible at once, the list scrolls to keep the cursor inside.
+pub const SETTINGS_MENU_VISIBLE_LINES: usize = 16;
+
+pub struct SettingsMenuEntry {
+    /// Position of the controller in `Controllers::get_ui_controllers`.
+    pub index: usize,
+    pub name: String,
+    pub value: String,
+}
+
+/// The settings overlay for builds without the HTML panel. Arrows move and adjust, enter cycles forward.
+#[derive(Default)]
+pub struct SettingsMenuState {
+    pub open: bool,
+    pub cursor: usize,
+    /// Controller being adjusted by a held key, with the (increase, decrease) state sent to it.
+    pub(crate) adjusting: Option<(usize, (bool, bool))>,
+}
+
+impl SettingsMenuState {
+    pub(crate) fn move_cursor(&mut self, delta: i32, len: usize) {
+        if len == 0 {
+            return;
+        }
+        self.cursor = (self.cursor as i32 + delta).rem_euclid(len as i32) as usize;
+    }
+
+    pub(crate) fn lines(&self, entries: &[SettingsMenuEntry]) -> Vec<String> {
+        let first = self
+            .cursor
+            .saturating_sub(SETTINGS_MENU_VISIBLE_LINES / 2)
+            .min(entries.len().saturating_sub(SETTINGS_MENU_VISIBLE_LINES));
+        let name_width = entries.iter().map(|entry| entry.name.len()).max().unwrap_or(0);
+        let mut lines = vec![format!("Settings {}/{}  (F1 closes)", self.cursor + 1, entries.len())];
+        lines.extend(entries.iter().enumerate().skip(first).take(SETTINGS_MENU_VISIBLE_LINES).map(|(i, entry)| {
+            let marker = if i == self.cursor { '>' } else { ' ' };
+            format!("{} {:width$}  {}", marker, entry.name, entry.value, width = name_width)
+        }));
+        lines
+    }
+}
+
+/// Every controller that reports its value to the frontend, named after the event it sends.
+pub(crate) fn settings_menu_entries(controllers: &Controllers) -> Vec<SettingsMenuEntry> {
+    controllers
+        .get_ui_controllers()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, controller)| {
+            let capture = ValueCapture::default();
+            controller.dispatch_event(&capture);
+            let (tag, value) = capture.event.into_inner()?;
+            let name = tag.trim_start_matches("back2front:").trim_start_matches("change_").replace('_', " ");
+            Some(SettingsMenuEntry {
+                index,
+                name,
+                value: short_value(&value),
+            })
+        })
+        .collect()
+}
+
+fn short_value(value: &str) -> String {
+    match value.parse::<f64>() {
+        Ok(number) if value.contains('.') => format!("{:.3}", number).trim_end_matches('0').trim_end_matches('.').into(),
+        _ => value.chars().take(40).collect(),
+    }
+}
+
+// Keeps the first event a controller sends when asked for its value, and drops everything else.
+#[derive(Default)]
+struct ValueCapture {
+    event: RefCell<Option<(&'static str, String)>>,
+}
+
+impl AppEventDispatcher for ValueCapture {
+    fn enable_extra_messages(&self, _: bool) {}
+    fn set_message_verbosity(&self, _: MessageVerbosityOptions) {}
+    fn are_extra_messages_enabled(&self, _: MessageCategory) -> bool {
+        false
+    }
+    fn dispatch_log(&self, _: String) {}
+    fn dispatch_string_event(&self, event_id: &'static str, message: &str) {
+        let mut event = self.event.borrow_mut();
+        if event.is_none() {
+            *event = Some((event_id, message.into()));
+        }
+    }
+    fn dispatch_camera_update(&self, _: &glm::Vec3, _: &glm::Vec3, _: &glm::Vec3) {}
+    fn dispatch_recovery_state(&self, _: &str, _: &glm::Vec3, _: &glm::Vec3, _: &glm::Vec3) {}
+    fn dispatch_change_pixel_width(&self, _: f32) {}
+    fn dispatch_change_camera_zoom(&self, _: f32) {}
+    fn dispatch_change_camera_focal_length(&self, _: f32) {}
+    fn dispatch_change_pixel_speed(&self, _: f32) {}
+    fn dispatch_change_turning_speed(&self, _: f32) {}
+    fn dispatch_change_movement_speed(&self, _: f32) {}
+    fn dispatch_scaling_method(&self, _: ScalingMethod) {}
+    fn dispatch_scaling_resolution_width(&self, _: u32) {}
+    fn dispatch_scaling_resolution_height(&self, _: u32) {}
+    fn dispatch_scaling_aspect_ratio_x(&self, _: f32) {}
+    fn dispatch_scaling_aspect_ratio_y(&self, _: f32) {}
+    fn dispatch_custom_scaling_stretch_nearest(&self, _: bool) {}
+    fn dispatch_exiting_session(&self) {}
+    fn dispatch_toggle_info_panel(&self) {}
+    fn dispatch_fps(&self, _: f32) {}
+    fn dispatch_frame_times(&self, _: &[f32]) {}
+    fn dispatch_request_fullscreen(&self) {}
+    fn dispatch_request_pointer_lock(&self) {}
+    fn dispatch_exit_pointer_lock(&self) {}
+    fn dispatch_play_sound(&self, _: SoundEffect, _: f32) {}
+    fn dispatch_light_gun_shot(&self, _: Option<(u32, u32)>) {}
+    fn dispatch_diagnostics(&self, _: &DiagnosticsReadout) {}
+    fn dispatch_moire_report(&self, _: &MoireReport) {}
+    fn request_screenshot_readback(&self, _: i32, _: i32) -> AppResult<()> {
+        Ok(())
+    }
+    fn dispatch_screenshot(&self, _: i32, _: i32, _: &mut [u8], _: Option<&str>) -> AppResult<()> {
+        Ok(())
+    }
+    fn dispatch_turntable_recording(&self, _: bool) -> AppResult<()> {
+        Ok(())
+    }
+    fn dispatch_change_camera_movement_mode(&self, _: CameraLockMode) {}
+    fn dispatch_top_message(&self, _: &str) {}
+    fn dispatch_minimum_value(&self, _: &ClampedValue) {}
+    fn dispatch_maximum_value(&self, _: &ClampedValue) {}
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    #[test]
+    fn test_settings_menu_entries__name_and_value_every_controller() {
+        let mut controllers = Controllers::default();
+        controllers.blur_passes.value = 3;
+        let entries = settings_menu_entries(&controllers);
+        let blur = entries.iter().find(|entry| entry.name == "blur level").expect("Blur is listed");
+        assert_eq!(blur.value, "3");
+        assert_eq!(controllers.get_ui_controllers()[blur.index].event_tag(), "front2back:blur-level");
+    }
+
+    #[test]
+    fn test_settings_menu_lines__scroll_with_the_cursor() {
+        let entries: Vec<SettingsMenuEntry> = (0..40)
+            .map(|index| SettingsMenuEntry {
+                index,
+                name: format!("setting {}", index),
+                value: "0".into(),
+            })
+            .collect();
+        let mut menu = SettingsMenuState::default();
+        menu.move_cursor(-1, entries.len());
+        assert_eq!(menu.cursor, 39);
+        let lines = menu.lines(&entries);
+        assert_eq!(lines.len(), SETTINGS_MENU_VISIBLE_LINES + 1);
+        assert!(lines.last().unwrap().starts_with("> setting 39"));
+    }
+}
@@ -13,37 +13,164 @@
  * You should have received a copy of the GNU Affero General Public License
  * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 use arraygen::Arraygen;
 use enum_len_derive::EnumLen;
 use num_derive::{FromPrimitive, ToPrimitive};
 
+use crate::blemishes::{ScratchSettings, BLEMISH_SCRATCHES};
+use crate::blind_test::BlindTestState;
+use crate::blooming::BloomingState;
 use crate::camera::CameraData;
+use crate::composite_signal::CompositeSignal;
+use crate::defects::DefectSettings;
 use crate::general_types::Size2D;
+use crate::input_replay::InputReplayState;
+use crate::ken_burns::KenBurnsState;
+use crate::moire::MoireAssistantState;
+use crate::monitor_case::CaseSettings;
+use crate::palettes::PaletteColors;
+use crate::procedural_sources::ProceduralSourceState;
+use crate::room::RoomSettings;
+use crate::screenshot_bracket::ScreenshotBracketState;
+use crate::settings_menu::SettingsMenuState;
+use crate::subtitles::{SubtitleCue, SubtitlesState};
+use crate::transitions::TransitionState;
+use crate::turntable::TurntableState;
 use crate::ui_controller::{
+    analog_static::AnalogStatic,
+    analog_static_speed::AnalogStaticSpeed,
+    background_blur_passes::BackgroundBlurPasses,
+    background_resolution_scale::BackgroundResolutionScale,
     backlight_percent::BacklightPercent,
+    bezel_offset_x::BezelOffsetX,
+    bezel_offset_y::BezelOffsetY,
+    bezel_scale::BezelScale,
+    black_pedestal::BlackPedestal,
+    blemish_seed::BlemishSeed,
+    blemish_strength::BlemishStrength,
+    blending_space::{BlendingSpace, BlendingSpaceOptions},
+    bloom_intensity::BloomIntensity,
+    bloom_threshold::BloomThreshold,
+    blooming_amount::BloomingAmount,
+    blooming_time_constant::BloomingTimeConstant,
+    blue_gain::BlueGain,
+    blue_offset::BlueOffset,
     blur_passes::BlurPasses,
     brightness_color::BrightnessColor,
+    burn_in_rate::BurnInRate,
+    burn_in_visibility::BurnInVisibility,
+    camera_collision::{CameraCollision, CameraCollisionOptions},
+    channel_offset::{ChannelOffsetBlueX, ChannelOffsetBlueY, ChannelOffsetGreenX, ChannelOffsetGreenY, ChannelOffsetRedX, ChannelOffsetRedY},
     color_channels::{ColorChannels, ColorChannelsOptions},
+    color_channels_order::{ColorChannelsOrder, ColorChannelsOrderOptions},
     color_gamma::ColorGamma,
+    color_hue::ColorHue,
     color_noise::ColorNoise,
+    color_saturation::ColorSaturation,
+    color_temperature::ColorTemperature,
+    contrast_knee::ContrastKnee,
+    contrast_shoulder::ContrastShoulder,
+    convergence::{ConvergenceBlueX, ConvergenceBlueY, ConvergenceGreenX, ConvergenceGreenY, ConvergenceRedX, ConvergenceRedY},
+    crt_model::{CrtMaskKind, CrtModel, CrtModelOptions, CrtModelSpec},
+    crt_sounds::{CrtSounds, CrtSoundsOptions},
     cur_pixel_horizontal_gap::CurPixelHorizontalGap,
+    cur_pixel_horizontal_spread::CurPixelHorizontalSpread,
     cur_pixel_spread::CurPixelSpread,
     cur_pixel_vertical_gap::CurPixelVerticalGap,
+    cur_pixel_vertical_spread::CurPixelVerticalSpread,
+    custom_palette::CustomPalette,
+    damper_wire_opacity::DamperWireOpacity,
+    damper_wire_position::DamperWirePosition,
+    damper_wires::{DamperWires, DamperWiresOptions},
+    dead_scanlines::DeadScanlines,
+    dead_subpixels::DeadSubpixels,
+    diagnostics_overlay::{DiagnosticsOverlay, DiagnosticsOverlayOptions},
+    display_dpi::DisplayDpi,
+    dithering::{Dithering, DitheringOptions},
+    dithering_bits::DitheringBits,
+    dot_pitch::DotPitch,
+    edge_reflection_intensity::EdgeReflectionIntensity,
+    edge_reflection_width::EdgeReflectionWidth,
     extra_bright::ExtraBright,
     extra_contrast::ExtraContrast,
     filter_preset::{FilterPreset, FilterPresetOptions},
+    flicker::{Flicker, FlickerOptions},
+    flicker_amplitude::FlickerAmplitude,
+    floor_reflection::{FloorReflection, FloorReflectionOptions},
+    floor_roughness::FloorRoughness,
+    foreground_opacity::ForegroundOpacity,
+    glass_reflection::{GlassReflection, GlassReflectionOptions},
+    glass_reflectivity::GlassReflectivity,
+    green_gain::GreenGain,
+    green_offset::GreenOffset,
     horizontal_lpp::HorizontalLpp,
+    hsync_wobble::HsyncWobble,
+    hsync_wobble_frequency::HsyncWobbleFrequency,
+    interlace_field_flicker::InterlaceFieldFlicker,
+    interlace_line_pairing::InterlaceLinePairing,
+    interlacing::{Interlacing, InterlacingOptions},
     internal_resolution::InternalResolution,
+    ken_burns_margin::KenBurnsMargin,
+    ken_burns_speed::KenBurnsSpeed,
+    layer_depth_test::{BackgroundDepthTest, BackgroundDepthTestOptions, ForegroundDepthTest, ForegroundDepthTestOptions},
+    layering_blend_mode::{LayeringBlendMode, LayeringBlendModeOptions},
+    layers::{Layer, LayerList, Layers},
     light_color::LightColor,
+    light_gun::{LightGun, LightGunOptions},
+    magnet_radius::MagnetRadius,
+    magnet_strength::MagnetStrength,
+    message_verbosity::{MessageVerbosity, MessageVerbosityOptions},
+    moire_assistant::{MoireAssistant, MoireAssistantOptions},
+    output_color_space::{OutputColorSpace, OutputColorSpaceOptions},
+    output_gamma::OutputGamma,
+    overlapping_blend_mode::{OverlappingBlendMode, OverlappingBlendModeOptions},
+    overlapping_gain_blue::OverlappingGainBlue,
+    overlapping_gain_green::OverlappingGainGreen,
+    overlapping_gain_red::OverlappingGainRed,
+    palette::{Palette, PaletteOptions},
+    phosphor_primaries::{PhosphorPrimaries, PhosphorPrimariesOptions},
+    physical_calibration::{PhysicalCalibration, PhysicalCalibrationOptions},
     pixel_geometry_kind::{PixelGeometryKind, PixelGeometryKindOptions},
     pixel_shadow_height::PixelShadowHeight,
     pixel_shadow_shape_kind::{PixelShadowShapeKind, ShadowShape},
+    procedural_source::{ProceduralSource, ProceduralSourceOptions},
+    red_gain::RedGain,
+    red_offset::RedOffset,
+    response_fall_time::ResponseFallTime,
+    response_rise_time::ResponseRiseTime,
     rgb_calibration::{RgbBlueB, RgbBlueG, RgbBlueR, RgbGreenB, RgbGreenG, RgbGreenR, RgbRedB, RgbRedG, RgbRedR},
+    rolling_scan::{RollingScan, RollingScanOptions},
+    rolling_scan_size::RollingScanSize,
+    rolling_scan_speed::RollingScanSpeed,
+    room_brightness::RoomBrightness,
+    room_environment::{RoomEnvironment, RoomEnvironmentOptions},
+    scanline_alignment::{ScanlinePhase, SyncOffset},
+    scanline_orientation::{ScanlineAngle, ScanlineOrientation, ScanlineOrientationOptions},
+    screen_corner_radius::ScreenCornerRadius,
+    screen_curvature_amount::ScreenCurvatureAmount,
     screen_curvature_kind::{ScreenCurvatureKind, ScreenCurvatureKindOptions},
-    texture_interpolation::{TextureInterpolation, TextureInterpolationOptions},
+    screen_matte::ScreenMatte,
+    screenshot_bracket::{ScreenshotBracket, ScreenshotBracketOptions},
+    sharpen_halo_suppression::SharpenHaloSuppression,
+    sharpen_strength::SharpenStrength,
+    signal_kind::{SignalKind, SignalKindOptions},
+    source_orientation::{SourceMirror, SourceMirrorOptions, SourceRotation, SourceRotationOptions},
+    status_bar::{StatusBar, StatusBarOptions},
+    stuck_pixels::StuckPixels,
+    subtitles::{Subtitles, SubtitlesOptions},
+    texture_interpolation::{
+        OutputInterpolation, OutputInterpolationOptions, SourceInterpolation, SourceInterpolationOptions, TextureInterpolation, TextureInterpolationOptions,
+    },
+    transition::{Transition, TransitionOptions},
+    transition_seconds::TransitionSeconds,
+    turntable_seconds::TurntableSeconds,
+    tv_lines::TvLines,
     vertical_lpp::VerticalLpp,
+    vignette_radius::VignetteRadius,
+    vignette_strength::VignetteStrength,
+    white_clipping::{WhiteClipping, WhiteClippingOptions},
     UiController,
 };
 
@@ -51,6 +178,11 @@ pub const PIXEL_MANIPULATION_BASE_SPEED: f32 = 20.0;
 pub const TURNING_BASE_SPEED: f32 = 3.0;
 pub const MOVEMENT_BASE_SPEED: f32 = 10.0;
 pub const MOVEMENT_SPEED_FACTOR: f32 = 50.0;
+pub const CRT_HUM_VOLUME: f32 = 0.05;
+pub const CRT_STATIC_MAX_VOLUME: f32 = 0.5;
+pub const MAGNET_RAMP_SPEED: f32 = 2.0;
+pub const MAGNET_DEGAUSS_SECONDS: f32 = 2.0;
+pub const MAGNET_DEGAUSS_FREQUENCY: f32 = 6.0;
 
 #[derive(Default, Clone)]
 pub struct VideoInputResources {
@@ -62,8 +194,11 @@ pub struct VideoInputResources {
     pub preset: Option<FilterPresetOptions>,
     pub current_frame: usize,
     pub last_frame_change: f64,
+    pub external_clock: bool,
+    pub pending_frame_advances: u32,
     pub needs_buffer_data_load: bool,
     pub drawing_activation: bool,
+    pub subtitles: Vec<SubtitleCue>,
 }
 
 #[derive(Clone, Copy)]
@@ -99,8 +234,21 @@ pub struct Resources {
     pub timers: SimulationTimers,
     pub initial_parameters: InitialParameters,
     pub screenshot_trigger: ScreenshotTrigger,
-    pub drawable: bool,
+    pub magnet: MagnetState,
+    pub procedural_source: ProceduralSourceState,
+    pub subtitles: SubtitlesState,
+    pub blooming: BloomingState,
+    pub moire_assistant: MoireAssistantState,
+    pub screenshot_bracket: ScreenshotBracketState,
+    pub turntable: TurntableState,
+    pub ken_burns: KenBurnsState,
+    pub input_replay: InputReplayState,
+    pub transition: TransitionState,
+    pub blind_test: BlindTestState,
+    pub settings_menu: SettingsMenuState,
     pub resetted: bool,
+    pub calibrated_distance: Option<f32>,
+    pub crt_sounds_static_volume: Option<f32>,
     pub quit: bool,
     pub controller_events: HashMap<&'static str, (KeyEventKind, usize)>,
 }
@@ -120,9 +268,26 @@ impl Default for Resources {
             scaling: Scaling::default(),
             saved_filters: None,
             custom_is_changed: false,
-            screenshot_trigger: ScreenshotTrigger { is_triggered: false, delay: 0 },
-            drawable: false,
+            screenshot_trigger: ScreenshotTrigger {
+                is_triggered: false,
+                delay: 0,
+                tag: None,
+            },
+            magnet: MagnetState::default(),
+            procedural_source: ProceduralSourceState::default(),
+            subtitles: SubtitlesState::default(),
+            blooming: BloomingState::default(),
+            moire_assistant: MoireAssistantState::default(),
+            screenshot_bracket: ScreenshotBracketState::default(),
+            turntable: TurntableState::default(),
+            ken_burns: KenBurnsState::default(),
+            input_replay: InputReplayState::default(),
+            transition: TransitionState::default(),
+            blind_test: BlindTestState::default(),
+            settings_menu: SettingsMenuState::default(),
             resetted: true,
+            calibrated_distance: None,
+            crt_sounds_static_volume: None,
             quit: false,
             controller_events: {
                 let mut map: HashMap<&'static str, (KeyEventKind, usize)> = HashMap::new();
@@ -166,10 +331,19 @@ impl Resources {
         }
         self.timers = SimulationTimers {
             frame_count: 0,
+            total_frames: 0,
             last_time: now,
             last_second: now,
+            last_fps: 0.0,
+            frame_times: VecDeque::with_capacity(FRAME_TIMES_CAPACITY),
+            max_dt: self.timers.max_dt,
         };
+        // Nothing was showing before the first source, so there is nothing to transition from.
+        if !self.video.steps.is_empty() {
+            self.transition.start(now);
+        }
         self.video = video_input;
+        self.subtitles.restart(now);
         for controller in self.controllers.get_ui_controllers_mut().iter_mut() {
             controller.reset_inputs();
         }
@@ -182,9 +356,17 @@ pub enum LatestCustomScalingChange {
     PixelSize,
 }
 
+#[derive(Default)]
+pub struct MagnetState {
+    pub center: [f32; 2],
+    pub intensity: f32,
+    pub degauss_started: Option<f64>,
+}
+
 pub struct ScreenshotTrigger {
     pub is_triggered: bool,
     pub delay: i32,
+    pub tag: Option<String>,
 }
 
 pub struct FlightDemoData {
@@ -213,11 +395,41 @@ impl Default for FlightDemoData {
     }
 }
 
-#[derive(Default)]
+pub const FRAME_TIMES_CAPACITY: usize = 240;
+pub const DEFAULT_MAX_DT: f32 = 0.25;
+
 pub struct SimulationTimers {
     pub frame_count: u32,
+    /// Frames since the start, it doesn't reset every second like frame_count.
+    pub total_frames: u64,
     pub last_time: f64,
     pub last_second: f64,
+    pub last_fps: f32,
+    pub frame_times: VecDeque<f32>,
+    pub max_dt: f32,
+}
+
+impl Default for SimulationTimers {
+    fn default() -> Self {
+        SimulationTimers {
+            frame_count: 0,
+            total_frames: 0,
+            last_time: 0.0,
+            last_second: 0.0,
+            last_fps: 0.0,
+            frame_times: VecDeque::new(),
+            max_dt: DEFAULT_MAX_DT,
+        }
+    }
+}
+
+impl SimulationTimers {
+    pub fn push_frame_time(&mut self, frame_time: f32) {
+        if self.frame_times.len() >= FRAME_TIMES_CAPACITY {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
 }
 
 #[derive(Default)]
@@ -238,6 +450,7 @@ pub struct Scaling {
     pub custom_stretch: bool,
     pub custom_change: LatestCustomScalingChange,
     pub scaling_initialized: bool,
+    pub quarter_turned: bool,
     pub scaling_method: ScalingMethod,
 }
 
@@ -245,6 +458,7 @@ impl Default for Scaling {
     fn default() -> Self {
         Scaling {
             scaling_initialized: false,
+            quarter_turned: false,
             scaling_method: ScalingMethod::AutoDetect,
             custom_resolution: Size2D { width: 256.0, height: 240.0 },
             custom_aspect_ratio: Size2D { width: 4.0, height: 3.0 },
@@ -261,6 +475,12 @@ impl Default for Scaling {
 pub struct Controllers {
     pub internal_resolution: InternalResolution,
     pub texture_interpolation: TextureInterpolation,
+    pub source_interpolation: SourceInterpolation,
+    pub output_interpolation: OutputInterpolation,
+    pub sharpen_strength: SharpenStrength,
+    pub sharpen_halo_suppression: SharpenHaloSuppression,
+    pub output_color_space: OutputColorSpace,
+    pub phosphor_primaries: PhosphorPrimaries,
     pub blur_passes: BlurPasses,
     pub vertical_lpp: VerticalLpp,
     pub horizontal_lpp: HorizontalLpp,
@@ -268,15 +488,119 @@ pub struct Controllers {
     pub brightness_color: BrightnessColor,
     pub extra_bright: ExtraBright,
     pub extra_contrast: ExtraContrast,
+    pub contrast_knee: ContrastKnee,
+    pub contrast_shoulder: ContrastShoulder,
     pub cur_pixel_vertical_gap: CurPixelVerticalGap,
     pub cur_pixel_horizontal_gap: CurPixelHorizontalGap,
     pub cur_pixel_spread: CurPixelSpread,
+    pub cur_pixel_horizontal_spread: CurPixelHorizontalSpread,
+    pub cur_pixel_vertical_spread: CurPixelVerticalSpread,
     pub pixel_shadow_height: PixelShadowHeight,
     pub pixels_geometry_kind: PixelGeometryKind,
+    pub foreground_depth_test: ForegroundDepthTest,
+    pub background_depth_test: BackgroundDepthTest,
     pub color_channels: ColorChannels,
+    pub color_channels_order: ColorChannelsOrder,
+    pub overlapping_blend_mode: OverlappingBlendMode,
+    pub overlapping_gain_red: OverlappingGainRed,
+    pub overlapping_gain_green: OverlappingGainGreen,
+    pub overlapping_gain_blue: OverlappingGainBlue,
+    pub channel_offset_red_x: ChannelOffsetRedX,
+    pub channel_offset_red_y: ChannelOffsetRedY,
+    pub channel_offset_green_x: ChannelOffsetGreenX,
+    pub channel_offset_green_y: ChannelOffsetGreenY,
+    pub channel_offset_blue_x: ChannelOffsetBlueX,
+    pub channel_offset_blue_y: ChannelOffsetBlueY,
+    pub convergence_red_x: ConvergenceRedX,
+    pub convergence_red_y: ConvergenceRedY,
+    pub convergence_green_x: ConvergenceGreenX,
+    pub convergence_green_y: ConvergenceGreenY,
+    pub convergence_blue_x: ConvergenceBlueX,
+    pub convergence_blue_y: ConvergenceBlueY,
+    pub scanline_phase: ScanlinePhase,
+    pub sync_offset: SyncOffset,
+    pub scanline_orientation: ScanlineOrientation,
+    pub scanline_angle: ScanlineAngle,
+    pub source_rotation: SourceRotation,
+    pub source_mirror: SourceMirror,
+    pub damper_wires: DamperWires,
+    pub damper_wire_position: DamperWirePosition,
+    pub damper_wire_opacity: DamperWireOpacity,
+    pub rolling_scan: RollingScan,
+    pub rolling_scan_speed: RollingScanSpeed,
+    pub rolling_scan_size: RollingScanSize,
+    pub screen_corner_radius: ScreenCornerRadius,
+    pub screen_matte: ScreenMatte,
+    pub flicker: Flicker,
+    pub flicker_amplitude: FlickerAmplitude,
+    pub glass_reflection: GlassReflection,
+    pub glass_reflectivity: GlassReflectivity,
+    pub blemish_strength: BlemishStrength,
+    pub blemish_seed: BlemishSeed,
+    pub dead_subpixels: DeadSubpixels,
+    pub stuck_pixels: StuckPixels,
+    pub dead_scanlines: DeadScanlines,
+    pub bezel_scale: BezelScale,
+    pub bezel_offset_x: BezelOffsetX,
+    pub bezel_offset_y: BezelOffsetY,
+    pub room_environment: RoomEnvironment,
+    pub room_brightness: RoomBrightness,
+    pub edge_reflection_intensity: EdgeReflectionIntensity,
+    pub edge_reflection_width: EdgeReflectionWidth,
+    pub floor_reflection: FloorReflection,
+    pub floor_roughness: FloorRoughness,
+    pub blooming_amount: BloomingAmount,
+    pub blooming_time_constant: BloomingTimeConstant,
+    pub bloom_threshold: BloomThreshold,
+    pub bloom_intensity: BloomIntensity,
+    pub white_clipping: WhiteClipping,
+    pub dithering: Dithering,
+    pub dithering_bits: DitheringBits,
+    pub burn_in_rate: BurnInRate,
+    pub burn_in_visibility: BurnInVisibility,
+    pub transition: Transition,
+    pub transition_seconds: TransitionSeconds,
+    pub vignette_strength: VignetteStrength,
+    pub vignette_radius: VignetteRadius,
+    pub tv_lines: TvLines,
+    pub signal_kind: SignalKind,
+    pub palette: Palette,
+    pub custom_palette: CustomPalette,
+    pub interlacing: Interlacing,
+    pub interlace_field_flicker: InterlaceFieldFlicker,
+    pub interlace_line_pairing: InterlaceLinePairing,
+    pub hsync_wobble: HsyncWobble,
+    pub hsync_wobble_frequency: HsyncWobbleFrequency,
+    pub response_rise_time: ResponseRiseTime,
+    pub response_fall_time: ResponseFallTime,
     pub screen_curvature_kind: ScreenCurvatureKind,
+    pub screen_curvature_amount: ScreenCurvatureAmount,
     pub pixel_shadow_shape_kind: PixelShadowShapeKind,
     pub backlight_percent: BacklightPercent,
+    pub layering_blend_mode: LayeringBlendMode,
+    pub layers: Layers,
+    pub foreground_opacity: ForegroundOpacity,
+    pub background_resolution_scale: BackgroundResolutionScale,
+    pub background_blur_passes: BackgroundBlurPasses,
+    pub physical_calibration: PhysicalCalibration,
+    pub display_dpi: DisplayDpi,
+    pub dot_pitch: DotPitch,
+    pub crt_sounds: CrtSounds,
+    pub light_gun: LightGun,
+    pub camera_collision: CameraCollision,
+    pub magnet_strength: MagnetStrength,
+    pub magnet_radius: MagnetRadius,
+    pub procedural_source: ProceduralSource,
+    pub subtitles: Subtitles,
+    pub diagnostics_overlay: DiagnosticsOverlay,
+    pub status_bar: StatusBar,
+    pub message_verbosity: MessageVerbosity,
+    pub moire_assistant: MoireAssistant,
+    pub screenshot_bracket: ScreenshotBracket,
+    pub turntable_seconds: TurntableSeconds,
+    pub ken_burns_speed: KenBurnsSpeed,
+    pub ken_burns_margin: KenBurnsMargin,
+    pub crt_model: CrtModel,
     pub rgb_red_r: RgbRedR,
     pub rgb_red_g: RgbRedG,
     pub rgb_red_b: RgbRedB,
@@ -287,7 +611,21 @@ pub struct Controllers {
     pub rgb_blue_g: RgbBlueG,
     pub rgb_blue_b: RgbBlueB,
     pub color_gamma: ColorGamma,
+    pub color_saturation: ColorSaturation,
+    pub color_hue: ColorHue,
+    pub black_pedestal: BlackPedestal,
+    pub color_temperature: ColorTemperature,
+    pub red_gain: RedGain,
+    pub red_offset: RedOffset,
+    pub green_gain: GreenGain,
+    pub green_offset: GreenOffset,
+    pub blue_gain: BlueGain,
+    pub blue_offset: BlueOffset,
+    pub output_gamma: OutputGamma,
     pub color_noise: ColorNoise,
+    pub analog_static: AnalogStatic,
+    pub analog_static_speed: AnalogStaticSpeed,
+    pub blending_space: BlendingSpace,
     pub preset_kind: FilterPreset,
 }
 
@@ -296,22 +634,132 @@ impl Default for Controllers {
         let mut controllers = Controllers {
             internal_resolution: InternalResolution::default(),
             texture_interpolation: TextureInterpolationOptions::Linear.into(),
+            source_interpolation: SourceInterpolationOptions::Linear.into(),
+            output_interpolation: OutputInterpolationOptions::Linear.into(),
+            sharpen_strength: 0.0.into(),
+            sharpen_halo_suppression: 0.5.into(),
+            output_color_space: OutputColorSpaceOptions::Srgb.into(),
+            phosphor_primaries: PhosphorPrimariesOptions::Srgb.into(),
             blur_passes: 0.into(),
-            vertical_lpp: 1.into(),
-            horizontal_lpp: 1.into(),
+            vertical_lpp: 1.0.into(),
+            horizontal_lpp: 1.0.into(),
             light_color: 0x00FF_FFFF.into(),
             brightness_color: 0x00FF_FFFF.into(),
             extra_bright: 0.0.into(),
             extra_contrast: 1.0.into(),
+            contrast_knee: 0.0.into(),
+            contrast_shoulder: 0.0.into(),
             cur_pixel_vertical_gap: 0.0.into(),
             cur_pixel_horizontal_gap: 0.0.into(),
             cur_pixel_spread: 0.0.into(),
+            cur_pixel_horizontal_spread: 0.0.into(),
+            cur_pixel_vertical_spread: 0.0.into(),
             pixel_shadow_height: 1.0.into(),
             pixels_geometry_kind: PixelGeometryKindOptions::Squares.into(),
+            foreground_depth_test: ForegroundDepthTestOptions::Auto.into(),
+            background_depth_test: BackgroundDepthTestOptions::Off.into(),
             pixel_shadow_shape_kind: ShadowShape { value: 0 }.into(),
             color_channels: ColorChannelsOptions::Combined.into(),
+            color_channels_order: ColorChannelsOrderOptions::Rgb.into(),
+            overlapping_blend_mode: OverlappingBlendModeOptions::Weighted.into(),
+            overlapping_gain_red: 1.0.into(),
+            overlapping_gain_green: 1.0.into(),
+            overlapping_gain_blue: 1.0.into(),
+            channel_offset_red_x: 0.0.into(),
+            channel_offset_red_y: 0.0.into(),
+            channel_offset_green_x: 0.0.into(),
+            channel_offset_green_y: 0.0.into(),
+            channel_offset_blue_x: 0.0.into(),
+            channel_offset_blue_y: 0.0.into(),
+            convergence_red_x: 0.0.into(),
+            convergence_red_y: 0.0.into(),
+            convergence_green_x: 0.0.into(),
+            convergence_green_y: 0.0.into(),
+            convergence_blue_x: 0.0.into(),
+            convergence_blue_y: 0.0.into(),
+            scanline_phase: 0.0.into(),
+            sync_offset: 0.0.into(),
+            scanline_orientation: ScanlineOrientationOptions::Horizontal.into(),
+            scanline_angle: 0.0.into(),
+            source_rotation: SourceRotationOptions::None.into(),
+            source_mirror: SourceMirrorOptions::Off.into(),
+            damper_wires: DamperWiresOptions::Off.into(),
+            damper_wire_position: (1.0 / 3.0).into(),
+            damper_wire_opacity: 0.25.into(),
+            rolling_scan: RollingScanOptions::Off.into(),
+            rolling_scan_speed: 0.5.into(),
+            rolling_scan_size: 0.25.into(),
+            screen_corner_radius: 0.0.into(),
+            screen_matte: 0.0.into(),
+            flicker: FlickerOptions::Off.into(),
+            flicker_amplitude: 0.05.into(),
+            glass_reflection: GlassReflectionOptions::Off.into(),
+            glass_reflectivity: 0.04.into(),
+            blemish_strength: 0.0.into(),
+            blemish_seed: 1.into(),
+            dead_subpixels: 0.into(),
+            stuck_pixels: 0.into(),
+            dead_scanlines: 0.into(),
+            bezel_scale: 1.5.into(),
+            bezel_offset_x: 0.0.into(),
+            bezel_offset_y: 0.0.into(),
+            room_environment: RoomEnvironmentOptions::Off.into(),
+            room_brightness: 0.0.into(),
+            edge_reflection_intensity: 0.0.into(),
+            edge_reflection_width: 16.0.into(),
+            floor_reflection: FloorReflectionOptions::Off.into(),
+            floor_roughness: 0.5.into(),
+            blooming_amount: 0.0.into(),
+            blooming_time_constant: 0.5.into(),
+            bloom_threshold: 0.7.into(),
+            bloom_intensity: 0.0.into(),
+            white_clipping: WhiteClippingOptions::HardClip.into(),
+            dithering: DitheringOptions::Off.into(),
+            dithering_bits: 6.into(),
+            burn_in_rate: 0.0.into(),
+            burn_in_visibility: 0.25.into(),
+            transition: TransitionOptions::Static.into(),
+            transition_seconds: 0.5.into(),
+            vignette_strength: 0.0.into(),
+            vignette_radius: 0.75.into(),
+            tv_lines: 0.0.into(),
+            signal_kind: SignalKindOptions::Rgb.into(),
+            palette: PaletteOptions::Off.into(),
+            custom_palette: PaletteColors::default().into(),
+            interlacing: InterlacingOptions::Off.into(),
+            interlace_field_flicker: 0.0.into(),
+            interlace_line_pairing: 0.0.into(),
+            hsync_wobble: 0.0.into(),
+            hsync_wobble_frequency: 3.0.into(),
+            response_rise_time: 0.0.into(),
+            response_fall_time: 0.0.into(),
             screen_curvature_kind: ScreenCurvatureKindOptions::Flat.into(),
+            screen_curvature_amount: 0.0.into(),
             backlight_percent: 0.0.into(),
+            layering_blend_mode: LayeringBlendModeOptions::Weighted.into(),
+            layers: LayerList::default().into(),
+            foreground_opacity: 1.0.into(),
+            background_resolution_scale: 0.5.into(),
+            background_blur_passes: 6.into(),
+            physical_calibration: PhysicalCalibrationOptions::Off.into(),
+            display_dpi: 96.0.into(),
+            dot_pitch: 0.25.into(),
+            crt_sounds: CrtSoundsOptions::Off.into(),
+            light_gun: LightGunOptions::Off.into(),
+            camera_collision: CameraCollisionOptions::On.into(),
+            magnet_strength: 1.0.into(),
+            magnet_radius: 40.0.into(),
+            procedural_source: ProceduralSourceOptions::Off.into(),
+            subtitles: SubtitlesOptions::On.into(),
+            diagnostics_overlay: DiagnosticsOverlayOptions::Off.into(),
+            status_bar: StatusBarOptions::Off.into(),
+            message_verbosity: MessageVerbosityOptions::All.into(),
+            moire_assistant: MoireAssistantOptions::Recommend.into(),
+            screenshot_bracket: ScreenshotBracketOptions::Curvature.into(),
+            turntable_seconds: 8.0.into(),
+            ken_burns_speed: 0.0.into(),
+            ken_burns_margin: 0.1.into(),
+            crt_model: CrtModelOptions::None.into(),
             rgb_red_r: 1.0.into(),
             rgb_red_g: 0.0.into(),
             rgb_red_b: 0.0.into(),
@@ -322,7 +770,21 @@ impl Default for Controllers {
             rgb_blue_g: 0.0.into(),
             rgb_blue_b: 1.0.into(),
             color_gamma: 1.0.into(),
+            color_saturation: 1.0.into(),
+            color_hue: 0.0.into(),
+            black_pedestal: 0.0.into(),
+            color_temperature: 6500.0.into(),
+            red_gain: 1.0.into(),
+            red_offset: 0.0.into(),
+            green_gain: 1.0.into(),
+            green_offset: 0.0.into(),
+            blue_gain: 1.0.into(),
+            blue_offset: 0.0.into(),
+            output_gamma: 1.0.into(),
             color_noise: 0.0.into(),
+            analog_static: 0.0.into(),
+            analog_static_speed: 1.0.into(),
+            blending_space: BlendingSpaceOptions::Srgb.into(),
             preset_kind: FilterPresetOptions::Sharp1.into(),
         };
         controllers.preset_crt_aperture_grille_1();
@@ -347,120 +809,609 @@ impl Controllers {
     pub fn preset_sharp_1(&mut self) {
         self.internal_resolution = InternalResolution::default();
         self.texture_interpolation = TextureInterpolationOptions::Linear.into();
+        self.source_interpolation = SourceInterpolationOptions::Linear.into();
+        self.output_interpolation = OutputInterpolationOptions::Linear.into();
+        self.sharpen_strength = 0.0.into();
+        self.sharpen_halo_suppression = 0.5.into();
+        self.output_color_space = OutputColorSpaceOptions::Srgb.into();
+        self.phosphor_primaries = PhosphorPrimariesOptions::Srgb.into();
         self.blur_passes = 0.into();
-        self.vertical_lpp = 1.into();
-        self.horizontal_lpp = 1.into();
+        self.vertical_lpp = 1.0.into();
+        self.horizontal_lpp = 1.0.into();
         self.light_color = 0x00FF_FFFF.into();
         self.brightness_color = 0x00FF_FFFF.into();
         self.extra_bright = 0.0.into();
         self.extra_contrast = 1.0.into();
+        self.contrast_knee = 0.0.into();
+        self.contrast_shoulder = 0.0.into();
         self.cur_pixel_vertical_gap = 0.0.into();
         self.cur_pixel_horizontal_gap = 0.0.into();
         self.cur_pixel_spread = 0.0.into();
+        self.cur_pixel_horizontal_spread = 0.0.into();
+        self.cur_pixel_vertical_spread = 0.0.into();
         self.pixel_shadow_height = 1.0.into();
         self.pixels_geometry_kind = PixelGeometryKindOptions::Squares.into();
+        self.foreground_depth_test = ForegroundDepthTestOptions::Auto.into();
+        self.background_depth_test = BackgroundDepthTestOptions::Off.into();
         self.pixel_shadow_shape_kind = ShadowShape { value: 0 }.into();
         self.color_channels = ColorChannelsOptions::Combined.into();
+        self.color_channels_order = ColorChannelsOrderOptions::Rgb.into();
+        self.overlapping_blend_mode = OverlappingBlendModeOptions::Weighted.into();
+        self.overlapping_gain_red = 1.0.into();
+        self.overlapping_gain_green = 1.0.into();
+        self.overlapping_gain_blue = 1.0.into();
+        self.channel_offset_red_x = 0.0.into();
+        self.channel_offset_red_y = 0.0.into();
+        self.channel_offset_green_x = 0.0.into();
+        self.channel_offset_green_y = 0.0.into();
+        self.channel_offset_blue_x = 0.0.into();
+        self.channel_offset_blue_y = 0.0.into();
+        self.convergence_red_x = 0.0.into();
+        self.convergence_red_y = 0.0.into();
+        self.convergence_green_x = 0.0.into();
+        self.convergence_green_y = 0.0.into();
+        self.convergence_blue_x = 0.0.into();
+        self.convergence_blue_y = 0.0.into();
         self.screen_curvature_kind = ScreenCurvatureKindOptions::Flat.into();
+        self.screen_curvature_amount = 0.0.into();
         self.backlight_percent.value = 0.0;
+        self.layering_blend_mode = LayeringBlendModeOptions::Weighted.into();
+        self.foreground_opacity = 1.0.into();
+        self.background_resolution_scale = 0.5.into();
+        self.background_blur_passes = 6.into();
+        self.analog_static = 0.0.into();
+        self.analog_static_speed = 1.0.into();
+        self.scanline_phase = 0.0.into();
+        self.sync_offset = 0.0.into();
+        self.scanline_orientation = ScanlineOrientationOptions::Horizontal.into();
+        self.scanline_angle = 0.0.into();
+        self.source_rotation = SourceRotationOptions::None.into();
+        self.source_mirror = SourceMirrorOptions::Off.into();
+        self.damper_wires = DamperWiresOptions::Off.into();
+        self.damper_wire_position = (1.0 / 3.0).into();
+        self.damper_wire_opacity = 0.25.into();
+        self.rolling_scan = RollingScanOptions::Off.into();
+        self.rolling_scan_speed = 0.5.into();
+        self.rolling_scan_size = 0.25.into();
+        self.screen_corner_radius = 0.0.into();
+        self.screen_matte = 0.0.into();
+        self.flicker = FlickerOptions::Off.into();
+        self.flicker_amplitude = 0.05.into();
+        self.glass_reflection = GlassReflectionOptions::Off.into();
+        self.glass_reflectivity = 0.04.into();
+        self.blemish_strength = 0.0.into();
+        self.blemish_seed = 1.into();
+        self.dead_subpixels = 0.into();
+        self.stuck_pixels = 0.into();
+        self.dead_scanlines = 0.into();
+        self.bezel_scale = 1.5.into();
+        self.bezel_offset_x = 0.0.into();
+        self.bezel_offset_y = 0.0.into();
+        self.room_environment = RoomEnvironmentOptions::Off.into();
+        self.room_brightness = 0.0.into();
+        self.edge_reflection_intensity = 0.0.into();
+        self.edge_reflection_width = 16.0.into();
+        self.floor_reflection = FloorReflectionOptions::Off.into();
+        self.floor_roughness = 0.5.into();
+        self.blooming_amount = 0.0.into();
+        self.blooming_time_constant = 0.5.into();
+        self.bloom_threshold = 0.7.into();
+        self.bloom_intensity = 0.0.into();
+        self.white_clipping = WhiteClippingOptions::HardClip.into();
+        self.dithering = DitheringOptions::Off.into();
+        self.dithering_bits = 6.into();
+        self.burn_in_rate = 0.0.into();
+        self.burn_in_visibility = 0.25.into();
+        self.vignette_strength = 0.0.into();
+        self.vignette_radius = 0.75.into();
+        self.tv_lines = 0.0.into();
+        self.signal_kind = SignalKindOptions::Rgb.into();
+        self.palette = PaletteOptions::Off.into();
+        self.interlacing = InterlacingOptions::Off.into();
+        self.interlace_field_flicker = 0.0.into();
+        self.interlace_line_pairing = 0.0.into();
+        self.hsync_wobble = 0.0.into();
+        self.hsync_wobble_frequency = 3.0.into();
+        self.response_rise_time = 0.0.into();
+        self.response_fall_time = 0.0.into();
         self.preset_kind = FilterPresetOptions::Sharp1.into();
     }
 
     pub fn preset_crt_aperture_grille_1(&mut self) {
         self.internal_resolution = InternalResolution::default();
         self.texture_interpolation = TextureInterpolationOptions::Linear.into();
+        self.source_interpolation = SourceInterpolationOptions::Linear.into();
+        self.output_interpolation = OutputInterpolationOptions::Linear.into();
+        self.sharpen_strength = 0.0.into();
+        self.sharpen_halo_suppression = 0.5.into();
+        self.output_color_space = OutputColorSpaceOptions::Srgb.into();
+        self.phosphor_primaries = PhosphorPrimariesOptions::Srgb.into();
         self.blur_passes = 1.into();
-        self.vertical_lpp = 3.into();
-        self.horizontal_lpp = 1.into();
+        self.vertical_lpp = 3.0.into();
+        self.horizontal_lpp = 1.0.into();
         self.light_color = 0x00FF_FFFF.into();
         self.brightness_color = 0x00FF_FFFF.into();
         self.extra_bright = 0.0.into();
         self.extra_contrast = 1.0.into();
+        self.contrast_knee = 0.0.into();
+        self.contrast_shoulder = 0.0.into();
         self.cur_pixel_vertical_gap = 0.0.into();
         self.cur_pixel_horizontal_gap = 0.0.into();
         self.cur_pixel_spread = 0.0.into();
+        self.cur_pixel_horizontal_spread = 0.0.into();
+        self.cur_pixel_vertical_spread = 0.0.into();
         self.pixel_shadow_height = 0.0.into();
         self.pixels_geometry_kind = PixelGeometryKindOptions::Squares.into();
+        self.foreground_depth_test = ForegroundDepthTestOptions::Auto.into();
+        self.background_depth_test = BackgroundDepthTestOptions::Off.into();
         self.pixel_shadow_shape_kind = ShadowShape { value: 3 }.into();
         self.color_channels = ColorChannelsOptions::Combined.into();
+        self.color_channels_order = ColorChannelsOrderOptions::Rgb.into();
+        self.overlapping_blend_mode = OverlappingBlendModeOptions::Weighted.into();
+        self.overlapping_gain_red = 1.0.into();
+        self.overlapping_gain_green = 1.0.into();
+        self.overlapping_gain_blue = 1.0.into();
+        self.channel_offset_red_x = 0.0.into();
+        self.channel_offset_red_y = 0.0.into();
+        self.channel_offset_green_x = 0.0.into();
+        self.channel_offset_green_y = 0.0.into();
+        self.channel_offset_blue_x = 0.0.into();
+        self.channel_offset_blue_y = 0.0.into();
+        self.convergence_red_x = 0.0.into();
+        self.convergence_red_y = 0.0.into();
+        self.convergence_green_x = 0.0.into();
+        self.convergence_green_y = 0.0.into();
+        self.convergence_blue_x = 0.0.into();
+        self.convergence_blue_y = 0.0.into();
         self.screen_curvature_kind = ScreenCurvatureKindOptions::Flat.into();
+        self.screen_curvature_amount = 0.0.into();
         self.backlight_percent.value = 0.5;
+        self.layering_blend_mode = LayeringBlendModeOptions::Weighted.into();
+        self.foreground_opacity = 1.0.into();
+        self.background_resolution_scale = 0.5.into();
+        self.background_blur_passes = 6.into();
+        self.analog_static = 0.0.into();
+        self.analog_static_speed = 1.0.into();
+        self.scanline_phase = 0.0.into();
+        self.sync_offset = 0.0.into();
+        self.scanline_orientation = ScanlineOrientationOptions::Horizontal.into();
+        self.scanline_angle = 0.0.into();
+        self.source_rotation = SourceRotationOptions::None.into();
+        self.source_mirror = SourceMirrorOptions::Off.into();
+        self.damper_wires = DamperWiresOptions::Two.into();
+        self.damper_wire_position = (1.0 / 3.0).into();
+        self.damper_wire_opacity = 0.25.into();
+        self.rolling_scan = RollingScanOptions::Off.into();
+        self.rolling_scan_speed = 0.5.into();
+        self.rolling_scan_size = 0.25.into();
+        self.screen_corner_radius = 0.0.into();
+        self.screen_matte = 0.0.into();
+        self.flicker = FlickerOptions::Off.into();
+        self.flicker_amplitude = 0.05.into();
+        self.glass_reflection = GlassReflectionOptions::Off.into();
+        self.glass_reflectivity = 0.04.into();
+        self.blemish_strength = 0.1.into();
+        self.blemish_seed = 1.into();
+        self.dead_subpixels = 0.into();
+        self.stuck_pixels = 0.into();
+        self.dead_scanlines = 0.into();
+        self.bezel_scale = 1.5.into();
+        self.bezel_offset_x = 0.0.into();
+        self.bezel_offset_y = 0.0.into();
+        self.room_environment = RoomEnvironmentOptions::Off.into();
+        self.room_brightness = 0.0.into();
+        self.edge_reflection_intensity = 0.0.into();
+        self.edge_reflection_width = 16.0.into();
+        self.floor_reflection = FloorReflectionOptions::Off.into();
+        self.floor_roughness = 0.5.into();
+        self.blooming_amount = 0.0.into();
+        self.blooming_time_constant = 0.5.into();
+        self.bloom_threshold = 0.7.into();
+        self.bloom_intensity = 0.0.into();
+        self.white_clipping = WhiteClippingOptions::HardClip.into();
+        self.dithering = DitheringOptions::Off.into();
+        self.dithering_bits = 6.into();
+        self.burn_in_rate = 0.0.into();
+        self.burn_in_visibility = 0.25.into();
+        self.vignette_strength = 0.0.into();
+        self.vignette_radius = 0.75.into();
+        self.tv_lines = 0.0.into();
+        self.signal_kind = SignalKindOptions::Rgb.into();
+        self.palette = PaletteOptions::Off.into();
+        self.interlacing = InterlacingOptions::Off.into();
+        self.interlace_field_flicker = 0.0.into();
+        self.interlace_line_pairing = 0.0.into();
+        self.hsync_wobble = 0.0.into();
+        self.hsync_wobble_frequency = 3.0.into();
+        self.response_rise_time = 0.0.into();
+        self.response_fall_time = 0.0.into();
         self.preset_kind = FilterPresetOptions::CrtApertureGrille1.into();
     }
 
     pub fn preset_crt_shadow_mask_1(&mut self) {
         self.internal_resolution = InternalResolution::default();
         self.texture_interpolation = TextureInterpolationOptions::Linear.into();
+        self.source_interpolation = SourceInterpolationOptions::Linear.into();
+        self.output_interpolation = OutputInterpolationOptions::Linear.into();
+        self.sharpen_strength = 0.0.into();
+        self.sharpen_halo_suppression = 0.5.into();
+        self.output_color_space = OutputColorSpaceOptions::Srgb.into();
+        self.phosphor_primaries = PhosphorPrimariesOptions::Srgb.into();
         self.blur_passes = 2.into();
-        self.vertical_lpp = 2.into();
-        self.horizontal_lpp = 2.into();
+        self.vertical_lpp = 2.0.into();
+        self.horizontal_lpp = 2.0.into();
         self.light_color = 0x00FF_FFFF.into();
         self.brightness_color = 0x00FF_FFFF.into();
         self.extra_bright = 0.05.into();
         self.extra_contrast = 1.2.into();
+        self.contrast_knee = 0.0.into();
+        self.contrast_shoulder = 0.0.into();
         self.cur_pixel_vertical_gap = 0.5.into();
         self.cur_pixel_horizontal_gap = 0.5.into();
         self.cur_pixel_spread = 0.0.into();
+        self.cur_pixel_horizontal_spread = 0.0.into();
+        self.cur_pixel_vertical_spread = 0.0.into();
         self.pixel_shadow_height = 1.0.into();
         self.pixels_geometry_kind = PixelGeometryKindOptions::Squares.into();
+        self.foreground_depth_test = ForegroundDepthTestOptions::Auto.into();
+        self.background_depth_test = BackgroundDepthTestOptions::Off.into();
         self.pixel_shadow_shape_kind = ShadowShape { value: 3 }.into();
         self.color_channels = ColorChannelsOptions::Combined.into();
+        self.color_channels_order = ColorChannelsOrderOptions::Rgb.into();
+        self.overlapping_blend_mode = OverlappingBlendModeOptions::Weighted.into();
+        self.overlapping_gain_red = 1.0.into();
+        self.overlapping_gain_green = 1.0.into();
+        self.overlapping_gain_blue = 1.0.into();
+        self.channel_offset_red_x = 0.0.into();
+        self.channel_offset_red_y = 0.0.into();
+        self.channel_offset_green_x = 0.0.into();
+        self.channel_offset_green_y = 0.0.into();
+        self.channel_offset_blue_x = 0.0.into();
+        self.channel_offset_blue_y = 0.0.into();
+        self.convergence_red_x = 0.0.into();
+        self.convergence_red_y = 0.0.into();
+        self.convergence_green_x = 0.0.into();
+        self.convergence_green_y = 0.0.into();
+        self.convergence_blue_x = 0.0.into();
+        self.convergence_blue_y = 0.0.into();
         self.screen_curvature_kind = ScreenCurvatureKindOptions::Flat.into();
+        self.screen_curvature_amount = 0.0.into();
         self.backlight_percent.value = 0.25;
+        self.layering_blend_mode = LayeringBlendModeOptions::Weighted.into();
+        self.foreground_opacity = 1.0.into();
+        self.background_resolution_scale = 0.5.into();
+        self.background_blur_passes = 6.into();
+        self.analog_static = 0.0.into();
+        self.analog_static_speed = 1.0.into();
+        self.scanline_phase = 0.0.into();
+        self.sync_offset = 0.0.into();
+        self.scanline_orientation = ScanlineOrientationOptions::Horizontal.into();
+        self.scanline_angle = 0.0.into();
+        self.source_rotation = SourceRotationOptions::None.into();
+        self.source_mirror = SourceMirrorOptions::Off.into();
+        self.damper_wires = DamperWiresOptions::Off.into();
+        self.damper_wire_position = (1.0 / 3.0).into();
+        self.damper_wire_opacity = 0.25.into();
+        self.rolling_scan = RollingScanOptions::Off.into();
+        self.rolling_scan_speed = 0.5.into();
+        self.rolling_scan_size = 0.25.into();
+        self.screen_corner_radius = 0.0.into();
+        self.screen_matte = 0.0.into();
+        self.flicker = FlickerOptions::Off.into();
+        self.flicker_amplitude = 0.05.into();
+        self.glass_reflection = GlassReflectionOptions::Off.into();
+        self.glass_reflectivity = 0.04.into();
+        self.blemish_strength = 0.1.into();
+        self.blemish_seed = 2.into();
+        self.dead_subpixels = 0.into();
+        self.stuck_pixels = 0.into();
+        self.dead_scanlines = 0.into();
+        self.bezel_scale = 1.5.into();
+        self.bezel_offset_x = 0.0.into();
+        self.bezel_offset_y = 0.0.into();
+        self.room_environment = RoomEnvironmentOptions::Off.into();
+        self.room_brightness = 0.0.into();
+        self.edge_reflection_intensity = 0.0.into();
+        self.edge_reflection_width = 16.0.into();
+        self.floor_reflection = FloorReflectionOptions::Off.into();
+        self.floor_roughness = 0.5.into();
+        self.blooming_amount = 0.0.into();
+        self.blooming_time_constant = 0.5.into();
+        self.bloom_threshold = 0.7.into();
+        self.bloom_intensity = 0.0.into();
+        self.white_clipping = WhiteClippingOptions::HardClip.into();
+        self.dithering = DitheringOptions::Off.into();
+        self.dithering_bits = 6.into();
+        self.burn_in_rate = 0.0.into();
+        self.burn_in_visibility = 0.25.into();
+        self.vignette_strength = 0.0.into();
+        self.vignette_radius = 0.75.into();
+        self.tv_lines = 0.0.into();
+        self.signal_kind = SignalKindOptions::Rgb.into();
+        self.palette = PaletteOptions::Off.into();
+        self.interlacing = InterlacingOptions::Off.into();
+        self.interlace_field_flicker = 0.0.into();
+        self.interlace_line_pairing = 0.0.into();
+        self.hsync_wobble = 0.0.into();
+        self.hsync_wobble_frequency = 3.0.into();
+        self.response_rise_time = 0.0.into();
+        self.response_fall_time = 0.0.into();
         self.preset_kind = FilterPresetOptions::CrtShadowMask1.into();
     }
 
     pub fn preset_crt_shadow_mask_2(&mut self) {
         self.internal_resolution = InternalResolution::default();
         self.texture_interpolation = TextureInterpolationOptions::Linear.into();
+        self.source_interpolation = SourceInterpolationOptions::Linear.into();
+        self.output_interpolation = OutputInterpolationOptions::Linear.into();
+        self.sharpen_strength = 0.0.into();
+        self.sharpen_halo_suppression = 0.5.into();
+        self.output_color_space = OutputColorSpaceOptions::Srgb.into();
+        self.phosphor_primaries = PhosphorPrimariesOptions::Srgb.into();
         self.blur_passes = 2.into();
-        self.vertical_lpp = 1.into();
-        self.horizontal_lpp = 2.into();
+        self.vertical_lpp = 1.0.into();
+        self.horizontal_lpp = 2.0.into();
         self.light_color = 0x00FF_FFFF.into();
         self.brightness_color = 0x00FF_FFFF.into();
         self.extra_bright = 0.05.into();
         self.extra_contrast = 1.2.into();
+        self.contrast_knee = 0.0.into();
+        self.contrast_shoulder = 0.0.into();
         self.cur_pixel_vertical_gap = 1.0.into();
         self.cur_pixel_horizontal_gap = 0.5.into();
         self.cur_pixel_spread = 0.0.into();
+        self.cur_pixel_horizontal_spread = 0.0.into();
+        self.cur_pixel_vertical_spread = 0.0.into();
         self.pixel_shadow_height = 1.0.into();
         self.pixels_geometry_kind = PixelGeometryKindOptions::Squares.into();
+        self.foreground_depth_test = ForegroundDepthTestOptions::Auto.into();
+        self.background_depth_test = BackgroundDepthTestOptions::Off.into();
         self.pixel_shadow_shape_kind = ShadowShape { value: 3 }.into();
         self.color_channels = ColorChannelsOptions::Combined.into();
+        self.color_channels_order = ColorChannelsOrderOptions::Rgb.into();
+        self.overlapping_blend_mode = OverlappingBlendModeOptions::Weighted.into();
+        self.overlapping_gain_red = 1.0.into();
+        self.overlapping_gain_green = 1.0.into();
+        self.overlapping_gain_blue = 1.0.into();
+        self.channel_offset_red_x = 0.0.into();
+        self.channel_offset_red_y = 0.0.into();
+        self.channel_offset_green_x = 0.0.into();
+        self.channel_offset_green_y = 0.0.into();
+        self.channel_offset_blue_x = 0.0.into();
+        self.channel_offset_blue_y = 0.0.into();
+        self.convergence_red_x = 0.0.into();
+        self.convergence_red_y = 0.0.into();
+        self.convergence_green_x = 0.0.into();
+        self.convergence_green_y = 0.0.into();
+        self.convergence_blue_x = 0.0.into();
+        self.convergence_blue_y = 0.0.into();
         self.screen_curvature_kind = ScreenCurvatureKindOptions::Flat.into();
+        self.screen_curvature_amount = 0.0.into();
         self.backlight_percent.value = 0.4;
+        self.layering_blend_mode = LayeringBlendModeOptions::Weighted.into();
+        self.foreground_opacity = 1.0.into();
+        self.background_resolution_scale = 0.5.into();
+        self.background_blur_passes = 6.into();
+        self.analog_static = 0.0.into();
+        self.analog_static_speed = 1.0.into();
+        self.scanline_phase = 0.0.into();
+        self.sync_offset = 0.0.into();
+        self.scanline_orientation = ScanlineOrientationOptions::Horizontal.into();
+        self.scanline_angle = 0.0.into();
+        self.source_rotation = SourceRotationOptions::None.into();
+        self.source_mirror = SourceMirrorOptions::Off.into();
+        self.damper_wires = DamperWiresOptions::Off.into();
+        self.damper_wire_position = (1.0 / 3.0).into();
+        self.damper_wire_opacity = 0.25.into();
+        self.rolling_scan = RollingScanOptions::Off.into();
+        self.rolling_scan_speed = 0.5.into();
+        self.rolling_scan_size = 0.25.into();
+        self.screen_corner_radius = 0.0.into();
+        self.screen_matte = 0.0.into();
+        self.flicker = FlickerOptions::Off.into();
+        self.flicker_amplitude = 0.05.into();
+        self.glass_reflection = GlassReflectionOptions::Off.into();
+        self.glass_reflectivity = 0.04.into();
+        self.blemish_strength = 0.15.into();
+        self.blemish_seed = 3.into();
+        self.dead_subpixels = 0.into();
+        self.stuck_pixels = 0.into();
+        self.dead_scanlines = 0.into();
+        self.bezel_scale = 1.5.into();
+        self.bezel_offset_x = 0.0.into();
+        self.bezel_offset_y = 0.0.into();
+        self.room_environment = RoomEnvironmentOptions::Off.into();
+        self.room_brightness = 0.0.into();
+        self.edge_reflection_intensity = 0.0.into();
+        self.edge_reflection_width = 16.0.into();
+        self.floor_reflection = FloorReflectionOptions::Off.into();
+        self.floor_roughness = 0.5.into();
+        self.blooming_amount = 0.0.into();
+        self.blooming_time_constant = 0.5.into();
+        self.bloom_threshold = 0.7.into();
+        self.bloom_intensity = 0.0.into();
+        self.white_clipping = WhiteClippingOptions::HardClip.into();
+        self.dithering = DitheringOptions::Off.into();
+        self.dithering_bits = 6.into();
+        self.burn_in_rate = 0.0.into();
+        self.burn_in_visibility = 0.25.into();
+        self.vignette_strength = 0.0.into();
+        self.vignette_radius = 0.75.into();
+        self.tv_lines = 0.0.into();
+        self.signal_kind = SignalKindOptions::Rgb.into();
+        self.palette = PaletteOptions::Off.into();
+        self.interlacing = InterlacingOptions::Off.into();
+        self.interlace_field_flicker = 0.0.into();
+        self.interlace_line_pairing = 0.0.into();
+        self.hsync_wobble = 0.0.into();
+        self.hsync_wobble_frequency = 3.0.into();
+        self.response_rise_time = 0.0.into();
+        self.response_fall_time = 0.0.into();
         self.preset_kind = FilterPresetOptions::CrtShadowMask2.into();
     }
 
     pub fn preset_demo_1(&mut self) {
         self.internal_resolution = InternalResolution::default();
         self.texture_interpolation = TextureInterpolationOptions::Linear.into();
+        self.source_interpolation = SourceInterpolationOptions::Linear.into();
+        self.output_interpolation = OutputInterpolationOptions::Linear.into();
+        self.sharpen_strength = 0.0.into();
+        self.sharpen_halo_suppression = 0.5.into();
+        self.output_color_space = OutputColorSpaceOptions::Srgb.into();
+        self.phosphor_primaries = PhosphorPrimariesOptions::Srgb.into();
         self.blur_passes = 0.into();
-        self.vertical_lpp = 1.into();
-        self.horizontal_lpp = 1.into();
+        self.vertical_lpp = 1.0.into();
+        self.horizontal_lpp = 1.0.into();
         self.brightness_color = 0x00FF_FFFF.into();
         self.extra_bright = 0.0.into();
         self.extra_contrast = 1.0.into();
+        self.contrast_knee = 0.0.into();
+        self.contrast_shoulder = 0.0.into();
         self.cur_pixel_vertical_gap = 0.0.into();
         self.cur_pixel_horizontal_gap = 0.0.into();
         self.cur_pixel_spread = 1.0.into();
+        self.cur_pixel_horizontal_spread = 0.0.into();
+        self.cur_pixel_vertical_spread = 0.0.into();
         self.pixel_shadow_height = 1.0.into();
         self.pixels_geometry_kind = PixelGeometryKindOptions::Cubes.into();
+        self.foreground_depth_test = ForegroundDepthTestOptions::Auto.into();
+        self.background_depth_test = BackgroundDepthTestOptions::Off.into();
         self.pixel_shadow_shape_kind = ShadowShape { value: 0 }.into();
         self.color_channels = ColorChannelsOptions::Combined.into();
+        self.color_channels_order = ColorChannelsOrderOptions::Rgb.into();
+        self.overlapping_blend_mode = OverlappingBlendModeOptions::Weighted.into();
+        self.overlapping_gain_red = 1.0.into();
+        self.overlapping_gain_green = 1.0.into();
+        self.overlapping_gain_blue = 1.0.into();
+        self.channel_offset_red_x = 0.0.into();
+        self.channel_offset_red_y = 0.0.into();
+        self.channel_offset_green_x = 0.0.into();
+        self.channel_offset_green_y = 0.0.into();
+        self.channel_offset_blue_x = 0.0.into();
+        self.channel_offset_blue_y = 0.0.into();
+        self.convergence_red_x = 0.0.into();
+        self.convergence_red_y = 0.0.into();
+        self.convergence_green_x = 0.0.into();
+        self.convergence_green_y = 0.0.into();
+        self.convergence_blue_x = 0.0.into();
+        self.convergence_blue_y = 0.0.into();
         self.screen_curvature_kind = ScreenCurvatureKindOptions::Pulse.into();
+        self.screen_curvature_amount = 0.0.into();
         self.backlight_percent.value = 0.2;
+        self.layering_blend_mode = LayeringBlendModeOptions::Weighted.into();
+        self.foreground_opacity = 1.0.into();
+        self.background_resolution_scale = 0.5.into();
+        self.background_blur_passes = 6.into();
+        self.analog_static = 0.0.into();
+        self.analog_static_speed = 1.0.into();
+        self.scanline_phase = 0.0.into();
+        self.sync_offset = 0.0.into();
+        self.scanline_orientation = ScanlineOrientationOptions::Horizontal.into();
+        self.scanline_angle = 0.0.into();
+        self.source_rotation = SourceRotationOptions::None.into();
+        self.source_mirror = SourceMirrorOptions::Off.into();
+        self.damper_wires = DamperWiresOptions::Off.into();
+        self.damper_wire_position = (1.0 / 3.0).into();
+        self.damper_wire_opacity = 0.25.into();
+        self.rolling_scan = RollingScanOptions::Off.into();
+        self.rolling_scan_speed = 0.5.into();
+        self.rolling_scan_size = 0.25.into();
+        self.screen_corner_radius = 0.0.into();
+        self.screen_matte = 0.0.into();
+        self.flicker = FlickerOptions::Off.into();
+        self.flicker_amplitude = 0.05.into();
+        self.glass_reflection = GlassReflectionOptions::Off.into();
+        self.glass_reflectivity = 0.04.into();
+        self.blemish_strength = 0.0.into();
+        self.blemish_seed = 1.into();
+        self.dead_subpixels = 0.into();
+        self.stuck_pixels = 0.into();
+        self.dead_scanlines = 0.into();
+        self.bezel_scale = 1.5.into();
+        self.bezel_offset_x = 0.0.into();
+        self.bezel_offset_y = 0.0.into();
+        self.room_environment = RoomEnvironmentOptions::Off.into();
+        self.room_brightness = 0.0.into();
+        self.edge_reflection_intensity = 0.0.into();
+        self.edge_reflection_width = 16.0.into();
+        self.floor_reflection = FloorReflectionOptions::Off.into();
+        self.floor_roughness = 0.5.into();
+        self.blooming_amount = 0.0.into();
+        self.blooming_time_constant = 0.5.into();
+        self.bloom_threshold = 0.7.into();
+        self.bloom_intensity = 0.0.into();
+        self.white_clipping = WhiteClippingOptions::HardClip.into();
+        self.dithering = DitheringOptions::Off.into();
+        self.dithering_bits = 6.into();
+        self.burn_in_rate = 0.0.into();
+        self.burn_in_visibility = 0.25.into();
+        self.vignette_strength = 0.0.into();
+        self.vignette_radius = 0.75.into();
+        self.tv_lines = 0.0.into();
+        self.signal_kind = SignalKindOptions::Rgb.into();
+        self.palette = PaletteOptions::Off.into();
+        self.interlacing = InterlacingOptions::Off.into();
+        self.interlace_field_flicker = 0.0.into();
+        self.interlace_line_pairing = 0.0.into();
+        self.hsync_wobble = 0.0.into();
+        self.hsync_wobble_frequency = 3.0.into();
+        self.response_rise_time = 0.0.into();
+        self.response_fall_time = 0.0.into();
         self.preset_kind = FilterPresetOptions::DemoFlight1.into();
     }
 
     pub fn preset_custom(&mut self) {
         self.preset_kind = FilterPresetOptions::Custom.into();
     }
+
+    pub fn apply_crt_model(&mut self, spec: &CrtModelSpec) {
+        self.apply_crt_mask(spec.mask);
+        self.pixel_shadow_shape_kind = ShadowShape { value: 3 }.into();
+        self.screen_curvature_kind = spec.curvature.into();
+        self.screen_curvature_amount = 0.0.into();
+        self.dot_pitch = spec.dot_pitch_mm.into();
+        self.response_fall_time = spec.phosphor_decay_ms.into();
+        // The white point belongs to the color temperature now, the light color would tint it a second time.
+        self.color_temperature = spec.white_point_kelvin.into();
+        self.light_color = 0x00FF_FFFF.into();
+    }
+
+    pub fn apply_crt_mask(&mut self, mask: CrtMaskKind) {
+        if let PixelGeometryKindOptions::ApertureGrille = self.pixels_geometry_kind.value {
+            self.pixels_geometry_kind = PixelGeometryKindOptions::Squares.into();
+        }
+        match mask {
+            CrtMaskKind::ApertureGrille => {
+                self.pixels_geometry_kind = PixelGeometryKindOptions::ApertureGrille.into();
+                self.color_channels = ColorChannelsOptions::Combined.into();
+                self.vertical_lpp = 1.0.into();
+                self.horizontal_lpp = 1.0.into();
+                self.cur_pixel_vertical_gap = 0.0.into();
+                self.cur_pixel_horizontal_gap = 0.0.into();
+            }
+            CrtMaskKind::ShadowMask => {
+                self.vertical_lpp = 2.0.into();
+                self.horizontal_lpp = 2.0.into();
+                self.cur_pixel_vertical_gap = 0.5.into();
+                self.cur_pixel_horizontal_gap = 0.5.into();
+            }
+            CrtMaskKind::SlotMask => {
+                self.vertical_lpp = 1.0.into();
+                self.horizontal_lpp = 2.0.into();
+                self.cur_pixel_vertical_gap = 1.0.into();
+                self.cur_pixel_horizontal_gap = 0.5.into();
+            }
+        }
+    }
 }
 
 #[derive(Default)]
 pub struct ViewModel {
     pub screen_curvature_factor: f32,
+    /// How much the curvature bends each axis, [1, 1] for a spherical tube and [1, 0] for a cylindrical one.
+    pub screen_curvature_axes: [f32; 2],
+    /// Strength of the barrel distortion done over the final picture, negative for a pincushion, 0 when the curvature is left to the geometry.
+    pub screen_barrel: f32,
     pub pixels_pulse: f32,
     pub color_splits: usize,
     pub light_color: [[f32; 3]; 3],
@@ -468,8 +1419,91 @@ pub struct ViewModel {
     pub extra_light: [f32; 3],
     pub ambient_strength: f32,
     pub pixel_have_depth: bool,
+    pub background_have_depth: bool,
     pub pixel_spread: [f32; 2],
+    pub pixel_rotation: [f32; 2],
+    pub source_transform: [f32; 4],
+    pub damper_wire_y: [f32; 2],
+    pub damper_wire_opacity: f32,
+    pub damper_wire_width: f32,
+    /// Center and half height of the rolling scan band, height of the raster and how much it darkens outside of the band.
+    pub rolling_scan: [f32; 4],
+    /// Half width and half height of the visible raster inside the matte, corner radius and edge softness, softness 0 skips it.
+    pub screen_matte: [f32; 4],
+    /// Fraction of the luminance lost in the final pass of this frame.
+    pub flicker: f32,
+    pub white_clipping: WhiteClippingOptions,
+    /// Threshold and intensity of the bloom pass, intensity 0 skips it.
+    pub bloom: [f32; 2],
+    pub dithering: DitheringOptions,
+    /// Highest value per channel the dithering quantizes to, 63 for a 6-bit panel.
+    pub dithering_levels: f32,
+    /// Blend of this frame into the burn-in buffer and how much of the buffer ghosts over the picture.
+    pub burn_in: [f32; 2],
+    /// Density of the analog static, random seed of this frame and vertical position of the noise bars, density 0 skips the pass.
+    pub analog_static: [f32; 3],
+    /// Gains per channel applied in the final pass, all 1 at the neutral color temperature.
+    pub white_point: [f32; 3],
+    /// Gamma the final pass encodes the image with, 1 leaves it untouched.
+    pub output_gamma: f32,
+    /// How much the final pass crushes the shadows (knee) and rolls off the highlights (shoulder), 0 keeps them linear.
+    pub contrast_curve: [f32; 2],
+    /// Matrix from the phosphor primaries to the output ones in linear RGB, none leaves the colors as they are.
+    pub phosphor_matrix: Option<[f32; 9]>,
+    /// Reflectivity of the front glass facing the camera, 0 when the glass isn't drawn.
+    pub glass_reflectivity: f32,
+    /// Colors the glass reflects from above and from below.
+    pub glass_environment: [[f32; 3]; 2],
+    /// Half width, half height and depth of the glass.
+    pub glass_size: [f32; 3],
+    /// Phosphor aging strength and width of the scratches, 0 strength means a flawless monitor.
+    pub blemish: [f32; 2],
+    pub blemish_seed: i32,
+    pub blemish_scratches: [f32; BLEMISH_SCRATCHES * 4],
+    pub scratch_settings: Option<ScratchSettings>,
+    pub defect_settings: DefectSettings,
+    /// Built from the defect settings, empty when the monitor is flawless.
+    pub defect_mask: Vec<u8>,
+    /// Bumped every time the mask is rebuilt, so it's only uploaded again when it changes.
+    pub defect_mask_version: u32,
+    /// Triangles of the monitor shell as position and normal, empty when the case layer isn't shown.
+    pub case_mesh: Vec<f32>,
+    pub case_settings: Option<CaseSettings>,
+    /// Changes every time the case mesh is rebuilt or cleared, so the renderer only uploads it then.
+    pub case_mesh_version: u32,
+    /// Center of the bezel artwork and its height, in world units. The width follows the aspect ratio of the artwork.
+    pub bezel_position: [f32; 3],
+    pub bezel_height: f32,
+    /// Triangles of the room behind the monitor as position and color, empty when there is no room.
+    pub room_mesh: Vec<f32>,
+    pub room_settings: Option<RoomSettings>,
+    /// Changes every time the room mesh is rebuilt, so the renderer only uploads it then.
+    pub room_mesh_version: u32,
+    pub room_light: [f32; 3],
+    pub case_color: [f32; 3],
+    pub edge_reflections: Vec<EdgeReflection>,
+    pub light_color_edge_reflection: [f32; 3],
+    pub showing_floor_reflection: bool,
+    pub light_color_floor_reflection: [f32; 3],
+    pub floor_blur_passes: usize,
+    pub floor_y: f32,
+    pub horizontal_band_limit: f32,
+    pub signal: CompositeSignal,
+    /// Colors the source is quantized to before anything else touches it, empty when not quantizing.
+    pub palette: PaletteColors,
+    /// Brightness of the field that isn't scanned in the current frame, 1 when not interlacing.
+    pub interlace_other_field: f32,
+    /// How far the lines of the odd field sag towards the even ones, in rows. 0 when not interlacing.
+    pub interlace_line_pairing: f32,
+    /// Amplitude in source pixels of the horizontal sync wobble, noise cells per row, and how far the noise has drifted. Amplitude 0 keeps the lines still.
+    pub hsync_wobble: [f32; 3],
+    /// Transition playing over the source, Off when there is none.
+    pub transition: TransitionOptions,
+    pub transition_progress: f32,
+    pub response_blend_factors: [f32; 2],
     pub pixel_scale_base: [f32; 3],
+    /// Source pixels every odd row is shifted to the right, so hex-packed pixels fit between the ones of the rows around.
+    pub pixel_row_stagger: f32,
     pub height_modifier_factor: f32,
     pub pixel_scale_foreground: Vec<[[f32; 3]; 3]>,
     pub pixel_offset_foreground: Vec<[[f32; 3]; 3]>,
@@ -479,9 +1513,37 @@ pub struct ViewModel {
     pub rgb_green: [f32; 3],
     pub rgb_blue: [f32; 3],
     pub color_gamma: f32,
+    /// Offset added to the source signal, as a fraction of its full range.
+    pub black_pedestal: f32,
+    pub color_saturation: f32,
+    /// Rotation of the chroma, in radians.
+    pub color_hue: f32,
+    /// Drive and bias of each gun, applied after the color matrix.
+    pub channel_gain: [f32; 3],
+    pub channel_offset: [f32; 3],
     pub color_noise: f32,
+    /// Offset of the red, green and blue beams, in source pixels.
+    pub convergence: [[f32; 2]; 3],
+    pub linear_blending: bool,
     pub showing_background: bool,
-    pub time: f64,
+    pub layers: Vec<Layer>,
+    pub light_gun_flash: bool,
+    pub magnet_center: [f32; 2],
+    pub magnet_radius: f32,
+    pub magnet_strength: f32,
+    pub noise_seed: i32,
+    /// Text drawn over the final picture, top left, one entry per line.
+    pub hud_lines: Vec<String>,
+}
+
+// A mirrored copy of the picture drawn beyond one of the raster edges, `edge` holding the outward normal,
+// the distance from the center to that edge and the width of the band where the copy is visible.
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub struct EdgeReflection {
+    pub source_transform: [f32; 4],
+    pub pixel_scale: [f32; 3],
+    pub pixel_offset: [f32; 3],
+    pub edge: [f32; 4],
 }
 
 #[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone)]
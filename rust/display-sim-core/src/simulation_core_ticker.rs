@@ -13,23 +13,48 @@
  * You should have received a copy of the GNU Affero General Public License
  * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
 
+use crate::app_events::{MessageCategory, SoundEffect};
+use crate::blemishes::{scratch_lines, ScratchSettings};
+use crate::blind_test::BlindTestSide;
 use crate::boolean_actions::{trigger_hotkey_action, ActionUsed};
-use crate::camera::{CameraData, CameraDirection, CameraLockMode, CameraSystem};
+use crate::camera::{dispatch_zoom, CameraData, CameraDirection, CameraLockMode, CameraSystem};
+use crate::defects::{build_defect_mask, DefectSettings};
+use crate::diagnostics::measure_diagnostics;
 use crate::field_changer::FieldChanger;
-use crate::general_types::{get_3_f32color_from_int, get_int_from_3_f32color, Size2D};
+use crate::general_types::OptionCursor;
+use crate::general_types::{get_3_f32color_from_int, get_int_from_3_f32color, get_primaries_conversion_matrix, get_white_point_gains, Size2D};
+use crate::input_replay::InputLog;
 use crate::input_types::{Input, InputEventValue};
-use crate::math::gcd;
+use crate::math::{
+    calculate_degauss_wobble, calculate_light_gun_hit, calculate_physical_distance, calculate_screen_plane_hit, calculate_screen_surface_z, gcd, radians,
+};
+use crate::moire::{build_moire_sweep, recommend_moire_candidate, MoireAdvance, MoireCandidate, MoireReport};
+use crate::monitor_case::{monitor_case_mesh, CaseSettings};
+use crate::procedural_sources::{ProceduralSourceState, PROCEDURAL_SOURCE_SIZE};
+use crate::response_time::response_blend_factor;
+use crate::room::{room_mesh, RoomSettings};
+use crate::screenshot_bracket::{build_bracket_sweep, BracketAdvance};
+use crate::settings_menu::settings_menu_entries;
 use crate::simulation_context::SimulationContext;
 use crate::simulation_core_state::{
-    Controllers, InitialParameters, LatestCustomScalingChange, Resources, ScalingMethod, MOVEMENT_BASE_SPEED, MOVEMENT_SPEED_FACTOR,
-    PIXEL_MANIPULATION_BASE_SPEED, TURNING_BASE_SPEED,
+    Controllers, EdgeReflection, InitialParameters, LatestCustomScalingChange, Resources, ScalingMethod, CRT_HUM_VOLUME, CRT_STATIC_MAX_VOLUME,
+    MAGNET_DEGAUSS_FREQUENCY, MAGNET_DEGAUSS_SECONDS, MAGNET_RAMP_SPEED, MOVEMENT_BASE_SPEED, MOVEMENT_SPEED_FACTOR, PIXEL_MANIPULATION_BASE_SPEED,
+    TURNING_BASE_SPEED,
 };
+use crate::turntable::TurntableAdvance;
+use crate::tv_lines::band_limit_sigma;
 use crate::ui_controller::{
-    color_channels::ColorChannelsOptions, filter_preset::FilterPresetOptions, internal_resolution::InternalResolution,
-    pixel_geometry_kind::PixelGeometryKindOptions, screen_curvature_kind::ScreenCurvatureKindOptions, UiController,
+    black_pedestal::NTSC_SETUP_IRE, blending_space::BlendingSpaceOptions, camera_collision::CameraCollisionOptions, color_channels::ColorChannelsOptions,
+    crt_sounds::CrtSoundsOptions, damper_wires::DamperWiresOptions, diagnostics_overlay::DiagnosticsOverlayOptions, filter_preset::FilterPresetOptions,
+    floor_reflection::FloorReflectionOptions, glass_reflection::GlassReflectionOptions, interlacing::InterlacingOptions,
+    internal_resolution::InternalResolution, layers::LayerKind, light_gun::LightGunOptions, moire_assistant::MoireAssistantOptions, palette::PaletteOptions,
+    physical_calibration::PhysicalCalibrationOptions, pixel_geometry_kind::PixelGeometryKindOptions, procedural_source::ProceduralSourceOptions,
+    rolling_scan::RollingScanOptions, screen_curvature_kind::ScreenCurvatureKindOptions, signal_kind::SignalKindOptions, status_bar::StatusBarOptions,
+    subtitles::SubtitlesOptions, transition::TransitionOptions, white_clipping::WhiteClippingOptions, UiController,
 };
 use app_error::AppResult;
 use derive_new::new;
+use std::str::FromStr;
 
 #[derive(new)]
 pub struct SimulationCoreTicker<'a> {
@@ -40,9 +65,34 @@ pub struct SimulationCoreTicker<'a> {
 
 impl<'a> SimulationCoreTicker<'a> {
     pub fn tick(&mut self, now: f64) -> AppResult<()> {
+        if self.res.input_replay.is_replaying() {
+            return self.tick_replay();
+        }
+        self.pre_process_input(now);
+        SimulationUpdater::new(self.ctx, self.res, self.input).update()?;
+        self.post_process_input();
+        self.res.input_replay.count_recorded_frame();
+        Ok(())
+    }
+
+    // While replaying, the clock stands still until a frame step is asked for, and then the simulation moves exactly
+    // one frame with the keys logged for it. The settings can still be changed between steps.
+    fn tick_replay(&mut self) -> AppResult<()> {
+        let pending = self.res.video.pending_frame_advances;
+        let now = if pending > 0 {
+            let (keys, now) = self.res.input_replay.step();
+            for (key, pressed) in keys {
+                trigger_hotkey_action(self.input, self.res, &key, pressed);
+            }
+            self.res.video.pending_frame_advances = 1;
+            now
+        } else {
+            self.res.input_replay.clock()
+        };
         self.pre_process_input(now);
         SimulationUpdater::new(self.ctx, self.res, self.input).update()?;
         self.post_process_input();
+        self.res.video.pending_frame_advances = pending.saturating_sub(1);
         Ok(())
     }
 
@@ -50,7 +100,10 @@ impl<'a> SimulationCoreTicker<'a> {
         self.input.now = now;
         for value in self.input.custom_event.consume_values() {
             match value {
+                // The live keys would mix with the ones of the log.
+                InputEventValue::Keyboard { .. } | InputEventValue::MouseClick(_) if self.res.input_replay.is_replaying() => {}
                 InputEventValue::Keyboard { pressed, key } => {
+                    self.res.input_replay.record(&key.to_lowercase(), pressed);
                     let result = trigger_hotkey_action(&mut self.input, &mut self.res, key.to_lowercase().as_ref(), pressed);
                     #[cfg(debug_assertions)]
                     {
@@ -60,6 +113,7 @@ impl<'a> SimulationCoreTicker<'a> {
                     }
                 }
                 InputEventValue::MouseClick(pressed) => {
+                    self.res.input_replay.record("mouse_click", pressed);
                     let result = trigger_hotkey_action(&mut self.input, &mut self.res, "mouse_click", pressed);
                     debug_assert_eq!(result, ActionUsed::Yes)
                 }
@@ -67,6 +121,7 @@ impl<'a> SimulationCoreTicker<'a> {
                     self.input.mouse_position_x = x;
                     self.input.mouse_position_y = y;
                 }
+                InputEventValue::PointerPosition { x, y } => self.input.pointer_position = (x, y),
                 InputEventValue::MouseWheel(wheel) => {
                     if self.input.canvas_focused {
                         self.input.mouse_scroll_y = wheel
@@ -82,6 +137,17 @@ impl<'a> SimulationCoreTicker<'a> {
                 InputEventValue::CustomScalingAspectRatioY(width) => self.input.event_scaling_aspect_ratio_y = Some(width),
                 InputEventValue::CustomScalingStretchNearest(flag) => self.input.event_custom_scaling_stretch_nearest = Some(flag),
                 InputEventValue::ViewportResize(width, height) => self.input.event_viewport_resize = Some(Size2D { width, height }),
+                InputEventValue::InputReplay(log) => self.load_input_replay(&log, now),
+                InputEventValue::InputRecording(true) => {
+                    self.res.input_replay.start_recording();
+                    self.ctx.dispatcher().dispatch_top_message("Recording the input.");
+                }
+                InputEventValue::InputRecording(false) => {
+                    if let Some(log) = self.res.input_replay.stop_recording() {
+                        self.ctx.dispatcher().dispatch_string_event("back2front:input_log", &log.to_string());
+                        self.ctx.dispatcher().dispatch_top_message("Input recorded.");
+                    }
+                }
                 InputEventValue::None => {}
             };
         }
@@ -92,6 +158,27 @@ impl<'a> SimulationCoreTicker<'a> {
         }
     }
 
+    fn load_input_replay(&mut self, log: &str, now: f64) {
+        if self.res.input_replay.is_replaying() {
+            self.res.video.external_clock = self.res.input_replay.stop_replay();
+        }
+        if log.trim().is_empty() {
+            self.ctx.dispatcher().dispatch_top_message("Replay stopped.");
+            return;
+        }
+        match InputLog::from_str(log) {
+            Ok(log) => {
+                // The source only moves with the steps, so each frame of the log lands on the same source frame every time.
+                self.res.input_replay.start_replay(log, now, self.res.video.external_clock);
+                self.res.video.external_clock = true;
+                self.res.video.pending_frame_advances = 0;
+                *self.input = Input::new(now);
+                self.ctx.dispatcher().dispatch_top_message("Replay loaded, step through it frame by frame.");
+            }
+            Err(e) => self.ctx.dispatcher().dispatch_top_message(&format!("Can't load the input log: {}", e)),
+        }
+    }
+
     fn post_process_input(&mut self) {
         self.input.mouse_scroll_y = 0.0;
         self.input.mouse_position_x = 0;
@@ -118,7 +205,7 @@ pub struct SimulationUpdater<'a> {
 impl<'a> SimulationUpdater<'a> {
     pub fn new(ctx: &'a dyn SimulationContext, res: &'a mut Resources, input: &'a Input) -> Self {
         SimulationUpdater {
-            dt: ((input.now - res.timers.last_time) / 1000.0) as f32,
+            dt: calculate_dt(input.now, res.timers.last_time, res.timers.max_dt),
             ctx,
             res,
             input,
@@ -135,6 +222,8 @@ impl<'a> SimulationUpdater<'a> {
         self.update_timers();
 
         self.update_animation_buffer();
+        self.update_procedural_source();
+        self.update_subtitles();
 
         if self.input.esc.is_just_pressed() {
             self.ctx.dispatcher().dispatch_exiting_session();
@@ -148,10 +237,21 @@ impl<'a> SimulationUpdater<'a> {
 
         self.update_speeds();
         self.update_scaling();
+        self.update_settings_menu();
         self.update_filters()?;
+        self.ctx.dispatcher().set_message_verbosity(self.res.controllers.message_verbosity.value);
         self.update_camera();
+        self.update_light_gun();
+        self.update_magnet();
         self.update_colors();
         self.update_screenshot();
+        self.update_screenshot_bracket();
+        self.update_turntable();
+        self.update_ken_burns();
+        self.update_room_lighting();
+        self.update_black_pedestal_setup();
+        self.update_moire_assistant();
+        self.update_blind_test();
         if self.res.controllers.preset_kind.value == FilterPresetOptions::DemoFlight1 {
             self.update_demo();
         }
@@ -162,13 +262,13 @@ impl<'a> SimulationUpdater<'a> {
             self.res.resetted = false;
             self.change_frontend_input_values();
         }
-        self.res.drawable = self.res.screenshot_trigger.is_triggered || self.res.screenshot_trigger.delay <= 0;
 
         Ok(())
     }
 
     fn update_screenshot(&mut self) {
         self.res.screenshot_trigger.is_triggered = false;
+        self.res.screenshot_trigger.tag = None;
         if self.res.screenshot_trigger.delay > 0 {
             self.res.screenshot_trigger.delay -= 1;
         } else if self.input.screenshot.is_just_released() {
@@ -181,16 +281,284 @@ impl<'a> SimulationUpdater<'a> {
         }
     }
 
+    fn update_screenshot_bracket(&mut self) {
+        if !self.res.screenshot_bracket.is_running() {
+            if self.input.screenshot_bracket.is_just_pressed() {
+                let sweep = build_bracket_sweep(self.res.controllers.screenshot_bracket.value);
+                self.res.screenshot_bracket.start(sweep, self.res.controllers.clone());
+                self.ctx.dispatcher().dispatch_top_message("Capturing screenshot bracket, please wait.");
+            }
+            return;
+        }
+        match self.res.screenshot_bracket.advance() {
+            BracketAdvance::Wait => {}
+            BracketAdvance::Apply(step) => {
+                step.apply(&mut self.res.controllers);
+                self.change_frontend_input_values();
+            }
+            BracketAdvance::Capture(tag) => {
+                self.res.screenshot_trigger.is_triggered = true;
+                self.res.screenshot_trigger.tag = Some(tag);
+            }
+            BracketAdvance::Finished(original) => {
+                self.res.controllers = *original;
+                self.change_frontend_input_values();
+                self.ctx.dispatcher().dispatch_top_message("Screenshot bracket captured.");
+            }
+        }
+    }
+
+    fn update_turntable(&mut self) {
+        if !self.res.turntable.is_running() {
+            if self.input.turntable.is_just_pressed() {
+                self.ctx.dispatcher().dispatch_top_message("Recording turntable, please wait.");
+                match self.ctx.dispatcher().dispatch_turntable_recording(true) {
+                    Ok(()) => {
+                        let seconds = self.res.controllers.turntable_seconds.value;
+                        self.res.turntable.start(self.res.camera.clone(), seconds, self.input.now);
+                    }
+                    Err(e) => self.ctx.dispatcher().dispatch_top_message(&e.to_string()),
+                }
+            }
+            return;
+        }
+        match self.res.turntable.advance(self.input.now) {
+            TurntableAdvance::Wait => {}
+            TurntableAdvance::Orbit(position) => {
+                // The 2D mode would keep the camera in front of the screen, so the orbit runs in 3D mode.
+                let camera = &mut self.res.camera;
+                camera.locked_mode = CameraLockMode::ThreeDimensional;
+                camera.set_position(position);
+                camera.direction = (-position).normalize();
+                camera.axis_up = glm::vec3(0.0, 1.0, 0.0);
+                camera.axis_right = glm::cross(&camera.direction, &camera.axis_up).normalize();
+                camera.axis_up = glm::cross(&camera.axis_right, &camera.direction);
+            }
+            TurntableAdvance::Finished(original) => {
+                self.res.camera = *original;
+                self.res.camera.position_changed = true;
+                if let Err(e) = self.ctx.dispatcher().dispatch_turntable_recording(false) {
+                    self.ctx.dispatcher().dispatch_top_message(&e.to_string());
+                    return;
+                }
+                self.ctx.dispatcher().dispatch_top_message("Turntable recorded.");
+            }
+        }
+    }
+
+    fn update_settings_menu(&mut self) {
+        let menu = &mut self.res.settings_menu;
+        if self.input.settings_menu.is_just_pressed() {
+            menu.open = !menu.open;
+        }
+        let entries = if menu.open { settings_menu_entries(&self.res.controllers) } else { vec![] };
+        if self.input.settings_menu_up.is_just_pressed() {
+            menu.move_cursor(-1, entries.len());
+        } else if self.input.settings_menu_down.is_just_pressed() {
+            menu.move_cursor(1, entries.len());
+        }
+
+        // Holding left or right works as holding the keys of the selected controller.
+        let held = (self.input.settings_menu_inc, self.input.settings_menu_dec);
+        let adjusting = match entries.get(menu.cursor) {
+            Some(entry) if held != (false, false) => Some((entry.index, held)),
+            _ => None,
+        };
+        if menu.adjusting == adjusting {
+            return;
+        }
+        let controllers = self.res.controllers.get_ui_controllers_mut();
+        if let Some((index, (increase, decrease))) = menu.adjusting {
+            if increase {
+                controllers[index].read_key_inc(false);
+            }
+            if decrease {
+                controllers[index].read_key_dec(false);
+            }
+        }
+        if let Some((index, (increase, decrease))) = adjusting {
+            controllers[index].read_key_inc(increase);
+            controllers[index].read_key_dec(decrease);
+        }
+        menu.adjusting = adjusting;
+    }
+
+    fn update_blind_test(&mut self) {
+        if !self.res.blind_test.is_running() {
+            if self.input.blind_test.is_just_pressed() {
+                if self.res.controllers.preset_kind.value == FilterPresetOptions::Custom && self.res.custom_is_changed {
+                    self.res.saved_filters = Some(self.res.controllers.clone());
+                }
+                let (first, second) = (self.ctx.random().next(), self.ctx.random().next());
+                let preset = self.res.blind_test.start(first, second);
+                self.res.controllers.preset_factory(preset, &None);
+                self.ctx
+                    .dispatcher()
+                    .dispatch_top_message("Blind test: flip between two presets with 1 and 2, run it again to vote for the one on screen.");
+            }
+            return;
+        }
+        let side = if self.input.blind_test_a.is_just_pressed() {
+            Some(BlindTestSide::A)
+        } else if self.input.blind_test_b.is_just_pressed() {
+            Some(BlindTestSide::B)
+        } else {
+            None
+        };
+        if let Some(side) = side {
+            if let Some(preset) = self.res.blind_test.show(side) {
+                self.res.controllers.preset_factory(preset, &None);
+            }
+        }
+        if self.input.blind_test.is_just_pressed() {
+            match self.res.blind_test.vote() {
+                Some(result) => {
+                    // Only now the settings reach the frontend, so the choice was made blind.
+                    self.res.controllers.preset_kind.value = result.preferred;
+                    self.res.controllers.preset_factory(result.preferred, &None);
+                    self.change_frontend_input_values();
+                    self.ctx.dispatcher().dispatch_top_message(&result.to_string());
+                }
+                None => self.ctx.dispatcher().dispatch_top_message("Look at the preset behind 2 before voting."),
+            }
+        }
+    }
+
+    fn update_ken_burns(&mut self) {
+        let filters = &self.res.controllers;
+        // Moving pictures don't need it, and the turntable and the demo drive the camera on their own.
+        let still = self.res.video.steps.len() <= 1 && self.res.procedural_source.active_frame().is_none();
+        let active =
+            filters.ken_burns_speed.value > 0.0 && still && !self.res.turntable.is_running() && filters.preset_kind.value != FilterPresetOptions::DemoFlight1;
+        let camera = &mut self.res.camera;
+        let delta = if active {
+            // The camera at rest fits the picture, so the margins are taken from what it sees from there.
+            let distance = self.res.initial_parameters.initial_position_z;
+            let half_height = distance * (radians(camera.zoom) * 0.5).tan();
+            let viewport = self.res.video.viewport_size;
+            let aspect = viewport.width as f32 / viewport.height.max(1) as f32;
+            self.res.ken_burns.advance(
+                filters.ken_burns_speed.value / 60.0 * self.dt,
+                filters.ken_burns_margin.value,
+                distance,
+                glm::vec2(half_height * aspect, half_height),
+            )
+        } else {
+            self.res.ken_burns.stop()
+        };
+        if delta == glm::vec3(0.0, 0.0, 0.0) {
+            return;
+        }
+        let movement = camera.axis_right * delta.x + camera.axis_up * delta.y + camera.direction * delta.z;
+        camera.position_destiny += movement;
+        camera.position_eye += movement;
+        camera.position_changed = true;
+    }
+
+    fn update_room_lighting(&mut self) {
+        if !self.input.room_lighting.is_just_pressed() {
+            return;
+        }
+        let room_brightness = &mut self.res.controllers.room_brightness;
+        let (value, message) = if room_brightness.value < 0.5 {
+            (1.0, "Room lighting: day.")
+        } else {
+            (0.0, "Room lighting: night.")
+        };
+        room_brightness.value = value;
+        room_brightness.dispatch_event(self.ctx.dispatcher());
+        self.ctx.dispatcher().dispatch_top_message(message);
+    }
+
+    fn update_black_pedestal_setup(&mut self) {
+        if !self.input.black_pedestal_setup.is_just_pressed() {
+            return;
+        }
+        let black_pedestal = &mut self.res.controllers.black_pedestal;
+        black_pedestal.value = if black_pedestal.value == NTSC_SETUP_IRE { 0.0 } else { NTSC_SETUP_IRE };
+        black_pedestal.dispatch_event(self.ctx.dispatcher());
+        self.ctx
+            .dispatcher()
+            .dispatch_top_message(&format!("Black pedestal: {} IRE.", self.res.controllers.black_pedestal.value));
+    }
+
+    fn update_moire_assistant(&mut self) {
+        if !self.res.moire_assistant.is_running() {
+            if self.input.moire_assistant.is_just_pressed() {
+                let internal_resolution = &self.res.controllers.internal_resolution;
+                let current = MoireCandidate {
+                    internal_resolution: internal_resolution.height(),
+                    pixel_width: self.res.scaling.pixel_width,
+                };
+                let mut lower = internal_resolution.clone();
+                lower.previous_option();
+                let mut higher = internal_resolution.clone();
+                higher.next_option();
+                let vary_pixel_width = matches!(self.res.scaling.scaling_method, ScalingMethod::Custom);
+                let sweep = build_moire_sweep(current, &[higher.height(), lower.height()], vary_pixel_width);
+                if let Some(candidate) = self.res.moire_assistant.start(sweep) {
+                    self.ctx
+                        .dispatcher()
+                        .dispatch_top_message("Looking for a moiré-free configuration, please wait.");
+                    self.apply_moire_candidate(candidate);
+                }
+            }
+            return;
+        }
+        match self.res.moire_assistant.advance() {
+            MoireAdvance::Wait => {}
+            MoireAdvance::Apply(candidate) => self.apply_moire_candidate(candidate),
+            MoireAdvance::Finished(measurements) => {
+                let recommended = match recommend_moire_candidate(&measurements) {
+                    Some(candidate) => candidate,
+                    None => return,
+                };
+                let applied = self.res.controllers.moire_assistant.value == MoireAssistantOptions::AutoApply;
+                self.apply_moire_candidate(if applied { recommended } else { measurements[0].candidate });
+                let message = if applied {
+                    format!("Applied moiré-free configuration: {}", recommended)
+                } else {
+                    format!("Recommended moiré-free configuration: {}", recommended)
+                };
+                self.ctx.dispatcher().dispatch_top_message(&message);
+                self.ctx.dispatcher().dispatch_moire_report(&MoireReport {
+                    measurements,
+                    recommended,
+                    applied,
+                });
+            }
+        }
+    }
+
+    fn apply_moire_candidate(&mut self, candidate: MoireCandidate) {
+        let internal_resolution = &mut self.res.controllers.internal_resolution;
+        if internal_resolution.height() != candidate.internal_resolution {
+            internal_resolution.set_resolution(candidate.internal_resolution);
+            internal_resolution.dispatch_event(self.ctx.dispatcher());
+            self.res.scaling.scaling_initialized = false;
+        }
+        if (self.res.scaling.pixel_width - candidate.pixel_width).abs() > f32::EPSILON {
+            self.res.scaling.pixel_width = candidate.pixel_width;
+            self.res.scaling.scaling_initialized = false;
+            self.ctx.dispatcher().dispatch_change_pixel_width(candidate.pixel_width);
+        }
+    }
+
     fn update_scaling(&mut self) {
         let ctx = &self.ctx;
         let input = &self.input;
         let mut changed = false;
-        FieldChanger::new(*ctx, &mut self.res.scaling.scaling_method, input.scaling_method.to_just_pressed())
-            .set_trigger_handler(|x: &ScalingMethod| {
-                changed = true;
-                ctx.dispatcher().dispatch_scaling_method(*x)
-            })
-            .process_options();
+        FieldChanger::new(
+            *ctx,
+            "scaling-method",
+            &mut self.res.scaling.scaling_method,
+            input.scaling_method.to_just_pressed(),
+        )
+        .set_trigger_handler(|x: &ScalingMethod| {
+            changed = true;
+            ctx.dispatcher().dispatch_scaling_method(*x)
+        })
+        .process_options();
 
         changed = changed
             || match self.res.scaling.scaling_method {
@@ -217,7 +585,7 @@ impl<'a> SimulationUpdater<'a> {
         }
 
         changed = changed
-            || FieldChanger::new(*ctx, &mut scaling.pixel_width, input.pixel_width)
+            || FieldChanger::new(*ctx, "pixel-width", &mut scaling.pixel_width, input.pixel_width)
                 .set_progression(pixel_velocity * 0.005)
                 .set_event_value(input.event_pixel_width)
                 .set_min(0.001)
@@ -227,43 +595,63 @@ impl<'a> SimulationUpdater<'a> {
                 })
                 .process_with_sums();
         changed = changed
-            || FieldChanger::new(*ctx, &mut scaling.custom_resolution.width, input.scaling_resolution_width.to_just_pressed())
-                .set_progression(1.0)
-                .set_event_value(input.event_scaling_resolution_width)
-                .set_min(1.0)
-                .set_max(100_000.0)
-                .set_trigger_handler(|x| ctx.dispatcher().dispatch_scaling_resolution_width(x as u32))
-                .process_with_sums();
+            || FieldChanger::new(
+                *ctx,
+                "custom-scaling-resolution-width",
+                &mut scaling.custom_resolution.width,
+                input.scaling_resolution_width.to_just_pressed(),
+            )
+            .set_progression(1.0)
+            .set_event_value(input.event_scaling_resolution_width)
+            .set_min(1.0)
+            .set_max(100_000.0)
+            .set_trigger_handler(|x| ctx.dispatcher().dispatch_scaling_resolution_width(x as u32))
+            .process_with_sums();
         changed = changed
-            || FieldChanger::new(*ctx, &mut scaling.custom_resolution.height, input.scaling_resolution_height.to_just_pressed())
-                .set_progression(1.0)
-                .set_event_value(input.event_scaling_resolution_height)
-                .set_min(1.0)
-                .set_max(100_000.0)
-                .set_trigger_handler(|x| ctx.dispatcher().dispatch_scaling_resolution_height(x as u32))
-                .process_with_sums();
+            || FieldChanger::new(
+                *ctx,
+                "custom-scaling-resolution-height",
+                &mut scaling.custom_resolution.height,
+                input.scaling_resolution_height.to_just_pressed(),
+            )
+            .set_progression(1.0)
+            .set_event_value(input.event_scaling_resolution_height)
+            .set_min(1.0)
+            .set_max(100_000.0)
+            .set_trigger_handler(|x| ctx.dispatcher().dispatch_scaling_resolution_height(x as u32))
+            .process_with_sums();
         changed = changed
-            || FieldChanger::new(*ctx, &mut scaling.custom_aspect_ratio.width, input.scaling_aspect_ratio_x.to_just_pressed())
-                .set_progression(1.0)
-                .set_event_value(input.event_scaling_aspect_ratio_x)
-                .set_min(1.0)
-                .set_max(1920.0 * 4.0)
-                .set_trigger_handler(|x| {
-                    ctx.dispatcher().dispatch_scaling_aspect_ratio_x(x);
-                    custom_change = LatestCustomScalingChange::AspectRatio;
-                })
-                .process_with_sums();
+            || FieldChanger::new(
+                *ctx,
+                "custom-scaling-aspect-ratio-x",
+                &mut scaling.custom_aspect_ratio.width,
+                input.scaling_aspect_ratio_x.to_just_pressed(),
+            )
+            .set_progression(1.0)
+            .set_event_value(input.event_scaling_aspect_ratio_x)
+            .set_min(1.0)
+            .set_max(1920.0 * 4.0)
+            .set_trigger_handler(|x| {
+                ctx.dispatcher().dispatch_scaling_aspect_ratio_x(x);
+                custom_change = LatestCustomScalingChange::AspectRatio;
+            })
+            .process_with_sums();
         changed = changed
-            || FieldChanger::new(*ctx, &mut scaling.custom_aspect_ratio.height, input.scaling_aspect_ratio_y.to_just_pressed())
-                .set_progression(1.0)
-                .set_event_value(input.event_scaling_aspect_ratio_y)
-                .set_min(1.0)
-                .set_max(1080.0 * 4.0)
-                .set_trigger_handler(|x| {
-                    ctx.dispatcher().dispatch_scaling_aspect_ratio_y(x);
-                    custom_change = LatestCustomScalingChange::AspectRatio;
-                })
-                .process_with_sums();
+            || FieldChanger::new(
+                *ctx,
+                "custom-scaling-aspect-ratio-y",
+                &mut scaling.custom_aspect_ratio.height,
+                input.scaling_aspect_ratio_y.to_just_pressed(),
+            )
+            .set_progression(1.0)
+            .set_event_value(input.event_scaling_aspect_ratio_y)
+            .set_min(1.0)
+            .set_max(1080.0 * 4.0)
+            .set_trigger_handler(|x| {
+                ctx.dispatcher().dispatch_scaling_aspect_ratio_y(x);
+                custom_change = LatestCustomScalingChange::AspectRatio;
+            })
+            .process_with_sums();
 
         scaling.custom_change = custom_change;
 
@@ -271,12 +659,30 @@ impl<'a> SimulationUpdater<'a> {
     }
 
     fn update_timers(&mut self) {
+        if self.input.now < self.res.timers.last_second {
+            self.res.timers.last_second = self.input.now;
+        }
         let ellapsed = self.input.now - self.res.timers.last_second;
+        self.res.timers.push_frame_time(((self.input.now - self.res.timers.last_time) as f32).max(0.0));
         self.res.timers.last_time = self.input.now;
+        self.res.timers.total_frames += 1;
+        self.res.main.dt = self.dt;
 
         if ellapsed >= 1_000.0 {
             let fps = self.res.timers.frame_count as f32;
+            self.res.timers.last_fps = fps;
             self.ctx.dispatcher().dispatch_fps(fps);
+            let frame_times: Vec<f32> = self.res.timers.frame_times.iter().copied().collect();
+            self.ctx.dispatcher().dispatch_frame_times(&frame_times);
+            self.ctx.dispatcher().dispatch_recovery_state(
+                &self.res.controllers.preset_kind.value.to_string(),
+                &self.res.camera.position_eye,
+                &self.res.camera.direction,
+                &self.res.camera.axis_up,
+            );
+            if let DiagnosticsOverlayOptions::On = self.res.controllers.diagnostics_overlay.value {
+                self.dispatch_diagnostics();
+            }
             self.res.timers.last_second = self.input.now;
             self.res.timers.frame_count = 0;
         } else {
@@ -284,9 +690,29 @@ impl<'a> SimulationUpdater<'a> {
         }
     }
 
+    fn dispatch_diagnostics(&self) {
+        let viewport = self.res.video.viewport_size.to_f32();
+        let view_projection = self.res.camera.get_projection(viewport.width, viewport.height) * self.res.camera.get_view();
+        let readout = measure_diagnostics(
+            &view_projection,
+            viewport.to_tuple(),
+            self.res.video.image_size.to_tuple(),
+            &self.res.main.render,
+        );
+        self.ctx.dispatcher().dispatch_diagnostics(&readout);
+    }
+
     fn update_animation_buffer(&mut self) {
         self.res.video.needs_buffer_data_load = self.res.resetted;
-        let next_frame_update = self.res.video.last_frame_change + 0.001 * f64::from(self.res.video.steps[self.res.video.current_frame].delay);
+        if self.res.video.external_clock {
+            self.advance_animation_buffer_externally();
+            return;
+        }
+        let mut next_frame_update = self.res.video.last_frame_change + 0.001 * f64::from(self.res.video.steps[self.res.video.current_frame].delay);
+        if self.input.now - next_frame_update > f64::from(self.res.timers.max_dt) * 1000.0 {
+            // After a long pause, resync instead of fast-forwarding through all the missed frames.
+            next_frame_update = self.input.now;
+        }
         if self.input.now >= next_frame_update {
             self.res.video.last_frame_change = next_frame_update;
             let last_frame = self.res.video.current_frame;
@@ -300,6 +726,50 @@ impl<'a> SimulationUpdater<'a> {
         }
     }
 
+    fn update_procedural_source(&mut self) {
+        let kind = self.res.controllers.procedural_source.value;
+        let video = &mut self.res.video;
+        let procedural = &mut self.res.procedural_source;
+        if kind == ProceduralSourceOptions::Off {
+            if let Some(original_image_size) = procedural.original_image_size.take() {
+                *procedural = ProceduralSourceState::default();
+                video.image_size = original_image_size;
+                video.needs_buffer_data_load = true;
+                self.res.scaling.scaling_initialized = false;
+            }
+            return;
+        }
+        if procedural.original_image_size.is_none() {
+            procedural.original_image_size = Some(video.image_size);
+            video.image_size = PROCEDURAL_SOURCE_SIZE;
+            self.res.scaling.scaling_initialized = false;
+        }
+        if procedural.generate(kind, self.dt) || self.res.resetted {
+            video.needs_buffer_data_load = true;
+        }
+    }
+
+    fn update_subtitles(&mut self) {
+        let enabled = self.res.controllers.subtitles.value == SubtitlesOptions::On;
+        if self.res.subtitles.update(&self.res.video, self.input.now, enabled) {
+            self.res.video.needs_buffer_data_load = true;
+        }
+    }
+
+    fn advance_animation_buffer_externally(&mut self) {
+        let video = &mut self.res.video;
+        if video.pending_frame_advances == 0 || video.steps.is_empty() {
+            return;
+        }
+        let last_frame = video.current_frame;
+        video.current_frame = (video.current_frame + video.pending_frame_advances as usize) % video.steps.len();
+        video.pending_frame_advances = 0;
+        video.last_frame_change = self.input.now;
+        if last_frame != video.current_frame {
+            video.needs_buffer_data_load = true;
+        }
+    }
+
     fn update_speeds(&mut self) {
         let initial_movement_speed = self.res.initial_parameters.initial_movement_speed;
         if self.input.reset_speeds {
@@ -311,30 +781,40 @@ impl<'a> SimulationUpdater<'a> {
         }
         let ctx = &self.ctx;
         let input = &self.input;
-        FieldChanger::new(*ctx, &mut self.res.camera.turning_speed, input.turn_speed.to_just_pressed())
+        FieldChanger::new(*ctx, "turn-speed", &mut self.res.camera.turning_speed, input.turn_speed.to_just_pressed())
             .set_progression(2.0)
             .set_min(0.007_812_5 * TURNING_BASE_SPEED)
             .set_max(16_384.0 * TURNING_BASE_SPEED)
             .set_trigger_handler(|x| ctx.dispatcher().dispatch_change_turning_speed(x / TURNING_BASE_SPEED))
             .process_with_multiplications();
-        FieldChanger::new(*ctx, &mut self.res.speed.filter_speed, input.filter_speed.to_just_pressed())
+        FieldChanger::new(*ctx, "pixel-speed", &mut self.res.speed.filter_speed, input.filter_speed.to_just_pressed())
             .set_progression(2.0)
             .set_min(0.007_812_5 * PIXEL_MANIPULATION_BASE_SPEED)
             .set_max(16_384.0 * PIXEL_MANIPULATION_BASE_SPEED)
             .set_trigger_handler(|x| ctx.dispatcher().dispatch_change_pixel_speed(x / PIXEL_MANIPULATION_BASE_SPEED))
             .process_with_multiplications();
-        FieldChanger::new(*ctx, &mut self.res.camera.turning_speed, input.translation_speed.to_just_pressed())
-            .set_progression(2.0)
-            .set_min(0.007_812_5 * TURNING_BASE_SPEED)
-            .set_max(16_384.0 * TURNING_BASE_SPEED)
-            .set_trigger_handler(|x| ctx.dispatcher().dispatch_change_turning_speed(x / TURNING_BASE_SPEED))
-            .process_with_multiplications();
-        FieldChanger::new(*ctx, &mut self.res.camera.movement_speed, input.translation_speed.to_just_pressed())
-            .set_progression(2.0)
-            .set_min(0.007_812_5 * initial_movement_speed)
-            .set_max(16_384.0 * initial_movement_speed)
-            .set_trigger_handler(|x| ctx.dispatcher().dispatch_change_movement_speed(x / initial_movement_speed))
-            .process_with_multiplications();
+        FieldChanger::new(
+            *ctx,
+            "turn-speed",
+            &mut self.res.camera.turning_speed,
+            input.translation_speed.to_just_pressed(),
+        )
+        .set_progression(2.0)
+        .set_min(0.007_812_5 * TURNING_BASE_SPEED)
+        .set_max(16_384.0 * TURNING_BASE_SPEED)
+        .set_trigger_handler(|x| ctx.dispatcher().dispatch_change_turning_speed(x / TURNING_BASE_SPEED))
+        .process_with_multiplications();
+        FieldChanger::new(
+            *ctx,
+            "move-speed",
+            &mut self.res.camera.movement_speed,
+            input.translation_speed.to_just_pressed(),
+        )
+        .set_progression(2.0)
+        .set_min(0.007_812_5 * initial_movement_speed)
+        .set_max(16_384.0 * initial_movement_speed)
+        .set_trigger_handler(|x| ctx.dispatcher().dispatch_change_movement_speed(x / initial_movement_speed))
+        .process_with_multiplications();
     }
 
     fn update_filters(&mut self) -> AppResult<()> {
@@ -347,12 +827,20 @@ impl<'a> SimulationUpdater<'a> {
         }
 
         let mut changed = false;
+        self.res.main.filter_speed = self.res.speed.filter_speed;
         self.res.controllers.internal_resolution.set_max_texture_size(self.res.video.max_texture_size);
         for controller in self.res.controllers.get_ui_controllers_mut().iter_mut() {
-            changed = changed || controller.update(&self.res.main, self.ctx);
+            changed = controller.update(&self.res.main, self.ctx) || changed;
+        }
+
+        if self.res.controllers.crt_model.changed {
+            if let Some(spec) = self.res.controllers.crt_model.value.spec() {
+                self.res.controllers.apply_crt_model(spec);
+                self.change_frontend_input_values();
+            }
         }
 
-        if self.res.controllers.internal_resolution.changed {
+        if self.res.controllers.internal_resolution.changed || self.res.controllers.source_rotation.value.is_quarter_turn() != self.res.scaling.quarter_turned {
             self.res.scaling.scaling_initialized = false;
         }
 
@@ -405,12 +893,17 @@ impl<'a> SimulationUpdater<'a> {
                 CameraLockMode::TwoDimensional => CameraLockMode::ThreeDimensional,
             };
             self.ctx.dispatcher().dispatch_change_camera_movement_mode(self.res.camera.locked_mode);
-            self.ctx
-                .dispatcher()
-                .dispatch_top_message(&format!("Camera movement: {}.", &self.res.camera.locked_mode.to_string()));
+            if self.ctx.dispatcher().are_extra_messages_enabled(MessageCategory::Camera) {
+                self.ctx
+                    .dispatcher()
+                    .dispatch_top_message(&format!("Camera movement: {}.", &self.res.camera.locked_mode.to_string()));
+            }
         }
 
+        self.res.camera.scene_radius = self.scene_radius();
         let camera_lock_mode = self.res.camera.locked_mode;
+        let previous_z = self.res.camera.position_destiny.z;
+        let collision_surface = self.camera_collision_surface();
         let mut camera = CameraSystem::new(&mut self.res.camera, self.ctx.dispatcher());
 
         if self.input.walk_left {
@@ -452,7 +945,9 @@ impl<'a> SimulationUpdater<'a> {
             camera.rotate(CameraDirection::Right, self.dt);
         }
 
-        if self.input.mouse_click.is_just_pressed() {
+        if let LightGunOptions::On = self.res.controllers.light_gun.value {
+            // Clicks are shots in light gun mode, so they don't drag the camera.
+        } else if self.input.mouse_click.is_just_pressed() {
             self.ctx.dispatcher().dispatch_request_fullscreen();
             match camera_lock_mode {
                 CameraLockMode::ThreeDimensional => self.ctx.dispatcher().dispatch_request_pointer_lock(),
@@ -467,6 +962,13 @@ impl<'a> SimulationUpdater<'a> {
             };
         }
 
+        if self.input.focal_length_preset.increase.is_just_pressed() || self.input.focal_length_preset.decrease.is_just_pressed() {
+            let focal_length = camera.next_focal_length_preset(self.input.focal_length_preset.increase.is_just_pressed());
+            if self.ctx.dispatcher().are_extra_messages_enabled(MessageCategory::Camera) {
+                self.ctx.dispatcher().dispatch_top_message(&format!("Focal length: {}mm.", focal_length));
+            }
+        }
+
         if self.input.camera_zoom.increase {
             camera.change_zoom(self.dt * -100.0, self.ctx.dispatcher());
         } else if self.input.camera_zoom.decrease {
@@ -479,9 +981,93 @@ impl<'a> SimulationUpdater<'a> {
             camera.handle_camera_change(change);
         }
 
+        if let Some(surface_z) = collision_surface {
+            camera.collide_with_screen(previous_z, surface_z);
+        }
+
         camera.update_view(self.dt)
     }
 
+    // The edge reflections add a whole picture around the screen, and the curvature pushes the corners back.
+    fn scene_radius(&self) -> f32 {
+        let output = &self.res.main.render;
+        let width = self.res.video.image_size.width as f32 * output.pixel_spread[0];
+        let height = self.res.video.image_size.height as f32 * output.pixel_spread[1];
+        1.5 * (width * width + height * height).sqrt() + output.screen_curvature_factor * 100.0 + 1.0
+    }
+
+    fn camera_collision_surface(&self) -> Option<impl Fn(f32, f32) -> Option<f32>> {
+        if let CameraCollisionOptions::Off = self.res.controllers.camera_collision.value {
+            return None;
+        }
+        let output = &self.res.main.render;
+        let image_size = if self.res.scaling.quarter_turned {
+            (self.res.video.image_size.height, self.res.video.image_size.width)
+        } else {
+            (self.res.video.image_size.width, self.res.video.image_size.height)
+        };
+        let front_depth = match self.res.controllers.pixels_geometry_kind.value {
+            PixelGeometryKindOptions::Cubes | PixelGeometryKindOptions::Hexagons => 0.5 / output.pixel_scale_base[2],
+            _ => 0.0,
+        };
+        let pixel_spread = output.pixel_spread;
+        let screen_curvature = output.screen_curvature_factor;
+        let curvature_axes = output.screen_curvature_axes;
+        Some(move |x, y| calculate_screen_surface_z((x, y), image_size, pixel_spread, screen_curvature, curvature_axes, front_depth))
+    }
+
+    fn update_light_gun(&mut self) {
+        self.res.main.render.light_gun_flash = false;
+        if let LightGunOptions::Off = self.res.controllers.light_gun.value {
+            return;
+        }
+        if !self.input.mouse_click.is_just_pressed() {
+            return;
+        }
+        self.res.main.render.light_gun_flash = true;
+        let viewport = self.res.video.viewport_size.to_f32();
+        let view_projection = self.res.camera.get_projection(viewport.width, viewport.height) * self.res.camera.get_view();
+        let hit = calculate_light_gun_hit(
+            self.input.pointer_position,
+            viewport.to_tuple(),
+            &view_projection,
+            self.res.video.image_size.to_tuple(),
+            self.res.main.render.pixel_spread,
+        );
+        self.ctx.dispatcher().dispatch_light_gun_shot(hit);
+    }
+
+    fn update_magnet(&mut self) {
+        if self.input.magnet {
+            let viewport = self.res.video.viewport_size.to_f32();
+            let view_projection = self.res.camera.get_projection(viewport.width, viewport.height) * self.res.camera.get_view();
+            if let Some(hit) = calculate_screen_plane_hit(self.input.pointer_position, viewport.to_tuple(), &view_projection) {
+                let pixel_spread = self.res.main.render.pixel_spread;
+                self.res.magnet.center = [hit.x / pixel_spread[0], hit.y / pixel_spread[1]];
+            }
+            self.res.magnet.intensity = (self.res.magnet.intensity + self.dt * MAGNET_RAMP_SPEED).min(1.0);
+            self.res.magnet.degauss_started = None;
+            return;
+        }
+        if self.res.magnet.intensity <= 0.0 {
+            return;
+        }
+        match self.res.magnet.degauss_started {
+            None => {
+                self.res.magnet.degauss_started = Some(self.input.now);
+                if let CrtSoundsOptions::On = self.res.controllers.crt_sounds.value {
+                    self.ctx.dispatcher().dispatch_play_sound(SoundEffect::Degauss, 1.0);
+                }
+            }
+            Some(started) => {
+                if (self.input.now - started) / 1000.0 >= MAGNET_DEGAUSS_SECONDS as f64 {
+                    self.res.magnet.intensity = 0.0;
+                    self.res.magnet.degauss_started = None;
+                }
+            }
+        }
+    }
+
     fn update_colors(&mut self) {
         for controller in self.res.controllers.get_ui_controllers_mut().iter_mut() {
             controller.apply_event();
@@ -491,7 +1077,7 @@ impl<'a> SimulationUpdater<'a> {
     fn change_frontend_input_values(&self) {
         let dispatcher = self.ctx.dispatcher();
         dispatcher.enable_extra_messages(false);
-        dispatcher.dispatch_change_camera_zoom(self.res.camera.zoom);
+        dispatch_zoom(dispatcher, self.res.camera.zoom);
         dispatcher.dispatch_change_camera_movement_mode(self.res.camera.locked_mode);
         dispatcher.dispatch_change_pixel_speed(self.res.speed.filter_speed / PIXEL_MANIPULATION_BASE_SPEED);
         dispatcher.dispatch_change_turning_speed(self.res.camera.turning_speed / TURNING_BASE_SPEED);
@@ -601,9 +1187,14 @@ impl<'a> SimulationUpdater<'a> {
         self.res.main.current_filter_preset = self.res.controllers.preset_kind.value;
 
         self.update_output_scaling();
+        self.update_output_tv_lines();
+        self.update_output_signal();
+        self.update_output_palette();
+        self.update_output_response_time();
         self.update_output_filter_source_colors();
         self.update_output_filter_curvature();
         self.update_output_filter_backlight();
+        self.update_output_layers();
 
         let output = &mut self.res.main.render;
         let controllers = &self.res.controllers;
@@ -611,13 +1202,59 @@ impl<'a> SimulationUpdater<'a> {
         let (ambient_strength, pixel_have_depth) = match controllers.pixels_geometry_kind.value {
             PixelGeometryKindOptions::Squares => (1.0, false),
             PixelGeometryKindOptions::Cubes => (0.5, true),
+            PixelGeometryKindOptions::ApertureGrille => (1.0, false),
+            PixelGeometryKindOptions::Hexagons => (0.5, true),
         };
         output.ambient_strength = ambient_strength;
-        output.pixel_have_depth = pixel_have_depth;
+        output.pixel_have_depth = controllers.foreground_depth_test.value.enabled(pixel_have_depth);
+        output.background_have_depth = controllers.background_depth_test.value.enabled();
         output.height_modifier_factor = 1.0 - controllers.pixel_shadow_height.value;
-        output.time = self.input.now;
+        output.noise_seed = noise_seed(self.input.now);
+
+        let (cos, sin) = controllers.source_rotation.value.cos_sin();
+        let (mirror_x, mirror_y) = controllers.source_mirror.value.scale();
+        output.source_transform = [cos * mirror_x, -sin * mirror_x, sin * mirror_y, cos * mirror_y];
 
         self.update_output_pixel_scale_gap_offset();
+        self.update_output_blooming();
+        self.update_output_damper_wires();
+        self.update_output_rolling_scan();
+        self.update_output_screen_matte();
+        self.update_output_flicker();
+        self.update_output_white_clipping();
+        self.update_output_dithering();
+        self.update_output_burn_in();
+        self.update_output_analog_static();
+        self.update_output_hsync_wobble();
+        self.update_output_transition();
+        self.update_output_edge_reflections();
+        self.update_output_floor_reflection();
+        self.update_output_glass_reflection();
+        self.update_output_blemishes();
+        self.update_output_defects();
+        self.update_output_monitor_case();
+        self.update_output_bezel();
+        self.update_output_room();
+        self.update_output_physical_calibration();
+        self.update_output_crt_sounds();
+        self.update_output_magnet();
+        self.update_output_hud();
+    }
+
+    fn update_output_hud(&mut self) {
+        let menu = &self.res.settings_menu;
+        let mut lines = if menu.open {
+            menu.lines(&settings_menu_entries(&self.res.controllers))
+        } else {
+            vec![]
+        };
+        if let StatusBarOptions::On = self.res.controllers.status_bar.value {
+            lines.push(status_bar_line(&self.res.controllers, self.res.timers.last_fps));
+        }
+        if let Some(line) = self.res.input_replay.status_line() {
+            lines.push(line);
+        }
+        self.res.main.render.hud_lines = lines;
     }
 
     fn update_output_scaling(&mut self) {
@@ -709,11 +1346,20 @@ impl<'a> SimulationUpdater<'a> {
         self.ctx.dispatcher().dispatch_custom_scaling_stretch_nearest(stretch);
 
         self.res.scaling.pixel_width = pixel_width;
+        self.res.scaling.quarter_turned = self.res.controllers.source_rotation.value.is_quarter_turn();
 
         let z = {
-            let background_size = Size2D {
-                width: image_width as f32,
-                height: image_height as f32,
+            // A quarter turn lays the rows along the horizontal axis, so the camera has to fit the swapped footprint.
+            let background_size = if self.res.scaling.quarter_turned {
+                Size2D {
+                    width: image_height as f32 / pixel_width,
+                    height: image_width as f32 * pixel_width,
+                }
+            } else {
+                Size2D {
+                    width: image_width as f32,
+                    height: image_height as f32,
+                }
             };
             calculate_far_away_position(
                 background_size,
@@ -729,6 +1375,64 @@ impl<'a> SimulationUpdater<'a> {
             initial_movement_speed: camera.movement_speed,
         };
         self.res.camera = camera;
+        self.res.ken_burns.forget();
+        self.res.calibrated_distance = None;
+    }
+
+    fn update_output_tv_lines(&mut self) {
+        let image_size = self.res.video.image_size;
+        let aspect_ratio = image_size.width as f32 * self.res.scaling.pixel_width / image_size.height as f32;
+        let sigma = band_limit_sigma(self.res.controllers.tv_lines.value, aspect_ratio, image_size.width);
+        if sigma != self.res.main.render.horizontal_band_limit {
+            self.res.main.render.horizontal_band_limit = sigma;
+            self.res.video.needs_buffer_data_load = true;
+        }
+    }
+
+    fn update_output_signal(&mut self) {
+        let kind = self.res.controllers.signal_kind.value;
+        let signal = &mut self.res.main.render.signal;
+        if signal.kind != kind {
+            signal.kind = kind;
+            self.res.video.needs_buffer_data_load = true;
+        }
+        if kind == SignalKindOptions::Composite {
+            // The subcarrier phase flips every field, so the artifacts crawl even over a still image.
+            signal.field = signal.field.wrapping_add(1);
+            self.res.video.needs_buffer_data_load = true;
+        }
+        let interlacing = self.res.controllers.interlacing.value;
+        self.res.main.render.interlace_other_field = interlacing.other_field_brightness();
+        self.res.main.render.interlace_line_pairing = match interlacing {
+            InterlacingOptions::Off => 0.0,
+            _ => self.res.controllers.interlace_line_pairing.value,
+        };
+    }
+
+    fn update_output_palette(&mut self) {
+        let controllers = &mut self.res.controllers;
+        if std::mem::take(&mut controllers.custom_palette.uploaded) && controllers.palette.value != PaletteOptions::Custom {
+            controllers.palette.value = PaletteOptions::Custom;
+            controllers.palette.dispatch_event(self.ctx.dispatcher());
+        }
+        let palette = controllers.palette.value.colors(&controllers.custom_palette.value);
+        if palette != self.res.main.render.palette {
+            self.res.main.render.palette = palette;
+            self.res.video.needs_buffer_data_load = true;
+        }
+    }
+
+    fn update_output_response_time(&mut self) {
+        let filters = &self.res.controllers;
+        let blend_factors = [
+            response_blend_factor(filters.response_rise_time.value, self.dt),
+            response_blend_factor(filters.response_fall_time.value, self.dt),
+        ];
+        // The smear keeps converging on still frames, so the source has to be uploaded again every frame.
+        if blend_factors != [1.0, 1.0] || self.res.main.render.response_blend_factors != blend_factors {
+            self.res.video.needs_buffer_data_load = true;
+        }
+        self.res.main.render.response_blend_factors = blend_factors;
     }
 
     fn update_output_filter_source_colors(&mut self) {
@@ -745,9 +1449,9 @@ impl<'a> SimulationUpdater<'a> {
             match filters.color_channels.value {
                 ColorChannelsOptions::Combined => {}
                 _ => {
-                    light_color[(i + 0) % 3] *= 1.0;
-                    light_color[(i + 1) % 3] = 0.0;
-                    light_color[(i + 2) % 3] = 0.0;
+                    let channel = filters.color_channels_order.value.channels()[i];
+                    light_color[(channel + 1) % 3] = 0.0;
+                    light_color[(channel + 2) % 3] = 0.0;
                 }
             }
             output.light_color[i] = light_color;
@@ -756,6 +1460,11 @@ impl<'a> SimulationUpdater<'a> {
         for light in output.extra_light.iter_mut() {
             *light *= filters.extra_bright.value;
         }
+        // The room light bouncing on the phosphors lifts the black level.
+        let room_light = get_3_f32color_from_int(filters.light_color.value);
+        for (light, room) in output.extra_light.iter_mut().zip(room_light.iter()) {
+            *light += ROOM_BLACK_LEVEL * filters.room_brightness.value * room;
+        }
         output.rgb_red[0] = filters.rgb_red_r.into();
         output.rgb_red[1] = filters.rgb_red_g.into();
         output.rgb_red[2] = filters.rgb_red_b.into();
@@ -766,7 +1475,31 @@ impl<'a> SimulationUpdater<'a> {
         output.rgb_blue[1] = filters.rgb_blue_g.into();
         output.rgb_blue[2] = filters.rgb_blue_b.into();
         output.color_gamma = filters.color_gamma.value;
+        output.black_pedestal = filters.black_pedestal.value / 100.0;
+        output.color_saturation = filters.color_saturation.value;
+        output.color_hue = filters.color_hue.value.to_radians();
+        output.channel_gain = [filters.red_gain.value, filters.green_gain.value, filters.blue_gain.value];
+        output.channel_offset = [filters.red_offset.value, filters.green_offset.value, filters.blue_offset.value];
+        output.white_point = get_white_point_gains(filters.color_temperature.value);
+        output.output_gamma = filters.output_gamma.value;
+        output.contrast_curve = [filters.contrast_knee.value, filters.contrast_shoulder.value];
+        let phosphors = filters.phosphor_primaries.value.primaries();
+        let display = filters.output_color_space.value.primaries();
+        output.phosphor_matrix = if phosphors != display {
+            Some(get_primaries_conversion_matrix(&phosphors, &display))
+        } else {
+            None
+        };
         output.color_noise = filters.color_noise.value;
+        output.convergence = [
+            [filters.convergence_red_x.value, filters.convergence_red_y.value],
+            [filters.convergence_green_x.value, filters.convergence_green_y.value],
+            [filters.convergence_blue_x.value, filters.convergence_blue_y.value],
+        ];
+        output.linear_blending = match filters.blending_space.value {
+            BlendingSpaceOptions::Srgb => false,
+            BlendingSpaceOptions::Linear => true,
+        };
     }
 
     fn update_output_filter_curvature(&mut self) {
@@ -777,8 +1510,20 @@ impl<'a> SimulationUpdater<'a> {
             ScreenCurvatureKindOptions::Curved1 => 0.15,
             ScreenCurvatureKindOptions::Curved2 => 0.3,
             ScreenCurvatureKindOptions::Curved3 => 0.45,
+            ScreenCurvatureKindOptions::Cylindrical => 0.3,
             _ => 0.0,
         };
+        // Trinitron tubes are a slice of a cylinder, curved from side to side but flat from top to bottom.
+        output.screen_curvature_axes = match filters.screen_curvature_kind.value {
+            ScreenCurvatureKindOptions::Cylindrical => [1.0, 0.0],
+            _ => [1.0, 1.0],
+        };
+        // The barrel is done in image space over the flat geometry, so it costs the same at any strength.
+        // The continuous amount goes on top of the steps, and below zero it bends the other way, into a pincushion.
+        output.screen_barrel = match filters.screen_curvature_kind.value {
+            ScreenCurvatureKindOptions::Barrel => SCREEN_BARREL_STRENGTH,
+            _ => 0.0,
+        } + filters.screen_curvature_amount.value;
 
         if let ScreenCurvatureKindOptions::Pulse = filters.screen_curvature_kind.value {
             output.pixels_pulse += self.dt * 0.3;
@@ -791,7 +1536,6 @@ impl<'a> SimulationUpdater<'a> {
         let output = &mut self.res.main.render;
         let filters = &self.res.controllers;
 
-        output.showing_background = filters.backlight_percent.value > 0.0;
         let solid_color_weight = filters.backlight_percent.value;
 
         for i in 0..3 {
@@ -799,72 +1543,550 @@ impl<'a> SimulationUpdater<'a> {
         }
     }
 
-    fn update_output_pixel_scale_gap_offset(&mut self) {
+    fn update_output_layers(&mut self) {
         let output = &mut self.res.main.render;
         let filters = &self.res.controllers;
-        let scaling = &self.res.scaling;
-
-        output.pixel_spread = [
-            (1.0 + filters.cur_pixel_spread.value) * scaling.pixel_width,
-            1.0 + filters.cur_pixel_spread.value,
-        ];
-        output.pixel_scale_base = [
-            (filters.cur_pixel_vertical_gap.value + 1.0) / scaling.pixel_width,
-            filters.cur_pixel_horizontal_gap.value + 1.0,
-            (filters.cur_pixel_vertical_gap.value + filters.cur_pixel_vertical_gap.value) * 0.5 + 1.0,
-        ];
 
-        let by_vertical_lpp = 1.0 / (filters.vertical_lpp.value as f32);
-        let by_horizontal_lpp = 1.0 / (filters.horizontal_lpp.value as f32);
-        let vl_offset_beginning = -(filters.vertical_lpp.value as f32 - 1.0) / 2.0;
-        let hl_offset_beginning = -(filters.horizontal_lpp.value as f32 - 1.0) / 2.0;
+        // Only the layers with something to show get to the render, so it doesn't spend passes on empty buffers.
+        output.layers = filters
+            .layers
+            .value
+            .0
+            .iter()
+            .filter(|layer| {
+                layer.enabled
+                    && layer.opacity > 0.0
+                    && match layer.kind {
+                        LayerKind::SourcePixels => true,
+                        LayerKind::SolidBackdrop => filters.backlight_percent.value > 0.0,
+                        LayerKind::Reflections => filters.edge_reflection_intensity.value > 0.0 || filters.floor_reflection.value == FloorReflectionOptions::On,
+                        LayerKind::MonitorCase => true,
+                    }
+            })
+            .copied()
+            .collect();
+        output.showing_background = output.layers.iter().any(|layer| layer.kind != LayerKind::SourcePixels);
+    }
 
-        let line_passes = filters.vertical_lpp.value * filters.horizontal_lpp.value;
-        output.pixel_scale_background.resize_with(line_passes, Default::default);
-        output.pixel_offset_background.resize_with(line_passes, Default::default);
-        for hl_idx in 0..filters.horizontal_lpp.value {
-            for vl_idx in 0..filters.vertical_lpp.value {
-                let pixel_offset = &mut output.pixel_offset_background[vl_idx * filters.horizontal_lpp.value + hl_idx];
-                let pixel_scale = &mut output.pixel_scale_background[vl_idx * filters.horizontal_lpp.value + hl_idx];
+    fn update_output_physical_calibration(&mut self) {
+        if let PhysicalCalibrationOptions::Off = self.res.controllers.physical_calibration.value {
+            self.res.calibrated_distance = None;
+            return;
+        }
+        let distance = calculate_physical_distance(
+            self.res.controllers.display_dpi.value,
+            self.res.controllers.dot_pitch.value,
+            self.res.main.render.pixel_spread[1],
+            self.res.video.viewport_size.height as f32,
+            self.res.camera.zoom,
+        );
+        if self.res.calibrated_distance == Some(distance) {
+            return;
+        }
+        self.res.calibrated_distance = Some(distance);
+        let position = self.res.camera.get_position();
+        self.res.camera.set_position(glm::vec3(position.x, position.y, distance));
+    }
 
-                *pixel_offset = [0.0, 0.0, 0.0];
-                *pixel_scale = [(0.0 + 1.0) / scaling.pixel_width, 0.0 + 1.0, (0.0 + 0.0) * 0.5 + 1.0];
-                if filters.vertical_lpp.value > 1 {
-                    let vl_cur_offset = vl_offset_beginning + vl_idx as f32;
-                    pixel_offset[0] = (pixel_offset[0] + vl_cur_offset * scaling.pixel_width) * by_vertical_lpp;
-                    pixel_scale[0] *= filters.vertical_lpp.value as f32;
-                }
-                if filters.horizontal_lpp.value > 1 {
-                    let hl_cur_offset = hl_offset_beginning + hl_idx as f32;
-                    pixel_offset[1] = (pixel_offset[1] + hl_cur_offset) * by_horizontal_lpp;
-                    pixel_scale[1] *= filters.horizontal_lpp.value as f32;
-                }
+    fn update_output_crt_sounds(&mut self) {
+        let dispatcher = self.ctx.dispatcher();
+        if let CrtSoundsOptions::Off = self.res.controllers.crt_sounds.value {
+            if self.res.crt_sounds_static_volume.take().is_some() {
+                dispatcher.dispatch_play_sound(SoundEffect::Hum, 0.0);
+                dispatcher.dispatch_play_sound(SoundEffect::Static, 0.0);
             }
+            return;
+        }
+        if self.res.crt_sounds_static_volume.is_none() {
+            dispatcher.dispatch_play_sound(SoundEffect::Degauss, 1.0);
+            dispatcher.dispatch_play_sound(SoundEffect::Hum, CRT_HUM_VOLUME);
+        }
+        let static_volume = self.res.controllers.color_noise.value * CRT_STATIC_MAX_VOLUME;
+        if self.res.crt_sounds_static_volume == Some(static_volume) {
+            return;
         }
+        self.res.crt_sounds_static_volume = Some(static_volume);
+        dispatcher.dispatch_play_sound(SoundEffect::Static, static_volume);
+    }
 
-        output.pixel_scale_foreground.resize_with(line_passes, Default::default);
-        output.pixel_offset_foreground.resize_with(line_passes, Default::default);
-        for hl_idx in 0..filters.horizontal_lpp.value {
-            for vl_idx in 0..filters.vertical_lpp.value {
-                for color_idx in 0..output.color_splits {
-                    let pixel_offset = &mut output.pixel_offset_foreground[vl_idx * filters.horizontal_lpp.value + hl_idx][color_idx];
-                    let pixel_scale = &mut output.pixel_scale_foreground[vl_idx * filters.horizontal_lpp.value + hl_idx][color_idx];
-                    *pixel_offset = [0.0, 0.0, 0.0];
-                    *pixel_scale = [
-                        (filters.cur_pixel_vertical_gap.value + 1.0) / scaling.pixel_width,
-                        filters.cur_pixel_horizontal_gap.value + 1.0,
-                        (filters.cur_pixel_vertical_gap.value + filters.cur_pixel_vertical_gap.value) * 0.5 + 1.0,
-                    ];
-                    if filters.vertical_lpp.value > 1 {
-                        let vl_cur_offset = vl_offset_beginning + vl_idx as f32;
-                        pixel_offset[0] = (pixel_offset[0] + vl_cur_offset * scaling.pixel_width) * by_vertical_lpp;
-                        pixel_scale[0] *= filters.vertical_lpp.value as f32;
-                    }
-                    if filters.horizontal_lpp.value > 1 {
-                        let hl_cur_offset = hl_offset_beginning + hl_idx as f32;
-                        pixel_offset[1] = (pixel_offset[1] + hl_cur_offset) * by_horizontal_lpp;
-                        pixel_scale[1] *= filters.horizontal_lpp.value as f32;
-                        if filters.horizontal_lpp.value % 2 == 0 && hl_idx % 2 == 1 {
+    fn update_output_magnet(&mut self) {
+        let magnet = &self.res.magnet;
+        let wobble = match magnet.degauss_started {
+            Some(started) => calculate_degauss_wobble(((self.input.now - started) / 1000.0) as f32, MAGNET_DEGAUSS_SECONDS, MAGNET_DEGAUSS_FREQUENCY),
+            None => 1.0,
+        };
+        let output = &mut self.res.main.render;
+        output.magnet_center = magnet.center;
+        output.magnet_radius = self.res.controllers.magnet_radius.value;
+        output.magnet_strength = magnet.intensity * self.res.controllers.magnet_strength.value * wobble;
+    }
+
+    fn update_output_white_clipping(&mut self) {
+        let filters = &self.res.controllers;
+        let output = &mut self.res.main.render;
+        output.white_clipping = filters.white_clipping.value;
+        output.bloom = [filters.bloom_threshold.value, filters.bloom_intensity.value];
+        if output.white_clipping == WhiteClippingOptions::Bleed {
+            // What is left of the overdriven pixels is spread around them by the bloom pass.
+            output.bloom = [output.bloom[0].min(WHITE_BLEED_THRESHOLD), output.bloom[1].max(WHITE_BLEED_INTENSITY)];
+        }
+    }
+
+    fn update_output_dithering(&mut self) {
+        let filters = &self.res.controllers;
+        let output = &mut self.res.main.render;
+        output.dithering = filters.dithering.value;
+        output.dithering_levels = ((1 << filters.dithering_bits.value) - 1) as f32;
+    }
+
+    fn update_output_burn_in(&mut self) {
+        let filters = &self.res.controllers;
+        // The rate is in time constants per minute, so at 1 a still picture takes about a minute to leave most of its mark.
+        let blend = 1.0 - (-self.dt * filters.burn_in_rate.value / 60.0).exp();
+        self.res.main.render.burn_in = [blend, filters.burn_in_visibility.value];
+    }
+
+    fn update_output_analog_static(&mut self) {
+        let filters = &self.res.controllers;
+        let output = &mut self.res.main.render;
+        let [_, seed, bars] = output.analog_static;
+        // The snow freezes at speed 0, so a still frame of it can be looked at.
+        let speed = filters.analog_static_speed.value;
+        let seed = if speed > 0.0 { self.ctx.random().next() } else { seed };
+        let bars = (bars + self.dt * speed * ANALOG_STATIC_BARS_PER_SECOND).fract();
+        output.analog_static = [filters.analog_static.value, seed, bars];
+    }
+
+    fn update_output_hsync_wobble(&mut self) {
+        let filters = &self.res.controllers;
+        let output = &mut self.res.main.render;
+        let rows = (self.res.video.image_size.height as f32).max(1.0);
+        // The noise repeats every HSYNC_WOBBLE_NOISE_PERIOD cells, so wrapping the drift there is seamless.
+        let drift = (output.hsync_wobble[2] + self.dt * HSYNC_WOBBLE_CELLS_PER_SECOND) % HSYNC_WOBBLE_NOISE_PERIOD;
+        output.hsync_wobble = [filters.hsync_wobble.value, filters.hsync_wobble_frequency.value / rows, drift];
+    }
+
+    fn update_output_transition(&mut self) {
+        let filters = &self.res.controllers;
+        let output = &mut self.res.main.render;
+        match self.res.transition.progress(self.input.now, filters.transition_seconds.value) {
+            Some(progress) => {
+                output.transition = filters.transition.value;
+                output.transition_progress = progress;
+            }
+            None => output.transition = TransitionOptions::Off,
+        }
+    }
+
+    fn update_output_blooming(&mut self) {
+        let filters = &self.res.controllers;
+        // The raster size is locked during a screenshot bracket, so the captures only differ in the swept parameter.
+        let scale = if self.res.screenshot_bracket.is_running() {
+            self.res.blooming.raster_scale
+        } else {
+            self.res
+                .blooming
+                .update(filters.blooming_amount.value, filters.blooming_time_constant.value, self.dt)
+        };
+        if scale == 1.0 {
+            return;
+        }
+        let output = &mut self.res.main.render;
+        output.pixel_spread[0] *= scale;
+        output.pixel_spread[1] *= scale;
+        let scales = output
+            .pixel_scale_foreground
+            .iter_mut()
+            .flatten()
+            .chain(output.pixel_scale_background.iter_mut());
+        for pixel_scale in std::iter::once(&mut output.pixel_scale_base).chain(scales) {
+            pixel_scale[0] /= scale;
+            pixel_scale[1] /= scale;
+        }
+        let offsets = output
+            .pixel_offset_foreground
+            .iter_mut()
+            .flatten()
+            .chain(output.pixel_offset_background.iter_mut());
+        for pixel_offset in offsets {
+            pixel_offset[0] *= scale;
+            pixel_offset[1] *= scale;
+        }
+    }
+
+    fn update_output_damper_wires(&mut self) {
+        let output = &mut self.res.main.render;
+        let filters = &self.res.controllers;
+        let rows = if self.res.scaling.quarter_turned {
+            self.res.video.image_size.width
+        } else {
+            self.res.video.image_size.height
+        };
+        // Positions are measured from the top of the raster, the wires stay fixed on the screen whatever the line passes do.
+        let wire_y = |position: f32| (0.5 - position) * rows as f32 * output.pixel_spread[1];
+        let position = filters.damper_wire_position.value;
+        let (opacity, damper_wire_y) = match filters.damper_wires.value {
+            DamperWiresOptions::Off => (0.0, [0.0, 0.0]),
+            DamperWiresOptions::One => (filters.damper_wire_opacity.value, [wire_y(position), wire_y(position)]),
+            DamperWiresOptions::Two => (filters.damper_wire_opacity.value, [wire_y(position), wire_y(1.0 - position)]),
+        };
+        output.damper_wire_y = damper_wire_y;
+        output.damper_wire_opacity = opacity;
+        output.damper_wire_width = 0.1 * output.pixel_spread[1];
+    }
+
+    fn update_output_rolling_scan(&mut self) {
+        let output = &mut self.res.main.render;
+        let filters = &self.res.controllers;
+        let rows = if self.res.scaling.quarter_turned {
+            self.res.video.image_size.width
+        } else {
+            self.res.video.image_size.height
+        };
+        let height = rows as f32 * output.pixel_spread[1];
+        // The band travels from top to bottom and wraps around, the speed is the beat between the refresh and the filming camera.
+        let phase = (self.input.now / 1000.0 * f64::from(filters.rolling_scan_speed.value)).fract() as f32;
+        let strength = match filters.rolling_scan.value {
+            RollingScanOptions::Off => 0.0,
+            RollingScanOptions::On => ROLLING_SCAN_STRENGTH,
+        };
+        output.rolling_scan = [(0.5 - phase) * height, 0.5 * filters.rolling_scan_size.value * height, height, strength];
+    }
+
+    fn update_output_screen_matte(&mut self) {
+        let output = &mut self.res.main.render;
+        let filters = &self.res.controllers;
+        let (columns, rows) = if self.res.scaling.quarter_turned {
+            (self.res.video.image_size.height, self.res.video.image_size.width)
+        } else {
+            (self.res.video.image_size.width, self.res.video.image_size.height)
+        };
+        let width = columns as f32 * output.pixel_spread[0];
+        let height = rows as f32 * output.pixel_spread[1];
+        let border = filters.screen_matte.value * height;
+        let radius = filters.screen_corner_radius.value * height;
+        // Both sizes go by the height, so the matte is as thick on the sides as on the top and bottom.
+        output.screen_matte = if border > 0.0 || radius > 0.0 {
+            let (half_width, half_height) = (0.5 * width - border, 0.5 * height - border);
+            [half_width, half_height, radius.min(half_width).min(half_height), output.pixel_spread[1]]
+        } else {
+            [0.0, 0.0, 0.0, 0.0]
+        };
+    }
+
+    fn update_output_flicker(&mut self) {
+        let filters = &self.res.controllers;
+        let timers = &self.res.timers;
+        // The wave advances one displayed frame at a time. A frequency the display can't keep up with
+        // is slowed down to the fastest flicker it can show, every other frame bright and dark,
+        // instead of sampling it always at the same phase when both rates match.
+        self.res.main.render.flicker = match filters.flicker.value.frequency() {
+            Some(frequency) => {
+                let display_rate = if timers.last_fps > 0.0 {
+                    timers.last_fps as f64
+                } else {
+                    FLICKER_FALLBACK_DISPLAY_RATE
+                };
+                let frequency = frequency.min(display_rate * 0.5);
+                let phase = (timers.total_frames as f64 * frequency / display_rate).fract();
+                let wave = (phase * std::f64::consts::TAU).cos() as f32;
+                filters.flicker_amplitude.value * (0.5 + 0.5 * wave)
+            }
+            None => 0.0,
+        };
+        // The two fields of an interlaced frame never come out equally bright.
+        if !matches!(filters.interlacing.value, InterlacingOptions::Off) && self.res.timers.total_frames % 2 == 1 {
+            let flicker = &mut self.res.main.render.flicker;
+            *flicker = 1.0 - (1.0 - *flicker) * (1.0 - filters.interlace_field_flicker.value);
+        }
+    }
+
+    fn update_output_edge_reflections(&mut self) {
+        let output = &mut self.res.main.render;
+        let filters = &self.res.controllers;
+        output.edge_reflections.clear();
+        if filters.edge_reflection_intensity.value <= 0.0 || filters.edge_reflection_width.value <= 0.0 {
+            return;
+        }
+        let light_color = get_3_f32color_from_int(filters.light_color.value);
+        output.light_color_edge_reflection = [
+            light_color[0] * filters.edge_reflection_intensity.value,
+            light_color[1] * filters.edge_reflection_intensity.value,
+            light_color[2] * filters.edge_reflection_intensity.value,
+        ];
+        let (columns, rows) = if self.res.scaling.quarter_turned {
+            (self.res.video.image_size.height, self.res.video.image_size.width)
+        } else {
+            (self.res.video.image_size.width, self.res.video.image_size.height)
+        };
+        let extent = [columns as f32 * output.pixel_spread[0], rows as f32 * output.pixel_spread[1]];
+        let width = filters.edge_reflection_width.value * output.pixel_spread[1];
+        let [a, b, c, d] = output.source_transform;
+        let pixel_scale = [1.0 / self.res.scaling.pixel_width, 1.0, 1.0];
+        // Each copy is the picture mirrored on one axis and moved a whole extent, so both meet at the edge.
+        for (axis, sign) in [(0, 1.0), (0, -1.0), (1, 1.0), (1, -1.0)] {
+            let mut normal = [0.0, 0.0];
+            normal[axis] = sign;
+            let mut pixel_offset = [0.0, 0.0, 0.0];
+            pixel_offset[axis] = sign * extent[axis];
+            output.edge_reflections.push(EdgeReflection {
+                source_transform: if axis == 0 { [-a, b, -c, d] } else { [a, -b, c, -d] },
+                pixel_scale,
+                pixel_offset,
+                edge: [normal[0], normal[1], extent[axis] * 0.5, width],
+            });
+        }
+    }
+
+    fn update_output_floor_reflection(&mut self) {
+        let output = &mut self.res.main.render;
+        let filters = &self.res.controllers;
+        output.showing_floor_reflection = filters.floor_reflection.value == FloorReflectionOptions::On;
+        if !output.showing_floor_reflection {
+            return;
+        }
+        let roughness = filters.floor_roughness.value;
+        // Rougher floors scatter more, so the reflection gets both blurrier and dimmer.
+        let intensity = 0.35 * (1.0 - 0.5 * roughness);
+        let light_color = get_3_f32color_from_int(filters.light_color.value);
+        output.light_color_floor_reflection = [light_color[0] * intensity, light_color[1] * intensity, light_color[2] * intensity];
+        output.floor_blur_passes = (roughness * 12.0).round() as usize;
+        let rows = if self.res.scaling.quarter_turned {
+            self.res.video.image_size.width
+        } else {
+            self.res.video.image_size.height
+        };
+        let height = rows as f32 * output.pixel_spread[1];
+        output.floor_y = -0.6 * height;
+    }
+
+    fn update_output_glass_reflection(&mut self) {
+        let output = &mut self.res.main.render;
+        let filters = &self.res.controllers;
+        let light_color = get_3_f32color_from_int(filters.light_color.value);
+        let (reflectivity, environment) = match filters.glass_reflection.value {
+            GlassReflectionOptions::Off => (0.0, [[0.0; 3]; 2]),
+            // The floor of the room bounces back much less light than what comes from above.
+            GlassReflectionOptions::Room => (
+                filters.glass_reflectivity.value,
+                [light_color, [light_color[0] * 0.15, light_color[1] * 0.15, light_color[2] * 0.15]],
+            ),
+            GlassReflectionOptions::Backdrop => (filters.glass_reflectivity.value, [output.light_color_background; 2]),
+        };
+        let room_glare = 1.0 + ROOM_GLARE_GAIN * filters.room_brightness.value;
+        output.glass_reflectivity = reflectivity;
+        output.glass_environment = [
+            [environment[0][0] * room_glare, environment[0][1] * room_glare, environment[0][2] * room_glare],
+            [environment[1][0] * room_glare, environment[1][1] * room_glare, environment[1][2] * room_glare],
+        ];
+        let (columns, rows) = if self.res.scaling.quarter_turned {
+            (self.res.video.image_size.height, self.res.video.image_size.width)
+        } else {
+            (self.res.video.image_size.width, self.res.video.image_size.height)
+        };
+        // Curved screens recede from the center, so a pixel in front of the raster clears the cubes in every case.
+        output.glass_size = [
+            0.5 * columns as f32 * output.pixel_spread[0],
+            0.5 * rows as f32 * output.pixel_spread[1],
+            output.pixel_spread[0],
+        ];
+    }
+
+    fn update_output_blemishes(&mut self) {
+        let output = &mut self.res.main.render;
+        let filters = &self.res.controllers;
+        let (columns, rows) = if self.res.scaling.quarter_turned {
+            (self.res.video.image_size.height, self.res.video.image_size.width)
+        } else {
+            (self.res.video.image_size.width, self.res.video.image_size.height)
+        };
+        let half_size = [0.5 * columns as f32 * output.pixel_spread[0], 0.5 * rows as f32 * output.pixel_spread[1]];
+        output.blemish = [filters.blemish_strength.value, 0.15 * output.pixel_spread[1]];
+        output.blemish_seed = filters.blemish_seed.value as i32;
+        let settings = ScratchSettings {
+            seed: filters.blemish_seed.value,
+            strength: filters.blemish_strength.value,
+            half_size,
+        };
+        if output.scratch_settings != Some(settings) {
+            output.blemish_scratches = scratch_lines(settings.seed, settings.half_size);
+            output.scratch_settings = Some(settings);
+        }
+    }
+
+    fn update_output_defects(&mut self) {
+        let filters = &self.res.controllers;
+        let settings = DefectSettings {
+            seed: filters.blemish_seed.value,
+            dead_subpixels: filters.dead_subpixels.value,
+            stuck_pixels: filters.stuck_pixels.value,
+            dead_scanlines: filters.dead_scanlines.value,
+            width: self.res.video.image_size.width,
+            height: self.res.video.image_size.height,
+        };
+        let output = &mut self.res.main.render;
+        if settings == output.defect_settings {
+            return;
+        }
+        output.defect_mask = if settings.is_flawless() { Vec::new() } else { build_defect_mask(&settings) };
+        output.defect_settings = settings;
+        output.defect_mask_version = output.defect_mask_version.wrapping_add(1);
+    }
+
+    fn update_output_bezel(&mut self) {
+        let output = &mut self.res.main.render;
+        let filters = &self.res.controllers;
+        let rows = if self.res.scaling.quarter_turned {
+            self.res.video.image_size.width
+        } else {
+            self.res.video.image_size.height
+        };
+        let picture_height = rows as f32 * output.pixel_spread[1];
+        // On the plane of the front glass, so the artwork frames the picture from any angle.
+        output.bezel_position = [
+            filters.bezel_offset_x.value * picture_height,
+            filters.bezel_offset_y.value * picture_height,
+            output.pixel_spread[0],
+        ];
+        output.bezel_height = filters.bezel_scale.value * picture_height;
+    }
+
+    fn update_output_room(&mut self) {
+        let output = &mut self.res.main.render;
+        let filters = &self.res.controllers;
+        let settings = RoomSettings {
+            environment: filters.room_environment.value,
+            width: self.res.video.image_size.width,
+            height: self.res.video.image_size.height,
+            pixel_spread: output.pixel_spread,
+            quarter_turned: self.res.scaling.quarter_turned,
+        };
+        if output.room_settings != Some(settings) {
+            let (columns, rows) = if settings.quarter_turned {
+                (settings.height, settings.width)
+            } else {
+                (settings.width, settings.height)
+            };
+            let half_size = [0.5 * columns as f32 * settings.pixel_spread[0], 0.5 * rows as f32 * settings.pixel_spread[1]];
+            // The same floor where the stand of the case and the floor reflection are.
+            output.room_mesh = room_mesh(settings.environment, half_size, -1.2 * half_size[1]);
+            output.room_settings = Some(settings);
+            output.room_mesh_version = output.room_mesh_version.wrapping_add(1);
+        }
+        let scene_light = ROOM_NIGHT_SCENE_LIGHT + (1.0 - ROOM_NIGHT_SCENE_LIGHT) * filters.room_brightness.value;
+        output.room_light = get_3_f32color_from_int(filters.light_color.value).map(|channel| channel * scene_light);
+    }
+
+    fn update_output_monitor_case(&mut self) {
+        let output = &mut self.res.main.render;
+        let settings = if output.layers.iter().any(|layer| layer.kind == LayerKind::MonitorCase) {
+            Some(CaseSettings {
+                width: self.res.video.image_size.width,
+                height: self.res.video.image_size.height,
+                pixel_spread: output.pixel_spread,
+                quarter_turned: self.res.scaling.quarter_turned,
+            })
+        } else {
+            None
+        };
+        if output.case_settings != settings {
+            output.case_mesh = match settings {
+                Some(settings) => {
+                    let (columns, rows) = if settings.quarter_turned {
+                        (settings.height, settings.width)
+                    } else {
+                        (settings.width, settings.height)
+                    };
+                    let half_size = [0.5 * columns as f32 * settings.pixel_spread[0], 0.5 * rows as f32 * settings.pixel_spread[1]];
+                    // The stand rests on the floor that the floor reflection draws.
+                    monitor_case_mesh(half_size, -1.2 * half_size[1])
+                }
+                None => Vec::new(),
+            };
+            output.case_settings = settings;
+            output.case_mesh_version = output.case_mesh_version.wrapping_add(1);
+        }
+        if settings.is_none() {
+            return;
+        }
+        let light_color = get_3_f32color_from_int(self.res.controllers.light_color.value);
+        output.case_color = [light_color[0] * CASE_COLOR[0], light_color[1] * CASE_COLOR[1], light_color[2] * CASE_COLOR[2]];
+    }
+
+    fn update_output_pixel_scale_gap_offset(&mut self) {
+        let output = &mut self.res.main.render;
+        let filters = &self.res.controllers;
+        let scaling = &self.res.scaling;
+
+        output.pixel_spread = [
+            (1.0 + filters.cur_pixel_spread.value + filters.cur_pixel_vertical_spread.value) * scaling.pixel_width,
+            1.0 + filters.cur_pixel_spread.value + filters.cur_pixel_horizontal_spread.value,
+        ];
+        output.pixel_scale_base = [
+            (filters.cur_pixel_vertical_gap.value + 1.0) / scaling.pixel_width,
+            filters.cur_pixel_horizontal_gap.value + 1.0,
+            (filters.cur_pixel_vertical_gap.value + filters.cur_pixel_vertical_gap.value) * 0.5 + 1.0,
+        ];
+
+        let rotation = filters.scanline_orientation.value.degrees(filters.scanline_angle.value).to_radians();
+        output.pixel_rotation = [rotation.cos(), rotation.sin()];
+
+        // The hexagons are as tall as a row and a third, so they only tile when every other row is shifted by half a pixel.
+        output.pixel_row_stagger = match filters.pixels_geometry_kind.value {
+            PixelGeometryKindOptions::Hexagons => 0.5,
+            _ => 0.0,
+        };
+
+        let by_vertical_lpp = 1.0 / filters.vertical_lpp.value;
+        let by_horizontal_lpp = 1.0 / filters.horizontal_lpp.value;
+        let vertical_line_passes = filters.vertical_lpp.line_passes();
+        let horizontal_line_passes = filters.horizontal_lpp.line_passes();
+
+        let line_passes = vertical_line_passes * horizontal_line_passes;
+        output.pixel_scale_background.resize_with(line_passes, Default::default);
+        output.pixel_offset_background.resize_with(line_passes, Default::default);
+        for hl_idx in 0..horizontal_line_passes {
+            for vl_idx in 0..vertical_line_passes {
+                let pixel_offset = &mut output.pixel_offset_background[vl_idx * horizontal_line_passes + hl_idx];
+                let pixel_scale = &mut output.pixel_scale_background[vl_idx * horizontal_line_passes + hl_idx];
+
+                *pixel_offset = [0.0, 0.0, 0.0];
+                *pixel_scale = [(0.0 + 1.0) / scaling.pixel_width, 0.0 + 1.0, (0.0 + 0.0) * 0.5 + 1.0];
+                if filters.vertical_lpp.value > 1.0 {
+                    pixel_offset[0] += line_pass_offset(filters.vertical_lpp.value, vl_idx) * scaling.pixel_width;
+                    pixel_scale[0] /= line_pass_height(filters.vertical_lpp.value, vl_idx);
+                }
+                if filters.horizontal_lpp.value > 1.0 {
+                    pixel_offset[1] += line_pass_offset(filters.horizontal_lpp.value, hl_idx);
+                    pixel_scale[1] /= line_pass_height(filters.horizontal_lpp.value, hl_idx);
+                }
+                pixel_offset[0] += filters.sync_offset.value * scaling.pixel_width;
+                pixel_offset[1] += filters.scanline_phase.value;
+                rotate_pixel_offset(pixel_offset, output.pixel_rotation);
+            }
+        }
+
+        output.pixel_scale_foreground.resize_with(line_passes, Default::default);
+        output.pixel_offset_foreground.resize_with(line_passes, Default::default);
+        for hl_idx in 0..horizontal_line_passes {
+            for vl_idx in 0..vertical_line_passes {
+                for color_idx in 0..output.color_splits {
+                    let pixel_offset = &mut output.pixel_offset_foreground[vl_idx * horizontal_line_passes + hl_idx][color_idx];
+                    let pixel_scale = &mut output.pixel_scale_foreground[vl_idx * horizontal_line_passes + hl_idx][color_idx];
+                    *pixel_offset = [0.0, 0.0, 0.0];
+                    *pixel_scale = [
+                        (filters.cur_pixel_vertical_gap.value + 1.0) / scaling.pixel_width,
+                        filters.cur_pixel_horizontal_gap.value + 1.0,
+                        (filters.cur_pixel_vertical_gap.value + filters.cur_pixel_vertical_gap.value) * 0.5 + 1.0,
+                    ];
+                    if filters.vertical_lpp.value > 1.0 {
+                        pixel_offset[0] += line_pass_offset(filters.vertical_lpp.value, vl_idx) * scaling.pixel_width;
+                        pixel_scale[0] /= line_pass_height(filters.vertical_lpp.value, vl_idx);
+                    }
+                    if filters.horizontal_lpp.value > 1.0 {
+                        pixel_offset[1] += line_pass_offset(filters.horizontal_lpp.value, hl_idx);
+                        pixel_scale[1] /= line_pass_height(filters.horizontal_lpp.value, hl_idx);
+                        if filters.horizontal_lpp.value % 2.0 == 0.0 && hl_idx % 2 == 1 {
                             pixel_offset[0] += 0.5 * scaling.pixel_width * by_vertical_lpp;
                         }
                     }
@@ -888,12 +2110,90 @@ impl<'a> SimulationUpdater<'a> {
                             _ => unreachable!(),
                         },
                     }
+                    if output.color_splits == 3 {
+                        let (offset_x, offset_y) = match filters.color_channels_order.value.channels()[color_idx] {
+                            0 => (filters.channel_offset_red_x.value, filters.channel_offset_red_y.value),
+                            1 => (filters.channel_offset_green_x.value, filters.channel_offset_green_y.value),
+                            _ => (filters.channel_offset_blue_x.value, filters.channel_offset_blue_y.value),
+                        };
+                        pixel_offset[0] += offset_x * scaling.pixel_width;
+                        pixel_offset[1] += offset_y;
+                    }
+                    pixel_offset[0] += filters.sync_offset.value * scaling.pixel_width;
+                    pixel_offset[1] += filters.scanline_phase.value;
+                    rotate_pixel_offset(pixel_offset, output.pixel_rotation);
                 }
             }
         }
     }
 }
 
+// How much a fully lit room lifts the black level of the tube, and brightens the glare on the glass.
+const ROOM_BLACK_LEVEL: f32 = 0.06;
+const ROOM_GLARE_GAIN: f32 = 4.0;
+// How much of the room scene is still visible with the lights off, lit only by the tube.
+const ROOM_NIGHT_SCENE_LIGHT: f32 = 0.3;
+
+// Display rate assumed for the flicker until the first second of frames has been measured.
+const FLICKER_FALLBACK_DISPLAY_RATE: f64 = 60.0;
+
+// Bloom pass forced for the pixels that clip into their neighbors.
+const WHITE_BLEED_THRESHOLD: f32 = 0.9;
+const WHITE_BLEED_INTENSITY: f32 = 0.5;
+
+// Dark grey plastic, lit by the light color.
+const CASE_COLOR: [f32; 3] = [0.18, 0.17, 0.16];
+
+// Fraction of the light lost outside of the rolling scan band.
+const ROLLING_SCAN_STRENGTH: f32 = 0.6;
+
+// Bulge of the barrel curvature, the corners are pulled in by about this fraction of the half screen.
+const SCREEN_BARREL_STRENGTH: f32 = 0.12;
+
+// How fast the horizontal sync wobble changes its shape, and after how many noise cells its noise repeats.
+const HSYNC_WOBBLE_CELLS_PER_SECOND: f32 = 2.0;
+const HSYNC_WOBBLE_NOISE_PERIOD: f32 = 256.0;
+
+// Screens the noise bars roll through per second at speed 1.
+const ANALOG_STATIC_BARS_PER_SECOND: f32 = 0.25;
+
+// The color noise changes at a fixed rate whatever the frame rate is. The seed wraps instead of
+// growing with the session, so the GPU hash gets exact integers even after hours running.
+const NOISE_SEEDS_PER_SECOND: f64 = 60.0;
+const NOISE_SEED_PERIOD: f64 = 1_000_000.0;
+
+fn noise_seed(now_ms: f64) -> i32 {
+    ((now_ms * NOISE_SEEDS_PER_SECOND / 1000.0).floor() % NOISE_SEED_PERIOD) as i32
+}
+
+/// Offset from the pixel center, in source pixels, of the line pass `idx` when each source pixel is drawn as `lines` lines.
+/// For a whole number of lines this is `(idx - (lines - 1) / 2) / lines`.
+fn line_pass_offset(lines: f32, idx: usize) -> f32 {
+    let (top, bottom) = line_pass_edges(lines, idx);
+    (top + bottom) * 0.5 - 0.5
+}
+
+/// Height, in source pixels, of the line pass `idx`. Only the last pass of a fractional line count is thinner than `1 / lines`.
+fn line_pass_height(lines: f32, idx: usize) -> f32 {
+    let (top, bottom) = line_pass_edges(lines, idx);
+    bottom - top
+}
+
+fn line_pass_edges(lines: f32, idx: usize) -> (f32, f32) {
+    (idx as f32 / lines, ((idx + 1) as f32 / lines).min(1.0))
+}
+
+/// Offsets are computed as if scanlines were horizontal, this turns them to the scanline orientation.
+fn rotate_pixel_offset(pixel_offset: &mut [f32; 3], rotation: [f32; 2]) {
+    let [x, y] = [pixel_offset[0], pixel_offset[1]];
+    pixel_offset[0] = x * rotation[0] - y * rotation[1];
+    pixel_offset[1] = x * rotation[1] + y * rotation[0];
+}
+
+fn calculate_dt(now: f64, last_time: f64, max_dt: f32) -> f32 {
+    (((now - last_time) / 1000.0) as f32).clamp(0.0, max_dt.max(0.0))
+}
+
 fn simplify_ar(ar: (f32, f32)) -> (f32, f32) {
     if ar.0.fract() == 0.0 && ar.1.fract() == 0.0 {
         let a = ar.0.trunc() as u32;
@@ -927,6 +2227,19 @@ fn calculate_aspect_ratio_from_image_size(image_size: Size2D<u32>) -> (&'static
     }
 }
 
+fn status_bar_line(controllers: &Controllers, fps: f32) -> String {
+    format!(
+        "{} | {} fps | blur {} | lpp {}x{} | mask {} | {}",
+        controllers.internal_resolution,
+        fps,
+        controllers.blur_passes.value,
+        controllers.horizontal_lpp.value,
+        controllers.vertical_lpp.value,
+        controllers.pixel_shadow_shape_kind.value,
+        controllers.preset_kind.value
+    )
+}
+
 fn calculate_far_away_position(bg_size: Size2D<f32>, internal_resolution: &InternalResolution, pixel_width: f32, stretch: bool) -> f32 {
     let resolution_width = internal_resolution.width() as f32;
     let resolution_height = internal_resolution.height() as f32;
@@ -952,3 +2265,1223 @@ fn calculate_far_away_position(bg_size: Size2D<f32>, internal_resolution: &Inter
         Interesting mathematical fact: 0.68 * squared(4/3) = 1.2076 = 0.68 * 16/9
     */
 }
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+    use crate::blemishes::BLEMISH_SCRATCHES;
+    use crate::input_types::Pressed;
+    use crate::monitor_case::CASE_VERTEX_SIZE;
+    use crate::palettes::PaletteColors;
+    use crate::simulation_context::make_fake_simulation_context;
+    use crate::simulation_core_state::{AnimationStep, VideoInputResources, DEFAULT_MAX_DT};
+    use crate::ui_controller::crt_model::{CrtMaskKind, CrtModelOptions};
+    use crate::ui_controller::dithering::DitheringOptions;
+    use crate::ui_controller::flicker::FlickerOptions;
+    use crate::ui_controller::layer_depth_test::{BackgroundDepthTestOptions, ForegroundDepthTestOptions};
+    use crate::ui_controller::output_color_space::OutputColorSpaceOptions;
+    use crate::ui_controller::phosphor_primaries::PhosphorPrimariesOptions;
+    use crate::ui_controller::room_environment::RoomEnvironmentOptions;
+    use crate::ui_controller::scanline_orientation::ScanlineOrientationOptions;
+    use crate::ui_controller::source_orientation::{SourceMirrorOptions, SourceRotationOptions};
+
+    fn make_resources(now: f64) -> Resources {
+        let mut res = Resources::default();
+        res.initialize(
+            VideoInputResources {
+                steps: vec![AnimationStep { delay: 16_000 }, AnimationStep { delay: 16_000 }],
+                image_size: Size2D { width: 256, height: 240 },
+                ..Default::default()
+            },
+            now,
+        );
+        res.video.last_frame_change = now;
+        res.resetted = false;
+        res
+    }
+
+    #[test]
+    fn test_calculate_dt__regular_frame__is_in_seconds() {
+        assert_eq!(calculate_dt(1016.0, 1000.0, DEFAULT_MAX_DT), 0.016);
+    }
+
+    #[test]
+    fn test_calculate_dt__long_pause__is_clamped_to_max() {
+        assert_eq!(calculate_dt(600_000.0, 1000.0, DEFAULT_MAX_DT), DEFAULT_MAX_DT);
+    }
+
+    #[test]
+    fn test_calculate_dt__clock_going_backwards__is_zero() {
+        assert_eq!(calculate_dt(1000.0, 5000.0, DEFAULT_MAX_DT), 0.0);
+    }
+
+    #[test]
+    fn test_updater__long_pause__dt_is_clamped() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        let input = Input::new(600_000.0);
+        let updater = SimulationUpdater::new(&ctx, &mut res, &input);
+        assert_eq!(updater.dt, DEFAULT_MAX_DT);
+    }
+
+    #[test]
+    fn test_update_animation_buffer__long_pause__resyncs_last_frame_change() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        let input = Input::new(600_000.0);
+        SimulationUpdater::new(&ctx, &mut res, &input).update_animation_buffer();
+        assert_eq!(res.video.last_frame_change, 600_000.0);
+        assert_eq!(res.video.current_frame, 1);
+    }
+
+    #[test]
+    fn test_update_animation_buffer__regular_frame__keeps_cadence() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        let input = Input::new(1020.0);
+        SimulationUpdater::new(&ctx, &mut res, &input).update_animation_buffer();
+        assert_eq!(res.video.last_frame_change, 1016.0);
+        assert_eq!(res.video.current_frame, 1);
+    }
+
+    #[test]
+    fn test_update_animation_buffer__external_clock__ignores_wall_clock() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.video.external_clock = true;
+        let input = Input::new(5000.0);
+        SimulationUpdater::new(&ctx, &mut res, &input).update_animation_buffer();
+        assert_eq!(res.video.current_frame, 0);
+        assert!(!res.video.needs_buffer_data_load);
+    }
+
+    #[test]
+    fn test_update_animation_buffer__external_clock__advances_requested_frames() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.video.external_clock = true;
+        res.video.pending_frame_advances = 3;
+        let input = Input::new(1001.0);
+        SimulationUpdater::new(&ctx, &mut res, &input).update_animation_buffer();
+        assert_eq!(res.video.current_frame, 1);
+        assert_eq!(res.video.pending_frame_advances, 0);
+        assert!(res.video.needs_buffer_data_load);
+    }
+
+    #[test]
+    fn test_update_timers__clock_going_backwards__resets_last_second() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(5000.0);
+        let input = Input::new(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &input).update_timers();
+        assert_eq!(res.timers.last_second, 1000.0);
+        assert_eq!(res.timers.last_time, 1000.0);
+    }
+
+    #[test]
+    fn test_update_output_filter_source_colors__convergence__is_per_beam() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.convergence_red_x.value = 0.5;
+        res.controllers.convergence_blue_y.value = -1.0;
+        let input = Input::new(1016.0);
+        SimulationUpdater::new(&ctx, &mut res, &input).update_output_filter_source_colors();
+        assert_eq!(res.main.render.convergence, [[0.5, 0.0], [0.0, 0.0], [0.0, -1.0]]);
+    }
+
+    #[test]
+    fn test_update_output_crt_sounds__enabled__static_follows_color_noise() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.crt_sounds.value = CrtSoundsOptions::On;
+        res.controllers.color_noise.value = 0.5;
+        let input = Input::new(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &input).update_output_crt_sounds();
+        assert_eq!(res.crt_sounds_static_volume, Some(0.5 * CRT_STATIC_MAX_VOLUME));
+    }
+
+    #[test]
+    fn test_update_output_crt_sounds__disabled__stops_sounds() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.crt_sounds_static_volume = Some(CRT_STATIC_MAX_VOLUME);
+        let input = Input::new(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &input).update_output_crt_sounds();
+        assert_eq!(res.crt_sounds_static_volume, None);
+    }
+
+    #[test]
+    fn test_update_output_pixel_scale_gap_offset__scanline_alignment__shifts_every_layer() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.scaling.pixel_width = 2.0;
+        res.controllers.horizontal_lpp.value = 1.0;
+        res.controllers.vertical_lpp.value = 1.0;
+        res.main.render.color_splits = 1;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_pixel_scale_gap_offset();
+        let background = res.main.render.pixel_offset_background[0];
+        let foreground = res.main.render.pixel_offset_foreground[0][0];
+
+        res.controllers.scanline_phase.value = 0.25;
+        res.controllers.sync_offset.value = -0.5;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_pixel_scale_gap_offset();
+        assert_eq!(
+            res.main.render.pixel_offset_background[0],
+            [background[0] - 1.0, background[1] + 0.25, background[2]]
+        );
+        assert_eq!(
+            res.main.render.pixel_offset_foreground[0][0],
+            [foreground[0] - 1.0, foreground[1] + 0.25, foreground[2]]
+        );
+    }
+
+    #[test]
+    fn test_update_output_pixel_scale_gap_offset__hexagons__stagger_the_odd_rows() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.main.render.color_splits = 1;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_pixel_scale_gap_offset();
+        assert_eq!(res.main.render.pixel_row_stagger, 0.0);
+
+        res.controllers.pixels_geometry_kind.value = PixelGeometryKindOptions::Hexagons;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_pixel_scale_gap_offset();
+        assert_eq!(res.main.render.pixel_row_stagger, 0.5);
+    }
+
+    fn make_flying_camera_resources() -> Resources {
+        let mut res = make_resources(1000.0);
+        res.main.render.pixel_spread = [1.0, 1.0];
+        res.camera.locked_mode = CameraLockMode::ThreeDimensional;
+        res.camera.movement_speed = 1000.0;
+        res.camera.set_position(glm::vec3(0.0, 0.0, 5.0));
+        res
+    }
+
+    #[test]
+    fn test_update_camera__collision_on__stops_in_front_of_the_screen() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_flying_camera_resources();
+        let mut input = Input::new(1016.0);
+        input.walk_forward = true;
+        SimulationUpdater::new(&ctx, &mut res, &input).update_camera();
+        assert_eq!(res.camera.position_destiny.z, 0.8);
+    }
+
+    #[test]
+    fn test_update_camera__collision_off__flies_through_the_screen() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_flying_camera_resources();
+        res.controllers.camera_collision.value = CameraCollisionOptions::Off;
+        let mut input = Input::new(1016.0);
+        input.walk_forward = true;
+        SimulationUpdater::new(&ctx, &mut res, &input).update_camera();
+        assert!(res.camera.position_destiny.z < 0.0);
+    }
+
+    #[test]
+    fn test_update_output_pixel_scale_gap_offset__fractional_vertical_lpp__draws_a_partial_last_line() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.scaling.pixel_width = 1.0;
+        res.controllers.vertical_lpp.value = 2.5;
+        res.controllers.horizontal_lpp.value = 1.0;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_pixel_scale_gap_offset();
+        let offsets: Vec<f32> = res.main.render.pixel_offset_background.iter().map(|offset| offset[0]).collect();
+        let scales: Vec<f32> = res.main.render.pixel_scale_background.iter().map(|scale| scale[0]).collect();
+        assert_eq!(offsets.len(), 3);
+        assert!((offsets[0] + 0.3).abs() < 1e-6);
+        assert!((offsets[1] - 0.1).abs() < 1e-6);
+        assert!((offsets[2] - 0.4).abs() < 1e-6);
+        assert!((scales[0] - 2.5).abs() < 1e-5);
+        assert!((scales[2] - 5.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_update_magnet__held__ramps_up_intensity() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        let mut input = Input::new(1100.0);
+        input.magnet = true;
+        SimulationUpdater::new(&ctx, &mut res, &input).update_magnet();
+        assert_eq!(res.magnet.intensity, 0.1 * MAGNET_RAMP_SPEED);
+        assert_eq!(res.magnet.degauss_started, None);
+    }
+
+    #[test]
+    fn test_update_magnet__released__degausses_until_intensity_is_zero() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.magnet.intensity = 1.0;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_magnet();
+        assert_eq!(res.magnet.degauss_started, Some(1000.0));
+        let after_degauss = 1000.0 + MAGNET_DEGAUSS_SECONDS as f64 * 1000.0;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(after_degauss)).update_magnet();
+        assert_eq!(res.magnet.intensity, 0.0);
+        assert_eq!(res.magnet.degauss_started, None);
+    }
+
+    #[test]
+    fn test_update_procedural_source__toggled__replaces_and_restores_image_size() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.procedural_source.value = ProceduralSourceOptions::ColorBars;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_procedural_source();
+        assert_eq!(res.video.image_size.to_tuple(), PROCEDURAL_SOURCE_SIZE.to_tuple());
+        assert!(res.video.needs_buffer_data_load);
+        assert!(res.procedural_source.active_frame().is_some());
+
+        res.controllers.procedural_source.value = ProceduralSourceOptions::Off;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1032.0)).update_procedural_source();
+        assert_eq!(res.video.image_size.to_tuple(), (256, 240));
+        assert!(res.procedural_source.active_frame().is_none());
+    }
+
+    #[test]
+    fn test_update_subtitles__cue_starts_and_gets_toggled_off__reloads_frame() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.video.subtitles = crate::subtitles::parse_srt("1\n00:00:00,100 --> 00:00:01,000\nHello\n").unwrap();
+        res.video.needs_buffer_data_load = false;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_subtitles();
+        assert!(!res.video.needs_buffer_data_load);
+
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1200.0)).update_subtitles();
+        assert!(res.video.needs_buffer_data_load);
+        assert_eq!(res.subtitles.active_text(&res.video), Some("Hello"));
+
+        res.video.needs_buffer_data_load = false;
+        res.controllers.subtitles.value = SubtitlesOptions::Off;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1216.0)).update_subtitles();
+        assert!(res.video.needs_buffer_data_load);
+        assert_eq!(res.subtitles.active_text(&res.video), None);
+    }
+
+    #[test]
+    fn test_update_moire_assistant__auto_apply__applies_first_moire_free_candidate() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.moire_assistant.value = MoireAssistantOptions::AutoApply;
+        let mut input = Input::new(1016.0);
+        input.moire_assistant.input = true;
+        input.moire_assistant.track_input();
+        SimulationUpdater::new(&ctx, &mut res, &input).update_moire_assistant();
+        assert!(res.moire_assistant.is_running());
+
+        let input = Input::new(1032.0);
+        let mut scores = vec![0.5, 0.001, 0.001].into_iter();
+        for _ in 0..20 {
+            SimulationUpdater::new(&ctx, &mut res, &input).update_moire_assistant();
+            if res.moire_assistant.capture_requested {
+                res.moire_assistant.record_score(scores.next().expect("Too many captures"));
+            }
+        }
+        assert!(!res.moire_assistant.is_running());
+        assert_eq!(res.controllers.internal_resolution.height(), 4320);
+    }
+
+    #[test]
+    fn test_update_blind_test__keys_flip_hidden_presets_until_the_vote() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        let mut input = Input::new(1016.0);
+        input.blind_test.input = true;
+        input.blind_test.track_input();
+        SimulationUpdater::new(&ctx, &mut res, &input).update_blind_test();
+        assert!(res.blind_test.is_running());
+        assert_eq!(res.controllers.preset_kind.value, FilterPresetOptions::Sharp1);
+
+        let mut input = Input::new(1032.0);
+        trigger_hotkey_action(&mut input, &mut res, "1", Pressed::Yes);
+        assert!(!input.magnet);
+        trigger_hotkey_action(&mut input, &mut res, "2", Pressed::Yes);
+        input.blind_test_b.track_input();
+        input.blind_test.input = true;
+        input.blind_test.track_input();
+        SimulationUpdater::new(&ctx, &mut res, &input).update_blind_test();
+        assert!(!res.blind_test.is_running());
+        assert_eq!(res.controllers.preset_kind.value, FilterPresetOptions::CrtApertureGrille1);
+        assert_eq!(res.controllers.pixel_shadow_shape_kind.value.value, 3);
+    }
+
+    #[test]
+    fn test_update_output_pixel_scale_gap_offset__vertical_scanlines__rotate_offsets() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.scaling.pixel_width = 2.0;
+        res.controllers.horizontal_lpp.value = 1.0;
+        res.controllers.vertical_lpp.value = 1.0;
+        res.main.render.color_splits = 1;
+        res.controllers.scanline_phase.value = 0.25;
+        res.controllers.sync_offset.value = -0.5;
+        res.controllers.scanline_orientation.value = ScanlineOrientationOptions::Vertical;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_pixel_scale_gap_offset();
+        let [cos, sin] = res.main.render.pixel_rotation;
+        assert!(cos.abs() < 1e-6 && (sin - 1.0).abs() < 1e-6);
+        let offset = res.main.render.pixel_offset_foreground[0][0];
+        assert!((offset[0] + 0.25).abs() < 1e-6, "{:?}", offset);
+        assert!((offset[1] + 1.0).abs() < 1e-6, "{:?}", offset);
+    }
+
+    #[test]
+    fn test_update_outputs__rotated_and_mirrored_source__transforms_offsets_and_rescales() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.source_rotation.value = SourceRotationOptions::Clockwise90;
+        res.controllers.source_mirror.value = SourceMirrorOptions::Horizontal;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_outputs();
+        assert_eq!(res.main.render.source_transform, [0.0, 1.0, 1.0, 0.0]);
+        assert!(res.scaling.quarter_turned);
+
+        res.controllers.source_rotation.value = SourceRotationOptions::Rotate180;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_filters().unwrap();
+        assert!(!res.scaling.scaling_initialized);
+    }
+
+    #[test]
+    fn test_update_output_damper_wires__two_wires__places_them_symmetrically() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.main.render.pixel_spread = [1.0, 1.0];
+        res.controllers.damper_wires.value = DamperWiresOptions::Two;
+        res.controllers.damper_wire_position.value = 0.25;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_damper_wires();
+        assert_eq!(res.main.render.damper_wire_y, [60.0, -60.0]);
+        assert_eq!(res.main.render.damper_wire_opacity, 0.25);
+
+        res.controllers.damper_wires.value = DamperWiresOptions::Off;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_damper_wires();
+        assert_eq!(res.main.render.damper_wire_opacity, 0.0);
+    }
+
+    #[test]
+    fn test_update_output_rolling_scan__on__moves_the_band_down_with_time() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.main.render.pixel_spread = [1.0, 1.0];
+        res.controllers.rolling_scan.value = RollingScanOptions::On;
+        res.controllers.rolling_scan_speed.value = 0.5;
+        res.controllers.rolling_scan_size.value = 0.25;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1500.0)).update_output_rolling_scan();
+        assert_eq!(res.main.render.rolling_scan, [-60.0, 30.0, 240.0, ROLLING_SCAN_STRENGTH]);
+
+        res.controllers.rolling_scan.value = RollingScanOptions::Off;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1516.0)).update_output_rolling_scan();
+        assert_eq!(res.main.render.rolling_scan[3], 0.0);
+    }
+
+    #[test]
+    fn test_update_output_screen_matte__border__shrinks_the_visible_raster() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.main.render.pixel_spread = [1.0, 1.0];
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_screen_matte();
+        assert_eq!(res.main.render.screen_matte[3], 0.0);
+
+        res.controllers.screen_matte.value = 0.125;
+        res.controllers.screen_corner_radius.value = 0.25;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_screen_matte();
+        let half_width = 0.5 * res.video.image_size.width as f32;
+        assert_eq!(res.main.render.screen_matte, [half_width - 30.0, 90.0, 60.0, 1.0]);
+    }
+
+    #[test]
+    fn test_update_output_flicker__60hz_on_a_60fps_display__alternates_every_frame() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.flicker.value = FlickerOptions::Hz60;
+        res.controllers.flicker_amplitude.value = 0.1;
+        res.timers.last_fps = 60.0;
+        res.timers.total_frames = 2;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_flicker();
+        assert!((res.main.render.flicker - 0.1).abs() < 1e-6);
+
+        res.timers.total_frames = 3;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_flicker();
+        assert!(res.main.render.flicker.abs() < 1e-6);
+
+        res.controllers.flicker.value = FlickerOptions::Off;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_flicker();
+        assert_eq!(res.main.render.flicker, 0.0);
+    }
+
+    #[test]
+    fn test_update_output_flicker__50hz_on_a_200fps_display__follows_the_refresh_wave() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.flicker.value = FlickerOptions::Hz50;
+        res.controllers.flicker_amplitude.value = 0.1;
+        res.timers.last_fps = 200.0;
+        for (frame, expected) in [(4, 0.1), (5, 0.05), (6, 0.0), (7, 0.05)] {
+            res.timers.total_frames = frame;
+            SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_flicker();
+            assert!((res.main.render.flicker - expected).abs() < 1e-6, "frame {}", frame);
+        }
+    }
+
+    #[test]
+    fn test_update_output_glass_reflection__backdrop__reflects_the_backdrop_glow() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.main.render.pixel_spread = [1.0, 1.0];
+        res.main.render.light_color_background = [0.5, 0.25, 0.0];
+        res.controllers.glass_reflection.value = GlassReflectionOptions::Backdrop;
+        res.controllers.glass_reflectivity.value = 0.1;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_glass_reflection();
+        assert_eq!(res.main.render.glass_reflectivity, 0.1);
+        assert_eq!(res.main.render.glass_environment, [[0.5, 0.25, 0.0]; 2]);
+        assert_eq!(res.main.render.glass_size[1], 120.0);
+
+        res.controllers.glass_reflection.value = GlassReflectionOptions::Off;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_glass_reflection();
+        assert_eq!(res.main.render.glass_reflectivity, 0.0);
+    }
+
+    #[test]
+    fn test_update_filters__reset_filters__restores_neutral_output_gamma() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.output_gamma.value = 2.2;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_outputs();
+        assert_eq!(res.main.render.output_gamma, 2.2);
+
+        let mut input = Input::new(1016.0);
+        input.reset_filters = true;
+        SimulationUpdater::new(&ctx, &mut res, &input).update_filters().unwrap();
+        SimulationUpdater::new(&ctx, &mut res, &input).update_outputs();
+        assert_eq!(res.main.render.output_gamma, 1.0);
+    }
+
+    #[test]
+    fn test_update_outputs__contrast_curve__is_linear_until_knee_or_shoulder_are_set() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_outputs();
+        assert_eq!(res.main.render.contrast_curve, [0.0, 0.0]);
+
+        res.controllers.contrast_knee.value = 0.25;
+        res.controllers.contrast_shoulder.value = 0.5;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_outputs();
+        assert_eq!(res.main.render.contrast_curve, [0.25, 0.5]);
+    }
+
+    #[test]
+    fn test_update_outputs__phosphor_matrix__keeps_the_white_point() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_outputs();
+        assert_eq!(res.main.render.phosphor_matrix, None);
+
+        res.controllers.output_color_space.value = OutputColorSpaceOptions::DisplayP3;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_outputs();
+        let matrix = res.main.render.phosphor_matrix.expect("the sRGB phosphors are mapped to Display P3");
+        for row in 0..3 {
+            let white = matrix[row] + matrix[row + 3] + matrix[row + 6];
+            assert!((white - 1.0).abs() < 0.0001, "row {} turns white into {}", row, white);
+        }
+        assert!(matrix[0] < 1.0, "the sRGB red is less saturated than the P3 one");
+
+        res.controllers.output_color_space.value = OutputColorSpaceOptions::Srgb;
+        res.controllers.phosphor_primaries.value = PhosphorPrimariesOptions::P22;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1032.0)).update_outputs();
+        assert!(res.main.render.phosphor_matrix.is_some());
+    }
+
+    #[test]
+    fn test_update_outputs__channel_gain_and_offset__go_per_gun() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_outputs();
+        assert_eq!(res.main.render.channel_gain, [1.0; 3]);
+        assert_eq!(res.main.render.channel_offset, [0.0; 3]);
+
+        res.controllers.green_gain.value = 0.9;
+        res.controllers.blue_offset.value = -0.05;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_outputs();
+        assert_eq!(res.main.render.channel_gain, [1.0, 0.9, 1.0]);
+        assert_eq!(res.main.render.channel_offset, [0.0, 0.0, -0.05]);
+    }
+
+    #[test]
+    fn test_update_outputs__color_hue__goes_to_the_shader_in_radians() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.color_saturation.value = 1.5;
+        res.controllers.color_hue.value = -90.0;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_outputs();
+        assert_eq!(res.main.render.color_saturation, 1.5);
+        assert_eq!(res.main.render.color_hue, -std::f32::consts::FRAC_PI_2);
+    }
+
+    #[test]
+    fn test_update_output_white_clipping__bleed__forces_the_bloom_pass() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_white_clipping();
+        assert_eq!(res.main.render.bloom, [0.7, 0.0]);
+
+        res.controllers.white_clipping.value = WhiteClippingOptions::Bleed;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_white_clipping();
+        assert_eq!(res.main.render.bloom, [0.7, WHITE_BLEED_INTENSITY]);
+    }
+
+    #[test]
+    fn test_update_output_dithering__bits__become_levels_per_channel() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.dithering.value = DitheringOptions::Bayer;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_dithering();
+        assert_eq!(res.main.render.dithering, DitheringOptions::Bayer);
+        assert_eq!(res.main.render.dithering_levels, 63.0);
+
+        res.controllers.dithering_bits.value = 1;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_dithering();
+        assert_eq!(res.main.render.dithering_levels, 1.0);
+    }
+
+    #[test]
+    fn test_update_output_filter_curvature__barrel__keeps_the_geometry_flat() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.screen_curvature_kind.value = ScreenCurvatureKindOptions::Barrel;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_filter_curvature();
+        assert_eq!(res.main.render.screen_curvature_factor, 0.0);
+        assert_eq!(res.main.render.screen_barrel, SCREEN_BARREL_STRENGTH);
+
+        res.controllers.screen_curvature_kind.value = ScreenCurvatureKindOptions::Curved2;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1032.0)).update_output_filter_curvature();
+        assert_eq!(res.main.render.screen_barrel, 0.0);
+    }
+
+    #[test]
+    fn test_update_output_filter_curvature__negative_amount__is_a_pincushion() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.screen_curvature_amount.value = -0.25;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_filter_curvature();
+        assert_eq!(res.main.render.screen_barrel, -0.25);
+
+        res.controllers.screen_curvature_kind.value = ScreenCurvatureKindOptions::Barrel;
+        res.controllers.screen_curvature_amount.value = 0.25;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_filter_curvature();
+        assert_eq!(res.main.render.screen_barrel, SCREEN_BARREL_STRENGTH + 0.25);
+    }
+
+    #[test]
+    fn test_update_output_hsync_wobble__frequency__is_spread_over_the_rows() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.video.image_size.height = 240;
+        res.controllers.hsync_wobble.value = 2.0;
+        res.controllers.hsync_wobble_frequency.value = 3.0;
+        res.main.render.hsync_wobble[2] = HSYNC_WOBBLE_NOISE_PERIOD - 0.01;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_hsync_wobble();
+        let [amplitude, frequency, drift] = res.main.render.hsync_wobble;
+        assert_eq!(amplitude, 2.0);
+        assert_eq!(frequency, 3.0 / 240.0);
+        assert!(drift < 0.1);
+    }
+
+    #[test]
+    fn test_update_output_analog_static__speed__rolls_the_bars_and_freezes_at_zero() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.analog_static.value = 0.5;
+        res.controllers.analog_static_speed.value = 2.0;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_analog_static();
+        let [density, seed, bars] = res.main.render.analog_static;
+        assert_eq!(density, 0.5);
+        assert!(bars > 0.0);
+
+        res.controllers.analog_static_speed.value = 0.0;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1032.0)).update_output_analog_static();
+        assert_eq!(res.main.render.analog_static, [0.5, seed, bars]);
+    }
+
+    #[test]
+    fn test_update_output_burn_in__rate__follows_the_frame_time() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_burn_in();
+        assert_eq!(res.main.render.burn_in, [0.0, 0.25]);
+
+        res.controllers.burn_in_rate.value = 60.0;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1032.0)).update_output_burn_in();
+        let blend = res.main.render.burn_in[0];
+        assert!(blend > 0.0 && blend < 0.05);
+    }
+
+    #[test]
+    fn test_update_output_flicker__interlacing__dims_every_other_field() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.interlacing.value = InterlacingOptions::Weave;
+        res.controllers.interlace_field_flicker.value = 0.1;
+        res.timers.total_frames = 2;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_flicker();
+        assert_eq!(res.main.render.flicker, 0.0);
+
+        res.timers.total_frames = 3;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_flicker();
+        assert!((res.main.render.flicker - 0.1).abs() < 1e-6);
+
+        res.controllers.interlacing.value = InterlacingOptions::Off;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1032.0)).update_output_flicker();
+        assert_eq!(res.main.render.flicker, 0.0);
+    }
+
+    #[test]
+    fn test_update_output_transition__new_source__plays_the_transition_once() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_transition();
+        assert_eq!(res.main.render.transition, TransitionOptions::Off);
+
+        let video = res.video.clone();
+        res.initialize(video, 2000.0);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(2250.0)).update_output_transition();
+        assert_eq!(res.main.render.transition, TransitionOptions::Static);
+        assert_eq!(res.main.render.transition_progress, 0.5);
+
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(2500.0)).update_output_transition();
+        assert_eq!(res.main.render.transition, TransitionOptions::Off);
+    }
+
+    #[test]
+    fn test_update_ken_burns__still_picture__drifts_and_comes_back_when_stopped() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.initial_parameters.initial_position_z = 500.0;
+        res.video.viewport_size = Size2D { width: 800, height: 600 };
+        res.camera.set_position(glm::vec3(0.0, 0.0, 500.0));
+        res.controllers.ken_burns_speed.value = 6.0;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_ken_burns();
+        assert_eq!(res.camera.get_position(), glm::vec3(0.0, 0.0, 500.0), "Animations are left alone.");
+
+        res.video.steps.truncate(1);
+        for frame in 2..60 {
+            SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0 + 16.0 * frame as f64)).update_ken_burns();
+        }
+        let position = res.camera.get_position();
+        assert!(position.z < 500.0 && position.z > 450.0);
+        assert!(position.x != 0.0 && position.y != 0.0);
+
+        res.controllers.ken_burns_speed.value = 0.0;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(2000.0)).update_ken_burns();
+        assert!((res.camera.get_position() - glm::vec3(0.0, 0.0, 500.0)).norm() < 0.001);
+    }
+
+    #[test]
+    fn test_tick__input_replay__moves_one_frame_per_step() -> AppResult<()> {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        let mut input = Input::new(1000.0);
+        input.push_event(InputEventValue::InputReplay("0 down f7\n1 up f7".into()));
+        SimulationCoreTicker::new(&ctx, &mut res, &mut input).tick(1000.0)?;
+        assert!(res.input_replay.is_replaying());
+        assert!(res.video.external_clock);
+        let room_brightness = res.controllers.room_brightness.value;
+        let current_frame = res.video.current_frame;
+
+        input.push_event(InputEventValue::Keyboard {
+            pressed: Pressed::Yes,
+            key: "f7".into(),
+        });
+        SimulationCoreTicker::new(&ctx, &mut res, &mut input).tick(1016.0)?;
+        assert_eq!(res.controllers.room_brightness.value, room_brightness, "Nothing moves without a step.");
+
+        res.video.pending_frame_advances = 1;
+        SimulationCoreTicker::new(&ctx, &mut res, &mut input).tick(1032.0)?;
+        assert_ne!(res.controllers.room_brightness.value, room_brightness);
+        assert_eq!(res.video.current_frame, (current_frame + 1) % res.video.steps.len());
+        assert_eq!(res.video.pending_frame_advances, 0);
+        assert_eq!(res.main.render.hud_lines.last().map(String::as_str), Some("Replay frame 1/2: f7"));
+
+        input.push_event(InputEventValue::InputReplay("".into()));
+        SimulationCoreTicker::new(&ctx, &mut res, &mut input).tick(1048.0)?;
+        assert!(!res.input_replay.is_replaying());
+        assert!(!res.video.external_clock);
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_outputs__room_brightness__lifts_black_level_and_glare() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.glass_reflection.value = GlassReflectionOptions::Room;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_outputs();
+        let dark_glare = res.main.render.glass_environment[0][0];
+        let dark_black = res.main.render.extra_light[0];
+
+        res.controllers.room_brightness.value = 1.0;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_outputs();
+        assert_eq!(res.main.render.glass_environment[0][0], dark_glare * (1.0 + ROOM_GLARE_GAIN));
+        assert!(res.main.render.extra_light[0] > dark_black);
+    }
+
+    #[test]
+    fn test_update_output_room__rebuilds_mesh_only_when_settings_change() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.room_environment.value = RoomEnvironmentOptions::Studio;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_room();
+        assert!(!res.main.render.room_mesh.is_empty());
+        let version = res.main.render.room_mesh_version;
+
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_room();
+        assert_eq!(res.main.render.room_mesh_version, version);
+
+        res.scaling.quarter_turned = !res.scaling.quarter_turned;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1032.0)).update_output_room();
+        assert_eq!(res.main.render.room_mesh_version, version + 1);
+
+        res.controllers.room_environment.value = RoomEnvironmentOptions::Off;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1048.0)).update_output_room();
+        assert!(res.main.render.room_mesh.is_empty());
+        assert_eq!(res.main.render.room_mesh_version, version + 2);
+    }
+
+    #[test]
+    fn test_update_room_lighting__toggles_between_night_and_day() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_room();
+        let night_scene = res.main.render.room_light[0];
+
+        let mut input = Input::new(1016.0);
+        trigger_hotkey_action(&mut input, &mut res, "f7", Pressed::Yes);
+        input.room_lighting.track_input();
+        SimulationUpdater::new(&ctx, &mut res, &input).update_room_lighting();
+        assert_eq!(res.controllers.room_brightness.value, 1.0);
+        SimulationUpdater::new(&ctx, &mut res, &input).update_output_room();
+        assert!((res.main.render.room_light[0] * ROOM_NIGHT_SCENE_LIGHT - night_scene).abs() < 1e-6);
+
+        let mut input = Input::new(1032.0);
+        trigger_hotkey_action(&mut input, &mut res, "f7", Pressed::Yes);
+        input.room_lighting.track_input();
+        SimulationUpdater::new(&ctx, &mut res, &input).update_room_lighting();
+        assert_eq!(res.controllers.room_brightness.value, 0.0);
+    }
+
+    #[test]
+    fn test_update_black_pedestal_setup__toggles_the_ntsc_setup() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        let mut input = Input::new(1016.0);
+        trigger_hotkey_action(&mut input, &mut res, "black-pedestal-setup", Pressed::Yes);
+        input.black_pedestal_setup.track_input();
+        SimulationUpdater::new(&ctx, &mut res, &input).update_black_pedestal_setup();
+        assert_eq!(res.controllers.black_pedestal.value, NTSC_SETUP_IRE);
+        SimulationUpdater::new(&ctx, &mut res, &input).update_outputs();
+        assert_eq!(res.main.render.black_pedestal, 0.075);
+
+        let mut input = Input::new(1032.0);
+        trigger_hotkey_action(&mut input, &mut res, "black-pedestal-setup", Pressed::Yes);
+        input.black_pedestal_setup.track_input();
+        SimulationUpdater::new(&ctx, &mut res, &input).update_black_pedestal_setup();
+        assert_eq!(res.controllers.black_pedestal.value, 0.0);
+    }
+
+    #[test]
+    fn test_update_output_blemishes__rebuilds_scratches_only_when_settings_change() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.main.render.pixel_spread = [1.0, 1.0];
+        res.controllers.blemish_strength.value = 0.1;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_blemishes();
+        let scratches = res.main.render.blemish_scratches;
+        assert_ne!(scratches, [0.0; BLEMISH_SCRATCHES * 4]);
+
+        // Cleared by hand, so a rebuild would be noticed.
+        res.main.render.blemish_scratches = [0.0; BLEMISH_SCRATCHES * 4];
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_blemishes();
+        assert_eq!(res.main.render.blemish_scratches, [0.0; BLEMISH_SCRATCHES * 4]);
+
+        res.controllers.blemish_seed.value = 2;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1032.0)).update_output_blemishes();
+        assert_ne!(res.main.render.blemish_scratches, [0.0; BLEMISH_SCRATCHES * 4]);
+        assert_ne!(res.main.render.blemish_scratches, scratches);
+    }
+
+    #[test]
+    fn test_update_output_defects__rebuilds_mask_only_when_settings_change() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_defects();
+        assert!(res.main.render.defect_mask.is_empty());
+        let version = res.main.render.defect_mask_version;
+
+        res.controllers.dead_scanlines.value = 2;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_defects();
+        let image_size = res.video.image_size;
+        assert_eq!(res.main.render.defect_mask.len(), (image_size.width * image_size.height * 4) as usize);
+        assert_eq!(res.main.render.defect_mask_version, version + 1);
+
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1032.0)).update_output_defects();
+        assert_eq!(res.main.render.defect_mask_version, version + 1);
+    }
+
+    #[test]
+    fn test_update_output_bezel__scales_and_offsets_with_the_picture_height() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.main.render.pixel_spread = [1.0, 1.0];
+        res.controllers.bezel_scale.value = 2.0;
+        res.controllers.bezel_offset_y.value = -0.25;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_bezel();
+        assert_eq!(res.main.render.bezel_height, 480.0);
+        assert_eq!(res.main.render.bezel_position, [0.0, -60.0, 1.0]);
+    }
+
+    #[test]
+    fn test_update_outputs__edge_reflection__mirrors_picture_beyond_each_edge() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_outputs();
+        assert!(res.main.render.edge_reflections.is_empty());
+
+        res.controllers.edge_reflection_intensity.value = 0.5;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_outputs();
+        let spread = res.main.render.pixel_spread;
+        let reflections = &res.main.render.edge_reflections;
+        assert_eq!(reflections.len(), 4);
+        assert_eq!(reflections[0].source_transform, [-1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(reflections[0].pixel_offset, [256.0 * spread[0], 0.0, 0.0]);
+        assert_eq!(reflections[0].edge, [1.0, 0.0, 128.0 * spread[0], 16.0 * spread[1]]);
+        assert_eq!(reflections[3].source_transform, [1.0, 0.0, 0.0, -1.0]);
+        assert_eq!(reflections[3].pixel_offset, [0.0, -240.0 * spread[1], 0.0]);
+        assert!(res.main.render.showing_background);
+    }
+
+    #[test]
+    fn test_update_outputs__depth_test__follows_geometry_unless_forced() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.pixels_geometry_kind.value = PixelGeometryKindOptions::Cubes;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_outputs();
+        assert!(res.main.render.pixel_have_depth);
+        assert!(!res.main.render.background_have_depth);
+
+        res.controllers.foreground_depth_test.value = ForegroundDepthTestOptions::Off;
+        res.controllers.background_depth_test.value = BackgroundDepthTestOptions::On;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_outputs();
+        assert!(!res.main.render.pixel_have_depth);
+        assert!(res.main.render.background_have_depth);
+    }
+
+    #[test]
+    fn test_update_settings_menu__arrows_select_and_adjust_a_controller() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        let mut input = Input::new(1016.0);
+        trigger_hotkey_action(&mut input, &mut res, "f1", Pressed::Yes);
+        input.settings_menu.track_input();
+        SimulationUpdater::new(&ctx, &mut res, &input).update_settings_menu();
+        assert!(res.settings_menu.open);
+
+        let blur_position = settings_menu_entries(&res.controllers)
+            .iter()
+            .position(|entry| entry.name == "blur level")
+            .expect("Blur is listed");
+        let mut input = Input::new(1032.0);
+        for _ in 0..blur_position {
+            trigger_hotkey_action(&mut input, &mut res, "down", Pressed::Yes);
+            input.settings_menu_down.track_input();
+            SimulationUpdater::new(&ctx, &mut res, &input).update_settings_menu();
+            trigger_hotkey_action(&mut input, &mut res, "down", Pressed::No);
+            input.settings_menu_down.track_input();
+        }
+        assert!(!input.turn_down);
+
+        let blur_before = res.controllers.blur_passes.value;
+        trigger_hotkey_action(&mut input, &mut res, "right", Pressed::Yes);
+        let mut updater = SimulationUpdater::new(&ctx, &mut res, &input);
+        updater.update_settings_menu();
+        updater.update_filters().unwrap();
+        updater.update_outputs();
+        assert_eq!(res.controllers.blur_passes.value, blur_before + 1);
+        let selected = format!(" {}", blur_before + 1);
+        assert!(res
+            .main
+            .render
+            .hud_lines
+            .iter()
+            .any(|line| line.starts_with("> blur level") && line.ends_with(&selected)));
+    }
+
+    #[test]
+    fn test_update_output_signal__bob_interlacing__leaves_the_other_field_dark() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.interlacing.value = InterlacingOptions::Bob;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_signal();
+        assert_eq!(res.main.render.interlace_other_field, 0.0);
+    }
+
+    #[test]
+    fn test_update_timers__every_frame__counts_total_frames_across_seconds() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_timers();
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(2100.0)).update_timers();
+        assert_eq!(res.timers.total_frames, 2);
+        assert_eq!(res.timers.frame_count, 0);
+    }
+
+    #[test]
+    fn test_update_output_signal__composite__reloads_the_frame_with_a_new_field_every_time() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.signal_kind.value = SignalKindOptions::Composite;
+        res.video.needs_buffer_data_load = false;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_signal();
+        assert!(res.video.needs_buffer_data_load);
+        let field = res.main.render.signal.field;
+
+        res.video.needs_buffer_data_load = false;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_signal();
+        assert!(res.video.needs_buffer_data_load);
+        assert_eq!(res.main.render.signal.field, field + 1);
+
+        res.controllers.signal_kind.value = SignalKindOptions::SVideo;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1032.0)).update_output_signal();
+        res.video.needs_buffer_data_load = false;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1048.0)).update_output_signal();
+        assert!(!res.video.needs_buffer_data_load);
+    }
+
+    #[test]
+    fn test_update_outputs__status_bar_toggled__shows_live_filter_values() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.timers.last_fps = 60.0;
+        let mut input = Input::new(1016.0);
+        trigger_hotkey_action(&mut input, &mut res, "f2", Pressed::Yes);
+        res.controllers.status_bar.pre_process_input();
+        let mut updater = SimulationUpdater::new(&ctx, &mut res, &input);
+        updater.update_filters().unwrap();
+        updater.update_outputs();
+        assert_eq!(res.main.render.hud_lines.len(), 1);
+        let line = &res.main.render.hud_lines[0];
+        assert!(line.contains("60 fps"));
+        assert!(line.contains(&format!("blur {}", res.controllers.blur_passes.value)));
+        assert!(line.ends_with(&res.controllers.preset_kind.value.to_string()));
+    }
+
+    #[test]
+    fn test_update_outputs__aperture_grille_mask__draws_flat_continuous_stripes() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.apply_crt_mask(CrtMaskKind::ApertureGrille);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_outputs();
+        assert_eq!(res.controllers.vertical_lpp.value, 1.0);
+        assert_eq!(res.main.render.color_splits, 1);
+        assert_eq!(res.main.render.pixel_scale_foreground[0][0][1], 1.0);
+        assert!(!res.main.render.pixel_have_depth);
+
+        res.controllers.apply_crt_mask(CrtMaskKind::ShadowMask);
+        assert!(matches!(res.controllers.pixels_geometry_kind.value, PixelGeometryKindOptions::Squares));
+    }
+
+    #[test]
+    fn test_apply_crt_model__maps_phosphor_decay_and_white_point() {
+        let mut res = make_resources(1000.0);
+        res.controllers.light_color.value = 0x00FF_0000;
+        let spec = CrtModelOptions::SonyKv27Fs120.spec().expect("Sony KV-27FS120 has a spec");
+        res.controllers.apply_crt_model(spec);
+        assert_eq!(res.controllers.response_fall_time.value, 1.5);
+        assert_eq!(res.controllers.color_temperature.value, 9300.0);
+        assert_eq!(res.controllers.light_color.value, 0x00FF_FFFF);
+    }
+
+    #[test]
+    fn test_noise_seed__advances_at_fixed_rate_and_wraps() {
+        assert_eq!(noise_seed(0.0), 0);
+        assert_eq!(noise_seed(1000.0), 60);
+        assert_eq!(noise_seed(1010.0), 60);
+        assert_eq!(noise_seed(1020.0), 61);
+        let long_session = 1000.0 * NOISE_SEED_PERIOD / NOISE_SEEDS_PER_SECOND * 3.0 + 1000.0;
+        assert_eq!(noise_seed(long_session), 60);
+    }
+
+    #[test]
+    fn test_update_outputs__layers__keep_order_and_skip_hidden_or_empty() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.backlight_percent.value = 0.5;
+        res.controllers.edge_reflection_intensity.value = 0.0;
+        res.controllers.layers.value.raise(LayerKind::SolidBackdrop);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_outputs();
+        let kinds: Vec<LayerKind> = res.main.render.layers.iter().map(|layer| layer.kind).collect();
+        assert_eq!(kinds, vec![LayerKind::SolidBackdrop, LayerKind::SourcePixels]);
+        assert!(res.main.render.showing_background);
+
+        res.controllers.layers.value.get_mut(LayerKind::SolidBackdrop).enabled = false;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_outputs();
+        let kinds: Vec<LayerKind> = res.main.render.layers.iter().map(|layer| layer.kind).collect();
+        assert_eq!(kinds, vec![LayerKind::SourcePixels]);
+        assert!(!res.main.render.showing_background);
+    }
+
+    #[test]
+    fn test_update_outputs__monitor_case__builds_mesh_only_when_enabled() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_outputs();
+        assert!(res.main.render.case_mesh.is_empty());
+
+        res.controllers.layers.value.get_mut(LayerKind::MonitorCase).enabled = true;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_outputs();
+        assert_eq!(res.main.render.layers.last().map(|layer| layer.kind), Some(LayerKind::MonitorCase));
+        assert!(!res.main.render.case_mesh.is_empty());
+        assert_eq!(res.main.render.case_mesh.len() % CASE_VERTEX_SIZE, 0);
+    }
+
+    #[test]
+    fn test_update_outputs__monitor_case__rebuilds_mesh_only_when_settings_change() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.controllers.layers.value.get_mut(LayerKind::MonitorCase).enabled = true;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_outputs();
+        let version = res.main.render.case_mesh_version;
+
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_outputs();
+        assert_eq!(res.main.render.case_mesh_version, version);
+
+        res.scaling.quarter_turned = !res.scaling.quarter_turned;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1032.0)).update_outputs();
+        assert_eq!(res.main.render.case_mesh_version, version + 1);
+        assert!(!res.main.render.case_mesh.is_empty());
+
+        res.controllers.layers.value.get_mut(LayerKind::MonitorCase).enabled = false;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1048.0)).update_outputs();
+        assert_eq!(res.main.render.case_mesh_version, version + 2);
+        assert!(res.main.render.case_mesh.is_empty());
+    }
+
+    #[test]
+    fn test_update_outputs__floor_reflection__sits_below_the_raster() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_outputs();
+        assert!(!res.main.render.showing_floor_reflection);
+
+        res.controllers.floor_reflection.value = FloorReflectionOptions::On;
+        res.controllers.floor_roughness.value = 0.5;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_outputs();
+        let render = &res.main.render;
+        assert!(render.showing_floor_reflection);
+        assert!(render.showing_background);
+        assert_eq!(render.floor_blur_passes, 6);
+        assert_eq!(render.floor_y, -0.6 * 240.0 * render.pixel_spread[1]);
+    }
+
+    #[test]
+    fn test_update_outputs__blooming_on_bright_frame__grows_the_raster() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_outputs();
+        let spread = res.main.render.pixel_spread;
+        let scale = res.main.render.pixel_scale_base;
+
+        res.controllers.blooming_amount.value = 2.0;
+        res.controllers.blooming_time_constant.value = 0.0;
+        res.blooming.record_luminance(1.0);
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_outputs();
+        assert_eq!(res.main.render.pixel_spread, [spread[0] * 1.02, spread[1] * 1.02]);
+        assert_eq!(res.main.render.pixel_scale_base, [scale[0] / 1.02, scale[1] / 1.02, scale[2]]);
+    }
+
+    #[test]
+    fn test_update_output_tv_lines__changed__reloads_band_limited_frame() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.video.needs_buffer_data_load = false;
+        res.controllers.tv_lines.value = 96.0;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_tv_lines();
+        assert!(res.video.needs_buffer_data_load);
+        assert!(res.main.render.horizontal_band_limit > 0.0);
+
+        res.video.needs_buffer_data_load = false;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_tv_lines();
+        assert!(!res.video.needs_buffer_data_load);
+    }
+
+    #[test]
+    fn test_update_output_palette__custom_upload__selects_it_and_reloads_the_frame() -> AppResult<()> {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.video.needs_buffer_data_load = false;
+        res.controllers.palette.value = PaletteOptions::Ega;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1000.0)).update_output_palette();
+        assert!(res.video.needs_buffer_data_load);
+        assert_eq!(res.main.render.palette.0.len(), 16);
+
+        res.video.needs_buffer_data_load = false;
+        res.controllers.custom_palette.value = PaletteColors::from_str("#000000,#ffffff")?;
+        res.controllers.custom_palette.uploaded = true;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_palette();
+        assert_eq!(res.controllers.palette.value, PaletteOptions::Custom);
+        assert_eq!(res.main.render.palette.0, vec![[0, 0, 0], [255, 255, 255]]);
+        assert!(res.video.needs_buffer_data_load);
+
+        res.video.needs_buffer_data_load = false;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1032.0)).update_output_palette();
+        assert!(!res.video.needs_buffer_data_load);
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_output_response_time__slow_fall__reloads_every_frame() {
+        let ctx = make_fake_simulation_context();
+        let mut res = make_resources(1000.0);
+        res.main.render.response_blend_factors = [1.0, 1.0];
+        res.video.needs_buffer_data_load = false;
+        SimulationUpdater::new(&ctx, &mut res, &Input::new(1016.0)).update_output_response_time();
+        assert!(!res.video.needs_buffer_data_load);
+
+        res.controllers.response_fall_time.value = 100.0;
+        for now in [1032.0, 1048.0] {
+            res.video.needs_buffer_data_load = false;
+            SimulationUpdater::new(&ctx, &mut res, &Input::new(now)).update_output_response_time();
+            assert!(res.video.needs_buffer_data_load);
+        }
+        assert_eq!(res.main.render.response_blend_factors[0], 1.0);
+        assert!(res.main.render.response_blend_factors[1] < 1.0);
+    }
+
+    #[test]
+    fn test_line_pass_offset__whole_lines__are_centered() {
+        assert_eq!(line_pass_offset(1.0, 0), 0.0);
+        assert_eq!(line_pass_offset(2.0, 0), -0.25);
+        assert_eq!(line_pass_offset(2.0, 1), 0.25);
+        assert_eq!(line_pass_offset(4.0, 0), -0.375);
+        assert_eq!(line_pass_offset(4.0, 3), 0.375);
+    }
+
+    #[test]
+    fn test_line_pass_offset__fractional_lines__clip_the_last_line() {
+        let offsets: Vec<f32> = (0..3).map(|idx| line_pass_offset(2.5, idx)).collect();
+        assert!((offsets[0] + 0.3).abs() < 1e-6);
+        assert!((offsets[1] - 0.1).abs() < 1e-6);
+        assert!((offsets[2] - 0.4).abs() < 1e-6);
+        assert!((line_pass_height(2.5, 1) - 0.4).abs() < 1e-6);
+        assert!((line_pass_height(2.5, 2) - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_line_pass_offset__any_line_count__centers_lines_inside_pixel() {
+        for lines in 1..=64 {
+            let count = lines as f32;
+            let offsets: Vec<f32> = (0..lines).map(|idx| line_pass_offset(count, idx)).collect();
+            let sum: f32 = offsets.iter().sum();
+            assert!(sum.abs() < 1e-4, "lines {} not centered: {}", lines, sum);
+            assert!((offsets[0] - 0.5 / count + 0.5).abs() < 1e-5, "lines {} first edge", lines);
+            assert!((offsets[lines - 1] + 0.5 / count - 0.5).abs() < 1e-5, "lines {} last edge", lines);
+            for pair in offsets.windows(2) {
+                assert!((pair[1] - pair[0] - 1.0 / count).abs() < 1e-5, "lines {} spacing", lines);
+            }
+        }
+    }
+}
@@ -0,0 +1,429 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::general_types::Size2D;
+use crate::simulation_core_state::VideoInputResources;
+
+const FONT_FIRST_CHAR: u8 = b' ';
+const FONT_GLYPH_SIZE: u32 = 8;
+// Public domain 8x8 font (font8x8_basic) covering printable ASCII. Each byte is a row, lowest bit on the left.
+const FONT_8X8: [[u8; 8]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x18, 0x3C, 0x3C, 0x18, 0x18, 0x00, 0x18, 0x00],
+    [0x36, 0x36, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x36, 0x36, 0x7F, 0x36, 0x7F, 0x36, 0x36, 0x00],
+    [0x0C, 0x3E, 0x03, 0x1E, 0x30, 0x1F, 0x0C, 0x00],
+    [0x00, 0x63, 0x33, 0x18, 0x0C, 0x66, 0x63, 0x00],
+    [0x1C, 0x36, 0x1C, 0x6E, 0x3B, 0x33, 0x6E, 0x00],
+    [0x06, 0x06, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x18, 0x0C, 0x06, 0x06, 0x06, 0x0C, 0x18, 0x00],
+    [0x06, 0x0C, 0x18, 0x18, 0x18, 0x0C, 0x06, 0x00],
+    [0x00, 0x66, 0x3C, 0xFF, 0x3C, 0x66, 0x00, 0x00],
+    [0x00, 0x0C, 0x0C, 0x3F, 0x0C, 0x0C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C, 0x06],
+    [0x00, 0x00, 0x00, 0x3F, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C, 0x00],
+    [0x60, 0x30, 0x18, 0x0C, 0x06, 0x03, 0x01, 0x00],
+    [0x3E, 0x63, 0x73, 0x7B, 0x6F, 0x67, 0x3E, 0x00],
+    [0x0C, 0x0E, 0x0C, 0x0C, 0x0C, 0x0C, 0x3F, 0x00],
+    [0x1E, 0x33, 0x30, 0x1C, 0x06, 0x33, 0x3F, 0x00],
+    [0x1E, 0x33, 0x30, 0x1C, 0x30, 0x33, 0x1E, 0x00],
+    [0x38, 0x3C, 0x36, 0x33, 0x7F, 0x30, 0x78, 0x00],
+    [0x3F, 0x03, 0x1F, 0x30, 0x30, 0x33, 0x1E, 0x00],
+    [0x1C, 0x06, 0x03, 0x1F, 0x33, 0x33, 0x1E, 0x00],
+    [0x3F, 0x33, 0x30, 0x18, 0x0C, 0x0C, 0x0C, 0x00],
+    [0x1E, 0x33, 0x33, 0x1E, 0x33, 0x33, 0x1E, 0x00],
+    [0x1E, 0x33, 0x33, 0x3E, 0x30, 0x18, 0x0E, 0x00],
+    [0x00, 0x0C, 0x0C, 0x00, 0x00, 0x0C, 0x0C, 0x00],
+    [0x00, 0x0C, 0x0C, 0x00, 0x00, 0x0C, 0x0C, 0x06],
+    [0x18, 0x0C, 0x06, 0x03, 0x06, 0x0C, 0x18, 0x00],
+    [0x00, 0x00, 0x3F, 0x00, 0x00, 0x3F, 0x00, 0x00],
+    [0x06, 0x0C, 0x18, 0x30, 0x18, 0x0C, 0x06, 0x00],
+    [0x1E, 0x33, 0x30, 0x18, 0x0C, 0x00, 0x0C, 0x00],
+    [0x3E, 0x63, 0x7B, 0x7B, 0x7B, 0x03, 0x1E, 0x00],
+    [0x0C, 0x1E, 0x33, 0x33, 0x3F, 0x33, 0x33, 0x00],
+    [0x3F, 0x66, 0x66, 0x3E, 0x66, 0x66, 0x3F, 0x00],
+    [0x3C, 0x66, 0x03, 0x03, 0x03, 0x66, 0x3C, 0x00],
+    [0x1F, 0x36, 0x66, 0x66, 0x66, 0x36, 0x1F, 0x00],
+    [0x7F, 0x46, 0x16, 0x1E, 0x16, 0x46, 0x7F, 0x00],
+    [0x7F, 0x46, 0x16, 0x1E, 0x16, 0x06, 0x0F, 0x00],
+    [0x3C, 0x66, 0x03, 0x03, 0x73, 0x66, 0x7C, 0x00],
+    [0x33, 0x33, 0x33, 0x3F, 0x33, 0x33, 0x33, 0x00],
+    [0x1E, 0x0C, 0x0C, 0x0C, 0x0C, 0x0C, 0x1E, 0x00],
+    [0x78, 0x30, 0x30, 0x30, 0x33, 0x33, 0x1E, 0x00],
+    [0x67, 0x66, 0x36, 0x1E, 0x36, 0x66, 0x67, 0x00],
+    [0x0F, 0x06, 0x06, 0x06, 0x46, 0x66, 0x7F, 0x00],
+    [0x63, 0x77, 0x7F, 0x7F, 0x6B, 0x63, 0x63, 0x00],
+    [0x63, 0x67, 0x6F, 0x7B, 0x73, 0x63, 0x63, 0x00],
+    [0x1C, 0x36, 0x63, 0x63, 0x63, 0x36, 0x1C, 0x00],
+    [0x3F, 0x66, 0x66, 0x3E, 0x06, 0x06, 0x0F, 0x00],
+    [0x1E, 0x33, 0x33, 0x33, 0x3B, 0x1E, 0x38, 0x00],
+    [0x3F, 0x66, 0x66, 0x3E, 0x36, 0x66, 0x67, 0x00],
+    [0x1E, 0x33, 0x07, 0x0E, 0x38, 0x33, 0x1E, 0x00],
+    [0x3F, 0x2D, 0x0C, 0x0C, 0x0C, 0x0C, 0x1E, 0x00],
+    [0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x3F, 0x00],
+    [0x33, 0x33, 0x33, 0x33, 0x33, 0x1E, 0x0C, 0x00],
+    [0x63, 0x63, 0x63, 0x6B, 0x7F, 0x77, 0x63, 0x00],
+    [0x63, 0x63, 0x36, 0x1C, 0x1C, 0x36, 0x63, 0x00],
+    [0x33, 0x33, 0x33, 0x1E, 0x0C, 0x0C, 0x1E, 0x00],
+    [0x7F, 0x63, 0x31, 0x18, 0x4C, 0x66, 0x7F, 0x00],
+    [0x1E, 0x06, 0x06, 0x06, 0x06, 0x06, 0x1E, 0x00],
+    [0x03, 0x06, 0x0C, 0x18, 0x30, 0x60, 0x40, 0x00],
+    [0x1E, 0x18, 0x18, 0x18, 0x18, 0x18, 0x1E, 0x00],
+    [0x08, 0x1C, 0x36, 0x63, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF],
+    [0x0C, 0x0C, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x1E, 0x30, 0x3E, 0x33, 0x6E, 0x00],
+    [0x07, 0x06, 0x06, 0x3E, 0x66, 0x66, 0x3B, 0x00],
+    [0x00, 0x00, 0x1E, 0x33, 0x03, 0x33, 0x1E, 0x00],
+    [0x38, 0x30, 0x30, 0x3E, 0x33, 0x33, 0x6E, 0x00],
+    [0x00, 0x00, 0x1E, 0x33, 0x3F, 0x03, 0x1E, 0x00],
+    [0x1C, 0x36, 0x06, 0x0F, 0x06, 0x06, 0x0F, 0x00],
+    [0x00, 0x00, 0x6E, 0x33, 0x33, 0x3E, 0x30, 0x1F],
+    [0x07, 0x06, 0x36, 0x6E, 0x66, 0x66, 0x67, 0x00],
+    [0x0C, 0x00, 0x0E, 0x0C, 0x0C, 0x0C, 0x1E, 0x00],
+    [0x30, 0x00, 0x30, 0x30, 0x30, 0x33, 0x33, 0x1E],
+    [0x07, 0x06, 0x66, 0x36, 0x1E, 0x36, 0x67, 0x00],
+    [0x0E, 0x0C, 0x0C, 0x0C, 0x0C, 0x0C, 0x1E, 0x00],
+    [0x00, 0x00, 0x33, 0x7F, 0x7F, 0x6B, 0x63, 0x00],
+    [0x00, 0x00, 0x1F, 0x33, 0x33, 0x33, 0x33, 0x00],
+    [0x00, 0x00, 0x1E, 0x33, 0x33, 0x33, 0x1E, 0x00],
+    [0x00, 0x00, 0x3B, 0x66, 0x66, 0x3E, 0x06, 0x0F],
+    [0x00, 0x00, 0x6E, 0x33, 0x33, 0x3E, 0x30, 0x78],
+    [0x00, 0x00, 0x3B, 0x6E, 0x66, 0x06, 0x0F, 0x00],
+    [0x00, 0x00, 0x3E, 0x03, 0x1E, 0x30, 0x1F, 0x00],
+    [0x08, 0x0C, 0x3E, 0x0C, 0x0C, 0x2C, 0x18, 0x00],
+    [0x00, 0x00, 0x33, 0x33, 0x33, 0x33, 0x6E, 0x00],
+    [0x00, 0x00, 0x33, 0x33, 0x33, 0x1E, 0x0C, 0x00],
+    [0x00, 0x00, 0x63, 0x6B, 0x7F, 0x7F, 0x36, 0x00],
+    [0x00, 0x00, 0x63, 0x36, 0x1C, 0x36, 0x63, 0x00],
+    [0x00, 0x00, 0x33, 0x33, 0x33, 0x3E, 0x30, 0x1F],
+    [0x00, 0x00, 0x3F, 0x19, 0x0C, 0x26, 0x3F, 0x00],
+    [0x38, 0x0C, 0x0C, 0x07, 0x0C, 0x0C, 0x38, 0x00],
+    [0x18, 0x18, 0x18, 0x00, 0x18, 0x18, 0x18, 0x00],
+    [0x07, 0x0C, 0x0C, 0x38, 0x0C, 0x0C, 0x07, 0x00],
+    [0x6E, 0x3B, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubtitleCue {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+/// Playback state of the subtitle track held in `VideoInputResources`. Times are in milliseconds.
+/// The clock follows the animation and restarts when it loops, unless a video feed sets `media_time`.
+#[derive(Default)]
+pub struct SubtitlesState {
+    pub media_time: Option<f64>,
+    animation_start: f64,
+    last_frame: usize,
+    active_cue: Option<usize>,
+}
+
+impl SubtitlesState {
+    pub fn restart(&mut self, now: f64) {
+        *self = SubtitlesState {
+            animation_start: now,
+            ..Default::default()
+        };
+    }
+
+    pub fn active_text<'a>(&self, video: &'a VideoInputResources) -> Option<&'a str> {
+        self.active_cue.and_then(|index| video.subtitles.get(index)).map(|cue| cue.text.as_str())
+    }
+
+    // Returns true when the visible cue changes, so the frame has to be uploaded again.
+    pub(crate) fn update(&mut self, video: &VideoInputResources, now: f64, enabled: bool) -> bool {
+        if video.current_frame < self.last_frame {
+            self.animation_start = video.last_frame_change;
+        }
+        self.last_frame = video.current_frame;
+        let clock = self.media_time.unwrap_or(now - self.animation_start);
+        let active_cue = if enabled {
+            video.subtitles.iter().position(|cue| cue.start <= clock && clock < cue.end)
+        } else {
+            None
+        };
+        let changed = active_cue != self.active_cue;
+        self.active_cue = active_cue;
+        changed
+    }
+}
+
+pub fn parse_srt(source: &str) -> Result<Vec<SubtitleCue>, String> {
+    let mut cues = vec![];
+    let mut lines = source.trim_start_matches('\u{feff}').lines().map(str::trim_end).peekable();
+    while let Some(line) = lines.next() {
+        let arrow = match line.find("-->") {
+            Some(arrow) => arrow,
+            None => continue,
+        };
+        let start = parse_srt_time(&line[..arrow])?;
+        let end = parse_srt_time(line[arrow + 3..].split_whitespace().next().unwrap_or(""))?;
+        let mut text = vec![];
+        while let Some(line) = lines.peek() {
+            if line.is_empty() {
+                break;
+            }
+            text.push(strip_srt_tags(line));
+            lines.next();
+        }
+        cues.push(SubtitleCue {
+            start,
+            end,
+            text: text.join("\n"),
+        });
+    }
+    if cues.is_empty() {
+        return Err("No subtitles found.".into());
+    }
+    Ok(cues)
+}
+
+// "hh:mm:ss,mmm", also accepting a dot as decimal separator.
+fn parse_srt_time(time: &str) -> Result<f64, String> {
+    let error = || format!("Wrong subtitle time '{}'.", time.trim());
+    let mut parts = time.trim().split([':', ',', '.']);
+    let mut next = || parts.next().and_then(|part| part.parse::<u32>().ok()).map(f64::from).ok_or_else(error);
+    let (hours, minutes, seconds, millis) = (next()?, next()?, next()?, next()?);
+    // In floating point, so huge hours in a malformed file can't overflow.
+    Ok(((hours * 60.0 + minutes) * 60.0 + seconds) * 1000.0 + millis)
+}
+
+// Formatting such as <i> or {\an8} is dropped, the font only has one style.
+fn strip_srt_tags(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut closing = None;
+    for c in line.chars() {
+        match (closing, c) {
+            (None, '<') => closing = Some('>'),
+            (None, '{') => closing = Some('}'),
+            (None, c) => text.push(c),
+            (Some(end), c) if c == end => closing = None,
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Draws white text with a black outline, centered at the bottom of an RGBA frame with rows from the top.
+/// Glyphs grow with the frame height, so they look the same at any source resolution.
+pub fn burn_subtitle(frame: &mut [u8], size: Size2D<u32>, text: &str) {
+    let scale = (size.height / 240).max(1);
+    let glyph = FONT_GLYPH_SIZE * scale;
+    let max_chars = ((size.width / glyph).max(1) as usize).saturating_sub(2).max(1);
+    let lines = wrap_lines(text, max_chars);
+    let line_height = glyph + scale * 2;
+    let bottom = size.height.saturating_sub(size.height / 16);
+    let top = bottom.saturating_sub(line_height * lines.len() as u32);
+
+    let mut mask = vec![false; (size.width * size.height) as usize];
+    for (line_index, line) in lines.iter().enumerate() {
+        let line_width = line.chars().count() as u32 * glyph;
+        let left = size.width.saturating_sub(line_width) / 2;
+        let line_top = top + line_index as u32 * line_height;
+        for (char_index, c) in line.chars().enumerate() {
+            let rows = glyph_rows(c);
+            for y in 0..glyph {
+                let pixel_y = line_top + y;
+                if pixel_y >= size.height {
+                    break;
+                }
+                let row = rows[(y / scale) as usize];
+                for x in 0..glyph {
+                    let pixel_x = left + char_index as u32 * glyph + x;
+                    if pixel_x < size.width && (row >> (x / scale)) & 1 == 1 {
+                        mask[(pixel_y * size.width + pixel_x) as usize] = true;
+                    }
+                }
+            }
+        }
+    }
+
+    let (width, height) = (size.width as i64, size.height as i64);
+    let outline = scale as i64;
+    for y in (top as i64 - outline).max(0)..(bottom as i64 + outline).min(height) {
+        for x in 0..width {
+            let is_text = mask[(y * width + x) as usize];
+            let is_outline = !is_text
+                && (-outline..=outline).any(|dy| {
+                    (-outline..=outline).any(|dx| {
+                        let (nx, ny) = (x + dx, y + dy);
+                        nx >= 0 && ny >= 0 && nx < width && ny < height && mask[(ny * width + nx) as usize]
+                    })
+                });
+            let color = if is_text {
+                255
+            } else if is_outline {
+                0
+            } else {
+                continue;
+            };
+            let index = ((y * width + x) * 4) as usize;
+            frame[index..index + 3].iter_mut().for_each(|channel| *channel = color);
+            frame[index + 3] = 255;
+        }
+    }
+}
+
+const HUD_PADDING: u32 = 4;
+const HUD_LINE_HEIGHT: u32 = FONT_GLYPH_SIZE + 2;
+
+/// Renders lines of text as premultiplied RGBA, white over a translucent black box, with rows from the top.
+/// The result is at the font's native size, the renderer scales it up.
+pub fn rasterize_hud(lines: &[String]) -> (Size2D<u32>, Vec<u8>) {
+    let columns = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0) as u32;
+    let size = Size2D {
+        width: columns * FONT_GLYPH_SIZE + HUD_PADDING * 2,
+        height: lines.len() as u32 * HUD_LINE_HEIGHT + HUD_PADDING * 2,
+    };
+    let mut pixels: Vec<u8> = [0, 0, 0, 160].iter().copied().cycle().take((size.width * size.height * 4) as usize).collect();
+    for (line_index, line) in lines.iter().enumerate() {
+        let line_top = HUD_PADDING + line_index as u32 * HUD_LINE_HEIGHT;
+        for (char_index, c) in line.chars().enumerate() {
+            let rows = glyph_rows(c);
+            for (y, row) in rows.iter().enumerate() {
+                for x in 0..FONT_GLYPH_SIZE {
+                    if (row >> x) & 1 == 1 {
+                        let pixel_x = HUD_PADDING + char_index as u32 * FONT_GLYPH_SIZE + x;
+                        let index = (((line_top + y as u32) * size.width + pixel_x) * 4) as usize;
+                        pixels[index..index + 4].copy_from_slice(&[255, 255, 255, 255]);
+                    }
+                }
+            }
+        }
+    }
+    (size, pixels)
+}
+
+fn glyph_rows(c: char) -> &'static [u8; 8] {
+    let index = if c.is_ascii() && c as u8 >= FONT_FIRST_CHAR && (c as u8 - FONT_FIRST_CHAR) < FONT_8X8.len() as u8 {
+        c as u8 - FONT_FIRST_CHAR
+    } else {
+        b'?' - FONT_FIRST_CHAR
+    };
+    &FONT_8X8[index as usize]
+}
+
+fn wrap_lines(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = vec![];
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    const SRT: &str = "\u{feff}1\r\n00:00:01,000 --> 00:00:02,500\r\nHello <i>there</i>\r\n\r\n2\r\n00:00:03.000 --> 00:00:04,000 X1:0\r\n{\\an8}First line\r\nSecond line\r\n";
+
+    #[test]
+    fn test_parse_srt__two_cues__parses_times_and_strips_tags() {
+        assert_eq!(
+            parse_srt(SRT),
+            Ok(vec![
+                SubtitleCue {
+                    start: 1000.0,
+                    end: 2500.0,
+                    text: "Hello there".into()
+                },
+                SubtitleCue {
+                    start: 3000.0,
+                    end: 4000.0,
+                    text: "First line\nSecond line".into()
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_srt_time__huge_hours__does_not_overflow() {
+        assert_eq!(parse_srt_time("4294967295:00:00,000"), Ok(4294967295.0 * 3_600_000.0));
+        assert!(parse_srt_time("99999999999:00:00,000").is_err());
+    }
+
+    #[test]
+    fn test_parse_srt__no_cues__returns_error() {
+        assert!(parse_srt("just some text").is_err());
+    }
+
+    #[test]
+    fn test_subtitles_update__animation_loops__restarts_clock() {
+        let mut video = VideoInputResources {
+            subtitles: parse_srt(SRT).unwrap(),
+            current_frame: 3,
+            ..Default::default()
+        };
+        let mut subtitles = SubtitlesState::default();
+        subtitles.restart(0.0);
+        assert!(subtitles.update(&video, 1500.0, true));
+        assert_eq!(subtitles.active_text(&video), Some("Hello there"));
+
+        video.current_frame = 0;
+        video.last_frame_change = 1400.0;
+        assert!(subtitles.update(&video, 1500.0, true));
+        assert_eq!(subtitles.active_text(&video), None);
+    }
+
+    #[test]
+    fn test_subtitles_update__disabled__hides_cue() {
+        let video = VideoInputResources {
+            subtitles: parse_srt(SRT).unwrap(),
+            ..Default::default()
+        };
+        let mut subtitles = SubtitlesState {
+            media_time: Some(3500.0),
+            ..Default::default()
+        };
+        assert!(subtitles.update(&video, 0.0, true));
+        assert!(subtitles.update(&video, 0.0, false));
+        assert!(!subtitles.update(&video, 0.0, false));
+    }
+
+    #[test]
+    fn test_burn_subtitle__draws_text_at_the_bottom() {
+        let size = Size2D { width: 64, height: 48 };
+        let mut frame = vec![128; (size.width * size.height * 4) as usize];
+        burn_subtitle(&mut frame, size, "Hi");
+        let row_has_text = |y: u32| (0..size.width).any(|x| frame[((y * size.width + x) * 4) as usize] == 255);
+        assert!((0..size.height / 2).all(|y| !row_has_text(y)));
+        assert!((size.height / 2..size.height).any(row_has_text));
+    }
+
+    #[test]
+    fn test_rasterize_hud__fits_the_longest_line() {
+        let (size, pixels) = rasterize_hud(&["ab".into(), "abcd".into()]);
+        assert_eq!(size.width, 4 * FONT_GLYPH_SIZE + HUD_PADDING * 2);
+        assert_eq!(size.height, 2 * HUD_LINE_HEIGHT + HUD_PADDING * 2);
+        assert_eq!(pixels.len(), (size.width * size.height * 4) as usize);
+        assert_eq!(pixels[..4], [0, 0, 0, 160]);
+        assert!(pixels.chunks(4).any(|pixel| pixel == [255, 255, 255, 255]));
+    }
+}
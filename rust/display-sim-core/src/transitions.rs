@@ -0,0 +1,54 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+/// Playback of the transition shown when the source changes. Times are in milliseconds, like the input clock.
+#[derive(Default)]
+pub struct TransitionState {
+    started: Option<f64>,
+}
+
+impl TransitionState {
+    pub(crate) fn start(&mut self, now: f64) {
+        self.started = Some(now);
+    }
+
+    /// Fraction of the transition already played, or None once it is over.
+    pub(crate) fn progress(&mut self, now: f64, seconds: f32) -> Option<f32> {
+        let started = self.started?;
+        let progress = ((now - started) / (1000.0 * f64::from(seconds))) as f32;
+        if progress >= 1.0 {
+            self.started = None;
+            return None;
+        }
+        Some(progress.max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    #[test]
+    fn test_progress__after_the_duration__finishes_the_transition() {
+        let mut transition = TransitionState::default();
+        assert_eq!(transition.progress(1000.0, 0.5), None);
+
+        transition.start(1000.0);
+        assert_eq!(transition.progress(1250.0, 0.5), Some(0.5));
+        assert_eq!(transition.progress(1500.0, 0.5), None);
+        assert_eq!(transition.progress(1250.0, 0.5), None);
+    }
+}
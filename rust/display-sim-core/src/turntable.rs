@@ -0,0 +1,99 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::camera::CameraData;
+
+pub(crate) enum TurntableAdvance {
+    Wait,
+    Orbit(glm::Vec3),
+    Finished(Box<CameraData>),
+}
+
+/// Orbits the camera around the vertical axis of the screen at a fixed radius and height, while the frontend
+/// records the canvas into a clip. The orbit follows the clock, so the clip lasts the same at any frame rate.
+/// The camera from before the turntable is given back at the end, so the orbit leaves no trace.
+#[derive(Default)]
+pub struct TurntableState {
+    started_at: f64,
+    duration: f64,
+    radius: f32,
+    height: f32,
+    start_angle: f32,
+    original: Option<Box<CameraData>>,
+}
+
+impl TurntableState {
+    pub fn is_running(&self) -> bool {
+        self.original.is_some()
+    }
+
+    pub(crate) fn start(&mut self, camera: CameraData, seconds: f32, now: f64) {
+        let position = camera.get_position();
+        *self = TurntableState {
+            started_at: now,
+            duration: seconds as f64 * 1000.0,
+            radius: glm::length(&glm::vec2(position.x, position.z)),
+            height: position.y,
+            start_angle: position.x.atan2(position.z),
+            original: Some(Box::new(camera)),
+        };
+    }
+
+    pub(crate) fn advance(&mut self, now: f64) -> TurntableAdvance {
+        let original = match self.original {
+            Some(_) => &mut self.original,
+            None => return TurntableAdvance::Wait,
+        };
+        let progress = (now - self.started_at) / self.duration;
+        if progress >= 1.0 {
+            return TurntableAdvance::Finished(original.take().expect("Turntable is running"));
+        }
+        let angle = self.start_angle + std::f32::consts::TAU * progress.max(0.0) as f32;
+        TurntableAdvance::Orbit(glm::vec3(self.radius * angle.sin(), self.height, self.radius * angle.cos()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    #[test]
+    fn test_turntable__orbits_at_fixed_radius_over_the_duration_then_restores() {
+        let mut state = TurntableState::default();
+        assert!(matches!(state.advance(1000.0), TurntableAdvance::Wait));
+
+        let mut camera = CameraData::new(1.0, 1.0);
+        camera.set_position(glm::vec3(0.0, 10.0, 100.0));
+        state.start(camera, 2.0, 1000.0);
+        let mut positions = vec![];
+        for frame in 0.. {
+            match state.advance(1000.0 + frame as f64 * 20.0) {
+                TurntableAdvance::Wait => {}
+                TurntableAdvance::Orbit(position) => positions.push(position),
+                TurntableAdvance::Finished(original) => {
+                    assert_eq!(original.get_position(), glm::vec3(0.0, 10.0, 100.0));
+                    break;
+                }
+            }
+        }
+        assert_eq!(positions.len(), 100);
+        assert_eq!(positions[0], glm::vec3(0.0, 10.0, 100.0));
+        let half = positions[positions.len() / 2];
+        assert!((half - glm::vec3(0.0, 10.0, -100.0)).norm() < 0.001);
+        assert!(positions.iter().all(|p| (glm::length(&glm::vec2(p.x, p.z)) - 100.0).abs() < 0.001));
+        assert!(!state.is_running());
+    }
+}
@@ -0,0 +1,83 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::general_types::Size2D;
+
+/// Gaussian deviation, in source pixels, that limits the horizontal detail to `tv_lines`.
+/// TV lines are counted over the picture height, so across the width they resolve `tv_lines * aspect_ratio` pixels.
+/// Returns 0 when the source doesn't have more detail than that.
+pub fn band_limit_sigma(tv_lines: f32, aspect_ratio: f32, source_width: u32) -> f32 {
+    let resolvable = tv_lines * aspect_ratio;
+    if tv_lines <= 0.0 || resolvable >= source_width as f32 {
+        return 0.0;
+    }
+    0.5 * source_width as f32 / resolvable
+}
+
+/// Low-pass filters every row of an RGBA frame with a gaussian of deviation `sigma`. Alpha is left untouched.
+pub fn band_limit_horizontally(frame: &mut [u8], size: Size2D<u32>, sigma: f32) {
+    if sigma <= 0.0 {
+        return;
+    }
+    let radius = (sigma * 3.0).ceil() as i32;
+    let mut kernel: Vec<f32> = (-radius..=radius).map(|x| (-(x * x) as f32 / (2.0 * sigma * sigma)).exp()).collect();
+    let total: f32 = kernel.iter().sum();
+    kernel.iter_mut().for_each(|weight| *weight /= total);
+
+    let width = size.width as usize;
+    let mut row = vec![0u8; width * 4];
+    for y in 0..size.height as usize {
+        let start = y * width * 4;
+        row.copy_from_slice(&frame[start..start + width * 4]);
+        for x in 0..width {
+            let mut color = [0.0f32; 3];
+            for (k, weight) in kernel.iter().enumerate() {
+                let sample = (x as i32 + k as i32 - radius).clamp(0, width as i32 - 1) as usize * 4;
+                for (channel, value) in color.iter_mut().enumerate() {
+                    *value += row[sample + channel] as f32 * weight;
+                }
+            }
+            for (channel, value) in color.iter().enumerate() {
+                frame[start + x * 4 + channel] = value.round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    #[test]
+    fn test_band_limit_sigma__enough_tv_lines__does_not_filter() {
+        assert_eq!(band_limit_sigma(0.0, 4.0 / 3.0, 640), 0.0);
+        assert_eq!(band_limit_sigma(600.0, 4.0 / 3.0, 640), 0.0);
+        assert_eq!(band_limit_sigma(240.0, 4.0 / 3.0, 640), 1.0);
+    }
+
+    #[test]
+    fn test_band_limit_horizontally__single_dot__spreads_along_the_row_only() {
+        let size = Size2D { width: 9, height: 2 };
+        let mut frame = vec![0u8; 9 * 2 * 4];
+        frame[4 * 4..4 * 4 + 4].copy_from_slice(&[255, 255, 255, 255]);
+        band_limit_horizontally(&mut frame, size, 1.0);
+        assert!(frame[4 * 4] < 255 && frame[3 * 4] > 0 && frame[5 * 4] > 0);
+        assert_eq!(frame[4 * 4 + 3], 255);
+        assert!(frame[9 * 4..].iter().all(|value| *value == 0));
+        let row_sum: u32 = (0..9).map(|x| frame[x * 4] as u32).sum();
+        assert!((250..=260).contains(&row_sum));
+    }
+}
@@ -42,26 +42,134 @@ pub trait EncodedValue {
     fn to_string(&self) -> AppResult<String>;
 }
 
+pub mod analog_static;
+pub mod analog_static_speed;
+pub mod background_blur_passes;
+pub mod background_resolution_scale;
 pub mod backlight_percent;
+pub mod bezel_offset_x;
+pub mod bezel_offset_y;
+pub mod bezel_scale;
+pub mod black_pedestal;
+pub mod blemish_seed;
+pub mod blemish_strength;
+pub mod blending_space;
+pub mod bloom_intensity;
+pub mod bloom_threshold;
+pub mod blooming_amount;
+pub mod blooming_time_constant;
+pub mod blue_gain;
+pub mod blue_offset;
 pub mod blur_passes;
 pub mod brightness_color;
+pub mod burn_in_rate;
+pub mod burn_in_visibility;
+pub mod camera_collision;
+pub mod channel_offset;
 pub mod color_channels;
+pub mod color_channels_order;
 pub mod color_gamma;
+pub mod color_hue;
 pub mod color_noise;
+pub mod color_saturation;
+pub mod color_temperature;
+pub mod contrast_knee;
+pub mod contrast_shoulder;
+pub mod convergence;
+pub mod crt_model;
+pub mod crt_sounds;
 pub mod cur_pixel_horizontal_gap;
+pub mod cur_pixel_horizontal_spread;
 pub mod cur_pixel_spread;
 pub mod cur_pixel_vertical_gap;
+pub mod cur_pixel_vertical_spread;
+pub mod custom_palette;
+pub mod damper_wire_opacity;
+pub mod damper_wire_position;
+pub mod damper_wires;
+pub mod dead_scanlines;
+pub mod dead_subpixels;
+pub mod diagnostics_overlay;
+pub mod display_dpi;
+pub mod dithering;
+pub mod dithering_bits;
+pub mod dot_pitch;
+pub mod edge_reflection_intensity;
+pub mod edge_reflection_width;
 mod enum_ui;
 pub mod extra_bright;
 pub mod extra_contrast;
 pub mod filter_preset;
+pub mod flicker;
+pub mod flicker_amplitude;
+pub mod floor_reflection;
+pub mod floor_roughness;
+pub mod foreground_opacity;
+pub mod glass_reflection;
+pub mod glass_reflectivity;
+pub mod green_gain;
+pub mod green_offset;
 pub mod horizontal_lpp;
+pub mod hsync_wobble;
+pub mod hsync_wobble_frequency;
+pub mod interlace_field_flicker;
+pub mod interlace_line_pairing;
+pub mod interlacing;
 pub mod internal_resolution;
+pub mod ken_burns_margin;
+pub mod ken_burns_speed;
+pub mod layer_depth_test;
+pub mod layering_blend_mode;
+pub mod layers;
 pub mod light_color;
+pub mod light_gun;
+pub mod magnet_radius;
+pub mod magnet_strength;
+pub mod message_verbosity;
+pub mod moire_assistant;
+pub mod output_color_space;
+pub mod output_gamma;
+pub mod overlapping_blend_mode;
+pub mod overlapping_gain_blue;
+pub mod overlapping_gain_green;
+pub mod overlapping_gain_red;
+pub mod palette;
+pub mod phosphor_primaries;
+pub mod physical_calibration;
 pub mod pixel_geometry_kind;
 pub mod pixel_shadow_height;
 pub mod pixel_shadow_shape_kind;
+pub mod procedural_source;
+pub mod red_gain;
+pub mod red_offset;
+pub mod response_fall_time;
+pub mod response_rise_time;
 pub mod rgb_calibration;
+pub mod rolling_scan;
+pub mod rolling_scan_size;
+pub mod rolling_scan_speed;
+pub mod room_brightness;
+pub mod room_environment;
+pub mod scanline_alignment;
+pub mod scanline_orientation;
+pub mod screen_corner_radius;
+pub mod screen_curvature_amount;
 pub mod screen_curvature_kind;
+pub mod screen_matte;
+pub mod screenshot_bracket;
+pub mod sharpen_halo_suppression;
+pub mod sharpen_strength;
+pub mod signal_kind;
+pub mod source_orientation;
+pub mod status_bar;
+pub mod stuck_pixels;
+pub mod subtitles;
 pub mod texture_interpolation;
+pub mod transition;
+pub mod transition_seconds;
+pub mod turntable_seconds;
+pub mod tv_lines;
 pub mod vertical_lpp;
+pub mod vignette_radius;
+pub mod vignette_strength;
+pub mod white_clipping;
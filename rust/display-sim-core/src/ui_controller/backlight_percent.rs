@@ -49,7 +49,7 @@ impl UiController for BacklightPercent {
         &["backlight-percent-dec", "."]
     }
     fn update(&mut self, main: &MainState, ctx: &dyn SimulationContext) -> bool {
-        FieldChanger::new(ctx, &mut self.value, self.input)
+        FieldChanger::new(ctx, "backlight-percent", &mut self.value, self.input)
             .set_progression(0.025 * main.dt * main.filter_speed)
             .set_event_value(self.event)
             .set_min(0.0)
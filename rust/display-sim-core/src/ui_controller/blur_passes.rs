@@ -49,7 +49,7 @@ impl UiController for BlurPasses {
         &["shift+j", "blur-level-dec"]
     }
     fn update(&mut self, _: &MainState, ctx: &dyn SimulationContext) -> bool {
-        FieldChanger::new(ctx, &mut self.value, self.input)
+        FieldChanger::new(ctx, "blur-level", &mut self.value, self.input)
             .set_progression(1)
             .set_event_value(self.event)
             .set_min(0)
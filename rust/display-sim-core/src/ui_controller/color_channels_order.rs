@@ -0,0 +1,86 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::ui_controller::enum_ui::{EnumHolder, EnumUi};
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone)]
+pub enum ColorChannelsOrderOptions {
+    Rgb,
+    Rbg,
+    Grb,
+    Gbr,
+    Brg,
+    Bgr,
+}
+
+impl ColorChannelsOrderOptions {
+    pub fn channels(self) -> [usize; 3] {
+        match self {
+            ColorChannelsOrderOptions::Rgb => [0, 1, 2],
+            ColorChannelsOrderOptions::Rbg => [0, 2, 1],
+            ColorChannelsOrderOptions::Grb => [1, 0, 2],
+            ColorChannelsOrderOptions::Gbr => [1, 2, 0],
+            ColorChannelsOrderOptions::Brg => [2, 0, 1],
+            ColorChannelsOrderOptions::Bgr => [2, 1, 0],
+        }
+    }
+}
+
+impl std::fmt::Display for ColorChannelsOrderOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ColorChannelsOrderOptions::Rgb => write!(f, "RGB"),
+            ColorChannelsOrderOptions::Rbg => write!(f, "RBG"),
+            ColorChannelsOrderOptions::Grb => write!(f, "GRB"),
+            ColorChannelsOrderOptions::Gbr => write!(f, "GBR"),
+            ColorChannelsOrderOptions::Brg => write!(f, "BRG"),
+            ColorChannelsOrderOptions::Bgr => write!(f, "BGR"),
+        }
+    }
+}
+
+impl EnumUi for ColorChannelsOrderOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["color-channels-order-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["color-channels-order-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:color_channels_order"
+    }
+}
+
+pub type ColorChannelsOrder = EnumHolder<ColorChannelsOrderOptions>;
+
+#[cfg(test)]
+mod color_channels_order_tests {
+    use super::ColorChannelsOrderOptions;
+    use enum_len_trait::EnumLen;
+    use num_traits::FromPrimitive;
+    #[test]
+    fn test_every_order_is_a_permutation() {
+        for i in 0..ColorChannelsOrderOptions::len() {
+            let order = ColorChannelsOrderOptions::from_usize(i).expect("Bad order index");
+            let mut channels = order.channels();
+            channels.sort_unstable();
+            assert_eq!(channels, [0, 1, 2], "{}", order);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::app_events::AppEventDispatcher;
+use crate::field_changer::FieldChanger;
+use crate::general_types::IncDec;
+use crate::simulation_context::SimulationContext;
+use crate::simulation_core_state::MainState;
+use crate::ui_controller::{EncodedValue, UiController};
+use app_error::AppResult;
+
+// Misconvergence of the three beams, in source pixels. It's applied when sampling each channel, so it also works with combined channels.
+macro_rules! convergence_impl {
+    ($ty:ident, $field:expr, $event_tag:expr, $key_inc:expr, $key_dec:expr, $dispatch_tag:expr) => {
+        #[derive(Default, Copy, Clone)]
+        pub struct $ty {
+            input: IncDec<bool>,
+            event: Option<f32>,
+            pub value: f32,
+        }
+
+        impl From<f32> for $ty {
+            fn from(value: f32) -> Self {
+                $ty {
+                    input: Default::default(),
+                    event: None,
+                    value,
+                }
+            }
+        }
+
+        impl UiController for $ty {
+            fn event_tag(&self) -> &'static str {
+                $event_tag
+            }
+            fn keys_inc(&self) -> &[&'static str] {
+                &[$key_inc]
+            }
+            fn keys_dec(&self) -> &[&'static str] {
+                &[$key_dec]
+            }
+            fn update(&mut self, main: &MainState, ctx: &dyn SimulationContext) -> bool {
+                FieldChanger::new(ctx, $field, &mut self.value, self.input)
+                    .set_progression(0.1 * main.dt * main.filter_speed)
+                    .set_event_value(self.event)
+                    .set_min(-CONVERGENCE_LIMIT)
+                    .set_max(CONVERGENCE_LIMIT)
+                    .set_trigger_handler(|x| {
+                        ctx.dispatcher()
+                            .dispatch_string_event($dispatch_tag, &if x.floor() == x { format!("{:.00}", x) } else { format!("{:.03}", x) })
+                    })
+                    .process_with_sums()
+            }
+            fn apply_event(&mut self) {
+                if let Some(v) = self.event {
+                    self.value = v;
+                }
+            }
+            fn reset_inputs(&mut self) {
+                self.event = None;
+                self.input.increase = false;
+                self.input.decrease = false;
+            }
+            fn read_event(&mut self, encoded: &dyn EncodedValue) -> AppResult<()> {
+                self.event = Some(encoded.to_f32()?);
+                Ok(())
+            }
+            fn read_key_inc(&mut self, pressed: bool) {
+                self.input.increase = pressed;
+            }
+            fn read_key_dec(&mut self, pressed: bool) {
+                self.input.decrease = pressed;
+            }
+            fn dispatch_event(&self, dispatcher: &dyn AppEventDispatcher) {
+                dispatcher.dispatch_string_event(
+                    $dispatch_tag,
+                    &if self.value.floor() == self.value {
+                        format!("{:.00}", self.value)
+                    } else {
+                        format!("{:.03}", self.value)
+                    },
+                );
+            }
+            fn pre_process_input(&mut self) {}
+            fn post_process_input(&mut self) {
+                self.event = None;
+            }
+        }
+    };
+}
+
+const CONVERGENCE_LIMIT: f32 = 3.0;
+
+convergence_impl! {ConvergenceRedX, "convergence-red-x", "front2back:convergence-red-x", "convergence-red-x-inc", "convergence-red-x-dec", "back2front:convergence_red_x"}
+convergence_impl! {ConvergenceRedY, "convergence-red-y", "front2back:convergence-red-y", "convergence-red-y-inc", "convergence-red-y-dec", "back2front:convergence_red_y"}
+
+convergence_impl! {ConvergenceGreenX, "convergence-green-x", "front2back:convergence-green-x", "convergence-green-x-inc", "convergence-green-x-dec", "back2front:convergence_green_x"}
+convergence_impl! {ConvergenceGreenY, "convergence-green-y", "front2back:convergence-green-y", "convergence-green-y-inc", "convergence-green-y-dec", "back2front:convergence_green_y"}
+
+convergence_impl! {ConvergenceBlueX, "convergence-blue-x", "front2back:convergence-blue-x", "convergence-blue-x-inc", "convergence-blue-x-dec", "back2front:convergence_blue_x"}
+convergence_impl! {ConvergenceBlueY, "convergence-blue-y", "front2back:convergence-blue-y", "convergence-blue-y-inc", "convergence-blue-y-dec", "back2front:convergence_blue_y"}
@@ -0,0 +1,179 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::app_events::AppEventDispatcher;
+use crate::boolean_button::BooleanButton;
+use crate::field_changer::FieldChanger;
+use crate::general_types::IncDec;
+use crate::simulation_context::SimulationContext;
+use crate::simulation_core_state::MainState;
+use crate::ui_controller::screen_curvature_kind::ScreenCurvatureKindOptions;
+use crate::ui_controller::{EncodedValue, UiController};
+use app_error::AppResult;
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CrtMaskKind {
+    ApertureGrille,
+    ShadowMask,
+    SlotMask,
+}
+
+pub struct CrtModelSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub dot_pitch_mm: f32,
+    pub curvature: ScreenCurvatureKindOptions,
+    pub mask: CrtMaskKind,
+    pub phosphor_decay_ms: f32,
+    pub white_point_kelvin: f32,
+}
+
+#[rustfmt::skip]
+pub const CRT_MODELS: [CrtModelSpec; 6] = [
+    CrtModelSpec { name: "sony-pvm-20m4", description: "Sony PVM-20M4", dot_pitch_mm: 0.25, curvature: ScreenCurvatureKindOptions::Curved1, mask: CrtMaskKind::ApertureGrille, phosphor_decay_ms: 1.0, white_point_kelvin: 6500.0 },
+    CrtModelSpec { name: "sony-kv-27fs120", description: "Sony KV-27FS120", dot_pitch_mm: 0.80, curvature: ScreenCurvatureKindOptions::Flat, mask: CrtMaskKind::ApertureGrille, phosphor_decay_ms: 1.5, white_point_kelvin: 9300.0 },
+    CrtModelSpec { name: "commodore-1084s", description: "Commodore 1084S", dot_pitch_mm: 0.42, curvature: ScreenCurvatureKindOptions::Curved2, mask: CrtMaskKind::ShadowMask, phosphor_decay_ms: 2.0, white_point_kelvin: 6500.0 },
+    CrtModelSpec { name: "nec-multisync-3d", description: "NEC MultiSync 3D", dot_pitch_mm: 0.31, curvature: ScreenCurvatureKindOptions::Curved2, mask: CrtMaskKind::ShadowMask, phosphor_decay_ms: 1.0, white_point_kelvin: 9300.0 },
+    CrtModelSpec { name: "mitsubishi-diamond-pro-2070sb", description: "Mitsubishi Diamond Pro 2070SB", dot_pitch_mm: 0.24, curvature: ScreenCurvatureKindOptions::Flat, mask: CrtMaskKind::ApertureGrille, phosphor_decay_ms: 0.5, white_point_kelvin: 6500.0 },
+    CrtModelSpec { name: "consumer-slot-mask-tv", description: "Consumer slot mask TV", dot_pitch_mm: 0.70, curvature: ScreenCurvatureKindOptions::Curved3, mask: CrtMaskKind::SlotMask, phosphor_decay_ms: 2.5, white_point_kelvin: 9300.0 },
+];
+
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq, Debug, Default)]
+pub enum CrtModelOptions {
+    #[default]
+    None,
+    SonyPvm20M4,
+    SonyKv27Fs120,
+    Commodore1084S,
+    NecMultiSync3D,
+    MitsubishiDiamondPro2070Sb,
+    ConsumerSlotMaskTv,
+}
+
+impl CrtModelOptions {
+    pub fn spec(self) -> Option<&'static CrtModelSpec> {
+        match self {
+            CrtModelOptions::None => None,
+            model => CRT_MODELS.get(model as usize - 1),
+        }
+    }
+}
+
+impl std::fmt::Display for CrtModelOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.spec() {
+            Some(spec) => write!(f, "{}", spec.description),
+            None => write!(f, "None"),
+        }
+    }
+}
+
+impl std::str::FromStr for CrtModelOptions {
+    type Err = String;
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        if name == "none" {
+            return Ok(CrtModelOptions::None);
+        }
+        match CRT_MODELS.iter().position(|spec| spec.name == name) {
+            Some(index) => num_traits::FromPrimitive::from_usize(index + 1).ok_or_else(|| "Bad CRT model index".into()),
+            None => Err("Unknown name for a CRT model".into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod crt_model_tests {
+    use super::{CrtModelOptions, CRT_MODELS};
+    use app_error::AppResult;
+    use std::str::FromStr;
+    #[test]
+    fn test_every_model_is_reachable_by_name() -> AppResult<()> {
+        assert_eq!(CrtModelOptions::from_str("none")?, CrtModelOptions::None);
+        for spec in CRT_MODELS.iter() {
+            let model = CrtModelOptions::from_str(spec.name)?;
+            assert_eq!(model.spec().map(|found| found.name), Some(spec.name));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct CrtModel {
+    input: IncDec<BooleanButton>,
+    event: Option<CrtModelOptions>,
+    pub value: CrtModelOptions,
+    pub changed: bool,
+}
+
+impl From<CrtModelOptions> for CrtModel {
+    fn from(value: CrtModelOptions) -> Self {
+        CrtModel {
+            input: Default::default(),
+            event: None,
+            value,
+            changed: false,
+        }
+    }
+}
+
+impl UiController for CrtModel {
+    fn event_tag(&self) -> &'static str {
+        "front2back:crt-model"
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["crt-model-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["crt-model-dec"]
+    }
+    fn update(&mut self, _: &MainState, ctx: &dyn SimulationContext) -> bool {
+        self.changed = FieldChanger::new(ctx, "crt-model", &mut self.value, self.input.to_just_pressed())
+            .set_event_value(self.event)
+            .set_trigger_handler(|x: &CrtModelOptions| dispatch(x, ctx.dispatcher()))
+            .process_options();
+        self.changed
+    }
+    fn apply_event(&mut self) {}
+    fn reset_inputs(&mut self) {
+        self.event = None;
+        self.input = Default::default();
+    }
+    fn read_event(&mut self, encoded: &dyn EncodedValue) -> AppResult<()> {
+        self.event = Some(CrtModelOptions::from_str(&encoded.to_string()?)?);
+        Ok(())
+    }
+    fn read_key_inc(&mut self, pressed: bool) {
+        self.input.increase.input = pressed;
+    }
+    fn read_key_dec(&mut self, pressed: bool) {
+        self.input.decrease.input = pressed;
+    }
+    fn dispatch_event(&self, dispatcher: &dyn AppEventDispatcher) {
+        dispatch(&self.value, dispatcher)
+    }
+    fn pre_process_input(&mut self) {
+        self.input.get_buttons().iter_mut().for_each(|button| button.track_input());
+    }
+    fn post_process_input(&mut self) {
+        self.event = None;
+        self.changed = false;
+    }
+}
+
+fn dispatch(value: &CrtModelOptions, dispatcher: &dyn AppEventDispatcher) {
+    dispatcher.dispatch_string_event("back2front:crt_model", &value.to_string());
+}
@@ -0,0 +1,76 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::app_events::AppEventDispatcher;
+use crate::palettes::PaletteColors;
+use crate::simulation_context::SimulationContext;
+use crate::simulation_core_state::MainState;
+use crate::ui_controller::{EncodedValue, UiController};
+use app_error::AppResult;
+use std::str::FromStr;
+
+// Receiving a palette also selects it, so `uploaded` stays up until the ticker switches the palette to Custom.
+#[derive(Default, Clone)]
+pub struct CustomPalette {
+    event: Option<PaletteColors>,
+    pub value: PaletteColors,
+    pub uploaded: bool,
+}
+
+impl From<PaletteColors> for CustomPalette {
+    fn from(value: PaletteColors) -> Self {
+        CustomPalette {
+            event: None,
+            value,
+            uploaded: false,
+        }
+    }
+}
+
+impl UiController for CustomPalette {
+    fn event_tag(&self) -> &'static str {
+        "front2back:custom-palette"
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &[]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &[]
+    }
+    fn update(&mut self, _: &MainState, _: &dyn SimulationContext) -> bool {
+        false
+    }
+    fn apply_event(&mut self) {
+        if let Some(v) = self.event.take() {
+            self.value = v;
+            self.uploaded = true;
+        }
+    }
+    fn reset_inputs(&mut self) {
+        self.event = None;
+    }
+    fn read_event(&mut self, encoded: &dyn EncodedValue) -> AppResult<()> {
+        self.event = Some(PaletteColors::from_str(&encoded.to_string()?)?);
+        Ok(())
+    }
+    fn read_key_inc(&mut self, _: bool) {}
+    fn read_key_dec(&mut self, _: bool) {}
+    fn dispatch_event(&self, dispatcher: &dyn AppEventDispatcher) {
+        dispatcher.dispatch_string_event("back2front:custom_palette", &self.value.to_string());
+    }
+    fn pre_process_input(&mut self) {}
+    fn post_process_input(&mut self) {
+        self.event = None;
+    }
+}
@@ -0,0 +1,52 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::ui_controller::enum_ui::{EnumHolder, EnumUi};
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq, Debug, Default)]
+pub enum DitheringOptions {
+    #[default]
+    Off,
+    Bayer,
+    BlueNoise,
+}
+
+impl std::fmt::Display for DitheringOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            DitheringOptions::Off => write!(f, "Off"),
+            DitheringOptions::Bayer => write!(f, "Bayer 4x4"),
+            DitheringOptions::BlueNoise => write!(f, "Blue noise"),
+        }
+    }
+}
+
+impl EnumUi for DitheringOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["dithering-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["dithering-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:dithering"
+    }
+}
+
+pub type Dithering = EnumHolder<DitheringOptions>;
@@ -57,7 +57,7 @@ impl<T: Clone + OptionCursor + Display + EnumUi> UiController for EnumHolder<T>
         self.value.keys_dec()
     }
     fn update(&mut self, _: &MainState, ctx: &dyn SimulationContext) -> bool {
-        FieldChanger::new(ctx, &mut self.value, self.input.to_just_pressed())
+        FieldChanger::new(ctx, field_name(self.value.keys_inc()), &mut self.value, self.input.to_just_pressed())
             .set_trigger_handler(|x: &T| dispatch(x, ctx.dispatcher()))
             .process_options()
     }
@@ -77,10 +77,17 @@ impl<T: Clone + OptionCursor + Display + EnumUi> UiController for EnumHolder<T>
     fn dispatch_event(&self, dispatcher: &dyn AppEventDispatcher) {
         dispatch(&self.value, dispatcher)
     }
-    fn pre_process_input(&mut self) {}
+    fn pre_process_input(&mut self) {
+        self.input.get_buttons().iter_mut().for_each(|button| button.track_input());
+    }
     fn post_process_input(&mut self) {}
 }
 
+// The event kind of the options, taken from their increment key.
+fn field_name(keys_inc: &[&'static str]) -> &'static str {
+    keys_inc.iter().find_map(|key| key.strip_suffix("-inc")).unwrap_or_default()
+}
+
 fn dispatch<T: Clone + OptionCursor + Display + EnumUi>(value: &T, dispatcher: &dyn AppEventDispatcher) {
     dispatcher.dispatch_string_event(value.dispatch_tag(), &(value.to_string()));
 }
@@ -49,7 +49,7 @@ impl UiController for ExtraBright {
         &["shift+x", "pixel-brightness-dec"]
     }
     fn update(&mut self, main: &MainState, ctx: &dyn SimulationContext) -> bool {
-        FieldChanger::new(ctx, &mut self.value, self.input)
+        FieldChanger::new(ctx, "pixel-brightness", &mut self.value, self.input)
             .set_progression(0.01 * main.dt * main.filter_speed)
             .set_event_value(self.event)
             .set_min(-1.0)
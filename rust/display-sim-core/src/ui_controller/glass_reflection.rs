@@ -0,0 +1,52 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::ui_controller::enum_ui::{EnumHolder, EnumUi};
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+// What the front glass reflects: the room lit by the light color, or the glow of the backdrop.
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq)]
+pub enum GlassReflectionOptions {
+    Off,
+    Room,
+    Backdrop,
+}
+
+impl std::fmt::Display for GlassReflectionOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            GlassReflectionOptions::Off => write!(f, "Off"),
+            GlassReflectionOptions::Room => write!(f, "Room"),
+            GlassReflectionOptions::Backdrop => write!(f, "Backdrop"),
+        }
+    }
+}
+
+impl EnumUi for GlassReflectionOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["glass-reflection-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["glass-reflection-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:glass_reflection"
+    }
+}
+
+pub type GlassReflection = EnumHolder<GlassReflectionOptions>;
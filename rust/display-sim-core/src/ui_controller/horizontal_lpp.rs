@@ -13,7 +13,7 @@
  * You should have received a copy of the GNU Affero General Public License
  * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
 
-use crate::app_events::AppEventDispatcher;
+use crate::app_events::{AppEventDispatcher, MessageCategory};
 use crate::field_changer::FieldChanger;
 use crate::general_types::IncDec;
 use crate::simulation_context::SimulationContext;
@@ -24,12 +24,12 @@ use app_error::AppResult;
 #[derive(Default, Copy, Clone)]
 pub struct HorizontalLpp {
     input: IncDec<bool>,
-    event: Option<usize>,
-    pub value: usize,
+    event: Option<f32>,
+    pub value: f32,
 }
 
-impl From<usize> for HorizontalLpp {
-    fn from(value: usize) -> Self {
+impl From<f32> for HorizontalLpp {
+    fn from(value: f32) -> Self {
         HorizontalLpp {
             input: Default::default(),
             event: None,
@@ -38,6 +38,13 @@ impl From<usize> for HorizontalLpp {
     }
 }
 
+impl HorizontalLpp {
+    /// Number of draw passes, the last one being partial when the value is fractional.
+    pub fn line_passes(&self) -> usize {
+        self.value.ceil() as usize
+    }
+}
+
 impl UiController for HorizontalLpp {
     fn event_tag(&self) -> &'static str {
         "front2back:horizontal-lpp"
@@ -49,11 +56,11 @@ impl UiController for HorizontalLpp {
         &["shift+l", "horizontal-lpp-dec"]
     }
     fn update(&mut self, _: &MainState, ctx: &dyn SimulationContext) -> bool {
-        FieldChanger::new(ctx, &mut self.value, self.input)
-            .set_progression(1)
+        FieldChanger::new(ctx, "horizontal-lpp", &mut self.value, self.input)
+            .set_progression(0.5)
             .set_event_value(self.event)
-            .set_min(1)
-            .set_max(20)
+            .set_min(1.0)
+            .set_max(20.0)
             .set_trigger_handler(|x| dispatch(x, ctx.dispatcher()))
             .process_with_sums()
     }
@@ -68,7 +75,7 @@ impl UiController for HorizontalLpp {
         self.input.decrease = false;
     }
     fn read_event(&mut self, encoded: &dyn EncodedValue) -> AppResult<()> {
-        self.event = Some(encoded.to_usize()?);
+        self.event = Some(encoded.to_f32()?);
         Ok(())
     }
     fn read_key_inc(&mut self, pressed: bool) {
@@ -86,9 +93,9 @@ impl UiController for HorizontalLpp {
     }
 }
 
-fn dispatch(value: usize, dispatcher: &dyn AppEventDispatcher) {
-    if dispatcher.are_extra_messages_enabled() {
+fn dispatch(value: f32, dispatcher: &dyn AppEventDispatcher) {
+    if dispatcher.are_extra_messages_enabled(MessageCategory::Filters) {
         dispatcher.dispatch_top_message(&format!("Horizontal lines per pixel: {}", value));
     }
-    dispatcher.dispatch_string_event("back2front:change_horizontal_lpp", &(value as i32).to_string());
+    dispatcher.dispatch_string_event("back2front:change_horizontal_lpp", &value.to_string());
 }
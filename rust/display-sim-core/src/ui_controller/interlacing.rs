@@ -0,0 +1,63 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+
+use crate::ui_controller::enum_ui::{EnumHolder, EnumUi};
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone)]
+pub enum InterlacingOptions {
+    Off,
+    Weave,
+    Bob,
+}
+
+impl std::fmt::Display for InterlacingOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            InterlacingOptions::Off => write!(f, "Off"),
+            InterlacingOptions::Weave => write!(f, "Weave"),
+            InterlacingOptions::Bob => write!(f, "Bob"),
+        }
+    }
+}
+
+impl InterlacingOptions {
+    /// Brightness of the field that isn't scanned in the current frame. Weave keeps it glowing, bob leaves it dark.
+    pub fn other_field_brightness(self) -> f32 {
+        match self {
+            InterlacingOptions::Off => 1.0,
+            InterlacingOptions::Weave => 0.5,
+            InterlacingOptions::Bob => 0.0,
+        }
+    }
+}
+
+impl EnumUi for InterlacingOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["0", "key0", "interlacing-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["shift+0", "shift+key0", "interlacing-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:interlacing"
+    }
+}
+
+pub type Interlacing = EnumHolder<InterlacingOptions>;
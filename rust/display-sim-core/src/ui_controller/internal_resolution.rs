@@ -152,7 +152,7 @@ impl UiController for InternalResolution {
     }
     fn update(&mut self, _: &MainState, ctx: &dyn SimulationContext) -> bool {
         let inputs = self.input.to_just_pressed();
-        self.changed = FieldChanger::new(ctx, self as &mut InternalResolution, inputs)
+        self.changed = FieldChanger::new(ctx, "internal-resolution", self as &mut InternalResolution, inputs)
             .set_trigger_handler(|x: &InternalResolution| dispatch(x, ctx.dispatcher()))
             .process_options();
         self.changed
@@ -0,0 +1,110 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::ui_controller::enum_ui::{EnumHolder, EnumUi};
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+/// Depth testing of the foreground pixels.
+///
+/// With depth, the nearest face of every pixel hides whatever is behind it, which is what makes
+/// the cubes look solid. Without it, later pixels are painted over earlier ones in draw order, so
+/// the cubes turn into an x-ray like overlap of faces, and overlapping squares stop z-fighting.
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq)]
+pub enum ForegroundDepthTestOptions {
+    Auto,
+    On,
+    Off,
+}
+
+impl ForegroundDepthTestOptions {
+    /// `Auto` follows the pixel geometry: only the cubes have faces worth sorting.
+    pub fn enabled(self, geometry_has_depth: bool) -> bool {
+        match self {
+            ForegroundDepthTestOptions::Auto => geometry_has_depth,
+            ForegroundDepthTestOptions::On => true,
+            ForegroundDepthTestOptions::Off => false,
+        }
+    }
+}
+
+impl std::fmt::Display for ForegroundDepthTestOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ForegroundDepthTestOptions::Auto => write!(f, "Auto"),
+            ForegroundDepthTestOptions::On => write!(f, "On"),
+            ForegroundDepthTestOptions::Off => write!(f, "Off"),
+        }
+    }
+}
+
+impl EnumUi for ForegroundDepthTestOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["foreground-depth-test-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["foreground-depth-test-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:foreground_depth_test"
+    }
+}
+
+pub type ForegroundDepthTest = EnumHolder<ForegroundDepthTestOptions>;
+
+/// Depth testing of the background layer (backlight and reflections).
+///
+/// The background is meant to be a soft glow, so by default its pixels are just painted on top of
+/// each other. With depth, the nearest pixels cut out the ones behind, and the glow gets the
+/// silhouette of the geometry instead.
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq)]
+pub enum BackgroundDepthTestOptions {
+    Off,
+    On,
+}
+
+impl BackgroundDepthTestOptions {
+    pub fn enabled(self) -> bool {
+        self == BackgroundDepthTestOptions::On
+    }
+}
+
+impl std::fmt::Display for BackgroundDepthTestOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            BackgroundDepthTestOptions::Off => write!(f, "Off"),
+            BackgroundDepthTestOptions::On => write!(f, "On"),
+        }
+    }
+}
+
+impl EnumUi for BackgroundDepthTestOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["background-depth-test-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["background-depth-test-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:background_depth_test"
+    }
+}
+
+pub type BackgroundDepthTest = EnumHolder<BackgroundDepthTestOptions>;
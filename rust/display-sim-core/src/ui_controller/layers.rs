@@ -0,0 +1,242 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::app_events::AppEventDispatcher;
+use crate::simulation_context::SimulationContext;
+use crate::simulation_core_state::MainState;
+use crate::ui_controller::{EncodedValue, UiController};
+use app_error::AppResult;
+use std::str::FromStr;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum LayerKind {
+    SourcePixels,
+    SolidBackdrop,
+    Reflections,
+    MonitorCase,
+}
+
+impl LayerKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            LayerKind::SourcePixels => "pixels",
+            LayerKind::SolidBackdrop => "backdrop",
+            LayerKind::Reflections => "reflections",
+            LayerKind::MonitorCase => "case",
+        }
+    }
+}
+
+impl FromStr for LayerKind {
+    type Err = String;
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        [
+            LayerKind::SourcePixels,
+            LayerKind::SolidBackdrop,
+            LayerKind::Reflections,
+            LayerKind::MonitorCase,
+        ]
+        .iter()
+        .find(|kind| kind.name() == name)
+        .copied()
+        .ok_or_else(|| format!("Unknown layer '{}'", name))
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Layer {
+    pub kind: LayerKind,
+    pub enabled: bool,
+    pub opacity: f32,
+}
+
+// Layers go from the bottom to the top of the composition. Every kind appears exactly once,
+// so hiding one is done with `enabled` and the list keeps its order for when it comes back.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LayerList(pub Vec<Layer>);
+
+impl Default for LayerList {
+    fn default() -> Self {
+        LayerList(
+            [
+                LayerKind::SolidBackdrop,
+                LayerKind::Reflections,
+                LayerKind::SourcePixels,
+                LayerKind::MonitorCase,
+            ]
+            .iter()
+            .map(|&kind| Layer {
+                kind,
+                // The case changes the look of the whole scene, so it has to be asked for.
+                enabled: kind != LayerKind::MonitorCase,
+                opacity: 1.0,
+            })
+            .collect(),
+        )
+    }
+}
+
+impl LayerList {
+    pub fn get(&self, kind: LayerKind) -> &Layer {
+        self.0.iter().find(|layer| layer.kind == kind).expect("Every layer kind is in the list")
+    }
+    pub fn get_mut(&mut self, kind: LayerKind) -> &mut Layer {
+        self.0.iter_mut().find(|layer| layer.kind == kind).expect("Every layer kind is in the list")
+    }
+    /// Moves the layer one step towards the top of the composition.
+    pub fn raise(&mut self, kind: LayerKind) {
+        let index = self.0.iter().position(|layer| layer.kind == kind).expect("Every layer kind is in the list");
+        if index + 1 < self.0.len() {
+            self.0.swap(index, index + 1);
+        }
+    }
+    /// Moves the layer one step towards the bottom of the composition.
+    pub fn lower(&mut self, kind: LayerKind) {
+        let index = self.0.iter().position(|layer| layer.kind == kind).expect("Every layer kind is in the list");
+        if index > 0 {
+            self.0.swap(index, index - 1);
+        }
+    }
+}
+
+impl std::fmt::Display for LayerList {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let encoded: Vec<String> = self
+            .0
+            .iter()
+            .map(|layer| format!("{}:{}:{}", layer.kind.name(), if layer.enabled { "on" } else { "off" }, layer.opacity))
+            .collect();
+        write!(f, "{}", encoded.join(","))
+    }
+}
+
+// The encoding is "kind:on|off:opacity" for each layer, bottom first, separated by commas.
+impl FromStr for LayerList {
+    type Err = String;
+    fn from_str(encoded: &str) -> Result<Self, Self::Err> {
+        let mut layers = Vec::new();
+        for entry in encoded.split(',') {
+            let parts: Vec<&str> = entry.trim().split(':').collect();
+            if parts.len() != 3 {
+                return Err(format!("Bad layer entry '{}'", entry));
+            }
+            let kind = LayerKind::from_str(parts[0])?;
+            let enabled = match parts[1] {
+                "on" => true,
+                "off" => false,
+                other => return Err(format!("Bad layer toggle '{}'", other)),
+            };
+            let opacity = f32::from_str(parts[2]).map_err(|e| format!("Bad layer opacity '{}': {}", parts[2], e))?;
+            if !opacity.is_finite() {
+                return Err(format!("Bad layer opacity '{}'", parts[2]));
+            }
+            if layers.iter().any(|layer: &Layer| layer.kind == kind) {
+                return Err(format!("Layer '{}' appears twice", parts[0]));
+            }
+            layers.push(Layer {
+                kind,
+                enabled,
+                opacity: opacity.clamp(0.0, 1.0),
+            });
+        }
+        let complete = LayerList(layers);
+        if LayerList::default()
+            .0
+            .iter()
+            .any(|layer| !complete.0.iter().any(|other| other.kind == layer.kind))
+        {
+            return Err("Every layer has to be listed".into());
+        }
+        Ok(complete)
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct Layers {
+    event: Option<LayerList>,
+    pub value: LayerList,
+}
+
+impl From<LayerList> for Layers {
+    fn from(value: LayerList) -> Self {
+        Layers { event: None, value }
+    }
+}
+
+impl UiController for Layers {
+    fn event_tag(&self) -> &'static str {
+        "front2back:layers"
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &[]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &[]
+    }
+    fn update(&mut self, _: &MainState, _: &dyn SimulationContext) -> bool {
+        false
+    }
+    fn apply_event(&mut self) {
+        if let Some(v) = self.event.take() {
+            self.value = v;
+        }
+    }
+    fn reset_inputs(&mut self) {
+        self.event = None;
+    }
+    fn read_event(&mut self, encoded: &dyn EncodedValue) -> AppResult<()> {
+        self.event = Some(LayerList::from_str(&encoded.to_string()?)?);
+        Ok(())
+    }
+    fn read_key_inc(&mut self, _: bool) {}
+    fn read_key_dec(&mut self, _: bool) {}
+    fn dispatch_event(&self, dispatcher: &dyn AppEventDispatcher) {
+        dispatcher.dispatch_string_event("back2front:layers", &self.value.to_string());
+    }
+    fn pre_process_input(&mut self) {}
+    fn post_process_input(&mut self) {
+        self.event = None;
+    }
+}
+
+#[cfg(test)]
+mod layers_tests {
+    use super::{LayerKind, LayerList};
+    use app_error::AppResult;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_layer_list_roundtrips_through_its_encoding() -> AppResult<()> {
+        let mut layers = LayerList::default();
+        layers.raise(LayerKind::SolidBackdrop);
+        layers.get_mut(LayerKind::Reflections).enabled = false;
+        layers.get_mut(LayerKind::SourcePixels).opacity = 0.5;
+        assert_eq!(layers.to_string(), "reflections:off:1,backdrop:on:1,pixels:on:0.5,case:off:1");
+        assert_eq!(LayerList::from_str(&layers.to_string())?, layers);
+        Ok(())
+    }
+
+    #[test]
+    fn test_layer_list_rejects_incomplete_or_repeated_layers() {
+        assert!(LayerList::from_str("pixels:on:1,backdrop:on:1").is_err());
+        assert!(LayerList::from_str("pixels:on:1,pixels:on:1,reflections:on:1,case:on:1").is_err());
+        assert!(LayerList::from_str("pixels:on:1,backdrop:maybe:1,reflections:on:1,case:on:1").is_err());
+    }
+
+    #[test]
+    fn test_layer_list_rejects_non_finite_opacity() {
+        assert!(LayerList::from_str("pixels:on:NaN,backdrop:on:1,reflections:on:1,case:on:1").is_err());
+        assert!(LayerList::from_str("pixels:on:inf,backdrop:on:1,reflections:on:1,case:on:1").is_err());
+    }
+}
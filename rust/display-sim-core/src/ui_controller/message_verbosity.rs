@@ -0,0 +1,77 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::app_events::MessageCategory;
+use crate::ui_controller::enum_ui::{EnumHolder, EnumUi};
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq, Debug)]
+pub enum MessageVerbosityOptions {
+    All,
+    FilterChanges,
+    CameraChanges,
+    None,
+}
+
+impl MessageVerbosityOptions {
+    pub fn shows(&self, category: MessageCategory) -> bool {
+        match *self {
+            MessageVerbosityOptions::All => true,
+            MessageVerbosityOptions::FilterChanges => category == MessageCategory::Filters,
+            MessageVerbosityOptions::CameraChanges => category == MessageCategory::Camera,
+            MessageVerbosityOptions::None => false,
+        }
+    }
+}
+
+impl std::fmt::Display for MessageVerbosityOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            MessageVerbosityOptions::All => write!(f, "All"),
+            MessageVerbosityOptions::FilterChanges => write!(f, "Filter changes"),
+            MessageVerbosityOptions::CameraChanges => write!(f, "Camera changes"),
+            MessageVerbosityOptions::None => write!(f, "None"),
+        }
+    }
+}
+
+impl EnumUi for MessageVerbosityOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["f3", "message-verbosity-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["message-verbosity-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:message_verbosity"
+    }
+}
+
+pub type MessageVerbosity = EnumHolder<MessageVerbosityOptions>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_changes_verbosity_hides_camera_messages() {
+        let verbosity = MessageVerbosityOptions::FilterChanges;
+        assert!(verbosity.shows(MessageCategory::Filters));
+        assert!(!verbosity.shows(MessageCategory::Camera));
+    }
+}
@@ -0,0 +1,63 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::general_types::{Primaries, SRGB_PRIMARIES};
+use crate::ui_controller::enum_ui::{EnumHolder, EnumUi};
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+/// Color space of the display the phosphor primaries are mapped to at the output.
+///
+/// `Display P3` also tags the canvas as P3, so wide-gamut displays can show the phosphor reds
+/// and greens where sRGB ones would fall short.
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq)]
+pub enum OutputColorSpaceOptions {
+    Srgb,
+    DisplayP3,
+}
+
+impl OutputColorSpaceOptions {
+    pub fn primaries(self) -> Primaries {
+        match self {
+            OutputColorSpaceOptions::Srgb => SRGB_PRIMARIES,
+            OutputColorSpaceOptions::DisplayP3 => [[0.680, 0.320], [0.265, 0.690], [0.150, 0.060]],
+        }
+    }
+}
+
+impl std::fmt::Display for OutputColorSpaceOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            OutputColorSpaceOptions::Srgb => write!(f, "sRGB"),
+            OutputColorSpaceOptions::DisplayP3 => write!(f, "Display P3"),
+        }
+    }
+}
+
+impl EnumUi for OutputColorSpaceOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["output-color-space-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["output-color-space-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:output_color_space"
+    }
+}
+
+pub type OutputColorSpace = EnumHolder<OutputColorSpaceOptions>;
@@ -0,0 +1,69 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::palettes::{PaletteColors, C64_PALETTE, EGA_PALETTE, NES_PALETTE};
+use crate::ui_controller::enum_ui::{EnumHolder, EnumUi};
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq, Debug)]
+pub enum PaletteOptions {
+    Off,
+    Nes,
+    C64,
+    Ega,
+    Custom,
+}
+
+impl std::fmt::Display for PaletteOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            PaletteOptions::Off => write!(f, "Off"),
+            PaletteOptions::Nes => write!(f, "NES"),
+            PaletteOptions::C64 => write!(f, "C64"),
+            PaletteOptions::Ega => write!(f, "EGA"),
+            PaletteOptions::Custom => write!(f, "Custom"),
+        }
+    }
+}
+
+impl PaletteOptions {
+    /// Colors the source gets quantized to, `custom` being the last uploaded palette. Off gives no colors.
+    pub fn colors(self, custom: &PaletteColors) -> PaletteColors {
+        match self {
+            PaletteOptions::Off => PaletteColors::default(),
+            PaletteOptions::Nes => PaletteColors::from_hex(&NES_PALETTE),
+            PaletteOptions::C64 => PaletteColors::from_hex(&C64_PALETTE),
+            PaletteOptions::Ega => PaletteColors::from_hex(&EGA_PALETTE),
+            PaletteOptions::Custom => custom.clone(),
+        }
+    }
+}
+
+impl EnumUi for PaletteOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["palette-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["palette-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:palette"
+    }
+}
+
+pub type Palette = EnumHolder<PaletteOptions>;
@@ -0,0 +1,70 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::general_types::{Primaries, SRGB_PRIMARIES};
+use crate::ui_controller::enum_ui::{EnumHolder, EnumUi};
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+/// Phosphor set of the tube. The source colors drive these phosphors as they are, so a set
+/// other than sRGB shifts the picture towards the gamut of the monitors of its time.
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq)]
+pub enum PhosphorPrimariesOptions {
+    Srgb,
+    /// The common consumer TV and monitor phosphors.
+    P22,
+    /// European broadcast monitors.
+    Ebu,
+    /// North American broadcast monitors.
+    SmpteC,
+}
+
+impl PhosphorPrimariesOptions {
+    pub fn primaries(self) -> Primaries {
+        match self {
+            PhosphorPrimariesOptions::Srgb => SRGB_PRIMARIES,
+            PhosphorPrimariesOptions::P22 => [[0.625, 0.340], [0.280, 0.595], [0.155, 0.070]],
+            PhosphorPrimariesOptions::Ebu => [[0.640, 0.330], [0.290, 0.600], [0.150, 0.060]],
+            PhosphorPrimariesOptions::SmpteC => [[0.630, 0.340], [0.310, 0.595], [0.155, 0.070]],
+        }
+    }
+}
+
+impl std::fmt::Display for PhosphorPrimariesOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            PhosphorPrimariesOptions::Srgb => write!(f, "sRGB"),
+            PhosphorPrimariesOptions::P22 => write!(f, "P22"),
+            PhosphorPrimariesOptions::Ebu => write!(f, "EBU"),
+            PhosphorPrimariesOptions::SmpteC => write!(f, "SMPTE-C"),
+        }
+    }
+}
+
+impl EnumUi for PhosphorPrimariesOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["]", "rbracket", "phosphor-primaries-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["[", "lbracket", "phosphor-primaries-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:phosphor_primaries"
+    }
+}
+
+pub type PhosphorPrimaries = EnumHolder<PhosphorPrimariesOptions>;
@@ -21,6 +21,10 @@ use num_derive::{FromPrimitive, ToPrimitive};
 pub enum PixelGeometryKindOptions {
     Squares,
     Cubes,
+    /// Flat pixels drawn as three continuous vertical phosphor stripes, Trinitron style.
+    ApertureGrille,
+    /// Hexagonal prisms, with every other row shifted by half a pixel so they pack like the phosphor dots of a delta mask.
+    Hexagons,
 }
 
 impl std::fmt::Display for PixelGeometryKindOptions {
@@ -28,6 +32,8 @@ impl std::fmt::Display for PixelGeometryKindOptions {
         match *self {
             PixelGeometryKindOptions::Squares => write!(f, "Squares"),
             PixelGeometryKindOptions::Cubes => write!(f, "Cubes"),
+            PixelGeometryKindOptions::ApertureGrille => write!(f, "Aperture grille"),
+            PixelGeometryKindOptions::Hexagons => write!(f, "Hexagons"),
         }
     }
 }
@@ -20,7 +20,10 @@ use std::fmt::{Display, Error, Formatter};
 
 pub const TEXTURE_SIZE: usize = 510;
 
-const SHADOWS_LEN: usize = 24;
+const SHADOWS_LEN: usize = 25;
+
+/// Rounded vertical slot. The renderer staggers it by half a pixel on odd columns, like the slots of an aperture grille with bridges.
+pub const SLOT_MASK_SHADOW: usize = 24;
 
 #[derive(Default, Clone, Copy)]
 pub struct ShadowShape {
@@ -35,7 +38,11 @@ impl From<usize> for ShadowShape {
 
 impl Display for ShadowShape {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
-        write!(f, "{}", self.value)
+        if self.value == SLOT_MASK_SHADOW {
+            write!(f, "Slot mask")
+        } else {
+            write!(f, "{}", self.value)
+        }
     }
 }
 
@@ -87,6 +94,7 @@ pub fn get_shadows() -> [Box<dyn Fn(usize, usize) -> f64>; SHADOWS_LEN] {
         Box::new(|i, j| calc_diamond(i, 0) * calc_diamond(j, 0) * 1.0 * 255.0),
         Box::new(|i, _j| calc_diamond(i, 0) * 1.0 * 255.0),
         Box::new(|i, _j| calc_diamond(i, 1) * 1.5 * 255.0),
+        Box::new(|i, j| calc_slot(i, j) * 255.0),
     ]
 }
 
@@ -101,6 +109,17 @@ fn calc_diamond(number: usize, count: usize) -> f64 {
     let result = 1.0 - ((number - TEXTURE_SIZE / 2) as f64 / (TEXTURE_SIZE as f64 / 2.0));
     pow(result, count)
 }
+fn calc_slot(row: usize, column: usize) -> f64 {
+    const HALF_WIDTH: f64 = 0.75;
+    const HALF_HEIGHT: f64 = 0.8;
+    const CORNER: f64 = 0.35;
+    const SOFTNESS: f64 = 0.15;
+    let half = (TEXTURE_SIZE / 2) as f64;
+    let x = (column as f64 - half) / half - (HALF_WIDTH - CORNER);
+    let y = (row as f64 - half) / half - (HALF_HEIGHT - CORNER);
+    let distance = (x.max(0.0).powi(2) + y.max(0.0).powi(2)).sqrt() + x.max(y).min(0.0) - CORNER;
+    (1.0 - (distance + SOFTNESS) / SOFTNESS).clamp(0.0, 1.0)
+}
 fn pow(mut number: f64, count: usize) -> f64 {
     for _i in 0..count {
         number *= number;
@@ -124,3 +143,29 @@ impl EnumUi for ShadowShape {
 }
 
 pub type PixelShadowShapeKind = EnumHolder<ShadowShape>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_mask_is_lit_in_the_middle_and_dark_at_the_ends() {
+        let slot = &get_shadows()[SLOT_MASK_SHADOW];
+        let center = TEXTURE_SIZE / 2;
+        assert_eq!(slot(center, center), 255.0);
+        assert_eq!(slot(TEXTURE_SIZE - 1, center), 0.0);
+        assert_eq!(slot(center, TEXTURE_SIZE - 1), 0.0);
+        assert!(slot(center + 200, center) < slot(center, center + 150));
+    }
+
+    #[test]
+    fn test_slot_mask_is_reachable_by_cycling() {
+        let mut shape = ShadowShape::from(0);
+        for _ in 0..SLOT_MASK_SHADOW {
+            shape.next_option();
+        }
+        assert_eq!(shape.to_string(), "Slot mask");
+        shape.next_option();
+        assert_eq!(shape.value, 0);
+    }
+}
@@ -0,0 +1,63 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::ui_controller::enum_ui::{EnumHolder, EnumUi};
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq)]
+pub enum ProceduralSourceOptions {
+    Off,
+    BouncingLogo,
+    Starfield,
+    ColorBars,
+    SmpteBars,
+    GrayscaleRamp,
+    ConvergenceGrid,
+    OverscanMarkers,
+    SharpnessWedges,
+}
+
+impl std::fmt::Display for ProceduralSourceOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ProceduralSourceOptions::Off => write!(f, "Off"),
+            ProceduralSourceOptions::BouncingLogo => write!(f, "Bouncing logo"),
+            ProceduralSourceOptions::Starfield => write!(f, "Starfield"),
+            ProceduralSourceOptions::ColorBars => write!(f, "Color bars"),
+            ProceduralSourceOptions::SmpteBars => write!(f, "SMPTE bars"),
+            ProceduralSourceOptions::GrayscaleRamp => write!(f, "Grayscale ramp"),
+            ProceduralSourceOptions::ConvergenceGrid => write!(f, "Convergence grid"),
+            ProceduralSourceOptions::OverscanMarkers => write!(f, "Overscan markers"),
+            ProceduralSourceOptions::SharpnessWedges => write!(f, "Sharpness wedges"),
+        }
+    }
+}
+
+impl EnumUi for ProceduralSourceOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["2", "key2", "procedural-source-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["shift+2", "shift+key2", "procedural-source-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:procedural_source"
+    }
+}
+
+pub type ProceduralSource = EnumHolder<ProceduralSourceOptions>;
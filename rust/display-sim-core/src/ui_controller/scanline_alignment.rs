@@ -0,0 +1,83 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::app_events::AppEventDispatcher;
+use crate::simulation_context::SimulationContext;
+use crate::simulation_core_state::MainState;
+use crate::ui_controller::{EncodedValue, UiController};
+use app_error::AppResult;
+
+// Both values are in source pixels. They shift the whole picture over the internal resolution grid,
+// so the lines can be aligned when some of them come out thicker than others.
+macro_rules! scanline_alignment_impl {
+    ($ty:ident, $event_tag:expr, $dispatch_tag:expr, $min:expr, $max:expr) => {
+        #[derive(Default, Copy, Clone)]
+        pub struct $ty {
+            event: Option<f32>,
+            pub value: f32,
+        }
+
+        impl From<f32> for $ty {
+            fn from(value: f32) -> Self {
+                $ty { event: None, value }
+            }
+        }
+
+        impl UiController for $ty {
+            fn event_tag(&self) -> &'static str {
+                $event_tag
+            }
+            fn keys_inc(&self) -> &[&'static str] {
+                &[]
+            }
+            fn keys_dec(&self) -> &[&'static str] {
+                &[]
+            }
+            fn update(&mut self, _: &MainState, _: &dyn SimulationContext) -> bool {
+                false
+            }
+            fn apply_event(&mut self) {
+                if let Some(v) = self.event {
+                    self.value = v.clamp($min, $max);
+                }
+            }
+            fn reset_inputs(&mut self) {
+                self.event = None;
+            }
+            fn read_event(&mut self, encoded: &dyn EncodedValue) -> AppResult<()> {
+                self.event = Some(encoded.to_f32()?);
+                Ok(())
+            }
+            fn read_key_inc(&mut self, _: bool) {}
+            fn read_key_dec(&mut self, _: bool) {}
+            fn dispatch_event(&self, dispatcher: &dyn AppEventDispatcher) {
+                dispatcher.dispatch_string_event(
+                    $dispatch_tag,
+                    &if self.value.floor() == self.value {
+                        format!("{:.00}", self.value)
+                    } else {
+                        format!("{:.03}", self.value)
+                    },
+                );
+            }
+            fn pre_process_input(&mut self) {}
+            fn post_process_input(&mut self) {
+                self.event = None;
+            }
+        }
+    };
+}
+
+scanline_alignment_impl! {ScanlinePhase, "front2back:scanline-phase", "back2front:scanline_phase", -0.5, 0.5}
+scanline_alignment_impl! {SyncOffset, "front2back:sync-offset", "back2front:sync_offset", -2.0, 2.0}
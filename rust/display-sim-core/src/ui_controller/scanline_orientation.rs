@@ -0,0 +1,122 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::app_events::AppEventDispatcher;
+use crate::simulation_context::SimulationContext;
+use crate::simulation_core_state::MainState;
+use crate::ui_controller::enum_ui::{EnumHolder, EnumUi};
+use crate::ui_controller::{EncodedValue, UiController};
+use app_error::AppResult;
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq)]
+pub enum ScanlineOrientationOptions {
+    Horizontal,
+    Vertical,
+    Angle,
+}
+
+impl ScanlineOrientationOptions {
+    /// Rotation of the scanlines in degrees, `angle` being the one picked for the Angle option.
+    pub fn degrees(self, angle: f32) -> f32 {
+        match self {
+            ScanlineOrientationOptions::Horizontal => 0.0,
+            ScanlineOrientationOptions::Vertical => 90.0,
+            ScanlineOrientationOptions::Angle => angle,
+        }
+    }
+}
+
+impl std::fmt::Display for ScanlineOrientationOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ScanlineOrientationOptions::Horizontal => write!(f, "Horizontal"),
+            ScanlineOrientationOptions::Vertical => write!(f, "Vertical (TATE)"),
+            ScanlineOrientationOptions::Angle => write!(f, "Custom angle"),
+        }
+    }
+}
+
+impl EnumUi for ScanlineOrientationOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["4", "key4", "scanline-orientation-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["shift+4", "shift+key4", "scanline-orientation-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:scanline_orientation"
+    }
+}
+
+pub type ScanlineOrientation = EnumHolder<ScanlineOrientationOptions>;
+
+#[derive(Default, Copy, Clone)]
+pub struct ScanlineAngle {
+    event: Option<f32>,
+    pub value: f32,
+}
+
+impl From<f32> for ScanlineAngle {
+    fn from(value: f32) -> Self {
+        ScanlineAngle { event: None, value }
+    }
+}
+
+impl UiController for ScanlineAngle {
+    fn event_tag(&self) -> &'static str {
+        "front2back:scanline-angle"
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &[]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &[]
+    }
+    fn update(&mut self, _: &MainState, _: &dyn SimulationContext) -> bool {
+        false
+    }
+    fn apply_event(&mut self) {
+        if let Some(v) = self.event {
+            self.value = v.clamp(-180.0, 180.0);
+        }
+    }
+    fn reset_inputs(&mut self) {
+        self.event = None;
+    }
+    fn read_event(&mut self, encoded: &dyn EncodedValue) -> AppResult<()> {
+        self.event = Some(encoded.to_f32()?);
+        Ok(())
+    }
+    fn read_key_inc(&mut self, _: bool) {}
+    fn read_key_dec(&mut self, _: bool) {}
+    fn dispatch_event(&self, dispatcher: &dyn AppEventDispatcher) {
+        dispatcher.dispatch_string_event(
+            "back2front:scanline_angle",
+            &if self.value.floor() == self.value {
+                format!("{:.00}", self.value)
+            } else {
+                format!("{:.03}", self.value)
+            },
+        );
+    }
+    fn pre_process_input(&mut self) {}
+    fn post_process_input(&mut self) {
+        self.event = None;
+    }
+}
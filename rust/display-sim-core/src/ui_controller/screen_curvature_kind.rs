@@ -23,7 +23,9 @@ pub enum ScreenCurvatureKindOptions {
     Curved1,
     Curved2,
     Curved3,
+    Cylindrical,
     Pulse,
+    Barrel,
 }
 
 impl std::fmt::Display for ScreenCurvatureKindOptions {
@@ -33,7 +35,9 @@ impl std::fmt::Display for ScreenCurvatureKindOptions {
             ScreenCurvatureKindOptions::Curved1 => write!(f, "Curved 1"),
             ScreenCurvatureKindOptions::Curved2 => write!(f, "Curved 2"),
             ScreenCurvatureKindOptions::Curved3 => write!(f, "Curved 3"),
+            ScreenCurvatureKindOptions::Cylindrical => write!(f, "Trinitron"),
             ScreenCurvatureKindOptions::Pulse => write!(f, "Weavy"),
+            ScreenCurvatureKindOptions::Barrel => write!(f, "Barrel"),
         }
     }
 }
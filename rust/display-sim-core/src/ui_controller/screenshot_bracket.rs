@@ -0,0 +1,60 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::ui_controller::enum_ui::{EnumHolder, EnumUi};
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq)]
+pub enum ScreenshotBracketOptions {
+    Curvature,
+    MaskType,
+}
+
+impl std::fmt::Display for ScreenshotBracketOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ScreenshotBracketOptions::Curvature => write!(f, "Curvature"),
+            ScreenshotBracketOptions::MaskType => write!(f, "Mask type"),
+        }
+    }
+}
+
+impl std::str::FromStr for ScreenshotBracketOptions {
+    type Err = String;
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "curvature" => Ok(Self::Curvature),
+            "mask-type" => Ok(Self::MaskType),
+            _ => Err(format!("Unknown sweep parameter '{}', expected curvature or mask-type", name)),
+        }
+    }
+}
+
+impl EnumUi for ScreenshotBracketOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["screenshot-bracket-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["screenshot-bracket-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:screenshot_bracket"
+    }
+}
+
+pub type ScreenshotBracket = EnumHolder<ScreenshotBracketOptions>;
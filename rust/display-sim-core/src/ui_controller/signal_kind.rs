@@ -0,0 +1,52 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::ui_controller::enum_ui::{EnumHolder, EnumUi};
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq, Debug, Default)]
+pub enum SignalKindOptions {
+    #[default]
+    Rgb,
+    SVideo,
+    Composite,
+}
+
+impl std::fmt::Display for SignalKindOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            SignalKindOptions::Rgb => write!(f, "RGB"),
+            SignalKindOptions::SVideo => write!(f, "S-Video"),
+            SignalKindOptions::Composite => write!(f, "Composite"),
+        }
+    }
+}
+
+impl EnumUi for SignalKindOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["signal-kind-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["signal-kind-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:signal_kind"
+    }
+}
+
+pub type SignalKind = EnumHolder<SignalKindOptions>;
@@ -0,0 +1,116 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::ui_controller::enum_ui::{EnumHolder, EnumUi};
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq)]
+pub enum SourceRotationOptions {
+    None,
+    Clockwise90,
+    Rotate180,
+    Clockwise270,
+}
+
+impl SourceRotationOptions {
+    /// Cosine and sine of the clockwise rotation, exact so the pixel grid stays aligned.
+    pub fn cos_sin(self) -> (f32, f32) {
+        match self {
+            SourceRotationOptions::None => (1.0, 0.0),
+            SourceRotationOptions::Clockwise90 => (0.0, 1.0),
+            SourceRotationOptions::Rotate180 => (-1.0, 0.0),
+            SourceRotationOptions::Clockwise270 => (0.0, -1.0),
+        }
+    }
+    pub fn is_quarter_turn(self) -> bool {
+        matches!(self, SourceRotationOptions::Clockwise90 | SourceRotationOptions::Clockwise270)
+    }
+}
+
+impl std::fmt::Display for SourceRotationOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            SourceRotationOptions::None => write!(f, "None"),
+            SourceRotationOptions::Clockwise90 => write!(f, "90°"),
+            SourceRotationOptions::Rotate180 => write!(f, "180°"),
+            SourceRotationOptions::Clockwise270 => write!(f, "270°"),
+        }
+    }
+}
+
+impl EnumUi for SourceRotationOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["5", "key5", "source-rotation-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["shift+5", "shift+key5", "source-rotation-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:source_rotation"
+    }
+}
+
+pub type SourceRotation = EnumHolder<SourceRotationOptions>;
+
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq)]
+pub enum SourceMirrorOptions {
+    Off,
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+impl SourceMirrorOptions {
+    /// Factors for the x and y source coordinates.
+    pub fn scale(self) -> (f32, f32) {
+        match self {
+            SourceMirrorOptions::Off => (1.0, 1.0),
+            SourceMirrorOptions::Horizontal => (-1.0, 1.0),
+            SourceMirrorOptions::Vertical => (1.0, -1.0),
+            SourceMirrorOptions::Both => (-1.0, -1.0),
+        }
+    }
+}
+
+impl std::fmt::Display for SourceMirrorOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            SourceMirrorOptions::Off => write!(f, "Off"),
+            SourceMirrorOptions::Horizontal => write!(f, "Horizontal"),
+            SourceMirrorOptions::Vertical => write!(f, "Vertical"),
+            SourceMirrorOptions::Both => write!(f, "Both"),
+        }
+    }
+}
+
+impl EnumUi for SourceMirrorOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["6", "key6", "source-mirror-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["shift+6", "shift+key6", "source-mirror-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:source_mirror"
+    }
+}
+
+pub type SourceMirror = EnumHolder<SourceMirrorOptions>;
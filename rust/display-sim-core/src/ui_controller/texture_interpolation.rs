@@ -48,3 +48,75 @@ impl EnumUi for TextureInterpolationOptions {
 }
 
 pub type TextureInterpolation = EnumHolder<TextureInterpolationOptions>;
+
+/// Filtering of the source texture, which is what the misconverged beams sample from.
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq)]
+pub enum SourceInterpolationOptions {
+    Nearest,
+    Linear,
+}
+
+impl std::fmt::Display for SourceInterpolationOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            SourceInterpolationOptions::Nearest => write!(f, "Nearest"),
+            SourceInterpolationOptions::Linear => write!(f, "Linear"),
+        }
+    }
+}
+
+impl EnumUi for SourceInterpolationOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["source-interpolation-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["source-interpolation-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:source_interpolation"
+    }
+}
+
+pub type SourceInterpolation = EnumHolder<SourceInterpolationOptions>;
+
+/// Filtering of the final upscale from the internal resolution to the viewport. The buffers in
+/// between keep following `TextureInterpolationOptions`. Bicubic (Catmull-Rom) and Lanczos (2 lobes)
+/// are done in the shader, and keep low internal resolutions sharp on big displays.
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq)]
+pub enum OutputInterpolationOptions {
+    Nearest,
+    Linear,
+    Bicubic,
+    Lanczos,
+}
+
+impl std::fmt::Display for OutputInterpolationOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            OutputInterpolationOptions::Nearest => write!(f, "Nearest"),
+            OutputInterpolationOptions::Linear => write!(f, "Linear"),
+            OutputInterpolationOptions::Bicubic => write!(f, "Bicubic"),
+            OutputInterpolationOptions::Lanczos => write!(f, "Lanczos"),
+        }
+    }
+}
+
+impl EnumUi for OutputInterpolationOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["output-interpolation-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["output-interpolation-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:output_interpolation"
+    }
+}
+
+pub type OutputInterpolation = EnumHolder<OutputInterpolationOptions>;
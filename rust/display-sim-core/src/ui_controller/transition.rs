@@ -0,0 +1,55 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::ui_controller::enum_ui::{EnumHolder, EnumUi};
+use enum_len_derive::EnumLen;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+/// What plays on the screen for a moment when the source changes, like switching channels or inputs.
+#[derive(FromPrimitive, ToPrimitive, EnumLen, Copy, Clone, PartialEq, Debug, Default)]
+pub enum TransitionOptions {
+    #[default]
+    Off,
+    Static,
+    Roll,
+    BlueScreen,
+}
+
+impl std::fmt::Display for TransitionOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            TransitionOptions::Off => write!(f, "Off"),
+            TransitionOptions::Static => write!(f, "Static burst"),
+            TransitionOptions::Roll => write!(f, "Vertical roll"),
+            TransitionOptions::BlueScreen => write!(f, "Blue screen"),
+        }
+    }
+}
+
+impl EnumUi for TransitionOptions {
+    fn event_tag(&self) -> &'static str {
+        ""
+    }
+    fn keys_inc(&self) -> &[&'static str] {
+        &["transition-inc"]
+    }
+    fn keys_dec(&self) -> &[&'static str] {
+        &["transition-dec"]
+    }
+    fn dispatch_tag(&self) -> &'static str {
+        "back2front:transition"
+    }
+}
+
+pub type Transition = EnumHolder<TransitionOptions>;
@@ -13,6 +13,13 @@
  * You should have received a copy of the GNU Affero General Public License
  * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
 
+mod native_audio;
 mod native_entrypoint;
+mod native_frame_output;
+mod native_render_cli;
+mod native_stdin_video;
+mod native_video_feed;
+mod native_watch_folder;
+mod native_y4m_video;
 
 pub use native_entrypoint::*;
@@ -0,0 +1,181 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use core::app_events::SoundEffect;
+use core::simulation_core_state::VideoInputResources;
+use render::error::AppResult;
+
+pub const AUDIO_TRACK_ENV_VAR: &str = "DISPLAY_SIM_AUDIO_TRACK";
+
+#[cfg(feature = "audio")]
+const HORIZONTAL_SCAN_FREQUENCY: u32 = 15_734;
+
+#[cfg(feature = "audio")]
+pub struct CompanionAudio {
+    _stream: rodio::OutputStream,
+    handle: rodio::OutputStreamHandle,
+    sink: rodio::Sink,
+    path: std::path::PathBuf,
+    muted: bool,
+    last_frame: usize,
+}
+
+#[cfg(feature = "audio")]
+impl CompanionAudio {
+    pub fn load(path: &str) -> AppResult<CompanionAudio> {
+        let (stream, handle) = rodio::OutputStream::try_default().map_err(|e| format!("{}", e))?;
+        let path = std::path::PathBuf::from(path);
+        let sink = Self::play_from_start(&handle, &path)?;
+        Ok(CompanionAudio {
+            _stream: stream,
+            handle,
+            sink,
+            path,
+            muted: false,
+            last_frame: 0,
+        })
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+        self.sink.set_volume(if self.muted { 0.0 } else { 1.0 });
+    }
+
+    pub fn sync(&mut self, video: &VideoInputResources) -> AppResult<()> {
+        let looped = video.steps.len() > 1 && video.current_frame < self.last_frame;
+        self.last_frame = video.current_frame;
+        if looped || self.sink.empty() {
+            self.sink = Self::play_from_start(&self.handle, &self.path)?;
+            self.sink.set_volume(if self.muted { 0.0 } else { 1.0 });
+        }
+        Ok(())
+    }
+
+    fn play_from_start(handle: &rodio::OutputStreamHandle, path: &std::path::Path) -> AppResult<rodio::Sink> {
+        let file = std::fs::File::open(path).map_err(|e| format!("{}", e))?;
+        let source = rodio::Decoder::new(std::io::BufReader::new(file)).map_err(|e| format!("{}", e))?;
+        let sink = rodio::Sink::try_new(handle).map_err(|e| format!("{}", e))?;
+        sink.append(source);
+        Ok(sink)
+    }
+}
+
+#[cfg(not(feature = "audio"))]
+pub struct CompanionAudio {}
+
+#[cfg(not(feature = "audio"))]
+impl CompanionAudio {
+    pub fn load(_: &str) -> AppResult<CompanionAudio> {
+        Err("Audio playback requires building display-sim-native with the 'audio' feature.".into())
+    }
+    pub fn toggle_mute(&mut self) {}
+    pub fn sync(&mut self, _: &VideoInputResources) -> AppResult<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "audio")]
+struct CrtSoundSinks {
+    _stream: rodio::OutputStream,
+    hum: rodio::Sink,
+    noise: rodio::Sink,
+    degauss: rodio::Sink,
+}
+
+/// Plays the CRT sound effects, opening the output device the first time a sound is requested.
+#[cfg(feature = "audio")]
+#[derive(Default)]
+pub struct CrtSoundPlayer {
+    sinks: std::cell::RefCell<Option<CrtSoundSinks>>,
+}
+
+#[cfg(feature = "audio")]
+impl CrtSoundPlayer {
+    pub fn play(&self, sound: SoundEffect, volume: f32) -> AppResult<()> {
+        use rodio::Source;
+        let mut sinks = self.sinks.borrow_mut();
+        if sinks.is_none() {
+            *sinks = Some(Self::open()?);
+        }
+        let sinks = sinks.as_ref().expect("Sinks were just opened.");
+        match sound {
+            SoundEffect::Hum => sinks.hum.set_volume(volume),
+            SoundEffect::Static => sinks.noise.set_volume(volume),
+            SoundEffect::Degauss => sinks.degauss.append(
+                rodio::source::SineWave::new(60)
+                    .mix(WhiteNoise::default().amplify(0.3))
+                    .take_duration(std::time::Duration::from_millis(350))
+                    .amplify(volume),
+            ),
+        }
+        Ok(())
+    }
+
+    fn open() -> AppResult<CrtSoundSinks> {
+        let (stream, handle) = rodio::OutputStream::try_default().map_err(|e| format!("{}", e))?;
+        let hum = rodio::Sink::try_new(&handle).map_err(|e| format!("{}", e))?;
+        hum.set_volume(0.0);
+        hum.append(rodio::source::SineWave::new(HORIZONTAL_SCAN_FREQUENCY));
+        let noise = rodio::Sink::try_new(&handle).map_err(|e| format!("{}", e))?;
+        noise.set_volume(0.0);
+        noise.append(WhiteNoise::default());
+        let degauss = rodio::Sink::try_new(&handle).map_err(|e| format!("{}", e))?;
+        Ok(CrtSoundSinks {
+            _stream: stream,
+            hum,
+            noise,
+            degauss,
+        })
+    }
+}
+
+#[cfg(feature = "audio")]
+#[derive(Default)]
+struct WhiteNoise {}
+
+#[cfg(feature = "audio")]
+impl Iterator for WhiteNoise {
+    type Item = f32;
+    fn next(&mut self) -> Option<f32> {
+        Some(rand::random::<f32>() * 2.0 - 1.0)
+    }
+}
+
+#[cfg(feature = "audio")]
+impl rodio::Source for WhiteNoise {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+    fn channels(&self) -> u16 {
+        1
+    }
+    fn sample_rate(&self) -> u32 {
+        48_000
+    }
+    fn total_duration(&self) -> Option<std::time::Duration> {
+        None
+    }
+}
+
+#[cfg(not(feature = "audio"))]
+#[derive(Default)]
+pub struct CrtSoundPlayer {}
+
+#[cfg(not(feature = "audio"))]
+impl CrtSoundPlayer {
+    pub fn play(&self, sound: SoundEffect, volume: f32) -> AppResult<()> {
+        println!("play_sound: {} {}", sound, volume);
+        Ok(())
+    }
+}
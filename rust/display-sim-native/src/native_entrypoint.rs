@@ -13,23 +13,33 @@
  * You should have received a copy of the GNU Affero General Public License
  * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
 
-use core::app_events::AppEventDispatcher;
+use crate::native_audio::{CompanionAudio, CrtSoundPlayer, AUDIO_TRACK_ENV_VAR};
+use crate::native_frame_output::{FrameOutput, VirtualCamera, NDI_NAME_ENV_VAR, VIRTUAL_CAMERA_ENV_VAR};
+use crate::native_render_cli;
+use crate::native_stdin_video::{StdinVideo, STDIN_VIDEO_USAGE};
+use crate::native_video_feed::VideoFeed;
+use crate::native_watch_folder::WatchFolder;
+use crate::native_y4m_video::{Y4mVideo, Y4M_VIDEO_USAGE};
+use core::app_events::{AppEventDispatcher, ClampedValue, MessageCategory, SoundEffect};
 use core::camera::CameraLockMode;
+use core::diagnostics::DiagnosticsReadout;
 use core::general_types::Size2D;
 use core::input_types::{Input, InputEventValue, Pressed};
+use core::moire::MoireReport;
 use core::simulation_context::{ConcreteSimulationContext, RandomGenerator};
 use core::simulation_core_state::ScalingMethod;
 use core::simulation_core_state::{AnimationStep, Resources, VideoInputResources};
 use core::simulation_core_ticker::SimulationCoreTicker;
+use core::subtitles::parse_srt;
+use core::ui_controller::message_verbosity::MessageVerbosityOptions;
 use render::error::AppResult;
 use render::simulation_draw::SimulationDrawer;
 use render::simulation_render_state::{Materials, VideoInputMaterials};
 
-use std::fmt::Display;
 use std::rc::Rc;
 use std::time::{Duration, Instant};
 
-use glutin::event::{ElementState, Event, MouseButton, MouseScrollDelta, WindowEvent};
+use glutin::event::{ElementState, Event, MouseButton, MouseScrollDelta, VirtualKeyCode, WindowEvent};
 use glutin::event_loop::{ControlFlow, EventLoop};
 use glutin::monitor::MonitorHandle;
 use glutin::window::{Fullscreen, WindowBuilder};
@@ -37,14 +47,34 @@ use glutin::{ContextBuilder, ContextError, GlProfile, GlRequest, PossiblyCurrent
 
 use glow::GlowSafeAdapter;
 
+const SUBTITLES_ENV_VAR: &str = "DISPLAY_SIM_SUBTITLES";
+const FILTER_MASK_ENV_VAR: &str = "DISPLAY_SIM_FILTER_MASK";
+
 pub fn main() {
-    if let Err(e) = program() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("render") => native_render_cli::program(&args[1..]),
+        Some("watch") => match args.get(1) {
+            Some(directory) => WatchFolder::new(directory).and_then(|watch_folder| program(Some(Box::new(watch_folder)))),
+            None => Err("Usage: display-sim-native watch <directory>".into()),
+        },
+        Some("play") => match args.get(1) {
+            Some(path) => Y4mVideo::new(path).and_then(|y4m_video| program(Some(Box::new(y4m_video)))),
+            None => Err(Y4M_VIDEO_USAGE.into()),
+        },
+        Some("--stdin") => match args.get(1) {
+            Some(spec) => StdinVideo::new(spec).and_then(|stdin_video| program(Some(Box::new(stdin_video)))),
+            None => Err(STDIN_VIDEO_USAGE.into()),
+        },
+        _ => program(None),
+    };
+    if let Err(e) = result {
         println!("Error: {:?}", e);
         std::process::exit(-1);
     }
 }
 
-struct NativeRnd {}
+pub(crate) struct NativeRnd {}
 
 impl RandomGenerator for NativeRnd {
     fn next(&self) -> f32 {
@@ -54,7 +84,7 @@ impl RandomGenerator for NativeRnd {
     }
 }
 
-fn program() -> AppResult<()> {
+fn program(video_feed: Option<Box<dyn VideoFeed>>) -> AppResult<()> {
     println!("Initializing Window.");
     let winit_loop = EventLoop::new();
     let monitor = winit_loop.primary_monitor();
@@ -111,8 +141,23 @@ fn program() -> AppResult<()> {
         current_frame: 0,
         preset: None,
         last_frame_change: 0.0,
+        external_clock: false,
+        pending_frame_advances: 0,
         needs_buffer_data_load: true,
         drawing_activation: true,
+        subtitles: match std::env::var(SUBTITLES_ENV_VAR) {
+            Ok(path) => {
+                println!("Loading subtitles: {} (3 toggles them)", path);
+                match std::fs::read_to_string(&path).map_err(|e| format!("{}", e)).and_then(|srt| parse_srt(&srt)) {
+                    Ok(subtitles) => subtitles,
+                    Err(e) => {
+                        println!("Subtitles disabled: {}", e);
+                        vec![]
+                    }
+                }
+            }
+            Err(_) => vec![],
+        },
     };
     let materials_input = VideoInputMaterials { buffers: vec![pixels] };
 
@@ -120,16 +165,76 @@ fn program() -> AppResult<()> {
     let mut res = Resources::default();
     res.initialize(res_input, 0.0);
     println!("Preparing materials.");
-    let materials = Materials::new(Rc::new(GlowSafeAdapter::new(gl_ctx)), materials_input)?;
+    let mut materials = Materials::new(Rc::new(GlowSafeAdapter::new(gl_ctx)), materials_input)?;
+    if let Ok(path) = std::env::var(FILTER_MASK_ENV_VAR) {
+        println!("Loading filter mask: {}", path);
+        match image::open(&path) {
+            Ok(mask) => {
+                let mask = mask.to_rgba8();
+                let size = Size2D {
+                    width: mask.width(),
+                    height: mask.height(),
+                };
+                materials.background_render.set_mask(size, &mask.into_raw())?;
+            }
+            Err(e) => println!("Filter mask disabled: {}", e),
+        }
+    }
 
     println!("Preparing input.");
     let input = Input::new(0.0);
     println!("Preparing simulation context.");
     let sim_ctx = ConcreteSimulationContext::new(NativeEventDispatcher::new(windowed_ctx.clone()), NativeRnd {});
 
+    let audio = match std::env::var(AUDIO_TRACK_ENV_VAR) {
+        Ok(path) => {
+            println!("Loading audio track: {} (F9 toggles mute)", path);
+            match CompanionAudio::load(&path) {
+                Ok(audio) => Some(audio),
+                Err(e) => {
+                    println!("Audio track disabled: {:?}", e);
+                    None
+                }
+            }
+        }
+        Err(_) => None,
+    };
+
+    let frame_output = match std::env::var(NDI_NAME_ENV_VAR) {
+        Ok(name) => {
+            println!("Publishing frames as NDI source: {}", name);
+            match FrameOutput::start(&name) {
+                Ok(frame_output) => Some(frame_output),
+                Err(e) => {
+                    println!("NDI output disabled: {:?}", e);
+                    None
+                }
+            }
+        }
+        Err(_) => None,
+    };
+
+    let virtual_camera = match std::env::var(VIRTUAL_CAMERA_ENV_VAR) {
+        Ok(path) => {
+            println!("Writing frames into virtual camera: {}", path);
+            match VirtualCamera::open(&path) {
+                Ok(virtual_camera) => Some(virtual_camera),
+                Err(e) => {
+                    println!("Virtual camera disabled: {:?}", e);
+                    None
+                }
+            }
+        }
+        Err(_) => None,
+    };
+
     let timings = Timings::new(Instant::now(), Duration::from_secs_f64(1.0 / 60.0));
 
-    let mut state = NativeSimulationState::new(sim_ctx, windowed_ctx, monitor, res, input, materials, timings);
+    let mut state = NativeSimulationState::new(sim_ctx, windowed_ctx, monitor, res, input, materials, timings)
+        .with_audio(audio)
+        .with_video_feed(video_feed)
+        .with_frame_output(frame_output)
+        .with_virtual_camera(virtual_camera);
 
     winit_loop.run(move |event, _, control_flow| match state.iteration(event, control_flow) {
         Ok(()) => {}
@@ -148,6 +253,10 @@ struct NativeSimulationState {
     input: Input,
     materials: Materials,
     timings: Timings,
+    audio: Option<CompanionAudio>,
+    video_feed: Option<Box<dyn VideoFeed>>,
+    frame_output: Option<FrameOutput>,
+    virtual_camera: Option<VirtualCamera>,
 }
 
 struct Timings {
@@ -184,9 +293,33 @@ impl NativeSimulationState {
             input,
             materials,
             timings,
+            audio: None,
+            video_feed: None,
+            frame_output: None,
+            virtual_camera: None,
         }
     }
 
+    pub fn with_audio(mut self, audio: Option<CompanionAudio>) -> Self {
+        self.audio = audio;
+        self
+    }
+
+    pub fn with_video_feed(mut self, video_feed: Option<Box<dyn VideoFeed>>) -> Self {
+        self.video_feed = video_feed;
+        self
+    }
+
+    pub fn with_frame_output(mut self, frame_output: Option<FrameOutput>) -> Self {
+        self.frame_output = frame_output;
+        self
+    }
+
+    pub fn with_virtual_camera(mut self, virtual_camera: Option<VirtualCamera>) -> Self {
+        self.virtual_camera = virtual_camera;
+        self
+    }
+
     pub fn iteration(&mut self, event: Event<()>, control_flow: &mut ControlFlow) -> Result<(), ContextError> {
         *control_flow = ControlFlow::Poll;
 
@@ -206,7 +339,9 @@ impl NativeSimulationState {
                     self.windowed_ctx.swap_buffers()?;
                 }
                 WindowEvent::KeyboardInput { input: keyevent, .. } => {
-                    if let Some(key) = keyevent.virtual_keycode {
+                    if let (Some(VirtualKeyCode::F9), ElementState::Pressed, Some(audio)) = (keyevent.virtual_keycode, keyevent.state, self.audio.as_mut()) {
+                        audio.toggle_mute();
+                    } else if let Some(key) = keyevent.virtual_keycode {
                         self.input.push_event(InputEventValue::Keyboard {
                             pressed: match keyevent.state {
                                 ElementState::Pressed => Pressed::Yes,
@@ -245,6 +380,10 @@ impl NativeSimulationState {
                         x: position.x as i32,
                         y: position.y as i32,
                     });
+                    self.input.push_event(InputEventValue::PointerPosition {
+                        x: position.x as i32,
+                        y: position.y as i32,
+                    });
                 }
                 WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
                 _ => (),
@@ -261,9 +400,37 @@ impl NativeSimulationState {
                 Err(e) => println!("Tick error: {:?}", e),
             };
 
-            if self.res.drawable {
-                if let Err(e) = SimulationDrawer::new(&self.sim_ctx, &mut self.materials, &self.res).draw() {
-                    println!("Draw error: {:?}", e);
+            if let Some(ref mut video_feed) = self.video_feed {
+                if let Err(e) = video_feed.sync(&mut self.res, &mut self.materials) {
+                    println!("Video feed error: {:?}", e);
+                }
+            }
+
+            if let Err(e) = SimulationDrawer::new(&self.sim_ctx, &mut self.materials, &self.res).draw() {
+                println!("Draw error: {:?}", e);
+            }
+
+            if let Some(luminance) = self.materials.frame_luminance.take() {
+                self.res.blooming.record_luminance(luminance);
+            }
+
+            if let Some(ref mut frame_output) = self.frame_output {
+                let viewport = self.res.video.viewport_size;
+                if let Err(e) = frame_output.publish(&self.materials, viewport.width, viewport.height) {
+                    println!("NDI output error: {:?}", e);
+                }
+            }
+
+            if let Some(ref mut virtual_camera) = self.virtual_camera {
+                let viewport = self.res.video.viewport_size;
+                if let Err(e) = virtual_camera.publish(&self.materials, viewport.width, viewport.height) {
+                    println!("Virtual camera error: {:?}", e);
+                }
+            }
+
+            if let Some(ref mut audio) = self.audio {
+                if let Err(e) = audio.sync(&self.res.video) {
+                    println!("Audio error: {:?}", e);
                 }
             }
 
@@ -280,17 +447,22 @@ impl NativeSimulationState {
 
 struct NativeEventDispatcher {
     video_ctx: Rc<WindowedContext<PossiblyCurrent>>,
+    sounds: CrtSoundPlayer,
 }
 
 impl NativeEventDispatcher {
     pub fn new(video_ctx: Rc<WindowedContext<PossiblyCurrent>>) -> Self {
-        NativeEventDispatcher { video_ctx }
+        NativeEventDispatcher {
+            video_ctx,
+            sounds: CrtSoundPlayer::default(),
+        }
     }
 }
 
 impl AppEventDispatcher for NativeEventDispatcher {
     fn enable_extra_messages(&self, _: bool) {}
-    fn are_extra_messages_enabled(&self) -> bool {
+    fn set_message_verbosity(&self, _: MessageVerbosityOptions) {}
+    fn are_extra_messages_enabled(&self, _: MessageCategory) -> bool {
         false
     }
     fn dispatch_log(&self, msg: String) {
@@ -302,12 +474,16 @@ impl AppEventDispatcher for NativeEventDispatcher {
     fn dispatch_camera_update(&self, a: &glm::Vec3, b: &glm::Vec3, c: &glm::Vec3) {
         println!("camera_update {}, {}, {}", a, b, c);
     }
+    fn dispatch_recovery_state(&self, _: &str, _: &glm::Vec3, _: &glm::Vec3, _: &glm::Vec3) {}
     fn dispatch_change_pixel_width(&self, size: f32) {
         println!("change_pixel_width: {}", size);
     }
     fn dispatch_change_camera_zoom(&self, zoom: f32) {
         println!("change_camera_zoom: {}", zoom);
     }
+    fn dispatch_change_camera_focal_length(&self, focal_length: f32) {
+        println!("change_camera_focal_length: {}", focal_length);
+    }
     fn dispatch_scaling_method(&self, method: ScalingMethod) {
         println!("scaling_method: {}", method);
     }
@@ -344,6 +520,7 @@ impl AppEventDispatcher for NativeEventDispatcher {
     fn dispatch_fps(&self, fps: f32) {
         println!("frames in 20 seconds: {}", fps);
     }
+    fn dispatch_frame_times(&self, _: &[f32]) {}
     fn dispatch_request_fullscreen(&self) {
         println!("request_fullscreen");
     }
@@ -355,19 +532,43 @@ impl AppEventDispatcher for NativeEventDispatcher {
         println!("exit_pointer_lock");
         self.video_ctx.window().set_cursor_visible(true);
     }
-    fn dispatch_screenshot(&self, _: i32, _: i32, _: &mut [u8]) -> AppResult<()> {
+    fn dispatch_diagnostics(&self, readout: &DiagnosticsReadout) {
+        println!("diagnostics: {:?}", readout);
+    }
+    fn dispatch_moire_report(&self, report: &MoireReport) {
+        println!("moire_report: {:?}", report);
+    }
+    fn dispatch_light_gun_shot(&self, hit: Option<(u32, u32)>) {
+        match hit {
+            Some((x, y)) => println!("light_gun_shot: hit at {}, {}", x, y),
+            None => println!("light_gun_shot: missed"),
+        }
+    }
+    fn dispatch_play_sound(&self, sound: SoundEffect, volume: f32) {
+        if let Err(e) = self.sounds.play(sound, volume) {
+            println!("Could not play sound {}: {}", sound, e);
+        }
+    }
+    // Nothing is read back here, so screenshots and the moiré assistant only work on the web.
+    fn request_screenshot_readback(&self, _: i32, _: i32) -> AppResult<()> {
         Ok(())
     }
+    fn dispatch_screenshot(&self, _: i32, _: i32, _: &mut [u8], _: Option<&str>) -> AppResult<()> {
+        Ok(())
+    }
+    fn dispatch_turntable_recording(&self, _: bool) -> AppResult<()> {
+        Err("Turntable clips can only be recorded in the browser.".into())
+    }
     fn dispatch_change_camera_movement_mode(&self, locked_mode: CameraLockMode) {
         println!("change_camera_movement_mode: {}", locked_mode);
     }
     fn dispatch_top_message(&self, message: &str) {
         println!("top_message: {}", message);
     }
-    fn dispatch_minimum_value(&self, value: &dyn Display) {
-        println!("minimum: {}", value);
+    fn dispatch_minimum_value(&self, clamped: &ClampedValue) {
+        println!("minimum: {} {} -> {}", clamped.field, clamped.attempted, clamped.bound);
     }
-    fn dispatch_maximum_value(&self, value: &dyn Display) {
-        println!("maximum: {}", value);
+    fn dispatch_maximum_value(&self, clamped: &ClampedValue) {
+        println!("maximum: {} {} -> {}", clamped.field, clamped.attempted, clamped.bound);
     }
 }
@@ -0,0 +1,318 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use render::error::AppResult;
+use render::simulation_render_state::Materials;
+
+pub const NDI_NAME_ENV_VAR: &str = "DISPLAY_SIM_NDI_NAME";
+pub const VIRTUAL_CAMERA_ENV_VAR: &str = "DISPLAY_SIM_VIRTUAL_CAMERA";
+
+#[cfg(feature = "ndi")]
+const NDI_LIBRARY_NAMES: [&str; 5] = ["libndi.so.5", "libndi.so.4", "libndi.so", "libndi.dylib", "Processing.NDI.Lib.x64.dll"];
+
+#[cfg(feature = "ndi")]
+const NDI_FOURCC_RGBA: u32 = u32::from_le_bytes(*b"RGBA");
+
+#[cfg(feature = "ndi")]
+const NDI_FRAME_FORMAT_PROGRESSIVE: i32 = 1;
+
+#[cfg(feature = "ndi")]
+const NDI_TIMECODE_SYNTHESIZE: i64 = i64::MAX;
+
+#[cfg(feature = "ndi")]
+#[repr(C)]
+struct NdiSendCreate {
+    p_ndi_name: *const std::os::raw::c_char,
+    p_groups: *const std::os::raw::c_char,
+    clock_video: bool,
+    clock_audio: bool,
+}
+
+#[cfg(feature = "ndi")]
+#[repr(C)]
+struct NdiVideoFrame {
+    xres: i32,
+    yres: i32,
+    four_cc: u32,
+    frame_rate_n: i32,
+    frame_rate_d: i32,
+    picture_aspect_ratio: f32,
+    frame_format_type: i32,
+    timecode: i64,
+    p_data: *const u8,
+    line_stride_in_bytes: i32,
+    p_metadata: *const std::os::raw::c_char,
+    timestamp: i64,
+}
+
+#[cfg(feature = "ndi")]
+type NdiSendInstance = *mut std::os::raw::c_void;
+
+// Publishes the final composited frame as an NDI source, so OBS and other video tools can ingest it live.
+// The NDI runtime is loaded when the output starts, so the build doesn't need the NDI SDK.
+#[cfg(feature = "ndi")]
+pub struct FrameOutput {
+    _library: libloading::Library,
+    instance: NdiSendInstance,
+    send_video: unsafe extern "C" fn(NdiSendInstance, *const NdiVideoFrame),
+    send_destroy: unsafe extern "C" fn(NdiSendInstance),
+    _name: std::ffi::CString,
+    pixels: Vec<u8>,
+    flipped: Vec<u8>,
+}
+
+#[cfg(feature = "ndi")]
+impl FrameOutput {
+    pub fn start(name: &str) -> AppResult<FrameOutput> {
+        let library = NDI_LIBRARY_NAMES
+            .iter()
+            .find_map(|library_name| libloading::Library::new(library_name).ok())
+            .ok_or("Could not find the NDI runtime library.")?;
+        let name = std::ffi::CString::new(name).map_err(|e| format!("{}", e))?;
+        unsafe {
+            let initialize = *library
+                .get::<unsafe extern "C" fn() -> bool>(b"NDIlib_initialize\0")
+                .map_err(|e| format!("{}", e))?;
+            let send_create = *library
+                .get::<unsafe extern "C" fn(*const NdiSendCreate) -> NdiSendInstance>(b"NDIlib_send_create\0")
+                .map_err(|e| format!("{}", e))?;
+            let send_video = *library
+                .get::<unsafe extern "C" fn(NdiSendInstance, *const NdiVideoFrame)>(b"NDIlib_send_send_video_v2\0")
+                .map_err(|e| format!("{}", e))?;
+            let send_destroy = *library
+                .get::<unsafe extern "C" fn(NdiSendInstance)>(b"NDIlib_send_destroy\0")
+                .map_err(|e| format!("{}", e))?;
+            if !initialize() {
+                return Err("The NDI runtime is not supported on this CPU.".into());
+            }
+            let instance = send_create(&NdiSendCreate {
+                p_ndi_name: name.as_ptr(),
+                p_groups: std::ptr::null(),
+                clock_video: false,
+                clock_audio: false,
+            });
+            if instance.is_null() {
+                return Err("Could not create the NDI sender.".into());
+            }
+            Ok(FrameOutput {
+                _library: library,
+                instance,
+                send_video,
+                send_destroy,
+                _name: name,
+                pixels: vec![],
+                flipped: vec![],
+            })
+        }
+    }
+
+    pub fn publish(&mut self, materials: &Materials, width: u32, height: u32) -> AppResult<()> {
+        read_frame_top_down(materials, width, height, &mut self.pixels, &mut self.flipped);
+        let row_size = width as usize * 4;
+        let frame = NdiVideoFrame {
+            xres: width as i32,
+            yres: height as i32,
+            four_cc: NDI_FOURCC_RGBA,
+            frame_rate_n: 60_000,
+            frame_rate_d: 1_000,
+            picture_aspect_ratio: width as f32 / height as f32,
+            frame_format_type: NDI_FRAME_FORMAT_PROGRESSIVE,
+            timecode: NDI_TIMECODE_SYNTHESIZE,
+            p_data: self.flipped.as_ptr(),
+            line_stride_in_bytes: row_size as i32,
+            p_metadata: std::ptr::null(),
+            timestamp: 0,
+        };
+        unsafe { (self.send_video)(self.instance, &frame) };
+        Ok(())
+    }
+}
+
+#[cfg(feature = "ndi")]
+impl Drop for FrameOutput {
+    fn drop(&mut self) {
+        unsafe { (self.send_destroy)(self.instance) };
+    }
+}
+
+#[cfg(not(feature = "ndi"))]
+pub struct FrameOutput {}
+
+#[cfg(not(feature = "ndi"))]
+impl FrameOutput {
+    pub fn start(_: &str) -> AppResult<FrameOutput> {
+        Err("NDI output requires building display-sim-native with the 'ndi' feature.".into())
+    }
+    pub fn publish(&mut self, _: &Materials, _: u32, _: u32) -> AppResult<()> {
+        Ok(())
+    }
+}
+
+// OpenGL reads rows from the bottom, while video outputs expect them from the top.
+#[cfg(any(feature = "ndi", target_os = "linux"))]
+fn read_frame_top_down(materials: &Materials, width: u32, height: u32, pixels: &mut Vec<u8>, flipped: &mut Vec<u8>) {
+    let row_size = width as usize * 4;
+    pixels.resize(row_size * height as usize, 0);
+    materials
+        .gl
+        .read_pixels(0, 0, width as i32, height as i32, glow::RGBA, glow::UNSIGNED_BYTE, pixels);
+    flipped.clear();
+    flipped.extend(pixels.chunks(row_size).rev().flatten());
+}
+
+#[cfg(target_os = "linux")]
+const VIRTUAL_CAMERA_SIZE: (u32, u32) = (1280, 720);
+
+#[cfg(target_os = "linux")]
+const V4L2_BUF_TYPE_VIDEO_OUTPUT: u32 = 2;
+
+#[cfg(target_os = "linux")]
+const V4L2_PIX_FMT_YUYV: u32 = u32::from_le_bytes(*b"YUYV");
+
+#[cfg(target_os = "linux")]
+const V4L2_FIELD_NONE: u32 = 1;
+
+#[cfg(target_os = "linux")]
+const V4L2_COLORSPACE_SRGB: u32 = 8;
+
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct V4l2PixFormat {
+    width: u32,
+    height: u32,
+    pixelformat: u32,
+    field: u32,
+    bytesperline: u32,
+    sizeimage: u32,
+    colorspace: u32,
+    private: u32,
+    flags: u32,
+    ycbcr_enc: u32,
+    quantization: u32,
+    xfer_func: u32,
+}
+
+// Same layout as the kernel union, which is pointer aligned because of the v4l2_window member.
+#[cfg(target_os = "linux")]
+#[repr(C)]
+union V4l2FormatUnion {
+    pix: V4l2PixFormat,
+    raw_data: [u8; 200],
+    _alignment: [*const u8; 0],
+}
+
+#[cfg(target_os = "linux")]
+#[repr(C)]
+struct V4l2Format {
+    buffer_type: u32,
+    fmt: V4l2FormatUnion,
+}
+
+#[cfg(target_os = "linux")]
+const VIDIOC_S_FMT: u64 = (3 << 30) | ((std::mem::size_of::<V4l2Format>() as u64) << 16) | ((b'V' as u64) << 8) | 5;
+
+// Writes the final composited frame into a v4l2loopback device, so video-call software can use it as a webcam.
+#[cfg(target_os = "linux")]
+pub struct VirtualCamera {
+    device: std::fs::File,
+    pixels: Vec<u8>,
+    flipped: Vec<u8>,
+    yuyv: Vec<u8>,
+}
+
+#[cfg(target_os = "linux")]
+impl VirtualCamera {
+    pub fn open(path: &str) -> AppResult<VirtualCamera> {
+        use std::os::unix::io::AsRawFd;
+        let device = std::fs::OpenOptions::new()
+            .write(true)
+            .open(path)
+            .map_err(|e| format!("Could not open {}: {}", path, e))?;
+        let (width, height) = VIRTUAL_CAMERA_SIZE;
+        let mut format = V4l2Format {
+            buffer_type: V4L2_BUF_TYPE_VIDEO_OUTPUT,
+            fmt: V4l2FormatUnion { raw_data: [0; 200] },
+        };
+        format.fmt.pix = V4l2PixFormat {
+            width,
+            height,
+            pixelformat: V4L2_PIX_FMT_YUYV,
+            field: V4L2_FIELD_NONE,
+            bytesperline: width * 2,
+            sizeimage: width * height * 2,
+            colorspace: V4L2_COLORSPACE_SRGB,
+            private: 0,
+            flags: 0,
+            ycbcr_enc: 0,
+            quantization: 0,
+            xfer_func: 0,
+        };
+        if unsafe { libc::ioctl(device.as_raw_fd(), VIDIOC_S_FMT as _, &mut format) } < 0 {
+            return Err(format!("Could not set the format of {}: {}", path, std::io::Error::last_os_error()).into());
+        }
+        Ok(VirtualCamera {
+            device,
+            pixels: vec![],
+            flipped: vec![],
+            yuyv: vec![],
+        })
+    }
+
+    pub fn publish(&mut self, materials: &Materials, width: u32, height: u32) -> AppResult<()> {
+        use std::io::Write;
+        read_frame_top_down(materials, width, height, &mut self.pixels, &mut self.flipped);
+        rgba_to_yuyv(&self.flipped, (width, height), VIRTUAL_CAMERA_SIZE, &mut self.yuyv);
+        self.device.write_all(&self.yuyv).map_err(|e| format!("{}", e))?;
+        Ok(())
+    }
+}
+
+// Nearest neighbour resampling into the camera size, with BT.601 limited range coefficients.
+#[cfg(target_os = "linux")]
+fn rgba_to_yuyv(rgba: &[u8], source: (u32, u32), target: (u32, u32), yuyv: &mut Vec<u8>) {
+    yuyv.clear();
+    if source.0 == 0 || source.1 == 0 {
+        yuyv.resize((target.0 * target.1 * 2) as usize, 0);
+        return;
+    }
+    let sample = |x: u32, y: u32| -> [f32; 3] {
+        let i = (((y * source.1 / target.1) * source.0 + x * source.0 / target.0) * 4) as usize;
+        [rgba[i] as f32, rgba[i + 1] as f32, rgba[i + 2] as f32]
+    };
+    let luma = |[r, g, b]: [f32; 3]| (16.0 + 0.257 * r + 0.504 * g + 0.098 * b) as u8;
+    for y in 0..target.1 {
+        for x in (0..target.0).step_by(2) {
+            let left = sample(x, y);
+            let right = sample((x + 1).min(target.0 - 1), y);
+            let [r, g, b] = [(left[0] + right[0]) * 0.5, (left[1] + right[1]) * 0.5, (left[2] + right[2]) * 0.5];
+            let u = (128.0 - 0.148 * r - 0.291 * g + 0.439 * b) as u8;
+            let v = (128.0 + 0.439 * r - 0.368 * g - 0.071 * b) as u8;
+            yuyv.extend_from_slice(&[luma(left), u, luma(right), v]);
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub struct VirtualCamera {}
+
+#[cfg(not(target_os = "linux"))]
+impl VirtualCamera {
+    pub fn open(_: &str) -> AppResult<VirtualCamera> {
+        Err("Virtual camera output is only supported on Linux through v4l2loopback.".into())
+    }
+    pub fn publish(&mut self, _: &Materials, _: u32, _: u32) -> AppResult<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,352 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::native_entrypoint::NativeRnd;
+use core::app_events::FakeEventDispatcher;
+use core::general_types::Size2D;
+use core::input_types::Input;
+use core::screenshot_bracket::{build_bracket_sweep, BracketStep};
+use core::simulation_context::ConcreteSimulationContext;
+use core::simulation_core_state::{AnimationStep, KeyEventKind, Resources, VideoInputResources};
+use core::simulation_core_ticker::SimulationCoreTicker;
+use core::ui_controller::filter_preset::FilterPresetOptions;
+use core::ui_controller::screenshot_bracket::ScreenshotBracketOptions;
+use core::ui_controller::EncodedValue;
+use render::error::AppResult;
+use render::simulation_draw::SimulationDrawer;
+use render::simulation_render_state::{Materials, VideoInputMaterials};
+
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use glutin::dpi::{LogicalSize, PhysicalSize};
+use glutin::event_loop::EventLoop;
+use glutin::window::WindowBuilder;
+use glutin::{ContextBuilder, GlProfile, GlRequest};
+
+use glow::GlowSafeAdapter;
+
+// Frames simulated before reading the output back, so scaling and camera have settled.
+const RENDER_WARMUP_FRAMES: u32 = 10;
+const RENDER_FRAME_MILLIS: f64 = 16.0;
+const IMAGE_EXTENSIONS: [&str; 5] = ["png", "jpg", "jpeg", "gif", "bmp"];
+
+const USAGE: &str = "Usage: display-sim-native render --input <image|directory> --output <png|directory> [--preset <file>] [--resolution <width>x<height>] [--rows <curvature|mask-type>] [--columns <curvature|mask-type>]";
+
+struct RenderOptions {
+    input: PathBuf,
+    output: PathBuf,
+    preset: Option<PathBuf>,
+    resolution: Size2D<u32>,
+    rows: Option<ScreenshotBracketOptions>,
+    columns: Option<ScreenshotBracketOptions>,
+}
+
+impl RenderOptions {
+    fn parse(args: &[String]) -> AppResult<RenderOptions> {
+        let mut input = None;
+        let mut output = None;
+        let mut preset = None;
+        let mut resolution = Size2D { width: 1920, height: 1080 };
+        let mut rows = None;
+        let mut columns = None;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("Missing value for {}\n{}", arg, USAGE));
+            match arg.as_ref() {
+                "--input" => input = Some(PathBuf::from(value()?)),
+                "--output" => output = Some(PathBuf::from(value()?)),
+                "--preset" => preset = Some(PathBuf::from(value()?)),
+                "--resolution" => resolution = parse_resolution(value()?)?,
+                "--rows" => rows = Some(ScreenshotBracketOptions::from_str(value()?)?),
+                "--columns" => columns = Some(ScreenshotBracketOptions::from_str(value()?)?),
+                _ => return Err(format!("Unknown argument: {}\n{}", arg, USAGE).into()),
+            }
+        }
+        Ok(RenderOptions {
+            input: input.ok_or(USAGE)?,
+            output: output.ok_or(USAGE)?,
+            preset,
+            resolution,
+            rows,
+            columns,
+        })
+    }
+}
+
+pub(crate) fn parse_resolution(value: &str) -> AppResult<Size2D<u32>> {
+    let mut parts = value.split('x').map(|part| part.trim().parse::<u32>());
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(width)), Some(Ok(height)), None) if width > 0 && height > 0 => Ok(Size2D { width, height }),
+        _ => Err(format!("Invalid resolution '{}', expected <width>x<height>", value).into()),
+    }
+}
+
+// Preset files are lines of `name = value`. The name `preset` selects one of the built-in presets,
+// any other name is one of the settings the frontend sends, like `blur-level = 2`. Lines starting with # are ignored.
+#[derive(Default)]
+struct PresetFile {
+    preset: Option<FilterPresetOptions>,
+    settings: Vec<(String, String)>,
+}
+
+impl PresetFile {
+    fn load(path: &Path) -> AppResult<PresetFile> {
+        let contents = std::fs::read_to_string(path).map_err(|e| format!("Could not read preset file {}: {}", path.display(), e))?;
+        let mut preset_file = PresetFile::default();
+        for line in contents.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let mut parts = line.splitn(2, '=').map(str::trim);
+            match (parts.next(), parts.next()) {
+                (Some("preset"), Some(value)) => preset_file.preset = Some(FilterPresetOptions::from_str(value)?),
+                (Some(name), Some(value)) => preset_file.settings.push((name.into(), value.into())),
+                _ => return Err(format!("Invalid preset line: {}", line).into()),
+            }
+        }
+        Ok(preset_file)
+    }
+
+    fn apply(&self, res: &mut Resources) -> AppResult<()> {
+        for (name, value) in self.settings.iter() {
+            let index = match res.controller_events.get(format!("front2back:{}", name).as_str()) {
+                Some((KeyEventKind::Set, index)) => *index,
+                _ => return Err(format!("Unknown preset setting: {}", name).into()),
+            };
+            res.controllers.get_ui_controllers_mut()[index].read_event(&TextEncodedValue { value })?;
+        }
+        Ok(())
+    }
+}
+
+struct TextEncodedValue<'a> {
+    value: &'a str,
+}
+
+impl<'a> EncodedValue for TextEncodedValue<'a> {
+    fn to_f64(&self) -> AppResult<f64> {
+        Ok(self.value.parse::<f64>().map_err(|e| format!("'{}' should be a number: {}", self.value, e))?)
+    }
+    fn to_f32(&self) -> AppResult<f32> {
+        Ok(self.to_f64()? as f32)
+    }
+    fn to_u32(&self) -> AppResult<u32> {
+        Ok(self.to_f64()? as u32)
+    }
+    fn to_i32(&self) -> AppResult<i32> {
+        Ok(self.to_f64()? as i32)
+    }
+    fn to_usize(&self) -> AppResult<usize> {
+        Ok(self.to_f64()? as usize)
+    }
+    fn to_string(&self) -> AppResult<String> {
+        Ok(self.value.into())
+    }
+}
+
+pub fn program(args: &[String]) -> AppResult<()> {
+    let options = RenderOptions::parse(args)?;
+    let preset_file = match options.preset {
+        Some(ref path) => PresetFile::load(path)?,
+        None => PresetFile::default(),
+    };
+    let inputs = collect_inputs(&options.input)?;
+    if inputs.is_empty() {
+        return Err(format!("No images found in {}", options.input.display()).into());
+    }
+    let single_output = options.input.is_file() && options.output.extension().is_some();
+    let grid = SweepGrid::new(options.rows, options.columns);
+    if grid.is_some() && !single_output {
+        return Err(format!("--rows and --columns need a single input image and a png output\n{}", USAGE).into());
+    }
+    if !single_output {
+        std::fs::create_dir_all(&options.output).map_err(|e| format!("Could not create {}: {}", options.output.display(), e))?;
+    }
+
+    let event_loop = EventLoop::new();
+    let hidpi = event_loop.primary_monitor().hidpi_factor();
+    let physical_size = PhysicalSize::new(options.resolution.width as f64, options.resolution.height as f64);
+    let wb = WindowBuilder::new()
+        .with_inner_size(LogicalSize::from_physical(physical_size, hidpi))
+        .with_visible(false)
+        .with_title("Display Sim Render");
+    let windowed_ctx = ContextBuilder::new()
+        .with_gl(GlRequest::Latest)
+        .with_gl_profile(GlProfile::Core)
+        .with_depth_buffer(24)
+        .build_windowed(wb, &event_loop)
+        .map_err(|e| format!("{}", e))?;
+    let windowed_ctx = unsafe { windowed_ctx.make_current().map_err(|e| format!("Context Error: {:?}", e))? };
+    let gl = Rc::new(GlowSafeAdapter::new(glow::Context::from_loader_function(|ptr| {
+        windowed_ctx.context().get_proc_address(ptr) as *const _
+    })));
+
+    if let Some(grid) = grid {
+        return render_grid(&gl, &options, &preset_file, &grid);
+    }
+
+    for input in inputs.iter() {
+        let output = if single_output {
+            options.output.clone()
+        } else {
+            options.output.join(input.file_stem().unwrap_or_default()).with_extension("png")
+        };
+        println!("Rendering {} into {}", input.display(), output.display());
+        let pixels = render_image(&gl, input, options.resolution, &preset_file, &[])?;
+        image::save_buffer(&output, &pixels, options.resolution.width, options.resolution.height, image::ColorType::Rgba8)
+            .map_err(|e| format!("Could not write {}: {}", output.display(), e))?;
+    }
+    Ok(())
+}
+
+// A declarative sweep: every tile is the same image rendered with one value of the row parameter and one of the column parameter.
+struct SweepGrid {
+    rows: Vec<BracketStep>,
+    columns: Vec<BracketStep>,
+}
+
+impl SweepGrid {
+    fn new(rows: Option<ScreenshotBracketOptions>, columns: Option<ScreenshotBracketOptions>) -> Option<SweepGrid> {
+        if rows.is_none() && columns.is_none() {
+            return None;
+        }
+        let steps = |parameter: Option<ScreenshotBracketOptions>| parameter.map(build_bracket_sweep).unwrap_or_default();
+        Some(SweepGrid {
+            rows: steps(rows),
+            columns: steps(columns),
+        })
+    }
+
+    fn tiles(&self) -> Vec<Vec<BracketStep>> {
+        let rows: Vec<Option<BracketStep>> = if self.rows.is_empty() {
+            vec![None]
+        } else {
+            self.rows.iter().copied().map(Some).collect()
+        };
+        let columns: Vec<Option<BracketStep>> = if self.columns.is_empty() {
+            vec![None]
+        } else {
+            self.columns.iter().copied().map(Some).collect()
+        };
+        rows.iter()
+            .flat_map(|row| columns.iter().map(move |column| row.iter().chain(column.iter()).copied().collect()))
+            .collect()
+    }
+
+    fn size(&self) -> Size2D<u32> {
+        Size2D {
+            width: self.columns.len().max(1) as u32,
+            height: self.rows.len().max(1) as u32,
+        }
+    }
+}
+
+fn render_grid(gl: &Rc<GlowSafeAdapter<glow::Context>>, options: &RenderOptions, preset_file: &PresetFile, grid: &SweepGrid) -> AppResult<()> {
+    let tile = options.resolution;
+    let cells = grid.size();
+    let (width, height) = ((tile.width * cells.width) as usize, (tile.height * cells.height) as usize);
+    let mut composite = vec![0; width * height * 4];
+    for (index, steps) in grid.tiles().iter().enumerate() {
+        let (cell_x, cell_y) = (index % cells.width as usize, index / cells.width as usize);
+        let tags: Vec<String> = steps.iter().map(BracketStep::tag).collect();
+        println!("Rendering tile {}x{}: {}", cell_x, cell_y, tags.join(" "));
+        let pixels = render_image(gl, &options.input, tile, preset_file, steps)?;
+        let row_size = tile.width as usize * 4;
+        for (y, row) in pixels.chunks(row_size).enumerate() {
+            let start = ((cell_y * tile.height as usize + y) * width + cell_x * tile.width as usize) * 4;
+            composite[start..start + row_size].copy_from_slice(row);
+        }
+    }
+    println!("Writing {}", options.output.display());
+    image::save_buffer(&options.output, &composite, width as u32, height as u32, image::ColorType::Rgba8)
+        .map_err(|e| format!("Could not write {}: {}", options.output.display(), e))?;
+    Ok(())
+}
+
+fn collect_inputs(input: &Path) -> AppResult<Vec<PathBuf>> {
+    if !input.is_dir() {
+        return Ok(vec![input.to_path_buf()]);
+    }
+    let entries = std::fs::read_dir(input).map_err(|e| format!("Could not read {}: {}", input.display(), e))?;
+    let mut inputs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| is_image_path(path))
+        .collect();
+    inputs.sort();
+    Ok(inputs)
+}
+
+pub(crate) fn is_image_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| IMAGE_EXTENSIONS.contains(&extension.to_lowercase().as_ref()))
+        .unwrap_or(false)
+}
+
+fn render_image(
+    gl: &Rc<GlowSafeAdapter<glow::Context>>,
+    path: &Path,
+    resolution: Size2D<u32>,
+    preset_file: &PresetFile,
+    steps: &[BracketStep],
+) -> AppResult<Vec<u8>> {
+    let img = image::open(path).map_err(|e| format!("Could not load {}: {}", path.display(), e))?.to_rgba8();
+    let img_size = img.dimensions();
+    let res_input = VideoInputResources {
+        steps: vec![AnimationStep { delay: 16 }],
+        max_texture_size: i32::MAX,
+        image_size: Size2D {
+            width: img_size.0,
+            height: img_size.1,
+        },
+        background_size: Size2D {
+            width: img_size.0,
+            height: img_size.1,
+        },
+        viewport_size: resolution,
+        current_frame: 0,
+        preset: preset_file.preset,
+        last_frame_change: 0.0,
+        external_clock: false,
+        pending_frame_advances: 0,
+        needs_buffer_data_load: true,
+        drawing_activation: true,
+        subtitles: vec![],
+    };
+    let mut res = Resources::default();
+    res.initialize(res_input, 0.0);
+    preset_file.apply(&mut res)?;
+    for step in steps {
+        step.apply(&mut res.controllers);
+    }
+    let mut materials = Materials::new(
+        gl.clone(),
+        VideoInputMaterials {
+            buffers: vec![img.into_vec().into_boxed_slice()],
+        },
+    )?;
+    let mut input = Input::new(0.0);
+    let ctx = ConcreteSimulationContext::new(FakeEventDispatcher {}, NativeRnd {});
+    for frame in 1..=RENDER_WARMUP_FRAMES {
+        SimulationCoreTicker::new(&ctx, &mut res, &mut input).tick(frame as f64 * RENDER_FRAME_MILLIS)?;
+        SimulationDrawer::new(&ctx, &mut materials, &res).draw()?;
+    }
+
+    let (width, height) = (resolution.width as usize, resolution.height as usize);
+    let mut pixels = vec![0; width * height * 4];
+    gl.read_pixels(0, 0, width as i32, height as i32, glow::RGBA, glow::UNSIGNED_BYTE, &mut pixels);
+    // OpenGL reads rows from the bottom, PNG writes them from the top.
+    let row_size = width * 4;
+    let flipped = pixels.chunks(row_size).rev().flatten().copied().collect();
+    Ok(flipped)
+}
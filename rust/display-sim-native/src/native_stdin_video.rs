@@ -0,0 +1,125 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::native_render_cli::parse_resolution;
+use crate::native_video_feed::{show_frame, yuv_to_rgba, ChromaSubsampling, VideoFeed};
+use core::general_types::Size2D;
+use core::simulation_core_state::Resources;
+use render::error::AppResult;
+use render::simulation_render_state::Materials;
+
+use std::io::Read;
+use std::sync::mpsc::{sync_channel, Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+// Frames read ahead of the simulation. When it's full, the reader blocks and so does the producer.
+const STDIN_VIDEO_QUEUE_SIZE: usize = 2;
+
+pub const STDIN_VIDEO_USAGE: &str = "Usage: display-sim-native --stdin <width>x<height>@<fps>[:rgba|:yuv420p]";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum StdinVideoFormat {
+    Rgba,
+    Yuv420p,
+}
+
+impl StdinVideoFormat {
+    fn frame_size(self, size: Size2D<u32>) -> usize {
+        match self {
+            StdinVideoFormat::Rgba => size.width as usize * size.height as usize * 4,
+            StdinVideoFormat::Yuv420p => ChromaSubsampling::Yuv420.frame_size(size),
+        }
+    }
+}
+
+// Raw frames piped into stdin, e.g. `ffmpeg -i video.mp4 -f rawvideo -pix_fmt rgba - | display-sim-native --stdin 320x240@60`.
+pub struct StdinVideo {
+    size: Size2D<u32>,
+    format: StdinVideoFormat,
+    frame_duration: Duration,
+    last_frame: Option<Instant>,
+    frame_index: u32,
+    frames: Receiver<Vec<u8>>,
+}
+
+impl StdinVideo {
+    pub fn new(spec: &str) -> AppResult<StdinVideo> {
+        let (spec, format) = match spec.find(':') {
+            Some(index) => (&spec[..index], &spec[index + 1..]),
+            None => (spec, "rgba"),
+        };
+        let format = match format {
+            "rgba" => StdinVideoFormat::Rgba,
+            "yuv420p" => StdinVideoFormat::Yuv420p,
+            _ => return Err(format!("Unknown pixel format '{}'\n{}", format, STDIN_VIDEO_USAGE).into()),
+        };
+        let (resolution, fps) = match spec.find('@') {
+            Some(index) => (&spec[..index], &spec[index + 1..]),
+            None => return Err(STDIN_VIDEO_USAGE.into()),
+        };
+        let size = parse_resolution(resolution)?;
+        let fps = fps.parse::<f64>().ok().filter(|fps| *fps > 0.0).ok_or(STDIN_VIDEO_USAGE)?;
+
+        let frame_size = format.frame_size(size);
+        let (sender, frames) = sync_channel(STDIN_VIDEO_QUEUE_SIZE);
+        std::thread::spawn(move || {
+            let stdin = std::io::stdin();
+            let mut stdin = stdin.lock();
+            loop {
+                let mut frame = vec![0; frame_size];
+                if stdin.read_exact(&mut frame).is_err() || sender.send(frame).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(StdinVideo {
+            size,
+            format,
+            frame_duration: Duration::from_secs_f64(1.0 / fps),
+            last_frame: None,
+            frame_index: 0,
+            frames,
+        })
+    }
+}
+
+impl VideoFeed for StdinVideo {
+    fn sync(&mut self, res: &mut Resources, materials: &mut Materials) -> AppResult<()> {
+        if let Some(last_frame) = self.last_frame {
+            if last_frame.elapsed() < self.frame_duration {
+                return Ok(());
+            }
+        }
+        let frame = match self.frames.try_recv() {
+            Ok(frame) => frame,
+            Err(TryRecvError::Empty) => return Ok(()),
+            Err(TryRecvError::Disconnected) => {
+                if self.last_frame.take().is_some() {
+                    println!("Stdin video ended.");
+                }
+                return Ok(());
+            }
+        };
+        self.last_frame = Some(Instant::now());
+        res.subtitles.media_time = Some(self.frame_index as f64 * self.frame_duration.as_secs_f64() * 1000.0);
+        self.frame_index += 1;
+        let pixels = match self.format {
+            StdinVideoFormat::Rgba => frame,
+            StdinVideoFormat::Yuv420p => yuv_to_rgba(&frame, self.size, ChromaSubsampling::Yuv420),
+        };
+        show_frame(res, materials, self.size, pixels.into_boxed_slice());
+        Ok(())
+    }
+}
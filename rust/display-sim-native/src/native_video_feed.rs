@@ -0,0 +1,85 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use core::general_types::Size2D;
+use core::simulation_core_state::{AnimationStep, Resources};
+use render::error::AppResult;
+use render::simulation_render_state::Materials;
+
+// External sources of frames that replace the loaded image while the simulation runs.
+pub trait VideoFeed {
+    fn sync(&mut self, res: &mut Resources, materials: &mut Materials) -> AppResult<()>;
+}
+
+// Replaces the video with a single frame, rescaling the screen when the size changes.
+pub(crate) fn show_frame(res: &mut Resources, materials: &mut Materials, size: Size2D<u32>, pixels: Box<[u8]>) {
+    let video = &mut res.video;
+    if video.image_size.width != size.width || video.image_size.height != size.height {
+        video.image_size = size;
+        video.background_size = size;
+        res.scaling.scaling_initialized = false;
+    }
+    materials.pixels_render.replace_video(vec![pixels]);
+    video.steps = vec![AnimationStep { delay: 16 }];
+    video.current_frame = 0;
+    video.needs_buffer_data_load = true;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ChromaSubsampling {
+    Yuv420,
+    Yuv422,
+    Yuv444,
+    Mono,
+}
+
+impl ChromaSubsampling {
+    fn chroma_size(self, size: Size2D<u32>) -> (usize, usize) {
+        let (width, height) = (size.width as usize, size.height as usize);
+        match self {
+            ChromaSubsampling::Yuv420 => (width.div_ceil(2), height.div_ceil(2)),
+            ChromaSubsampling::Yuv422 => (width.div_ceil(2), height),
+            ChromaSubsampling::Yuv444 => (width, height),
+            ChromaSubsampling::Mono => (0, 0),
+        }
+    }
+
+    pub(crate) fn frame_size(self, size: Size2D<u32>) -> usize {
+        let (chroma_width, chroma_height) = self.chroma_size(size);
+        size.width as usize * size.height as usize + 2 * chroma_width * chroma_height
+    }
+}
+
+// Planar YUV with BT.601 limited range, which is what ffmpeg produces by default.
+pub(crate) fn yuv_to_rgba(frame: &[u8], size: Size2D<u32>, chroma: ChromaSubsampling) -> Vec<u8> {
+    let (width, height) = (size.width as usize, size.height as usize);
+    let (chroma_width, chroma_height) = chroma.chroma_size(size);
+    let (luma, chroma_planes) = frame.split_at(width * height);
+    let (u_plane, v_plane) = chroma_planes.split_at(chroma_width * chroma_height);
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let c = 1.164 * (luma[y * width + x] as f32 - 16.0);
+            let (d, e) = if chroma_width == 0 {
+                (0.0, 0.0)
+            } else {
+                let chroma_index = (y * chroma_height / height) * chroma_width + x * chroma_width / width;
+                (u_plane[chroma_index] as f32 - 128.0, v_plane[chroma_index] as f32 - 128.0)
+            };
+            let to_u8 = |value: f32| value.clamp(0.0, 255.0) as u8;
+            rgba.extend_from_slice(&[to_u8(c + 1.596 * e), to_u8(c - 0.391 * d - 0.813 * e), to_u8(c + 2.018 * d), 255]);
+        }
+    }
+    rgba
+}
@@ -0,0 +1,122 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::native_render_cli::is_image_path;
+use crate::native_video_feed::{show_frame, VideoFeed};
+use core::general_types::Size2D;
+use core::simulation_core_state::Resources;
+use render::error::AppResult;
+use render::simulation_render_state::Materials;
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+const WATCH_FOLDER_SCAN_INTERVAL: Duration = Duration::from_millis(500);
+const WATCH_FOLDER_CROSSFADE: Duration = Duration::from_millis(500);
+
+struct Crossfade {
+    from: Box<[u8]>,
+    to: Box<[u8]>,
+    started: Instant,
+}
+
+// Shows the newest image of a directory, so other programs can feed frames just by writing files there.
+// Images with the same size as the current one are crossfaded, others replace it right away.
+pub struct WatchFolder {
+    directory: PathBuf,
+    newest: Option<(PathBuf, SystemTime)>,
+    failed: Option<(PathBuf, SystemTime)>,
+    last_scan: Option<Instant>,
+    crossfade: Option<Crossfade>,
+}
+
+impl WatchFolder {
+    pub fn new(directory: &str) -> AppResult<WatchFolder> {
+        let directory = PathBuf::from(directory);
+        if !directory.is_dir() {
+            return Err(format!("{} is not a directory", directory.display()).into());
+        }
+        Ok(WatchFolder {
+            directory,
+            newest: None,
+            failed: None,
+            last_scan: None,
+            crossfade: None,
+        })
+    }
+
+    fn show(&mut self, path: &Path, res: &mut Resources, materials: &mut Materials) -> AppResult<()> {
+        let img = image::open(path).map_err(|e| format!("{}", e))?.to_rgba8();
+        let (width, height) = img.dimensions();
+        let pixels = img.into_vec().into_boxed_slice();
+        println!("Showing image: {}", path.display());
+
+        let video = &res.video;
+        let same_size = video.image_size.width == width && video.image_size.height == height && video.steps.len() == 1;
+        self.crossfade = match materials.pixels_render.video_frame_mut(video.current_frame) {
+            Some(from) if same_size => Some(Crossfade {
+                from: Box::from(&*from),
+                to: pixels.clone(),
+                started: Instant::now(),
+            }),
+            _ => None,
+        };
+        show_frame(res, materials, Size2D { width, height }, pixels);
+        Ok(())
+    }
+}
+
+impl VideoFeed for WatchFolder {
+    fn sync(&mut self, res: &mut Resources, materials: &mut Materials) -> AppResult<()> {
+        let now = Instant::now();
+        if self.last_scan.map(|last_scan| now - last_scan >= WATCH_FOLDER_SCAN_INTERVAL).unwrap_or(true) {
+            self.last_scan = Some(now);
+            if let Some(newest) = find_newest_image(&self.directory) {
+                if self.newest.as_ref() != Some(&newest) && self.failed.as_ref() != Some(&newest) {
+                    match self.show(&newest.0, res, materials) {
+                        // The file might still be in the middle of being written, so it's retried once it's modified again.
+                        Err(e) => {
+                            println!("Could not show {}: {:?}", newest.0.display(), e);
+                            self.failed = Some(newest);
+                        }
+                        Ok(()) => self.newest = Some(newest),
+                    }
+                }
+            }
+        }
+
+        if let Some(ref crossfade) = self.crossfade {
+            let progress = (crossfade.started.elapsed().as_secs_f32() / WATCH_FOLDER_CROSSFADE.as_secs_f32()).min(1.0);
+            if let Some(frame) = materials.pixels_render.video_frame_mut(0) {
+                for ((pixel, from), to) in frame.iter_mut().zip(crossfade.from.iter()).zip(crossfade.to.iter()) {
+                    *pixel = (*from as f32 + (*to as f32 - *from as f32) * progress).round() as u8;
+                }
+            }
+            res.video.needs_buffer_data_load = true;
+            if progress >= 1.0 {
+                self.crossfade = None;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn find_newest_image(directory: &Path) -> Option<(PathBuf, SystemTime)> {
+    std::fs::read_dir(directory)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| is_image_path(&entry.path()))
+        .filter_map(|entry| Some((entry.path(), entry.metadata().ok()?.modified().ok()?)))
+        .max_by_key(|(_, modified)| *modified)
+}
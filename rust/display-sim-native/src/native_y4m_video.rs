@@ -0,0 +1,155 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::native_video_feed::{show_frame, yuv_to_rgba, ChromaSubsampling, VideoFeed};
+use core::general_types::Size2D;
+use core::simulation_core_state::Resources;
+use render::error::AppResult;
+use render::simulation_render_state::Materials;
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::time::{Duration, Instant};
+
+pub const Y4M_VIDEO_USAGE: &str =
+    "Usage: display-sim-native play <video.y4m>\nOther formats can be converted with: ffmpeg -i video.mp4 -pix_fmt yuv420p video.y4m";
+
+struct Y4mHeader {
+    size: Size2D<u32>,
+    chroma: ChromaSubsampling,
+    frame_duration: Duration,
+}
+
+// Plays a YUV4MPEG2 file in a loop, presenting each frame at the rate declared in its header.
+pub struct Y4mVideo {
+    reader: BufReader<File>,
+    header: Y4mHeader,
+    first_frame_offset: u64,
+    frame_index: u32,
+    next_frame: Option<Instant>,
+}
+
+impl Y4mVideo {
+    pub fn new(path: &str) -> AppResult<Y4mVideo> {
+        let file = File::open(path).map_err(|e| format!("Could not open '{}': {}", path, e))?;
+        let mut reader = BufReader::new(file);
+        let mut line = String::new();
+        reader.read_line(&mut line).map_err(|e| format!("Could not read '{}': {}", path, e))?;
+        let header = parse_header(&line).map_err(|e| format!("'{}' is not a valid Y4M file: {}\n{}", path, e, Y4M_VIDEO_USAGE))?;
+        println!(
+            "Playing '{}': {}x{} at {:.3} fps.",
+            path,
+            header.size.width,
+            header.size.height,
+            1.0 / header.frame_duration.as_secs_f64()
+        );
+        Ok(Y4mVideo {
+            reader,
+            header,
+            first_frame_offset: line.len() as u64,
+            frame_index: 0,
+            next_frame: None,
+        })
+    }
+
+    fn read_frame(&mut self) -> AppResult<Option<Vec<u8>>> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line).map_err(|e| format!("Could not read video: {}", e))? == 0 {
+            return Ok(None);
+        }
+        if !line.starts_with("FRAME") {
+            return Err(format!("Expected a FRAME marker, found '{}'", line.trim_end()).into());
+        }
+        let mut frame = vec![0; self.header.chroma.frame_size(self.header.size)];
+        match self.reader.read_exact(&mut frame) {
+            Ok(()) => Ok(Some(frame)),
+            // A truncated last frame is treated as the end of the clip.
+            Err(ref e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(None),
+            Err(e) => Err(format!("Could not read video: {}", e).into()),
+        }
+    }
+}
+
+impl VideoFeed for Y4mVideo {
+    fn sync(&mut self, res: &mut Resources, materials: &mut Materials) -> AppResult<()> {
+        let now = Instant::now();
+        if let Some(next_frame) = self.next_frame {
+            if now < next_frame {
+                return Ok(());
+            }
+        }
+        let frame = match self.read_frame()? {
+            Some(frame) => frame,
+            None => {
+                self.frame_index = 0;
+                self.reader
+                    .seek(SeekFrom::Start(self.first_frame_offset))
+                    .map_err(|e| format!("Could not rewind video: {}", e))?;
+                self.read_frame()?.ok_or("The video doesn't contain any frame.")?
+            }
+        };
+        // Keeps the clip on the container clock, unless rendering fell behind by more than a frame.
+        self.next_frame = match self.next_frame {
+            Some(next_frame) if now - next_frame < self.header.frame_duration => Some(next_frame + self.header.frame_duration),
+            _ => Some(now + self.header.frame_duration),
+        };
+        res.subtitles.media_time = Some(self.frame_index as f64 * self.header.frame_duration.as_secs_f64() * 1000.0);
+        self.frame_index += 1;
+        let pixels = yuv_to_rgba(&frame, self.header.size, self.header.chroma);
+        show_frame(res, materials, self.header.size, pixels.into_boxed_slice());
+        Ok(())
+    }
+}
+
+fn parse_header(line: &str) -> Result<Y4mHeader, String> {
+    let mut tokens = line.trim_end().split(' ');
+    if tokens.next() != Some("YUV4MPEG2") {
+        return Err("missing YUV4MPEG2 signature".into());
+    }
+    let (mut width, mut height) = (None, None);
+    let mut frame_rate = (25, 1);
+    let mut chroma = ChromaSubsampling::Yuv420;
+    for token in tokens.filter(|token| !token.is_empty()) {
+        let (tag, value) = token.split_at(1);
+        match tag {
+            "W" => width = value.parse::<u32>().ok(),
+            "H" => height = value.parse::<u32>().ok(),
+            "F" => {
+                let mut parts = value.split(':').map(|part| part.parse::<u32>().ok());
+                frame_rate = match (parts.next().flatten(), parts.next().flatten()) {
+                    (Some(numerator), Some(denominator)) if numerator > 0 && denominator > 0 => (numerator, denominator),
+                    _ => return Err(format!("invalid frame rate '{}'", value)),
+                };
+            }
+            "C" => {
+                chroma = match value {
+                    "420" | "420jpeg" | "420paldv" | "420mpeg2" => ChromaSubsampling::Yuv420,
+                    "422" => ChromaSubsampling::Yuv422,
+                    "444" => ChromaSubsampling::Yuv444,
+                    "mono" => ChromaSubsampling::Mono,
+                    _ => return Err(format!("unsupported colorspace '{}', only 8 bit 420, 422, 444 and mono are supported", value)),
+                }
+            }
+            _ => {}
+        }
+    }
+    match (width, height) {
+        (Some(width), Some(height)) if width > 0 && height > 0 => Ok(Y4mHeader {
+            size: Size2D { width, height },
+            chroma,
+            frame_duration: Duration::from_secs_f64(frame_rate.1 as f64 / frame_rate.0 as f64),
+        }),
+        _ => Err("missing frame size".into()),
+    }
+}
@@ -0,0 +1,82 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::error::AppResult;
+use crate::shaders::{make_quad_vao, make_shader, TEXTURE_VERTEX_SHADER};
+
+use glow::GlowSafeAdapter;
+use glow::HasContext;
+use std::rc::Rc;
+
+pub struct AnalogStaticRender<GL: HasContext> {
+    vao: Option<GL::VertexArray>,
+    shader: GL::Program,
+    gl: Rc<GlowSafeAdapter<GL>>,
+}
+
+impl<GL: HasContext> AnalogStaticRender<GL> {
+    pub fn new(gl: Rc<GlowSafeAdapter<GL>>) -> AppResult<AnalogStaticRender<GL>> {
+        let shader = make_shader(&*gl, TEXTURE_VERTEX_SHADER, ANALOG_STATIC_FRAGMENT_SHADER)?;
+        let vao = make_quad_vao(&*gl, &shader)?;
+        Ok(AnalogStaticRender { vao, shader, gl })
+    }
+
+    // Mixes snow over the bound framebuffer. A new seed gives a new pattern, and the bars are the vertical
+    // position of the noisier horizontal bands, from 0 to 1.
+    pub fn render(&self, density: f32, seed: f32, bars: f32) {
+        self.gl.use_program(Some(self.shader));
+        self.gl.uniform_1_f32(self.gl.get_uniform_location(self.shader, "density"), density);
+        self.gl.uniform_1_f32(self.gl.get_uniform_location(self.shader, "seed"), seed);
+        self.gl.uniform_1_f32(self.gl.get_uniform_location(self.shader, "bars"), bars);
+        self.gl.bind_vertex_array(self.vao);
+        self.gl.enable(glow::BLEND);
+        self.gl.blend_func(glow::SRC_ALPHA, glow::ONE_MINUS_SRC_ALPHA);
+        self.gl.draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0);
+        self.gl.disable(glow::BLEND);
+    }
+}
+
+pub const ANALOG_STATIC_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+out vec4 FragColor;
+in vec2 TexCoord;
+
+uniform float density;
+uniform float seed;
+uniform float bars;
+
+float hash(vec2 p) {
+    vec3 p3 = fract(vec3(p.xyx) * 0.1031);
+    p3 += dot(p3, p3.yzx + 33.33);
+    return fract((p3.x + p3.y) * p3.z);
+}
+
+// Closeness to a band centered at `center`, wrapping around the screen.
+float band(float center, float width) {
+    float distance = abs(fract(TexCoord.y - center + 0.5) - 0.5);
+    return 1.0 - smoothstep(width * 0.5, width, distance);
+}
+
+void main()
+{
+    vec2 offset = vec2(seed * 1731.0, seed * 917.0);
+    float snow = hash(floor(gl_FragCoord.xy) + offset);
+    float bands = max(band(bars, 0.08), 0.6 * band(bars + 0.37, 0.04));
+    // Inside the bands, some lines are streaked across, like a signal losing its horizontal sync for a moment.
+    float streak = step(0.75, hash(vec2(floor(gl_FragCoord.y * 0.5), seed * 613.0)));
+    float value = mix(snow, max(snow, streak), bands);
+    FragColor = vec4(vec3(value), clamp(density * (0.6 + 0.4 * bands), 0.0, 1.0));
+} 
+"#;
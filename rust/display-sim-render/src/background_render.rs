@@ -15,6 +15,8 @@
 
 use crate::error::AppResult;
 use crate::shaders::{make_quad_vao, make_shader, TEXTURE_VERTEX_SHADER};
+use core::general_types::Size2D;
+use core::ui_controller::layering_blend_mode::LayeringBlendModeOptions;
 
 use glow::GlowSafeAdapter;
 use glow::HasContext;
@@ -23,6 +25,7 @@ use std::rc::Rc;
 pub struct BackgroundRender<GL: HasContext> {
     vao: Option<GL::VertexArray>,
     shader: GL::Program,
+    mask: Option<GL::Texture>,
     gl: Rc<GlowSafeAdapter<GL>>,
 }
 
@@ -30,14 +33,93 @@ impl<GL: HasContext> BackgroundRender<GL> {
     pub fn new(gl: Rc<GlowSafeAdapter<GL>>) -> AppResult<BackgroundRender<GL>> {
         let shader = make_shader(&*gl, TEXTURE_VERTEX_SHADER, BACKGROUND_FRAGMENT_SHADER)?;
         let vao = make_quad_vao(&*gl, &shader)?;
-        Ok(BackgroundRender { vao, shader, gl })
+        Ok(BackgroundRender { vao, shader, mask: None, gl })
     }
 
-    pub fn render(&self) {
+    // The mask is stretched over the whole screen, and each RGBA channel modulates a filter in that region:
+    // red the foreground opacity, green the brightness, blue the background and alpha the blur level.
+    // A white opaque mask leaves everything as without mask. Rows go from the top, like in the video frames.
+    pub fn set_mask(&mut self, size: Size2D<u32>, pixels: &[u8]) -> AppResult<()> {
+        if pixels.len() != (size.width * size.height * 4) as usize {
+            return Err(format!(
+                "Filter mask of {}x{} needs {} bytes, got {}.",
+                size.width,
+                size.height,
+                size.width * size.height * 4,
+                pixels.len()
+            )
+            .into());
+        }
+        self.clear_mask();
+        let row_size = (size.width * 4) as usize;
+        let flipped: Vec<u8> = pixels.chunks(row_size).rev().flatten().copied().collect();
+        let texture = Some(self.gl.create_texture()?);
+        self.gl.bind_texture(glow::TEXTURE_2D, texture);
+        self.gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA as i32,
+            size.width as i32,
+            size.height as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            Some(&flipped),
+        );
+        self.gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);
+        self.gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+        self.gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
+        self.gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
+        self.gl.bind_texture(glow::TEXTURE_2D, None);
+        self.mask = texture;
+        Ok(())
+    }
+
+    pub fn clear_mask(&mut self) {
+        if let Some(texture) = self.mask.take() {
+            self.gl.delete_texture(texture);
+        }
+    }
+
+    pub fn has_mask(&self) -> bool {
+        self.mask.is_some()
+    }
+
+    // When a blurred version of the composition is given, the mask alpha decides how much of it is shown.
+    pub fn render(&self, blend_mode: LayeringBlendModeOptions, foreground_opacity: f32, blurred: Option<GL::Texture>) {
         self.gl.bind_vertex_array(self.vao);
         self.gl.use_program(Some(self.shader));
         self.gl.uniform_1_i32(self.gl.get_uniform_location(self.shader, "foregroundImage"), 0);
         self.gl.uniform_1_i32(self.gl.get_uniform_location(self.shader, "backgroundImage"), 1);
+        self.gl.uniform_1_i32(self.gl.get_uniform_location(self.shader, "maskImage"), 2);
+        self.gl.uniform_1_i32(self.gl.get_uniform_location(self.shader, "blurredImage"), 3);
+        self.gl.uniform_1_i32(
+            self.gl.get_uniform_location(self.shader, "blendMode"),
+            match blend_mode {
+                LayeringBlendModeOptions::Weighted => 0,
+                LayeringBlendModeOptions::Normal => 1,
+                LayeringBlendModeOptions::Additive => 2,
+                LayeringBlendModeOptions::Screen => 3,
+                LayeringBlendModeOptions::Premultiplied => 4,
+            },
+        );
+        self.gl.uniform_1_i32(
+            self.gl.get_uniform_location(self.shader, "maskMode"),
+            match (self.mask, blurred) {
+                (None, _) => 0,
+                (Some(_), None) => 1,
+                (Some(_), Some(_)) => 2,
+            },
+        );
+        self.gl
+            .uniform_1_f32(self.gl.get_uniform_location(self.shader, "foregroundOpacity"), foreground_opacity);
+        if self.mask.is_some() {
+            self.gl.active_texture(glow::TEXTURE0 + 2);
+            self.gl.bind_texture(glow::TEXTURE_2D, self.mask);
+            self.gl.active_texture(glow::TEXTURE0 + 3);
+            self.gl.bind_texture(glow::TEXTURE_2D, blurred);
+            self.gl.active_texture(glow::TEXTURE0 + 0);
+        }
         self.gl.draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0);
     }
 }
@@ -50,12 +132,24 @@ in vec2 TexCoord;
 
 uniform sampler2D foregroundImage;
 uniform sampler2D backgroundImage;
+uniform sampler2D maskImage;
+uniform sampler2D blurredImage;
+uniform int blendMode;
+uniform int maskMode;
+uniform float foregroundOpacity;
 
-void main()
+vec4 blend(vec4 foregroundColor, vec4 backgroundColor)
 {
-    vec4 foregroundColor = texture(foregroundImage, TexCoord);
+    if (blendMode == 1) {
+        return foregroundColor.a * foregroundColor + (1.0 - foregroundColor.a) * backgroundColor;
+    } else if (blendMode == 2) {
+        return min(foregroundColor + backgroundColor, 1.0);
+    } else if (blendMode == 3) {
+        return 1.0 - (1.0 - foregroundColor) * (1.0 - backgroundColor);
+    } else if (blendMode == 4) {
+        return foregroundColor + (1.0 - foregroundColor.a) * backgroundColor;
+    }
     float foregroundWeight = (foregroundColor.r + foregroundColor.g + foregroundColor.b + foregroundColor.a) / 4.0;
-    vec4 backgroundColor = texture(backgroundImage, TexCoord);
     float backgroundWeight = (backgroundColor.r + backgroundColor.g + backgroundColor.b + backgroundColor.a) / 4.0;
     vec4 result1 = foregroundColor.a * foregroundColor + (1.0 - foregroundColor.a) * backgroundColor;
     float weight1 = (result1.r + result1.g + result1.b + result1.a) / 4.0;
@@ -63,6 +157,19 @@ void main()
         weight1 = 0.0;
     }
     float factor = weight1 / (weight1 + backgroundWeight * 0.1);
-    FragColor = result1 * factor + (1.0 - factor) * backgroundColor;
+    return result1 * factor + (1.0 - factor) * backgroundColor;
+}
+
+void main()
+{
+    vec4 mask = maskMode == 0 ? vec4(1.0) : texture(maskImage, TexCoord);
+    vec4 foregroundColor = texture(foregroundImage, TexCoord) * foregroundOpacity * mask.r;
+    vec4 backgroundColor = texture(backgroundImage, TexCoord) * mask.b;
+    vec4 color = blend(foregroundColor, backgroundColor);
+    color.rgb *= mask.g;
+    if (maskMode == 2) {
+        color = mix(color, texture(blurredImage, TexCoord), mask.a);
+    }
+    FragColor = color;
 } 
 "#;
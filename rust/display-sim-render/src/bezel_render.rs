@@ -0,0 +1,156 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::error::AppResult;
+use crate::shaders::{make_quad_vao, make_shader};
+use core::general_types::Size2D;
+
+use glow::GlowSafeAdapter;
+use glow::HasContext;
+use std::rc::Rc;
+
+pub struct BezelUniform<'a> {
+    pub view: &'a [f32; 16],
+    pub projection: &'a [f32; 16],
+    pub position: &'a [f32; 3],
+    pub height: f32,
+}
+
+pub struct BezelRender<GL: HasContext> {
+    vao: Option<GL::VertexArray>,
+    shader: GL::Program,
+    artwork: Option<GL::Texture>,
+    aspect_ratio: f32,
+    gl: Rc<GlowSafeAdapter<GL>>,
+}
+
+impl<GL: HasContext> BezelRender<GL> {
+    pub fn new(gl: Rc<GlowSafeAdapter<GL>>) -> AppResult<BezelRender<GL>> {
+        let shader = make_shader(&*gl, BEZEL_VERTEX_SHADER, BEZEL_FRAGMENT_SHADER)?;
+        let vao = make_quad_vao(&*gl, &shader)?;
+        Ok(BezelRender {
+            vao,
+            shader,
+            artwork: None,
+            aspect_ratio: 1.0,
+            gl,
+        })
+    }
+
+    // The artwork is a RGBA picture with a transparent hole where the screen goes, like the overlays of the
+    // arcade frontends. Rows go from the top, like in the video frames.
+    pub fn set_artwork(&mut self, size: Size2D<u32>, pixels: &[u8]) -> AppResult<()> {
+        if pixels.len() != (size.width * size.height * 4) as usize || size.height == 0 {
+            return Err(format!(
+                "Bezel artwork of {}x{} needs {} bytes, got {}.",
+                size.width,
+                size.height,
+                size.width * size.height * 4,
+                pixels.len()
+            )
+            .into());
+        }
+        self.clear_artwork();
+        let row_size = (size.width * 4) as usize;
+        let flipped: Vec<u8> = pixels.chunks(row_size).rev().flatten().copied().collect();
+        let texture = Some(self.gl.create_texture()?);
+        self.gl.bind_texture(glow::TEXTURE_2D, texture);
+        self.gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA as i32,
+            size.width as i32,
+            size.height as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            Some(&flipped),
+        );
+        self.gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);
+        self.gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+        self.gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
+        self.gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
+        self.gl.bind_texture(glow::TEXTURE_2D, None);
+        self.artwork = texture;
+        self.aspect_ratio = size.width as f32 / size.height as f32;
+        Ok(())
+    }
+
+    pub fn clear_artwork(&mut self) {
+        if let Some(texture) = self.artwork.take() {
+            self.gl.delete_texture(texture);
+        }
+    }
+
+    // Drawn over everything but the glass, so the artwork covers the edges of the picture and the room behind.
+    pub fn render(&self, uniforms: BezelUniform) {
+        if self.artwork.is_none() {
+            return;
+        }
+        let gl = &self.gl;
+        let shader = self.shader;
+        gl.use_program(Some(shader));
+        gl.uniform_matrix_4_f32_slice(gl.get_uniform_location(shader, "view"), false, uniforms.view);
+        gl.uniform_matrix_4_f32_slice(gl.get_uniform_location(shader, "projection"), false, uniforms.projection);
+        gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "position"), uniforms.position);
+        gl.uniform_2_f32_slice(
+            gl.get_uniform_location(shader, "half_size"),
+            &[0.5 * uniforms.height * self.aspect_ratio, 0.5 * uniforms.height],
+        );
+        gl.uniform_1_i32(gl.get_uniform_location(shader, "artwork"), 0);
+        gl.active_texture(glow::TEXTURE0);
+        gl.bind_texture(glow::TEXTURE_2D, self.artwork);
+        gl.bind_vertex_array(self.vao);
+        gl.disable(glow::DEPTH_TEST);
+        gl.enable(glow::BLEND);
+        gl.blend_func(glow::SRC_ALPHA, glow::ONE_MINUS_SRC_ALPHA);
+        gl.draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0);
+        gl.disable(glow::BLEND);
+        gl.enable(glow::DEPTH_TEST);
+    }
+}
+
+pub const BEZEL_VERTEX_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+layout (location = 0) in vec3 qPos;
+layout (location = 1) in vec2 qTexCoords;
+
+out vec2 TexCoord;
+
+uniform mat4 view;
+uniform mat4 projection;
+uniform vec3 position;
+uniform vec2 half_size;
+
+void main()
+{
+    TexCoord = qTexCoords;
+    gl_Position = projection * view * vec4(position + vec3(qPos.xy * half_size, 0.0), 1.0);
+}
+"#;
+
+pub const BEZEL_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+out vec4 FragColor;
+in vec2 TexCoord;
+
+uniform sampler2D artwork;
+
+void main()
+{
+    FragColor = texture(artwork, TexCoord);
+} 
+"#;
@@ -0,0 +1,98 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::error::AppResult;
+use crate::shaders::{make_quad_vao, make_shader, TEXTURE_VERTEX_SHADER};
+
+use glow::GlowSafeAdapter;
+use glow::HasContext;
+use std::rc::Rc;
+
+pub const BLOOM_BLUR_PASSES: usize = 6;
+
+pub struct BloomRender<GL: HasContext> {
+    vao: Option<GL::VertexArray>,
+    bright_pass_shader: GL::Program,
+    combine_shader: GL::Program,
+    gl: Rc<GlowSafeAdapter<GL>>,
+}
+
+impl<GL: HasContext> BloomRender<GL> {
+    pub fn new(gl: Rc<GlowSafeAdapter<GL>>) -> AppResult<BloomRender<GL>> {
+        let bright_pass_shader = make_shader(&*gl, TEXTURE_VERTEX_SHADER, BRIGHT_PASS_FRAGMENT_SHADER)?;
+        let combine_shader = make_shader(&*gl, TEXTURE_VERTEX_SHADER, BLOOM_COMBINE_FRAGMENT_SHADER)?;
+        let vao = make_quad_vao(&*gl, &bright_pass_shader)?;
+        Ok(BloomRender {
+            vao,
+            bright_pass_shader,
+            combine_shader,
+            gl,
+        })
+    }
+
+    // Writes to the bound framebuffer only the part of the texture that is brighter than the threshold.
+    pub fn extract(&self, texture: Option<GL::Texture>, threshold: f32) {
+        self.gl.use_program(Some(self.bright_pass_shader));
+        self.gl
+            .uniform_1_f32(self.gl.get_uniform_location(self.bright_pass_shader, "threshold"), threshold);
+        self.gl.bind_vertex_array(self.vao);
+        self.gl.bind_texture(glow::TEXTURE_2D, texture);
+        self.gl.draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0);
+    }
+
+    // Adds the blurred bright pass on top of the bound framebuffer, scaled by the intensity.
+    pub fn combine(&self, texture: Option<GL::Texture>, intensity: f32) {
+        self.gl.use_program(Some(self.combine_shader));
+        self.gl.uniform_1_f32(self.gl.get_uniform_location(self.combine_shader, "intensity"), intensity);
+        self.gl.bind_vertex_array(self.vao);
+        self.gl.bind_texture(glow::TEXTURE_2D, texture);
+        self.gl.enable(glow::BLEND);
+        self.gl.blend_func(glow::ONE, glow::ONE);
+        self.gl.draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0);
+        self.gl.disable(glow::BLEND);
+    }
+}
+
+pub const BRIGHT_PASS_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+out vec4 FragColor;
+in vec2 TexCoord;
+
+uniform sampler2D image;
+uniform float threshold;
+
+void main()
+{
+    vec4 color = texture(image, TexCoord);
+    float brightness = max(color.r, max(color.g, color.b));
+    float contribution = max(brightness - threshold, 0.0) / max(brightness, 0.0001);
+    FragColor = vec4(color.rgb * contribution, color.a);
+} 
+"#;
+
+pub const BLOOM_COMBINE_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+out vec4 FragColor;
+in vec2 TexCoord;
+
+uniform sampler2D image;
+uniform float intensity;
+
+void main()
+{
+    FragColor = vec4(texture(image, TexCoord).rgb * intensity, 0.0);
+} 
+"#;
@@ -39,8 +39,8 @@ impl<GL: HasContext> BlurRender<GL> {
             panic!("Should not be called when passes < 1!");
         }
 
-        stack.push()?;
-        stack.push()?;
+        stack.push("blur")?;
+        stack.push("blur")?;
 
         let texture_buffers = [stack.get_nth(0)?, stack.get_nth(-1)?];
 
@@ -68,8 +68,8 @@ impl<GL: HasContext> BlurRender<GL> {
         blur_iteration(texture_buffers[texture_index].texture(), target, buffer_index == 0);
         self.gl.bind_vertex_array(None);
         self.gl.bind_texture(glow::TEXTURE_2D, None);
-        stack.pop()?;
-        stack.pop()?;
+        stack.pop("blur")?;
+        stack.pop("blur")?;
         Ok(())
     }
 }
@@ -0,0 +1,131 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::error::AppResult;
+use crate::render_types::TextureBuffer;
+use crate::shaders::{make_quad_vao, make_shader, TEXTURE_VERTEX_SHADER};
+
+use glow::GlowSafeAdapter;
+use glow::HasContext;
+use std::rc::Rc;
+
+// The marks heal this many times slower than they are made.
+const BURN_IN_RECOVERY: f32 = 0.1;
+
+pub struct BurnInRender<GL: HasContext> {
+    vao: Option<GL::VertexArray>,
+    shader: GL::Program,
+    // Two buffers with one texel per source pixel, the current one is read while the other one is written.
+    buffers: Vec<TextureBuffer<GL>>,
+    current: usize,
+    gl: Rc<GlowSafeAdapter<GL>>,
+}
+
+impl<GL: HasContext> BurnInRender<GL> {
+    pub fn new(gl: Rc<GlowSafeAdapter<GL>>) -> AppResult<BurnInRender<GL>> {
+        let shader = make_shader(&*gl, TEXTURE_VERTEX_SHADER, BURN_IN_FRAGMENT_SHADER)?;
+        let vao = make_quad_vao(&*gl, &shader)?;
+        Ok(BurnInRender {
+            vao,
+            shader,
+            buffers: vec![],
+            current: 0,
+            gl,
+        })
+    }
+
+    // Moves the marks towards the bright parts of the source by `blend`. The buffers are made again, blank, when
+    // the size of the source changes. Leaves its own framebuffer bound, so the caller has to bind its target after.
+    pub fn update(&mut self, source: Option<GL::Texture>, width: i32, height: i32, blend: f32, seed: i32) -> AppResult<()> {
+        if width <= 0 || height <= 0 {
+            return Ok(());
+        }
+        let stale = match self.buffers.first() {
+            Some(buffer) => buffer.width != width || buffer.height != height,
+            None => true,
+        };
+        if stale {
+            self.reset(width, height)?;
+        }
+        if blend <= 0.0 {
+            return Ok(());
+        }
+        let previous = self.current;
+        self.current = 1 - self.current;
+        self.gl.bind_framebuffer(glow::FRAMEBUFFER, self.buffers[self.current].framebuffer());
+        self.gl.viewport(0, 0, width, height);
+        self.gl.use_program(Some(self.shader));
+        self.gl.uniform_1_i32(self.gl.get_uniform_location(self.shader, "marks"), 0);
+        self.gl.uniform_1_i32(self.gl.get_uniform_location(self.shader, "source_image"), 1);
+        self.gl.uniform_1_f32(self.gl.get_uniform_location(self.shader, "blend"), blend);
+        self.gl.uniform_1_f32(self.gl.get_uniform_location(self.shader, "recovery"), BURN_IN_RECOVERY);
+        self.gl.uniform_1_i32(self.gl.get_uniform_location(self.shader, "seed"), seed);
+        self.gl.active_texture(glow::TEXTURE0 + 1);
+        self.gl.bind_texture(glow::TEXTURE_2D, source);
+        self.gl.active_texture(glow::TEXTURE0);
+        self.gl.bind_texture(glow::TEXTURE_2D, self.buffers[previous].texture());
+        self.gl.bind_vertex_array(self.vao);
+        self.gl.draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0);
+        Ok(())
+    }
+
+    pub fn texture(&self) -> Option<GL::Texture> {
+        self.buffers.get(self.current).and_then(|buffer| buffer.texture())
+    }
+
+    fn reset(&mut self, width: i32, height: i32) -> AppResult<()> {
+        for buffer in self.buffers.iter() {
+            self.gl
+                .delete_framebuffer(buffer.framebuffer().ok_or_else(|| Into::<String>::into("can't access framebuffer"))?);
+            self.gl
+                .delete_texture(buffer.texture().ok_or_else(|| Into::<String>::into("can't access texture"))?);
+        }
+        self.buffers.clear();
+        for _ in 0..2 {
+            let buffer = TextureBuffer::new(&*self.gl, width, height, glow::NEAREST)?;
+            self.gl.clear_color(0.0, 0.0, 0.0, 0.0);
+            self.gl.clear(glow::COLOR_BUFFER_BIT);
+            self.buffers.push(buffer);
+        }
+        self.current = 0;
+        Ok(())
+    }
+}
+
+pub const BURN_IN_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+out vec4 FragColor;
+in vec2 TexCoord;
+
+uniform sampler2D marks;
+uniform sampler2D source_image;
+uniform float blend;
+uniform float recovery;
+uniform int seed;
+
+void main()
+{
+    ivec2 texel = ivec2(gl_FragCoord.xy);
+    vec3 mark = texelFetch(marks, texel, 0).rgb;
+    vec3 shown = texelFetch(source_image, texel, 0).rgb;
+    // Only the bright parts wear the phosphor down.
+    vec3 target = shown * smoothstep(0.3, 0.9, max(max(shown.r, shown.g), shown.b));
+    mark += (target - mark) * blend * mix(vec3(recovery), vec3(1.0), step(mark, target));
+    // The steps of a single frame are far below what 8 bits can hold, so the rounding is randomized
+    // to keep the average growth right.
+    float noise = fract(52.9829189 * fract(dot(gl_FragCoord.xy + float(seed) * vec2(5.588, 7.231), vec2(0.06711056, 0.00583715))));
+    FragColor = vec4(mark + (noise - 0.5) / 255.0, 1.0);
+}
+"#;
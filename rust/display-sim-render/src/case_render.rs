@@ -0,0 +1,141 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::error::AppResult;
+use crate::shaders::make_shader;
+use core::general_types::f32_to_u8;
+use core::monitor_case::CASE_VERTEX_SIZE;
+
+use glow::GlowSafeAdapter;
+use glow::HasContext;
+use std::mem::size_of;
+use std::rc::Rc;
+
+pub struct CaseUniform<'a> {
+    pub view: &'a [f32; 16],
+    pub projection: &'a [f32; 16],
+    pub light_pos: &'a [f32; 3],
+    pub color: &'a [f32; 3],
+    pub ambient_strength: f32,
+}
+
+pub struct CaseRender<GL: HasContext> {
+    vao: Option<GL::VertexArray>,
+    vbo: GL::Buffer,
+    shader: GL::Program,
+    mesh_version: u32,
+    vertex_count: i32,
+    gl: Rc<GlowSafeAdapter<GL>>,
+}
+
+impl<GL: HasContext> CaseRender<GL> {
+    pub fn new(gl: Rc<GlowSafeAdapter<GL>>) -> AppResult<CaseRender<GL>> {
+        let shader = make_shader(&*gl, CASE_VERTEX_SHADER, CASE_FRAGMENT_SHADER)?;
+
+        let vao = Some(gl.create_vertex_array()?);
+        gl.bind_vertex_array(vao);
+
+        let vbo = gl.create_buffer()?;
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(vbo));
+
+        let stride = (CASE_VERTEX_SIZE * size_of::<f32>()) as i32;
+        let a_pos_position = gl.get_attrib_location(shader, "aPos");
+        gl.vertex_attrib_pointer_f32(a_pos_position, 3, glow::FLOAT, false, stride, 0);
+        gl.enable_vertex_attrib_array(a_pos_position);
+
+        let a_normal_position = gl.get_attrib_location(shader, "aNormal");
+        gl.vertex_attrib_pointer_f32(a_normal_position, 3, glow::FLOAT, false, stride, 3 * size_of::<f32>() as i32);
+        gl.enable_vertex_attrib_array(a_normal_position);
+
+        gl.bind_vertex_array(None);
+        Ok(CaseRender {
+            vao,
+            vbo,
+            shader,
+            mesh_version: 0,
+            vertex_count: 0,
+            gl,
+        })
+    }
+
+    // The mesh only changes with the image size, so it's uploaded once per version.
+    pub fn load_mesh(&mut self, version: u32, mesh: &[f32]) {
+        if version == self.mesh_version {
+            return;
+        }
+        self.mesh_version = version;
+        self.vertex_count = (mesh.len() / CASE_VERTEX_SIZE) as i32;
+        self.gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.vbo));
+        self.gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, f32_to_u8(mesh), glow::STATIC_DRAW);
+        self.gl.bind_buffer(glow::ARRAY_BUFFER, None);
+    }
+
+    pub fn render(&self, uniforms: CaseUniform) {
+        if self.vertex_count == 0 {
+            return;
+        }
+        let gl = &self.gl;
+        let shader = self.shader;
+        gl.use_program(Some(shader));
+        gl.uniform_matrix_4_f32_slice(gl.get_uniform_location(shader, "view"), false, uniforms.view);
+        gl.uniform_matrix_4_f32_slice(gl.get_uniform_location(shader, "projection"), false, uniforms.projection);
+        gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "lightPos"), uniforms.light_pos);
+        gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "color"), uniforms.color);
+        gl.uniform_1_f32(gl.get_uniform_location(shader, "ambientStrength"), uniforms.ambient_strength);
+        gl.bind_vertex_array(self.vao);
+        gl.enable(glow::CULL_FACE);
+        gl.draw_arrays(glow::TRIANGLES, 0, self.vertex_count);
+        gl.disable(glow::CULL_FACE);
+        gl.bind_vertex_array(None);
+    }
+}
+
+pub const CASE_VERTEX_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec3 aNormal;
+
+out vec3 FragPos;
+out vec3 Normal;
+
+uniform mat4 view;
+uniform mat4 projection;
+
+void main()
+{
+    FragPos = aPos;
+    Normal = aNormal;
+    gl_Position = projection * view * vec4(aPos, 1.0);
+}
+"#;
+
+pub const CASE_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+out vec4 FragColor;
+in vec3 FragPos;
+in vec3 Normal;
+
+uniform vec3 lightPos;
+uniform vec3 color;
+uniform float ambientStrength;
+
+void main()
+{
+    vec3 lightDir = normalize(lightPos - FragPos);
+    float diffuse = max(dot(normalize(Normal), lightDir), 0.0);
+    FragColor = vec4(color * (ambientStrength + (1.0 - ambientStrength) * diffuse), 1.0);
+} 
+"#;
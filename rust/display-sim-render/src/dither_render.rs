@@ -0,0 +1,102 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::error::AppResult;
+use crate::shaders::{make_quad_vao, make_shader, TEXTURE_VERTEX_SHADER};
+use core::ui_controller::dithering::DitheringOptions;
+
+use glow::GlowSafeAdapter;
+use glow::HasContext;
+use std::rc::Rc;
+
+pub struct DitherRender<GL: HasContext> {
+    vao: Option<GL::VertexArray>,
+    shader: GL::Program,
+    gl: Rc<GlowSafeAdapter<GL>>,
+}
+
+impl<GL: HasContext> DitherRender<GL> {
+    pub fn new(gl: Rc<GlowSafeAdapter<GL>>) -> AppResult<DitherRender<GL>> {
+        let shader = make_shader(&*gl, TEXTURE_VERTEX_SHADER, DITHER_FRAGMENT_SHADER)?;
+        let vao = make_quad_vao(&*gl, &shader)?;
+        Ok(DitherRender { vao, shader, gl })
+    }
+
+    // Writes the texture to the bound framebuffer quantized to the given levels per channel. Linear values
+    // are quantized after encoding them, like a panel would get them.
+    pub fn render(&self, texture: Option<GL::Texture>, dithering: DitheringOptions, levels: f32, linear_values: bool) {
+        self.gl.use_program(Some(self.shader));
+        self.gl.uniform_1_i32(
+            self.gl.get_uniform_location(self.shader, "pattern"),
+            match dithering {
+                DitheringOptions::Off => 0,
+                DitheringOptions::Bayer => 1,
+                DitheringOptions::BlueNoise => 2,
+            },
+        );
+        self.gl.uniform_1_f32(self.gl.get_uniform_location(self.shader, "levels"), levels);
+        self.gl
+            .uniform_1_i32(self.gl.get_uniform_location(self.shader, "linear_values"), if linear_values { 1 } else { 0 });
+        self.gl.bind_vertex_array(self.vao);
+        self.gl.bind_texture(glow::TEXTURE_2D, texture);
+        self.gl.draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0);
+    }
+}
+
+pub const DITHER_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+out vec4 FragColor;
+in vec2 TexCoord;
+
+uniform sampler2D image;
+uniform int pattern;
+uniform float levels;
+uniform int linear_values;
+
+const float bayer[16] = float[16](
+     0.0,  8.0,  2.0, 10.0,
+    12.0,  4.0, 14.0,  6.0,
+     3.0, 11.0,  1.0,  9.0,
+    15.0,  7.0, 13.0,  5.0
+);
+
+vec3 srgbToLinear(vec3 color) {
+    return mix(color / 12.92, pow((color + 0.055) / 1.055, vec3(2.4)), step(0.04045, color));
+}
+
+vec3 linearToSrgb(vec3 color) {
+    return mix(color * 12.92, 1.055 * pow(color, vec3(1.0 / 2.4)) - 0.055, step(0.0031308, color));
+}
+
+float threshold() {
+    ivec2 cell = ivec2(gl_FragCoord.xy);
+    if (pattern == 1) {
+        return (bayer[(cell.y % 4) * 4 + cell.x % 4] + 0.5) / 16.0;
+    }
+    // Interleaved gradient noise, a cheap stand-in for a blue noise texture that also keeps its energy in the high frequencies.
+    return fract(52.9829189 * fract(dot(gl_FragCoord.xy, vec2(0.06711056, 0.00583715))));
+}
+
+void main()
+{
+    vec4 color = texture(image, TexCoord);
+    vec3 encoded = clamp(color.rgb, 0.0, 1.0);
+    if (linear_values == 1) {
+        encoded = linearToSrgb(encoded);
+    }
+    encoded = floor(encoded * levels + threshold()) / levels;
+    FragColor = vec4(linear_values == 1 ? srgbToLinear(encoded) : encoded, color.a);
+} 
+"#;
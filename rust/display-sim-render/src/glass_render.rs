@@ -0,0 +1,114 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::error::AppResult;
+use crate::shaders::{make_quad_vao, make_shader};
+
+use glow::GlowSafeAdapter;
+use glow::HasContext;
+use std::rc::Rc;
+
+pub struct GlassUniform<'a> {
+    pub view: &'a [f32; 16],
+    pub projection: &'a [f32; 16],
+    pub camera_pos: &'a [f32; 3],
+    /// Half width, half height and depth of the glass, in world units.
+    pub size: &'a [f32; 3],
+    /// Colors reflected from above and from below the glass.
+    pub environment_top: &'a [f32; 3],
+    pub environment_bottom: &'a [f32; 3],
+    pub reflectivity: f32,
+}
+
+pub struct GlassRender<GL: HasContext> {
+    vao: Option<GL::VertexArray>,
+    shader: GL::Program,
+    gl: Rc<GlowSafeAdapter<GL>>,
+}
+
+impl<GL: HasContext> GlassRender<GL> {
+    pub fn new(gl: Rc<GlowSafeAdapter<GL>>) -> AppResult<GlassRender<GL>> {
+        let shader = make_shader(&*gl, GLASS_VERTEX_SHADER, GLASS_FRAGMENT_SHADER)?;
+        let vao = make_quad_vao(&*gl, &shader)?;
+        Ok(GlassRender { vao, shader, gl })
+    }
+
+    // Adds the glare of a flat front glass over the bound framebuffer. It's drawn on top of everything,
+    // so it doesn't need the depth of the scene.
+    pub fn render(&self, uniforms: GlassUniform) {
+        let gl = &self.gl;
+        let shader = self.shader;
+        gl.use_program(Some(shader));
+        gl.uniform_matrix_4_f32_slice(gl.get_uniform_location(shader, "view"), false, uniforms.view);
+        gl.uniform_matrix_4_f32_slice(gl.get_uniform_location(shader, "projection"), false, uniforms.projection);
+        gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "camera_pos"), uniforms.camera_pos);
+        gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "size"), uniforms.size);
+        gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "environment_top"), uniforms.environment_top);
+        gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "environment_bottom"), uniforms.environment_bottom);
+        gl.uniform_1_f32(gl.get_uniform_location(shader, "reflectivity"), uniforms.reflectivity);
+        gl.bind_vertex_array(self.vao);
+        gl.disable(glow::DEPTH_TEST);
+        gl.enable(glow::BLEND);
+        gl.blend_func(glow::ONE, glow::ONE);
+        gl.draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0);
+        gl.disable(glow::BLEND);
+        gl.enable(glow::DEPTH_TEST);
+    }
+}
+
+pub const GLASS_VERTEX_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+layout (location = 0) in vec3 qPos;
+layout (location = 1) in vec2 qTexCoords;
+
+out vec3 FragPos;
+
+uniform mat4 view;
+uniform mat4 projection;
+uniform vec3 size;
+
+void main()
+{
+    FragPos = vec3(qPos.xy * size.xy, size.z);
+    gl_Position = projection * view * vec4(FragPos, 1.0);
+}
+"#;
+
+pub const GLASS_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+out vec4 FragColor;
+in vec3 FragPos;
+
+uniform vec3 camera_pos;
+uniform vec3 environment_top;
+uniform vec3 environment_bottom;
+uniform float reflectivity;
+
+void main()
+{
+    vec3 normal = vec3(0.0, 0.0, 1.0);
+    vec3 incident = normalize(FragPos - camera_pos);
+    float facing = dot(-incident, normal);
+    if (facing <= 0.0) {
+        discard;
+    }
+    vec3 reflected = reflect(incident, normal);
+    vec3 environment = mix(environment_bottom, environment_top, smoothstep(-0.5, 0.5, reflected.y));
+    // Schlick's approximation, the glass reflects more at grazing angles.
+    float fresnel = reflectivity + (1.0 - reflectivity) * pow(1.0 - facing, 5.0);
+    FragColor = vec4(environment * fresnel, 0.0);
+} 
+"#;
@@ -0,0 +1,108 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+
+use crate::error::AppResult;
+use crate::shaders::{make_quad_vao, make_shader, TEXTURE_VERTEX_SHADER};
+use core::general_types::Size2D;
+use core::subtitles::rasterize_hud;
+
+use glow::GlowSafeAdapter;
+use glow::HasContext;
+use std::rc::Rc;
+
+/// Pixels from the border of the viewport to the text box.
+const HUD_MARGIN: i32 = 16;
+
+pub struct HudRender<GL: HasContext> {
+    vao: Option<GL::VertexArray>,
+    shader: GL::Program,
+    texture: Option<GL::Texture>,
+    texture_size: Size2D<u32>,
+    lines: Vec<String>,
+    gl: Rc<GlowSafeAdapter<GL>>,
+}
+
+impl<GL: HasContext> HudRender<GL> {
+    pub fn new(gl: Rc<GlowSafeAdapter<GL>>) -> AppResult<HudRender<GL>> {
+        let shader = make_shader(&*gl, TEXTURE_VERTEX_SHADER, HUD_FRAGMENT_SHADER)?;
+        let vao = make_quad_vao(&*gl, &shader)?;
+        let texture = Some(gl.create_texture()?);
+        gl.bind_texture(glow::TEXTURE_2D, texture);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::NEAREST as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::NEAREST as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
+        gl.bind_texture(glow::TEXTURE_2D, None);
+        Ok(HudRender {
+            vao,
+            shader,
+            texture,
+            texture_size: Size2D { width: 0, height: 0 },
+            lines: vec![],
+            gl,
+        })
+    }
+
+    // Draws the lines at the top left of the bound framebuffer, scaled with its height so they stay readable.
+    // The text is only rasterized again when it changes.
+    pub fn render(&mut self, lines: &[String], viewport_width: u32, viewport_height: u32) {
+        if lines.is_empty() {
+            return;
+        }
+        let gl = &self.gl;
+        gl.bind_texture(glow::TEXTURE_2D, self.texture);
+        if self.lines != lines {
+            let (size, pixels) = rasterize_hud(lines);
+            gl.tex_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                glow::RGBA as i32,
+                size.width as i32,
+                size.height as i32,
+                0,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                Some(&pixels),
+            );
+            self.texture_size = size;
+            self.lines = lines.to_vec();
+        }
+        let scale = (viewport_height / 360).max(1) as i32;
+        let (width, height) = (self.texture_size.width as i32 * scale, self.texture_size.height as i32 * scale);
+        gl.viewport(HUD_MARGIN, viewport_height as i32 - HUD_MARGIN - height, width, height);
+        gl.use_program(Some(self.shader));
+        gl.bind_vertex_array(self.vao);
+        gl.enable(glow::BLEND);
+        gl.blend_func(glow::ONE, glow::ONE_MINUS_SRC_ALPHA);
+        gl.draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0);
+        gl.disable(glow::BLEND);
+        gl.viewport(0, 0, viewport_width as i32, viewport_height as i32);
+    }
+}
+
+pub const HUD_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+out vec4 FragColor;
+in vec2 TexCoord;
+
+uniform sampler2D image;
+
+void main()
+{
+    // The text comes with rows from the top.
+    FragColor = texture(image, vec2(TexCoord.x, 1.0 - TexCoord.y));
+} 
+"#;
@@ -14,12 +14,35 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
 
 use crate::error::AppResult;
-use crate::shaders::{make_quad_vao, make_shader, TEXTURE_FRAGMENT_SHADER, TEXTURE_VERTEX_SHADER};
+use crate::shaders::{make_quad_vao, make_shader, TEXTURE_VERTEX_SHADER};
 
 use glow::GlowSafeAdapter;
 use glow::HasContext;
 use std::rc::Rc;
 
+/// How the texture is resampled when it doesn't match the size of the framebuffer.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Resampling {
+    /// Whatever filter the texture has set, nearest or bilinear.
+    Hardware,
+    CatmullRom,
+    Lanczos2,
+}
+
+pub struct OutputUniform<'a> {
+    pub encode_srgb: bool,
+    pub flicker: f32,
+    pub white_point: &'a [f32; 3],
+    pub gamma: f32,
+    pub contrast_curve: &'a [f32; 2],
+    /// Maps the phosphor primaries to the ones of the output in linear RGB, before encoding it.
+    pub phosphor_matrix: Option<&'a [f32; 9]>,
+    /// Strength of the barrel distortion around the center, what falls outside of the source is left black.
+    /// Negative values pinch it into a pincushion instead.
+    pub barrel: f32,
+    pub resampling: Resampling,
+}
+
 pub struct InternalResolutionRender<GL: HasContext> {
     vao: Option<GL::VertexArray>,
     shader: GL::Program,
@@ -28,15 +51,169 @@ pub struct InternalResolutionRender<GL: HasContext> {
 
 impl<GL: HasContext> InternalResolutionRender<GL> {
     pub fn new(gl: Rc<GlowSafeAdapter<GL>>) -> AppResult<InternalResolutionRender<GL>> {
-        let shader = make_shader(&*gl, TEXTURE_VERTEX_SHADER, TEXTURE_FRAGMENT_SHADER)?;
+        let shader = make_shader(&*gl, TEXTURE_VERTEX_SHADER, OUTPUT_FRAGMENT_SHADER)?;
         let vao = make_quad_vao(&*gl, &shader)?;
         Ok(InternalResolutionRender { vao, shader, gl })
     }
 
-    pub fn render(&self, texture: Option<GL::Texture>) {
+    // Copies the texture to the bound framebuffer as it is.
+    pub fn copy(&self, texture: Option<GL::Texture>) {
+        self.render(
+            texture,
+            &OutputUniform {
+                encode_srgb: false,
+                flicker: 0.0,
+                white_point: &[1.0, 1.0, 1.0],
+                gamma: 1.0,
+                contrast_curve: &[0.0, 0.0],
+                phosphor_matrix: None,
+                barrel: 0.0,
+                resampling: Resampling::Hardware,
+            },
+        );
+    }
+
+    pub fn render(&self, texture: Option<GL::Texture>, uniforms: &OutputUniform) {
         self.gl.use_program(Some(self.shader));
+        self.gl.uniform_1_i32(
+            self.gl.get_uniform_location(self.shader, "encode_srgb"),
+            if uniforms.encode_srgb { 1 } else { 0 },
+        );
+        self.gl.uniform_1_f32(self.gl.get_uniform_location(self.shader, "flicker"), uniforms.flicker);
+        self.gl
+            .uniform_3_f32_slice(self.gl.get_uniform_location(self.shader, "white_point"), uniforms.white_point);
+        self.gl.uniform_1_f32(self.gl.get_uniform_location(self.shader, "gamma"), uniforms.gamma);
+        self.gl
+            .uniform_2_f32_slice(self.gl.get_uniform_location(self.shader, "contrast_curve"), uniforms.contrast_curve);
+        self.gl.uniform_1_i32(
+            self.gl.get_uniform_location(self.shader, "map_primaries"),
+            if uniforms.phosphor_matrix.is_some() { 1 } else { 0 },
+        );
+        if let Some(matrix) = uniforms.phosphor_matrix {
+            self.gl
+                .uniform_matrix_3_f32_slice(self.gl.get_uniform_location(self.shader, "phosphor_matrix"), false, matrix);
+        }
+        self.gl.uniform_1_f32(self.gl.get_uniform_location(self.shader, "barrel"), uniforms.barrel);
+        self.gl.uniform_1_i32(
+            self.gl.get_uniform_location(self.shader, "resampling"),
+            match uniforms.resampling {
+                Resampling::Hardware => 0,
+                Resampling::CatmullRom => 1,
+                Resampling::Lanczos2 => 2,
+            },
+        );
         self.gl.bind_vertex_array(self.vao);
         self.gl.bind_texture(glow::TEXTURE_2D, texture);
         self.gl.draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0);
     }
 }
+
+pub const OUTPUT_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+out vec4 FragColor;
+in vec2 TexCoord;
+
+uniform sampler2D image;
+uniform int encode_srgb;
+uniform float flicker;
+uniform vec3 white_point;
+uniform float gamma;
+uniform vec2 contrast_curve;
+uniform int map_primaries;
+uniform mat3 phosphor_matrix;
+uniform float barrel;
+uniform int resampling;
+
+const float PI = 3.14159265;
+
+float catmullRom(float x) {
+    x = abs(x);
+    if (x < 1.0) {
+        return (1.5 * x - 2.5) * x * x + 1.0;
+    }
+    if (x < 2.0) {
+        return ((-0.5 * x + 2.5) * x - 4.0) * x + 2.0;
+    }
+    return 0.0;
+}
+
+float lanczos2(float x) {
+    if (abs(x) < 0.0001) {
+        return 1.0;
+    }
+    if (abs(x) >= 2.0) {
+        return 0.0;
+    }
+    float px = PI * x;
+    return 2.0 * sin(px) * sin(px * 0.5) / (px * px);
+}
+
+// Both kernels span the 4x4 texels around the sample. Their negative lobes sharpen the edges, and they can
+// overshoot a bit there, so the result is clamped.
+vec4 resample(vec2 coord) {
+    ivec2 size = textureSize(image, 0);
+    vec2 position = coord * vec2(size) - 0.5;
+    vec2 base = floor(position);
+    vec2 fraction = position - base;
+    vec4 sum = vec4(0.0);
+    float total = 0.0;
+    for (int j = -1; j <= 2; j++) {
+        float dy = float(j) - fraction.y;
+        float weight_y = resampling == 1 ? catmullRom(dy) : lanczos2(dy);
+        for (int i = -1; i <= 2; i++) {
+            float dx = float(i) - fraction.x;
+            float weight = weight_y * (resampling == 1 ? catmullRom(dx) : lanczos2(dx));
+            ivec2 texel = clamp(ivec2(base) + ivec2(i, j), ivec2(0), size - 1);
+            sum += texelFetch(image, texel, 0) * weight;
+            total += weight;
+        }
+    }
+    return clamp(sum / total, 0.0, 1.0);
+}
+
+vec3 linearToSrgb(vec3 color) {
+    return mix(color * 12.92, 1.055 * pow(color, vec3(1.0 / 2.4)) - 0.055, step(0.0031308, color));
+}
+
+vec3 srgbToLinear(vec3 color) {
+    return mix(color / 12.92, pow((color + 0.055) / 1.055, vec3(2.4)), step(0.04045, color));
+}
+
+// S-curve that keeps black, white and the middle grey in place, bending the lower half by the knee and the upper half by the shoulder.
+vec3 contrastCurve(vec3 color) {
+    vec3 lower = 0.5 * pow(2.0 * color, vec3(1.0 + 2.0 * contrast_curve.x));
+    vec3 upper = 1.0 - 0.5 * pow(2.0 * (1.0 - color), vec3(1.0 + 2.0 * contrast_curve.y));
+    return mix(lower, upper, step(0.5, color));
+}
+
+void main()
+{
+    vec2 coord = TexCoord;
+    if (barrel != 0.0) {
+        vec2 centered = coord * 2.0 - 1.0;
+        centered *= 1.0 + barrel * centered.yx * centered.yx;
+        if (abs(centered.x) > 1.0 || abs(centered.y) > 1.0) {
+            FragColor = vec4(0.0, 0.0, 0.0, 1.0);
+            return;
+        }
+        coord = centered * 0.5 + 0.5;
+    }
+    vec4 color = resampling == 0 ? texture(image, coord) : resample(coord);
+    color.rgb *= (1.0 - flicker) * white_point;
+    // Display P3 shares the sRGB transfer function, so the colors get back the same encoding they came with.
+    if (map_primaries == 1) {
+        vec3 linear = encode_srgb == 1 ? color.rgb : srgbToLinear(clamp(color.rgb, 0.0, 1.0));
+        color.rgb = linearToSrgb(clamp(phosphor_matrix * linear, 0.0, 1.0));
+    } else if (encode_srgb == 1) {
+        color.rgb = linearToSrgb(clamp(color.rgb, 0.0, 1.0));
+    }
+    if (contrast_curve != vec2(0.0)) {
+        color.rgb = contrastCurve(clamp(color.rgb, 0.0, 1.0));
+    }
+    if (gamma != 1.0) {
+        color.rgb = pow(clamp(color.rgb, 0.0, 1.0), vec3(1.0 / gamma));
+    }
+    FragColor = color;
+} 
+"#;
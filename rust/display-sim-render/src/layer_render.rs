@@ -0,0 +1,61 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::error::AppResult;
+use crate::shaders::{make_quad_vao, make_shader, TEXTURE_VERTEX_SHADER};
+
+use glow::GlowSafeAdapter;
+use glow::HasContext;
+use std::rc::Rc;
+
+pub struct LayerRender<GL: HasContext> {
+    vao: Option<GL::VertexArray>,
+    shader: GL::Program,
+    gl: Rc<GlowSafeAdapter<GL>>,
+}
+
+impl<GL: HasContext> LayerRender<GL> {
+    pub fn new(gl: Rc<GlowSafeAdapter<GL>>) -> AppResult<LayerRender<GL>> {
+        let shader = make_shader(&*gl, TEXTURE_VERTEX_SHADER, LAYER_FRAGMENT_SHADER)?;
+        let vao = make_quad_vao(&*gl, &shader)?;
+        Ok(LayerRender { vao, shader, gl })
+    }
+
+    // Draws the texture over whatever is in the bound framebuffer, as premultiplied color scaled by the opacity.
+    pub fn render(&self, texture: Option<GL::Texture>, opacity: f32) {
+        self.gl.use_program(Some(self.shader));
+        self.gl.uniform_1_f32(self.gl.get_uniform_location(self.shader, "opacity"), opacity);
+        self.gl.bind_vertex_array(self.vao);
+        self.gl.bind_texture(glow::TEXTURE_2D, texture);
+        self.gl.enable(glow::BLEND);
+        self.gl.blend_func(glow::ONE, glow::ONE_MINUS_SRC_ALPHA);
+        self.gl.draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0);
+        self.gl.disable(glow::BLEND);
+    }
+}
+
+pub const LAYER_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+out vec4 FragColor;
+in vec2 TexCoord;
+
+uniform sampler2D image;
+uniform float opacity;
+
+void main()
+{
+    FragColor = texture(image, TexCoord) * opacity;
+} 
+"#;
@@ -15,15 +15,28 @@
 
 #![allow(clippy::identity_op)]
 
+pub mod analog_static_render;
 pub mod background_render;
+pub mod bezel_render;
+pub mod bloom_render;
 pub mod blur_render;
+pub mod burn_in_render;
+pub mod case_render;
+pub mod dither_render;
+pub mod glass_render;
+pub mod hud_render;
 pub mod internal_resolution_render;
+pub mod layer_render;
 pub mod pixels_render;
 pub mod render_types;
 pub mod rgb_render;
+pub mod room_render;
+pub mod screenshot_readback;
 mod shaders;
+pub mod sharpen_render;
 pub mod simulation_draw;
 pub mod simulation_render_state;
+pub mod vignette_render;
 
 pub mod error {
     pub use app_error::*;
@@ -14,12 +14,21 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
 
 use crate::error::AppResult;
-use crate::shaders::make_shader;
+use crate::shaders::{make_shader, with_color_adjustment};
 use crate::simulation_render_state::VideoInputMaterials;
+use core::blooming::average_luminance;
+use core::composite_signal::{encode_signal, CompositeSignal};
+use core::defects::DefectSettings;
 use core::general_types::f32_to_u8;
+use core::palettes::quantize_to_palette;
+use core::response_time::smear_frame;
 use core::simulation_core_state::VideoInputResources;
+use core::subtitles::burn_subtitle;
+use core::tv_lines::band_limit_horizontally;
 use core::ui_controller::pixel_geometry_kind::PixelGeometryKindOptions;
-use core::ui_controller::pixel_shadow_shape_kind::{get_shadows, TEXTURE_SIZE};
+use core::ui_controller::pixel_shadow_shape_kind::{get_shadows, SLOT_MASK_SHADOW, TEXTURE_SIZE};
+use core::ui_controller::signal_kind::SignalKindOptions;
+use core::ui_controller::white_clipping::WhiteClippingOptions;
 
 use glow::GlowSafeAdapter;
 use glow::HasContext;
@@ -29,13 +38,24 @@ use std::rc::Rc;
 pub struct PixelsRender<GL: HasContext> {
     shader: GL::Program,
     vao: Option<GL::VertexArray>,
-    colors_vbo: GL::Buffer,
+    colors_vbos: [GL::Buffer; 2],
+    colors_vbo_index: usize,
+    a_color_position: Option<u32>,
     offsets_vbo: GL::Buffer,
     width: u32,
     height: u32,
     offset_inverse_max_length: f32,
     shadows: Vec<Option<GL::Texture>>,
+    source_texture: Option<GL::Texture>,
+    source_interpolation: u32,
+    defect_texture: Option<GL::Texture>,
+    defect_mask_version: u32,
+    has_defects: bool,
+    burn_in_texture: Option<GL::Texture>,
+    burn_in_visibility: f32,
     video_buffers: Vec<Box<[u8]>>,
+    processed_frame: Vec<u8>,
+    response_history: Vec<f32>,
     gl: Rc<GlowSafeAdapter<GL>>,
 }
 
@@ -50,7 +70,17 @@ pub struct PixelsUniform<'a> {
     pub ambient_strength: f32,
     pub contrast_factor: f32,
     pub screen_curvature: f32,
+    pub screen_curvature_axes: &'a [f32; 2],
     pub pixel_spread: &'a [f32; 2],
+    pub pixel_rotation: &'a [f32; 2],
+    pub source_transform: &'a [f32; 4],
+    pub damper_wire_y: &'a [f32; 2],
+    pub damper_wire_opacity: f32,
+    pub damper_wire_width: f32,
+    /// Band center, band half height, raster height and darkening outside of the band.
+    pub rolling_scan: &'a [f32; 4],
+    pub screen_matte: &'a [f32; 4],
+    pub reflection_edge: &'a [f32; 4],
     pub pixel_scale: &'a [f32; 3],
     pub pixel_offset: &'a [f32; 3],
 
@@ -58,23 +88,50 @@ pub struct PixelsUniform<'a> {
     pub rgb_green: &'a [f32; 3],
     pub rgb_blue: &'a [f32; 3],
     pub color_gamma: f32,
-    pub time: f32,
+    /// Offset of the source signal, applied before it gets decoded to light.
+    pub black_pedestal: f32,
+    pub white_clipping: WhiteClippingOptions,
+    pub color_saturation: f32,
+    pub color_hue: f32,
+    pub channel_gain: &'a [f32; 3],
+    pub channel_offset: &'a [f32; 3],
+    pub noise_seed: i32,
+    /// Phosphor aging strength and scratch width, the seed of the monitor and its scratches as center, angle and half length.
+    pub blemish: &'a [f32; 2],
+    pub blemish_seed: i32,
+    pub blemish_scratches: &'a [f32; 12],
     pub color_noise: f32,
+    pub convergence: &'a [[f32; 2]; 3],
+    /// Field being scanned (0 or 1) and brightness of the other one.
+    pub interlace: &'a [f32; 2],
+    pub interlace_line_pairing: f32,
+    pub hsync_wobble: &'a [f32; 3],
+    /// Rows the picture is rolled by, and the rows it wraps around.
+    pub vertical_roll: &'a [f32; 2],
+    pub row_stagger: f32,
+    pub linear_blending: bool,
 
     pub pixel_pulse: f32,
     pub height_modifier_factor: f32,
+
+    pub magnet_center: &'a [f32; 2],
+    pub magnet_radius: f32,
+    pub magnet_strength: f32,
 }
 
 impl<GL: HasContext> PixelsRender<GL> {
     pub fn new(gl: Rc<GlowSafeAdapter<GL>>, video_materials: VideoInputMaterials) -> AppResult<PixelsRender<GL>> {
-        let shader = make_shader(&*gl, PIXEL_VERTEX_SHADER, PIXEL_FRAGMENT_SHADER)?;
+        let shader = make_shader(&*gl, PIXEL_VERTEX_SHADER, &with_color_adjustment(PIXEL_FRAGMENT_SHADER))?;
 
         let vao = Some(gl.create_vertex_array()?);
         gl.bind_vertex_array(vao);
 
         let pixels_vbo = gl.create_buffer()?;
         gl.bind_buffer(glow::ARRAY_BUFFER, Some(pixels_vbo));
-        gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, f32_to_u8(&CUBE_GEOMETRY), glow::STATIC_DRAW);
+        // The hexagonal prisms go right after the cube, so both are drawn from the same buffer.
+        let mut geometry = CUBE_GEOMETRY.to_vec();
+        geometry.extend(hexagon_prism_geometry());
+        gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, f32_to_u8(&geometry), glow::STATIC_DRAW);
 
         let a_pos_position = gl.get_attrib_location(shader, "aPos");
         gl.vertex_attrib_pointer_f32(a_pos_position, 3, glow::FLOAT, false, 6 * size_of::<f32>() as i32, 0);
@@ -91,8 +148,10 @@ impl<GL: HasContext> PixelsRender<GL> {
         );
         gl.enable_vertex_attrib_array(a_normal_position);
 
-        let colors_vbo = gl.create_buffer()?;
-        gl.bind_buffer(glow::ARRAY_BUFFER, Some(colors_vbo));
+        // Video frames are uploaded alternating between these two buffers, so a new frame never
+        // respecifies the buffer that the previous draw call might still be reading from.
+        let colors_vbos = [gl.create_buffer()?, gl.create_buffer()?];
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(colors_vbos[0]));
 
         let a_color_position = gl.get_attrib_location(shader, "aColor");
         gl.enable_vertex_attrib_array(a_color_position);
@@ -111,17 +170,48 @@ impl<GL: HasContext> PixelsRender<GL> {
             .iter()
             .map(|closure| Self::create_shadow_texture(&*gl, &**closure))
             .collect::<AppResult<Vec<Option<GL::Texture>>>>()?;
+        // The slot mask is sampled half a pixel off on odd columns, so it has to tile vertically.
+        gl.bind_texture(glow::TEXTURE_2D, shadows[SLOT_MASK_SHADOW]);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::REPEAT as i32);
+        gl.bind_texture(glow::TEXTURE_2D, None);
+
+        // Copy of the uploaded colors, so the vertex shader can read the neighbours of each pixel when the beams don't converge.
+        let source_texture = Some(gl.create_texture()?);
+        gl.bind_texture(glow::TEXTURE_2D, source_texture);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
+        gl.bind_texture(glow::TEXTURE_2D, None);
+
+        // One texel per source pixel, read with texelFetch, so it never gets filtered.
+        let defect_texture = Some(gl.create_texture()?);
+        gl.bind_texture(glow::TEXTURE_2D, defect_texture);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::NEAREST as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::NEAREST as i32);
+        gl.bind_texture(glow::TEXTURE_2D, None);
 
         Ok(PixelsRender {
             video_buffers: video_materials.buffers,
+            processed_frame: Vec::new(),
+            response_history: Vec::new(),
             vao,
             shader,
             offsets_vbo,
-            colors_vbo,
+            colors_vbos,
+            colors_vbo_index: 0,
+            a_color_position,
             width: 0,
             height: 0,
             offset_inverse_max_length: 0.0,
             shadows,
+            source_texture,
+            source_interpolation: glow::LINEAR,
+            defect_texture,
+            defect_mask_version: 0,
+            has_defects: false,
+            burn_in_texture: None,
+            burn_in_visibility: 0.0,
             gl,
         })
     }
@@ -188,7 +278,28 @@ impl<GL: HasContext> PixelsRender<GL> {
         Ok(pixel_shadow_texture)
     }
 
-    pub fn load_image(&mut self, video_res: &VideoInputResources) {
+    pub fn video_frame_mut(&mut self, index: usize) -> Option<&mut [u8]> {
+        self.video_buffers.get_mut(index).map(|buffer| &mut **buffer)
+    }
+
+    // The new frames are uploaded on the next load_image, so the caller has to request it.
+    pub fn replace_video(&mut self, buffers: Vec<Box<[u8]>>) {
+        self.video_buffers = buffers;
+    }
+
+    // The palette, the video signal, the band limit, the response time smear and the subtitle are applied to a copy of the frame,
+    // so the original buffers stay clean for the next settings. Returns the average luminance of the uploaded frame.
+    #[allow(clippy::too_many_arguments)]
+    pub fn load_image(
+        &mut self,
+        video_res: &VideoInputResources,
+        procedural_frame: Option<&[u8]>,
+        palette: &[[u8; 3]],
+        subtitle: Option<&str>,
+        horizontal_band_limit: f32,
+        signal: CompositeSignal,
+        response_blend_factors: [f32; 2],
+    ) -> f32 {
         if video_res.image_size.width != self.width || video_res.image_size.height != self.height {
             self.width = video_res.image_size.width;
             self.height = video_res.image_size.height;
@@ -197,11 +308,97 @@ impl<GL: HasContext> PixelsRender<GL> {
             let offsets = calculate_offsets(self.width, self.height);
             self.gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, f32_to_u8(&offsets), glow::STATIC_DRAW);
         }
+        self.colors_vbo_index = (self.colors_vbo_index + 1) % self.colors_vbos.len();
         self.gl.bind_vertex_array(self.vao);
-        self.gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.colors_vbo));
+        self.gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.colors_vbos[self.colors_vbo_index]));
 
+        let frame = procedural_frame.unwrap_or(&self.video_buffers[video_res.current_frame]);
+        let luminance = average_luminance(frame);
+        let smearing = response_blend_factors != [1.0, 1.0];
+        if !smearing {
+            self.response_history.clear();
+        }
+        let colors: &[u8] = if !palette.is_empty() || subtitle.is_some() || horizontal_band_limit > 0.0 || signal.kind != SignalKindOptions::Rgb || smearing {
+            self.processed_frame.clear();
+            self.processed_frame.extend_from_slice(frame);
+            quantize_to_palette(&mut self.processed_frame, palette);
+            encode_signal(&mut self.processed_frame, video_res.image_size, signal);
+            band_limit_horizontally(&mut self.processed_frame, video_res.image_size, horizontal_band_limit);
+            if smearing {
+                smear_frame(&mut self.response_history, &mut self.processed_frame, response_blend_factors);
+            }
+            if let Some(text) = subtitle {
+                burn_subtitle(&mut self.processed_frame, video_res.image_size, text);
+            }
+            &self.processed_frame
+        } else {
+            frame
+        };
+        self.gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, colors, glow::STREAM_DRAW);
+        self.gl.bind_texture(glow::TEXTURE_2D, self.source_texture);
+        self.gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA as i32,
+            self.width as i32,
+            self.height as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            Some(colors),
+        );
+        self.gl.bind_texture(glow::TEXTURE_2D, None);
         self.gl
-            .buffer_data_u8_slice(glow::ARRAY_BUFFER, &self.video_buffers[video_res.current_frame], glow::STATIC_DRAW);
+            .vertex_attrib_pointer_f32(self.a_color_position, 1, glow::FLOAT, false, size_of::<f32>() as i32, 0);
+        luminance
+    }
+
+    pub fn load_defect_mask(&mut self, version: u32, settings: &DefectSettings, mask: &[u8]) {
+        if version == self.defect_mask_version {
+            return;
+        }
+        self.defect_mask_version = version;
+        self.has_defects = !mask.is_empty();
+        if !self.has_defects {
+            return;
+        }
+        self.gl.bind_texture(glow::TEXTURE_2D, self.defect_texture);
+        self.gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA as i32,
+            settings.width as i32,
+            settings.height as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            Some(mask),
+        );
+        self.gl.bind_texture(glow::TEXTURE_2D, None);
+    }
+
+    // The marks have one texel per source pixel, like the defect mask. No texture or no visibility leaves them out.
+    pub fn set_burn_in(&mut self, texture: Option<GL::Texture>, visibility: f32) {
+        self.burn_in_texture = texture;
+        self.burn_in_visibility = if texture.is_some() { visibility } else { 0.0 };
+    }
+
+    pub fn set_source_interpolation(&mut self, interpolation: u32) {
+        if self.source_interpolation != interpolation {
+            self.source_interpolation = interpolation;
+            self.gl.bind_texture(glow::TEXTURE_2D, self.source_texture);
+            self.gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, interpolation as i32);
+            self.gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, interpolation as i32);
+            self.gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+    }
+
+    pub fn source_texture(&self) -> Option<GL::Texture> {
+        self.source_texture
+    }
+
+    pub fn source_size(&self) -> (u32, u32) {
+        (self.width, self.height)
     }
 
     pub fn render(&self, uniforms: PixelsUniform) {
@@ -212,7 +409,18 @@ impl<GL: HasContext> PixelsRender<GL> {
         if uniforms.shadow_kind >= self.shadows.len() {
             panic!("Bug on shadow_kind!")
         }
+        gl.active_texture(glow::TEXTURE0 + 1);
+        gl.bind_texture(glow::TEXTURE_2D, self.source_texture);
+        gl.active_texture(glow::TEXTURE0 + 2);
+        gl.bind_texture(glow::TEXTURE_2D, if self.has_defects { self.defect_texture } else { None });
+        gl.active_texture(glow::TEXTURE0 + 3);
+        gl.bind_texture(glow::TEXTURE_2D, self.burn_in_texture);
+        gl.active_texture(glow::TEXTURE0);
         gl.bind_texture(glow::TEXTURE_2D, self.shadows[uniforms.shadow_kind]);
+        gl.uniform_1_f32(
+            gl.get_uniform_location(shader, "shadow_stagger"),
+            if uniforms.shadow_kind == SLOT_MASK_SHADOW { 0.5 } else { 0.0 },
+        );
         gl.uniform_matrix_4_f32_slice(gl.get_uniform_location(shader, "view"), false, uniforms.view);
         gl.uniform_matrix_4_f32_slice(gl.get_uniform_location(shader, "projection"), false, uniforms.projection);
         gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "lightPos"), uniforms.light_pos);
@@ -222,29 +430,96 @@ impl<GL: HasContext> PixelsRender<GL> {
         gl.uniform_1_f32(gl.get_uniform_location(shader, "contrastFactor"), uniforms.contrast_factor);
         gl.uniform_1_f32(gl.get_uniform_location(shader, "offset_inverse_max_length"), self.offset_inverse_max_length);
         gl.uniform_1_f32(gl.get_uniform_location(shader, "screen_curvature"), uniforms.screen_curvature);
+        gl.uniform_2_f32_slice(gl.get_uniform_location(shader, "screen_curvature_axes"), uniforms.screen_curvature_axes);
         gl.uniform_2_f32_slice(gl.get_uniform_location(shader, "pixel_spread"), uniforms.pixel_spread);
+        gl.uniform_2_f32_slice(gl.get_uniform_location(shader, "pixel_rotation"), uniforms.pixel_rotation);
+        gl.uniform_matrix_2_f32_slice(gl.get_uniform_location(shader, "source_transform"), false, uniforms.source_transform);
         gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "pixel_scale"), uniforms.pixel_scale);
         gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "pixel_offset"), uniforms.pixel_offset);
         gl.uniform_1_f32(gl.get_uniform_location(shader, "pixel_pulse"), uniforms.pixel_pulse);
         gl.uniform_1_f32(gl.get_uniform_location(shader, "heightModifierFactor"), uniforms.height_modifier_factor);
+        gl.uniform_2_f32_slice(gl.get_uniform_location(shader, "magnet_center"), uniforms.magnet_center);
+        gl.uniform_1_f32(gl.get_uniform_location(shader, "magnet_radius"), uniforms.magnet_radius);
+        gl.uniform_1_f32(gl.get_uniform_location(shader, "magnet_strength"), uniforms.magnet_strength);
 
         gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "red"), uniforms.rgb_red);
         gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "green"), uniforms.rgb_green);
         gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "blue"), uniforms.rgb_blue);
         gl.uniform_1_f32(gl.get_uniform_location(shader, "gamma"), uniforms.color_gamma);
-        gl.uniform_1_f32(gl.get_uniform_location(shader, "time"), uniforms.time);
+        gl.uniform_1_f32(gl.get_uniform_location(shader, "black_pedestal"), uniforms.black_pedestal);
+        gl.uniform_1_i32(
+            gl.get_uniform_location(shader, "white_clipping"),
+            match uniforms.white_clipping {
+                WhiteClippingOptions::HardClip => 0,
+                WhiteClippingOptions::SoftShoulder => 1,
+                WhiteClippingOptions::Bleed => 2,
+            },
+        );
+        gl.uniform_1_f32(gl.get_uniform_location(shader, "saturation"), uniforms.color_saturation);
+        gl.uniform_1_f32(gl.get_uniform_location(shader, "hue"), uniforms.color_hue);
+        gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "channel_gain"), uniforms.channel_gain);
+        gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "channel_offset"), uniforms.channel_offset);
+        gl.uniform_1_i32(gl.get_uniform_location(shader, "noise_seed"), uniforms.noise_seed);
         gl.uniform_1_f32(gl.get_uniform_location(shader, "color_noise"), uniforms.color_noise);
-
-        gl.bind_vertex_array(self.vao);
-        gl.draw_arrays_instanced(
-            glow::TRIANGLES,
-            0,
-            match uniforms.geometry_kind {
-                PixelGeometryKindOptions::Squares => 6,
-                PixelGeometryKindOptions::Cubes => 36,
+        gl.uniform_2_f32_slice(gl.get_uniform_location(shader, "blemish"), uniforms.blemish);
+        gl.uniform_1_i32(gl.get_uniform_location(shader, "blemish_seed"), uniforms.blemish_seed);
+        for (i, scratch) in uniforms.blemish_scratches.chunks(4).enumerate() {
+            gl.uniform_4_f32_slice(
+                gl.get_uniform_location(shader, &format!("blemish_scratches[{}]", i)),
+                &[scratch[0], scratch[1], scratch[2], scratch[3]],
+            );
+        }
+        gl.uniform_1_i32(gl.get_uniform_location(shader, "source_image"), 1);
+        gl.uniform_1_i32(gl.get_uniform_location(shader, "defect_mask"), 2);
+        gl.uniform_1_i32(gl.get_uniform_location(shader, "defects"), if self.has_defects { 1 } else { 0 });
+        gl.uniform_1_i32(gl.get_uniform_location(shader, "burn_in_marks"), 3);
+        gl.uniform_1_f32(gl.get_uniform_location(shader, "burn_in_visibility"), self.burn_in_visibility);
+        gl.uniform_2_f32_slice(
+            gl.get_uniform_location(shader, "source_center"),
+            &[
+                (self.width as f32 + (self.width % 2) as f32) * 0.5,
+                (self.height as f32 - (self.height % 2) as f32) * 0.5,
+            ],
+        );
+        gl.uniform_1_i32(
+            gl.get_uniform_location(shader, "converging"),
+            if uniforms.convergence.iter().flatten().any(|offset| *offset != 0.0) {
+                1
+            } else {
+                0
+            },
+        );
+        gl.uniform_2_f32_slice(gl.get_uniform_location(shader, "convergence_red"), &uniforms.convergence[0]);
+        gl.uniform_2_f32_slice(gl.get_uniform_location(shader, "convergence_green"), &uniforms.convergence[1]);
+        gl.uniform_2_f32_slice(gl.get_uniform_location(shader, "convergence_blue"), &uniforms.convergence[2]);
+        gl.uniform_1_i32(gl.get_uniform_location(shader, "linear_blending"), if uniforms.linear_blending { 1 } else { 0 });
+        gl.uniform_1_i32(
+            gl.get_uniform_location(shader, "aperture_grille"),
+            if let PixelGeometryKindOptions::ApertureGrille = uniforms.geometry_kind {
+                1
+            } else {
+                0
             },
-            (self.width * self.height) as i32,
         );
+        gl.uniform_2_f32_slice(gl.get_uniform_location(shader, "interlace"), uniforms.interlace);
+        gl.uniform_1_f32(gl.get_uniform_location(shader, "interlace_line_pairing"), uniforms.interlace_line_pairing);
+        gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "hsync_wobble"), uniforms.hsync_wobble);
+        gl.uniform_2_f32_slice(gl.get_uniform_location(shader, "vertical_roll"), uniforms.vertical_roll);
+        gl.uniform_1_f32(gl.get_uniform_location(shader, "row_stagger"), uniforms.row_stagger);
+        gl.uniform_2_f32_slice(gl.get_uniform_location(shader, "damper_wire_y"), uniforms.damper_wire_y);
+        gl.uniform_1_f32(gl.get_uniform_location(shader, "damper_wire_opacity"), uniforms.damper_wire_opacity);
+        gl.uniform_1_f32(gl.get_uniform_location(shader, "damper_wire_width"), uniforms.damper_wire_width);
+        gl.uniform_4_f32_slice(gl.get_uniform_location(shader, "rolling_scan"), uniforms.rolling_scan);
+        gl.uniform_4_f32_slice(gl.get_uniform_location(shader, "screen_matte"), uniforms.screen_matte);
+        gl.uniform_4_f32_slice(gl.get_uniform_location(shader, "reflection_edge"), uniforms.reflection_edge);
+
+        gl.bind_vertex_array(self.vao);
+        let (first, count) = match uniforms.geometry_kind {
+            PixelGeometryKindOptions::Squares | PixelGeometryKindOptions::ApertureGrille => (0, 6),
+            PixelGeometryKindOptions::Cubes => (0, CUBE_VERTICES),
+            PixelGeometryKindOptions::Hexagons => (CUBE_VERTICES, HEXAGON_PRISM_VERTICES),
+        };
+        gl.draw_arrays_instanced(glow::TRIANGLES, first, count, (self.width * self.height) as i32);
     }
 }
 
@@ -269,6 +544,47 @@ fn calculate_offsets(width: u32, height: u32) -> Vec<f32> {
     offsets
 }
 
+const CUBE_VERTICES: i32 = 36;
+const HEXAGON_PRISM_VERTICES: i32 = 72;
+
+// Pointy-top hexagon one pixel wide and four thirds of a pixel tall, so the rows overlap by a third when they are
+// staggered. The front and back faces are fans around the center, and every side is a quad, with the same vertex
+// layout as the cube.
+fn hexagon_prism_geometry() -> Vec<f32> {
+    let corners = [
+        [0.0, 2.0 / 3.0],
+        [-0.5, 1.0 / 3.0],
+        [-0.5, -1.0 / 3.0],
+        [0.0, -2.0 / 3.0],
+        [0.5, -1.0 / 3.0],
+        [0.5, 1.0 / 3.0],
+    ];
+    let mut geometry = Vec::with_capacity(HEXAGON_PRISM_VERTICES as usize * 6);
+    let mut push = |[x, y]: [f32; 2], z: f32, normal: [f32; 3]| geometry.extend_from_slice(&[x, y, z, normal[0], normal[1], normal[2]]);
+    for i in 0..corners.len() {
+        let (a, b) = (corners[i], corners[(i + 1) % corners.len()]);
+        push([0.0, 0.0], 0.5, [0.0, 0.0, 1.0]);
+        push(a, 0.5, [0.0, 0.0, 1.0]);
+        push(b, 0.5, [0.0, 0.0, 1.0]);
+        push([0.0, 0.0], -0.5, [0.0, 0.0, -1.0]);
+        push(b, -0.5, [0.0, 0.0, -1.0]);
+        push(a, -0.5, [0.0, 0.0, -1.0]);
+    }
+    for i in 0..corners.len() {
+        let (a, b) = (corners[i], corners[(i + 1) % corners.len()]);
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let length = (dx * dx + dy * dy).sqrt();
+        let normal = [dy / length, -dx / length, 0.0];
+        push(a, 0.5, normal);
+        push(a, -0.5, normal);
+        push(b, -0.5, normal);
+        push(b, -0.5, normal);
+        push(b, 0.5, normal);
+        push(a, 0.5, normal);
+    }
+    geometry
+}
+
 #[rustfmt::skip]
 const CUBE_GEOMETRY : [f32; 216] = [
     // cube coordinates       cube normals
@@ -327,21 +643,59 @@ out vec3 FragPos;
 out vec3 Normal;
 out vec4 ObjectColor;
 out vec2 ImagePos;
+out float FlatX;
+out float FlatY;
+out vec2 ScreenPos;
+flat out vec2 SourcePos;
 
 uniform mat4 view;
 uniform mat4 projection;
 
 uniform float offset_inverse_max_length;
 uniform float screen_curvature;
+uniform vec2 screen_curvature_axes;
 uniform vec2 pixel_spread;
+uniform vec2 pixel_rotation;
+uniform mat2 source_transform;
 uniform vec3 pixel_scale;
 uniform float pixel_pulse;
 uniform vec3 pixel_offset;
 uniform float heightModifierFactor;
+uniform vec2 magnet_center;
+uniform float magnet_radius;
+uniform float magnet_strength;
+uniform sampler2D source_image;
+uniform vec2 source_center;
+uniform int converging;
+uniform vec2 convergence_red;
+uniform vec2 convergence_green;
+uniform vec2 convergence_blue;
+uniform sampler2D defect_mask;
+uniform int defects;
+uniform sampler2D burn_in_marks;
+uniform float burn_in_visibility;
+uniform float interlace_line_pairing;
+uniform vec3 hsync_wobble;
+uniform vec2 vertical_roll;
+uniform float row_stagger;
 
 const float COLOR_FACTOR = 1.0/255.0;
 const uint hex_FF = uint(0xFF);
 
+// Smooth value noise between -1 and 1 that repeats every 256 cells, so the drift can wrap around.
+float wobbleNoise(vec2 p) {
+    vec2 cell = floor(p);
+    vec2 t = fract(p);
+    t = t * t * (3.0 - 2.0 * t);
+    vec2 c0 = mod(cell, 256.0);
+    vec2 c1 = mod(cell + 1.0, 256.0);
+    float a = fract(sin(dot(c0, vec2(12.9898, 78.233))) * 43758.5453);
+    float b = fract(sin(dot(vec2(c1.x, c0.y), vec2(12.9898, 78.233))) * 43758.5453);
+    float c = fract(sin(dot(vec2(c0.x, c1.y), vec2(12.9898, 78.233))) * 43758.5453);
+    float d = fract(sin(dot(c1, vec2(12.9898, 78.233))) * 43758.5453);
+    return mix(mix(a, b, t.x), mix(c, d, t.x), t.y) * 2.0 - 1.0;
+}
+
 void main()
 {
     uint color = floatBitsToUint(aColor);
@@ -349,8 +703,29 @@ void main()
     float g = float((color >>  8) & hex_FF);
     float b = float((color >> 16) & hex_FF);
     float a = float((color >> 24) & hex_FF);
+    if (converging == 1) {
+        // Each beam lands displaced on the screen, so each channel shows the source displaced by its offset. Rows go from the top.
+        vec2 texel = vec2(source_center.x + aOffset.x, source_center.y - aOffset.y);
+        vec2 size = vec2(textureSize(source_image, 0));
+        mat2 to_source = transpose(source_transform);
+        r = textureLod(source_image, (texel - vec2(1.0, -1.0) * (to_source * convergence_red)) / size, 0.0).r * 255.0;
+        g = textureLod(source_image, (texel - vec2(1.0, -1.0) * (to_source * convergence_green)) / size, 0.0).g * 255.0;
+        b = textureLod(source_image, (texel - vec2(1.0, -1.0) * (to_source * convergence_blue)) / size, 0.0).b * 255.0;
+    }
 
     vec4 vecColor = vec4(r * COLOR_FACTOR, g * COLOR_FACTOR, b * COLOR_FACTOR, a * COLOR_FACTOR);
+    if (defects == 1) {
+        // The instances follow the rows of the frame from the top, like the mask.
+        int mask_width = textureSize(defect_mask, 0).x;
+        vec4 defect = texelFetch(defect_mask, ivec2(gl_InstanceID % mask_width, gl_InstanceID / mask_width), 0);
+        vecColor.rgb = mix(vecColor.rgb, vec3(1.0), defect.a) * defect.rgb;
+    }
+    if (burn_in_visibility > 0.0) {
+        // The worn phosphor shows the old picture faintly, mostly over the darker parts of the new one.
+        int marks_width = textureSize(burn_in_marks, 0).x;
+        vec3 mark = texelFetch(burn_in_marks, ivec2(gl_InstanceID % marks_width, gl_InstanceID / marks_width), 0).rgb;
+        vecColor.rgb += mark * burn_in_visibility * (1.0 - vecColor.rgb);
+    }
 
     float height_mod = 0.5 * ((vecColor.r + vecColor.g + vecColor.b) / 4.0 + 0.25) + 0.5 * (max(max(vecColor.r, vecColor.g), vecColor.b) / 1.33 + 0.25);
 
@@ -358,27 +733,60 @@ void main()
 
     vec3 modPos = (1.0 - heightModifierFactor) * aPos + heightModifierFactor * vec3(aPos.x, aPos.y * height_mod, aPos.z);
 
-    vec3 pos = modPos / pixel_scale + vec3(aOffset * pixel_spread, 0);
+    vec3 pos = modPos / pixel_scale;
+    vec3 normal = aNormal;
+    if (pixel_rotation.y != 0.0) {
+        mat2 rotation = mat2(pixel_rotation.x, pixel_rotation.y, -pixel_rotation.y, pixel_rotation.x);
+        pos.xy = rotation * pos.xy;
+        normal.xy = rotation * normal.xy;
+    }
+    vec2 source_offset = aOffset;
+    if (mod(floor(aOffset.y), 2.0) == 1.0) {
+        // Poor interlace timing lets the lines of one field drift towards the other one.
+        source_offset.y -= interlace_line_pairing;
+    }
+    if (row_stagger != 0.0) {
+        source_offset.x += row_stagger * mod(floor(aOffset.y), 2.0);
+    }
+    if (hsync_wobble.x != 0.0) {
+        // A weak horizontal sync starts each line a bit early or late, and the error drifts slowly along the frame.
+        source_offset.x += hsync_wobble.x * wobbleNoise(vec2(floor(aOffset.y) * hsync_wobble.y, hsync_wobble.z));
+    }
+    if (vertical_roll.x != 0.0) {
+        source_offset.y = mod(source_offset.y + vertical_roll.x + 0.5 * vertical_roll.y, vertical_roll.y) - 0.5 * vertical_roll.y;
+    }
+    vec2 offset = source_transform * source_offset;
+    pos += vec3(offset * pixel_spread, 0);
+    FlatX = pos.x;
+    FlatY = pos.y;
 
     if (pixel_pulse > 0.0) {
-        float radius = length(aOffset);
+        float radius = length(offset);
         pos += vec3(0, 0, sin(pixel_pulse + sin(pixel_pulse * 0.1) * radius * 0.25) * 2.0);
     }
     if (screen_curvature > 0.0) {
-        float radius = length(aOffset);
+        float radius = length(offset * screen_curvature_axes);
         float normalized = radius * offset_inverse_max_length;
         pos.z -= sin(normalized) * screen_curvature * 100.0;
     }
     if (pixel_offset.x != 0.0 || pixel_offset.y != 0.0 || pixel_offset.z != 0.0) {
         pos += pixel_offset;
     }
+    ScreenPos = pos.xy;
+    if (magnet_strength != 0.0) {
+        vec2 magnet_delta = offset - magnet_center;
+        float magnet_falloff = magnet_strength * exp(-dot(magnet_delta, magnet_delta) / (magnet_radius * magnet_radius));
+        pos.xy += normalize(magnet_delta + vec2(0.0001)) * magnet_falloff * magnet_radius * 0.25 * pixel_spread;
+        ObjectColor.rgb = mix(ObjectColor.rgb, ObjectColor.gbr, clamp(abs(magnet_falloff), 0.0, 1.0));
+    }
 
     FragPos = pos;
-    Normal = aNormal;
+    Normal = normal;
     
     gl_Position = projection * view * vec4(FragPos, 1.0);
 
     ImagePos = aPos.xy + 0.5;
+    SourcePos = aOffset;
 }
 "#;
 
@@ -391,12 +799,22 @@ in vec3 Normal;
 in vec3 FragPos;
 in vec4 ObjectColor;
 in vec2 ImagePos;
+in float FlatX;
+in float FlatY;
+in vec2 ScreenPos;
+flat in vec2 SourcePos;
 
 uniform vec3 red;
 uniform vec3 green;
 uniform vec3 blue;
+uniform vec3 channel_gain;
+uniform vec3 channel_offset;
 
 uniform float gamma;
+uniform float black_pedestal;
+uniform int white_clipping;
+uniform float saturation;
+uniform float hue;
 
 uniform vec3 lightColor;
 uniform vec3 extraLight;
@@ -405,8 +823,42 @@ uniform float ambientStrength;
 uniform float contrastFactor;
 
 uniform sampler2D image;
-uniform float time;
+uniform float shadow_stagger;
+uniform int noise_seed;
 uniform float color_noise;
+uniform int linear_blending;
+uniform int aperture_grille;
+uniform vec2 damper_wire_y;
+uniform float damper_wire_opacity;
+uniform float damper_wire_width;
+uniform vec4 rolling_scan;
+uniform vec4 screen_matte;
+uniform vec4 reflection_edge;
+uniform vec2 interlace;
+uniform vec2 blemish;
+uniform int blemish_seed;
+uniform vec4 blemish_scratches[3];
+
+#include color_adjustment
+
+// Over-bright values roll off towards white from this point instead of clipping.
+const float shoulderStart = 0.8;
+
+vec3 clipWhite(vec3 color) {
+    if (white_clipping == 1) {
+        vec3 over = max(color - shoulderStart, 0.0);
+        return min(color, shoulderStart) + (1.0 - shoulderStart) * (1.0 - exp(-over / (1.0 - shoulderStart)));
+    } else if (white_clipping == 2) {
+        // An overdriven gun spills into the other ones, so saturated colors wash out to white.
+        vec3 excess = max(color - 1.0, 0.0);
+        return color + 0.5 * (excess.r + excess.g + excess.b);
+    }
+    return color;
+}
+
+vec3 srgbToLinear(vec3 color) {
+    return mix(color / 12.92, pow((color + 0.055) / 1.055, vec3(2.4)), step(0.04045, color));
+}
 
 uint hash( uint x ) {
     x += ( x << 10u );
@@ -430,17 +882,70 @@ float floatConstruct( uint m ) {
     return f - 1.0;                        // Range [0:1]
 }
 
-float random( vec3  v ) { return floatConstruct(hash(floatBitsToUint(v))); }
+// Three continuous phosphor stripes across the pixel, with a thin dark gap between them.
+vec3 apertureGrille(float x) {
+    float stripe = clamp(x, 0.0, 0.999) * 3.0;
+    float within = fract(stripe);
+    float edge = smoothstep(0.0, 0.12, within) * smoothstep(0.0, 0.12, 1.0 - within);
+    return vec3(equal(vec3(floor(stripe)), vec3(0.0, 1.0, 2.0))) * edge;
+}
+
+// Noise per source pixel and channel, so it doesn't depend on the output resolution.
+float noise( uint channel ) { return floatConstruct(hash(uvec3(uvec2(ivec2(floor(SourcePos)) + 32768), uint(noise_seed) * 3u + channel))); }
+
+// Unlike the noise, it only depends on the seed of the monitor, so every cell keeps its flaws from frame to frame.
+float blemishNoise( uint channel ) { return floatConstruct(hash(uvec3(uvec2(ivec2(floor(SourcePos)) + 32768), uint(blemish_seed) * 5u + channel + 0x8000000u))); }
+
+vec3 phosphorEfficiency() {
+    vec3 efficiency = 1.0 - blemish.x * 0.25 * vec3(blemishNoise(0u), blemishNoise(1u), blemishNoise(2u));
+    // A few subpixels are weak and fewer are dead, more of them as the monitor ages.
+    float defect = blemishNoise(3u);
+    float damage = defect < blemish.x * 0.0005 ? 1.0 : defect < blemish.x * 0.005 ? 0.5 : 0.0;
+    vec3 channel = vec3(equal(vec3(floor(blemishNoise(4u) * 3.0)), vec3(0.0, 1.0, 2.0)));
+    return efficiency * (1.0 - damage * channel);
+}
+
+float scratches() {
+    float darkening = 0.0;
+    for (int i = 0; i < 3; i++) {
+        vec4 scratch = blemish_scratches[i];
+        vec2 tangent = vec2(cos(scratch.z), sin(scratch.z));
+        vec2 delta = ScreenPos - scratch.xy;
+        float across = abs(dot(delta, vec2(-tangent.y, tangent.x)));
+        float along = abs(dot(delta, tangent));
+        darkening = max(darkening, (1.0 - smoothstep(0.0, blemish.y, across)) * (1.0 - smoothstep(0.8 * scratch.w, scratch.w, along)));
+    }
+    return 0.35 * blemish.x * darkening;
+}
 
 void main()
 {
     if (ObjectColor.a == 0.0) {
         discard;
     }
+    float reflectionFade = 1.0;
+    if (reflection_edge.w > 0.0) {
+        float beyondEdge = dot(ScreenPos, reflection_edge.xy) - reflection_edge.z;
+        if (beyondEdge < 0.0 || beyondEdge > reflection_edge.w) {
+            discard;
+        }
+        reflectionFade = 1.0 - beyondEdge / reflection_edge.w;
+    }
+
+    vec4 objectColor = ObjectColor;
+    objectColor.rgb = clamp(objectColor.rgb + black_pedestal, 0.0, 1.0);
+    if (saturation != 1.0 || hue != 0.0) {
+        objectColor.rgb = adjustColor(objectColor.rgb, saturation, hue);
+    }
+    if (linear_blending == 1) {
+        objectColor.rgb = srgbToLinear(objectColor.rgb);
+    }
 
+    // The stripes run the whole height of the screen, so the aperture grille skips the pixel shadow.
+    vec4 shadow = aperture_grille == 1 ? vec4(apertureGrille(ImagePos.x), 1.0) : texture(image, ImagePos + vec2(0.0, mod(floor(SourcePos.x), 2.0) * shadow_stagger));
     vec4 result;
     if (ambientStrength == 1.0) {
-        result = ObjectColor * vec4(lightColor, 1.0) * texture(image, ImagePos);
+        result = objectColor * vec4(lightColor, 1.0) * shadow;
     } else {
         vec3 norm = normalize(Normal);
         vec3 lightDir = normalize(lightPos - FragPos);
@@ -450,13 +955,38 @@ void main()
         float diff = max(dot(norm, lightDir), 0.0);
         vec3 diffuse = diff * lightColor;
         
-        result = ObjectColor * vec4(ambient + diffuse * (1.0 - ambientStrength), 1.0) * texture(image, ImagePos);
+        result = objectColor * vec4(ambient + diffuse * (1.0 - ambientStrength), 1.0) * shadow;
     }
     float contrastUmbral = 0.5;
-    result.r = (result.r - contrastUmbral) * contrastFactor + contrastFactor * contrastUmbral - color_noise/2.0 + color_noise * random(vec3(ImagePos, time * 0.5));
-    result.g = (result.g - contrastUmbral) * contrastFactor + contrastFactor * contrastUmbral - color_noise/2.0 + color_noise * random(vec3(ImagePos, time));
-    result.b = (result.b - contrastUmbral) * contrastFactor + contrastFactor * contrastUmbral - color_noise/2.0 + color_noise * random(vec3(ImagePos, time * 2.0));
-    result = result.r * vec4(red, result.a) + result.g * vec4(green, result.a) + result.b * vec4(blue, result.a) + vec4(extraLight, 0.0);
+    result.r = (result.r - contrastUmbral) * contrastFactor + contrastFactor * contrastUmbral - color_noise/2.0 + color_noise * noise(0u);
+    result.g = (result.g - contrastUmbral) * contrastFactor + contrastFactor * contrastUmbral - color_noise/2.0 + color_noise * noise(1u);
+    result.b = (result.b - contrastUmbral) * contrastFactor + contrastFactor * contrastUmbral - color_noise/2.0 + color_noise * noise(2u);
+    result = result.r * vec4(red, result.a) + result.g * vec4(green, result.a) + result.b * vec4(blue, result.a);
+    result.rgb = result.rgb * channel_gain + channel_offset + extraLight;
+    if (blemish.x > 0.0) {
+        result.rgb *= phosphorEfficiency() * (1.0 - scratches());
+    }
+    if (damper_wire_opacity > 0.0) {
+        vec2 distance = abs(vec2(FlatY) - damper_wire_y);
+        float wire = 1.0 - smoothstep(0.0, damper_wire_width, min(distance.x, distance.y));
+        result.rgb *= 1.0 - damper_wire_opacity * wire;
+    }
+    if (rolling_scan.w > 0.0) {
+        float distance = mod(abs(FlatY - rolling_scan.x), rolling_scan.z);
+        distance = min(distance, rolling_scan.z - distance);
+        float band = 1.0 - smoothstep(0.0, rolling_scan.y, distance);
+        result.rgb *= 1.0 - rolling_scan.w * (1.0 - band);
+    }
+    if (screen_matte.w > 0.0) {
+        // Distance to the rounded rectangle of the visible raster, everything beyond it is covered by the matte.
+        vec2 corner = abs(vec2(FlatX, FlatY)) - screen_matte.xy + screen_matte.z;
+        float outside = length(max(corner, 0.0)) + min(max(corner.x, corner.y), 0.0) - screen_matte.z;
+        result.rgb *= 1.0 - smoothstep(-screen_matte.w, 0.0, outside);
+    }
+    if (interlace.y < 1.0 && mod(floor(SourcePos.y), 2.0) != interlace.x) {
+        result.rgb *= interlace.y;
+    }
+    result.rgb = clipWhite(result.rgb) * reflectionFade;
     FragColor = vec4(pow(result.r, gamma), pow(result.g, gamma), pow(result.b, gamma), result.a);
 } 
 "#;
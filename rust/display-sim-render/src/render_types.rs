@@ -38,7 +38,7 @@ impl<GL: HasContext> std::clone::Clone for TextureBuffer<GL> {
 }
 
 impl<GL: HasContext> TextureBuffer<GL> {
-    fn new(gl: &GlowSafeAdapter<GL>, width: i32, height: i32, interpolation: u32) -> AppResult<TextureBuffer<GL>> {
+    pub fn new(gl: &GlowSafeAdapter<GL>, width: i32, height: i32, interpolation: u32) -> AppResult<TextureBuffer<GL>> {
         let framebuffer = Some(gl.create_framebuffer()?);
         gl.bind_framebuffer(glow::FRAMEBUFFER, framebuffer);
 
@@ -73,6 +73,12 @@ impl<GL: HasContext> TextureBuffer<GL> {
         self.texture
     }
 
+    pub fn set_interpolation(&self, gl: &GlowSafeAdapter<GL>, interpolation: u32) {
+        gl.bind_texture(glow::TEXTURE_2D, self.texture);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, interpolation as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, interpolation as i32);
+    }
+
     pub fn framebuffer(&self) -> Option<GL::Framebuffer> {
         self.framebuffer
     }
@@ -86,6 +92,8 @@ pub struct TextureBufferStack<GL: HasContext> {
     cursor: usize,
     max_cursor: usize,
     depthbuffer_active: bool,
+    pass_tracking: bool,
+    passes: Vec<&'static str>,
     gl: Rc<GlowSafeAdapter<GL>>,
 }
 
@@ -99,10 +107,19 @@ impl<GL: HasContext> TextureBufferStack<GL> {
             cursor: 0,
             max_cursor: 0,
             depthbuffer_active: false,
+            pass_tracking: cfg!(debug_assertions),
+            passes: vec![],
             gl,
         }
     }
 
+    // With pass tracking, every pop has to come from the same pass as the push it undoes, so a misuse
+    // is reported by the pass responsible instead of by whoever reads the wrong buffer later on.
+    pub fn set_pass_tracking(&mut self, enabled: bool) {
+        self.pass_tracking = enabled;
+        self.passes.clear();
+    }
+
     pub fn set_depthbuffer(&mut self, new_value: bool) -> AppResult<()> {
         if self.depthbuffer_active != new_value {
             self.depthbuffer_active = new_value;
@@ -131,9 +148,14 @@ impl<GL: HasContext> TextureBufferStack<GL> {
         Ok(())
     }
 
+    pub fn interpolation(&self) -> u32 {
+        self.interpolation
+    }
+
     fn reset_stack(&mut self) -> AppResult<()> {
         self.cursor = 0;
         self.max_cursor = 0;
+        self.passes.clear();
         for tb in self.stack.iter() {
             self.gl
                 .delete_framebuffer(tb.framebuffer().ok_or_else(|| Into::<String>::into("can't access framebuffer"))?);
@@ -144,7 +166,7 @@ impl<GL: HasContext> TextureBufferStack<GL> {
         Ok(())
     }
 
-    pub fn push(&mut self) -> AppResult<()> {
+    pub fn push(&mut self, pass: &'static str) -> AppResult<()> {
         if self.stack.len() == self.cursor {
             let tb = if self.depthbuffer_active {
                 TextureBuffer::new_with_depthbuffer(&*self.gl, self.width, self.height, self.interpolation)?
@@ -157,10 +179,24 @@ impl<GL: HasContext> TextureBufferStack<GL> {
         if self.cursor > self.max_cursor {
             self.max_cursor = self.cursor;
         }
+        if self.pass_tracking {
+            self.passes.push(pass);
+        }
         Ok(())
     }
 
-    pub fn pop(&mut self) -> AppResult<()> {
+    pub fn pop(&mut self, pass: &'static str) -> AppResult<()> {
+        if self.pass_tracking {
+            match self.passes.last() {
+                None => return Err(format!("Pass '{}' popped an empty texture buffer stack.", pass).into()),
+                Some(pushed_by) if *pushed_by != pass => {
+                    return Err(format!("Pass '{}' popped the texture buffer pushed by pass '{}'.", pass, pushed_by).into())
+                }
+                Some(_) => {
+                    self.passes.pop();
+                }
+            }
+        }
         self.get_current()?;
         self.cursor -= 1;
         Ok(())
@@ -190,6 +226,10 @@ impl<GL: HasContext> TextureBufferStack<GL> {
 
     pub fn assert_no_stack(&self) -> AppResult<()> {
         if self.cursor != 0 {
+            if self.pass_tracking {
+                let passes = self.passes.iter().map(|pass| format!("'{}'", pass)).collect::<Vec<_>>().join(", ");
+                return Err(format!("Texture buffer stack cursor not zero, '{}' instead. Still pushed by: {}.", self.cursor, passes).into());
+            }
             return Err(format!("Texture buffer stack cursor not zero, '{}' instead.", self.cursor).into());
         }
         Ok(())
@@ -15,6 +15,7 @@
 
 use crate::error::AppResult;
 use crate::shaders::{make_quad_vao, make_shader, TEXTURE_VERTEX_SHADER};
+use core::ui_controller::overlapping_blend_mode::OverlappingBlendModeOptions;
 
 use glow::GlowSafeAdapter;
 use glow::HasContext;
@@ -33,13 +34,23 @@ impl<GL: HasContext> RgbRender<GL> {
         Ok(RgbRender { vao, shader, gl })
     }
 
-    pub fn render(&self) {
+    pub fn render(&self, blend_mode: OverlappingBlendModeOptions, gain: [f32; 3]) {
         self.gl.bind_vertex_array(self.vao);
         self.gl.use_program(Some(self.shader));
 
         self.gl.uniform_1_i32(self.gl.get_uniform_location(self.shader, "redImage"), 0);
         self.gl.uniform_1_i32(self.gl.get_uniform_location(self.shader, "greenImage"), 1);
         self.gl.uniform_1_i32(self.gl.get_uniform_location(self.shader, "blueImage"), 2);
+        self.gl.uniform_1_i32(
+            self.gl.get_uniform_location(self.shader, "blendMode"),
+            match blend_mode {
+                OverlappingBlendModeOptions::Weighted => 0,
+                OverlappingBlendModeOptions::Additive => 1,
+                OverlappingBlendModeOptions::Max => 2,
+                OverlappingBlendModeOptions::Screen => 3,
+            },
+        );
+        self.gl.uniform_3_f32_slice(self.gl.get_uniform_location(self.shader, "gain"), &gain);
 
         self.gl.draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0);
     }
@@ -54,12 +65,24 @@ in vec2 TexCoord;
 uniform sampler2D redImage;
 uniform sampler2D greenImage;
 uniform sampler2D blueImage;
+uniform int blendMode;
+uniform vec3 gain;
 
 void main()
 {
-    vec4 red = texture(redImage, TexCoord);
-    vec4 green = texture(greenImage, TexCoord);
-    vec4 blue = texture(blueImage, TexCoord);
+    vec4 red = texture(redImage, TexCoord) * gain.r;
+    vec4 green = texture(greenImage, TexCoord) * gain.g;
+    vec4 blue = texture(blueImage, TexCoord) * gain.b;
+    if (blendMode == 1) {
+        FragColor = min(red + green + blue, 1.0);
+        return;
+    } else if (blendMode == 2) {
+        FragColor = max(max(red, green), blue);
+        return;
+    } else if (blendMode == 3) {
+        FragColor = 1.0 - (1.0 - red) * (1.0 - green) * (1.0 - blue);
+        return;
+    }
     FragColor = red * red.a + green * green.a + blue * blue.a;
 } 
 "#;
@@ -0,0 +1,129 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::error::AppResult;
+use crate::shaders::make_shader;
+use core::general_types::f32_to_u8;
+use core::room::ROOM_VERTEX_SIZE;
+
+use glow::GlowSafeAdapter;
+use glow::HasContext;
+use std::mem::size_of;
+use std::rc::Rc;
+
+pub struct RoomUniform<'a> {
+    pub view: &'a [f32; 16],
+    pub projection: &'a [f32; 16],
+    pub light_color: &'a [f32; 3],
+}
+
+pub struct RoomRender<GL: HasContext> {
+    vao: Option<GL::VertexArray>,
+    vbo: GL::Buffer,
+    shader: GL::Program,
+    mesh_version: u32,
+    vertex_count: i32,
+    gl: Rc<GlowSafeAdapter<GL>>,
+}
+
+impl<GL: HasContext> RoomRender<GL> {
+    pub fn new(gl: Rc<GlowSafeAdapter<GL>>) -> AppResult<RoomRender<GL>> {
+        let shader = make_shader(&*gl, ROOM_VERTEX_SHADER, ROOM_FRAGMENT_SHADER)?;
+
+        let vao = Some(gl.create_vertex_array()?);
+        gl.bind_vertex_array(vao);
+
+        let vbo = gl.create_buffer()?;
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(vbo));
+
+        let stride = (ROOM_VERTEX_SIZE * size_of::<f32>()) as i32;
+        let a_pos_position = gl.get_attrib_location(shader, "aPos");
+        gl.vertex_attrib_pointer_f32(a_pos_position, 3, glow::FLOAT, false, stride, 0);
+        gl.enable_vertex_attrib_array(a_pos_position);
+
+        let a_color_position = gl.get_attrib_location(shader, "aColor");
+        gl.vertex_attrib_pointer_f32(a_color_position, 3, glow::FLOAT, false, stride, 3 * size_of::<f32>() as i32);
+        gl.enable_vertex_attrib_array(a_color_position);
+
+        gl.bind_vertex_array(None);
+        Ok(RoomRender {
+            vao,
+            vbo,
+            shader,
+            mesh_version: 0,
+            vertex_count: 0,
+            gl,
+        })
+    }
+
+    // The mesh only changes with the room settings, so it's uploaded once per version.
+    pub fn load_mesh(&mut self, version: u32, mesh: &[f32]) {
+        if version == self.mesh_version {
+            return;
+        }
+        self.mesh_version = version;
+        self.vertex_count = (mesh.len() / ROOM_VERTEX_SIZE) as i32;
+        self.gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.vbo));
+        self.gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, f32_to_u8(mesh), glow::STATIC_DRAW);
+        self.gl.bind_buffer(glow::ARRAY_BUFFER, None);
+    }
+
+    // Drawn first, so everything else ends over it.
+    pub fn render(&self, uniforms: RoomUniform) {
+        if self.vertex_count == 0 {
+            return;
+        }
+        let gl = &self.gl;
+        let shader = self.shader;
+        gl.use_program(Some(shader));
+        gl.uniform_matrix_4_f32_slice(gl.get_uniform_location(shader, "view"), false, uniforms.view);
+        gl.uniform_matrix_4_f32_slice(gl.get_uniform_location(shader, "projection"), false, uniforms.projection);
+        gl.uniform_3_f32_slice(gl.get_uniform_location(shader, "lightColor"), uniforms.light_color);
+        gl.bind_vertex_array(self.vao);
+        gl.draw_arrays(glow::TRIANGLES, 0, self.vertex_count);
+        gl.bind_vertex_array(None);
+    }
+}
+
+pub const ROOM_VERTEX_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec3 aColor;
+
+out vec3 Color;
+
+uniform mat4 view;
+uniform mat4 projection;
+
+void main()
+{
+    Color = aColor;
+    gl_Position = projection * view * vec4(aPos, 1.0);
+}
+"#;
+
+pub const ROOM_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+out vec4 FragColor;
+in vec3 Color;
+
+uniform vec3 lightColor;
+
+void main()
+{
+    FragColor = vec4(Color * lightColor, 1.0);
+} 
+"#;
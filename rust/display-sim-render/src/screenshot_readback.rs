@@ -0,0 +1,114 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::error::AppResult;
+use core::app_events::AppEventDispatcher;
+
+use glow::GlowSafeAdapter;
+use glow::HasContext;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+struct PendingReadback<GL: HasContext> {
+    buffer: GL::Buffer,
+    fence: GL::Fence,
+    width: i32,
+    height: i32,
+    tag: Option<String>,
+}
+
+struct ReadyReadback {
+    width: i32,
+    height: i32,
+    pixels: Box<[u8]>,
+    tag: Option<String>,
+}
+
+// Reads the screenshot framebuffer into a PIXEL_PACK buffer and waits on a fence over
+// the following frames, so the pixels are only fetched once the GPU is done with them.
+// Several readbacks can be in flight, as a screenshot bracket captures one every few frames.
+pub struct ScreenshotReadback<GL: HasContext> {
+    pending: VecDeque<PendingReadback<GL>>,
+    gl: Rc<GlowSafeAdapter<GL>>,
+}
+
+impl<GL: HasContext> ScreenshotReadback<GL> {
+    pub fn new(gl: Rc<GlowSafeAdapter<GL>>) -> ScreenshotReadback<GL> {
+        ScreenshotReadback { pending: VecDeque::new(), gl }
+    }
+
+    pub fn request(&mut self, dispatcher: &dyn AppEventDispatcher, width: i32, height: i32, tag: Option<String>) -> AppResult<()> {
+        let buffer = self.gl.create_buffer()?;
+        self.gl.bind_buffer(glow::PIXEL_PACK_BUFFER, Some(buffer));
+        self.gl.buffer_data_size(glow::PIXEL_PACK_BUFFER, width * height * 4, glow::STREAM_READ);
+        dispatcher.request_screenshot_readback(width, height)?;
+        self.gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
+        let fence = self.gl.fence_sync(glow::SYNC_GPU_COMMANDS_COMPLETE, 0)?;
+        self.pending.push_back(PendingReadback {
+            buffer,
+            fence,
+            width,
+            height,
+            tag,
+        });
+        Ok(())
+    }
+
+    pub fn poll(&mut self, dispatcher: &dyn AppEventDispatcher) -> AppResult<()> {
+        while let Some(ReadyReadback {
+            width,
+            height,
+            mut pixels,
+            tag,
+        }) = self.take_ready()
+        {
+            dispatcher.dispatch_screenshot(width, height, &mut pixels, tag.as_deref())?;
+        }
+        Ok(())
+    }
+
+    // Same as poll, but the pixels are returned to the caller instead of being downloaded as a screenshot.
+    pub fn take_pixels(&mut self) -> Option<(i32, i32, Box<[u8]>)> {
+        self.take_ready().map(|ready| (ready.width, ready.height, ready.pixels))
+    }
+
+    // Readbacks are requested in order, so only the oldest one needs to be checked.
+    fn take_ready(&mut self) -> Option<ReadyReadback> {
+        let ready = match self.pending.front() {
+            Some(pending) => self.gl.get_sync_status(pending.fence) == glow::SIGNALED,
+            None => false,
+        };
+        if !ready {
+            return None;
+        }
+        let mut pending = self.pending.pop_front()?;
+        let mut pixels: Box<[u8]> = vec![0; (pending.width * pending.height * 4) as usize].into_boxed_slice();
+        self.gl.bind_buffer(glow::PIXEL_PACK_BUFFER, Some(pending.buffer));
+        self.gl.get_buffer_sub_data(glow::PIXEL_PACK_BUFFER, 0, &mut pixels);
+        self.gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
+        let ready = ReadyReadback {
+            width: pending.width,
+            height: pending.height,
+            pixels,
+            tag: pending.tag.take(),
+        };
+        self.release(pending);
+        Some(ready)
+    }
+
+    fn release(&self, pending: PendingReadback<GL>) {
+        self.gl.delete_sync(pending.fence);
+        self.gl.delete_buffer(pending.buffer);
+    }
+}
@@ -51,6 +51,11 @@ fn link_shader<'a, GL: HasContext + 'a, T: IntoIterator<Item = &'a GL::Shader>>(
     }
 }
 
+/// Puts the shared color adjustment functions where the fragment shader asks for them.
+pub fn with_color_adjustment(fragment_shader: &str) -> String {
+    fragment_shader.replace("#include color_adjustment", COLOR_ADJUSTMENT_FUNCTIONS)
+}
+
 pub fn make_quad_vao<GL: HasContext>(gl: &GlowSafeAdapter<GL>, shader: &GL::Program) -> AppResult<Option<GL::VertexArray>> {
     let vao = gl.create_vertex_array()?;
     gl.bind_vertex_array(Some(vao));
@@ -109,16 +114,16 @@ void main()
 }
 "#;
 
-pub const TEXTURE_FRAGMENT_SHADER: &str = r#"#version 300 es
-precision highp float;
-
-out vec4 FragColor;
-in vec2 TexCoord;
-
-uniform sampler2D image;
-
-void main()
-{
-    FragColor = texture(image, TexCoord);
-} 
+/// Saturation and hue work on the chroma in YIQ, the same space the composite signal carries it in.
+pub const COLOR_ADJUSTMENT_FUNCTIONS: &str = r#"
+const mat3 rgbToYiq = mat3(0.299, 0.596, 0.211, 0.587, -0.274, -0.523, 0.114, -0.322, 0.312);
+const mat3 yiqToRgb = mat3(1.0, 1.0, 1.0, 0.956, -0.272, -1.106, 0.621, -0.647, 1.703);
+
+vec3 adjustColor(vec3 color, float saturation, float hue) {
+    vec3 yiq = rgbToYiq * color;
+    float c = cos(hue);
+    float s = sin(hue);
+    yiq.yz = saturation * vec2(yiq.y * c - yiq.z * s, yiq.y * s + yiq.z * c);
+    return clamp(yiqToRgb * yiq, 0.0, 1.0);
+}
 "#;
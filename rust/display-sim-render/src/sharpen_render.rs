@@ -0,0 +1,104 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::error::AppResult;
+use crate::render_types::TextureBuffer;
+use crate::shaders::{make_quad_vao, make_shader, TEXTURE_VERTEX_SHADER};
+
+use glow::GlowSafeAdapter;
+use glow::HasContext;
+use std::rc::Rc;
+
+pub struct SharpenRender<GL: HasContext> {
+    vao: Option<GL::VertexArray>,
+    shader: GL::Program,
+    // Viewport sized, so the sharpening works on the pixels of the display and not on the internal resolution ones.
+    buffer: Option<TextureBuffer<GL>>,
+    gl: Rc<GlowSafeAdapter<GL>>,
+}
+
+impl<GL: HasContext> SharpenRender<GL> {
+    pub fn new(gl: Rc<GlowSafeAdapter<GL>>) -> AppResult<SharpenRender<GL>> {
+        let shader = make_shader(&*gl, TEXTURE_VERTEX_SHADER, SHARPEN_FRAGMENT_SHADER)?;
+        let vao = make_quad_vao(&*gl, &shader)?;
+        Ok(SharpenRender { vao, shader, buffer: None, gl })
+    }
+
+    // Binds the buffer the upscaled picture has to be drawn into before sharpening it. It's made again when the
+    // viewport changes its size.
+    pub fn bind_target(&mut self, width: i32, height: i32) -> AppResult<()> {
+        let stale = match &self.buffer {
+            Some(buffer) => buffer.width != width || buffer.height != height,
+            None => true,
+        };
+        if stale {
+            if let Some(buffer) = self.buffer.take() {
+                self.gl
+                    .delete_framebuffer(buffer.framebuffer().ok_or_else(|| Into::<String>::into("can't access framebuffer"))?);
+                self.gl
+                    .delete_texture(buffer.texture().ok_or_else(|| Into::<String>::into("can't access texture"))?);
+            }
+            self.buffer = Some(TextureBuffer::new(&*self.gl, width, height, glow::NEAREST)?);
+        }
+        let buffer = self.buffer.as_ref().ok_or_else(|| Into::<String>::into("can't access sharpen buffer"))?;
+        self.gl.bind_framebuffer(glow::FRAMEBUFFER, buffer.framebuffer());
+        self.gl.viewport(0, 0, width, height);
+        Ok(())
+    }
+
+    // Writes the target to the bound framebuffer sharpened with contrast adaptive sharpening. The halo suppression
+    // keeps every pixel within the range of its neighbours, so the edges don't get bright or dark outlines.
+    pub fn render(&self, strength: f32, halo_suppression: f32) {
+        self.gl.use_program(Some(self.shader));
+        self.gl.uniform_1_f32(self.gl.get_uniform_location(self.shader, "strength"), strength);
+        self.gl
+            .uniform_1_f32(self.gl.get_uniform_location(self.shader, "halo_suppression"), halo_suppression);
+        self.gl.bind_vertex_array(self.vao);
+        self.gl.bind_texture(glow::TEXTURE_2D, self.buffer.as_ref().and_then(|buffer| buffer.texture()));
+        self.gl.draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0);
+    }
+}
+
+pub const SHARPEN_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+out vec4 FragColor;
+in vec2 TexCoord;
+
+uniform sampler2D image;
+uniform float strength;
+uniform float halo_suppression;
+
+vec3 fetch(ivec2 texel) {
+    return texelFetch(image, clamp(texel, ivec2(0), textureSize(image, 0) - 1), 0).rgb;
+}
+
+void main()
+{
+    ivec2 texel = ivec2(gl_FragCoord.xy);
+    vec3 up = fetch(texel + ivec2(0, 1));
+    vec3 left = fetch(texel + ivec2(-1, 0));
+    vec3 center = fetch(texel);
+    vec3 right = fetch(texel + ivec2(1, 0));
+    vec3 down = fetch(texel + ivec2(0, -1));
+    vec3 lowest = min(center, min(min(up, down), min(left, right)));
+    vec3 highest = max(center, max(max(up, down), max(left, right)));
+    // Flat and already contrasted areas get less sharpening, so noise and hard edges aren't pushed further.
+    vec3 amount = sqrt(clamp(min(lowest, 2.0 - highest) / max(highest, vec3(0.0001)), 0.0, 1.0));
+    vec3 weight = amount * (-1.0 / mix(8.0, 5.0, strength));
+    vec3 sharpened = ((up + left + right + down) * weight + center) / (1.0 + 4.0 * weight);
+    sharpened = mix(sharpened, clamp(sharpened, lowest, highest), halo_suppression);
+    FragColor = vec4(clamp(sharpened, 0.0, 1.0), 1.0);
+}
+"#;
@@ -13,17 +13,29 @@
  * You should have received a copy of the GNU Affero General Public License
  * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
 
+use crate::bezel_render::BezelUniform;
+use crate::bloom_render::BLOOM_BLUR_PASSES;
+use crate::case_render::CaseUniform;
 use crate::error::AppResult;
+use crate::glass_render::GlassUniform;
+use crate::internal_resolution_render::{OutputUniform, Resampling};
 use crate::pixels_render::PixelsUniform;
+use crate::room_render::RoomUniform;
 use crate::simulation_render_state::Materials;
+use core::moire::measure_moire;
 use core::simulation_context::SimulationContext;
 use core::simulation_core_state::Resources;
-use core::ui_controller::{color_channels::ColorChannelsOptions, texture_interpolation::TextureInterpolationOptions};
+use core::ui_controller::layers::{Layer, LayerKind};
+use core::ui_controller::transition::TransitionOptions;
+use core::ui_controller::{
+    color_channels::ColorChannelsOptions,
+    dithering::DitheringOptions,
+    texture_interpolation::{OutputInterpolationOptions, SourceInterpolationOptions, TextureInterpolationOptions},
+};
 
 use glow::GlowSafeAdapter;
 
 pub struct SimulationDrawer<'a> {
-    #[allow(dead_code)]
     ctx: &'a dyn SimulationContext,
     materials: &'a mut Materials,
     res: &'a Resources,
@@ -44,7 +56,12 @@ impl<'a> SimulationDrawer<'a> {
         let output = &self.res.main.render;
 
         let materials = &mut self.materials;
-        let gl = &materials.gl;
+        let gl = materials.gl.clone();
+
+        materials.screenshot_readback.poll(self.ctx.dispatcher())?;
+        if let Some((width, height, pixels)) = materials.moire_readback.take_pixels() {
+            materials.moire_score = Some(measure_moire(&pixels, width as usize, height as usize));
+        }
 
         let resolution_width = filters.internal_resolution.width();
         let resolution_height = filters.internal_resolution.height();
@@ -52,9 +69,34 @@ impl<'a> SimulationDrawer<'a> {
         let viewport_width = self.res.video.viewport_size.width;
         let viewport_height = self.res.video.viewport_size.height;
 
+        materials
+            .pixels_render
+            .load_defect_mask(output.defect_mask_version, &output.defect_settings, &output.defect_mask);
         if self.res.video.needs_buffer_data_load {
-            materials.pixels_render.load_image(&self.res.video);
+            materials.frame_luminance = Some(materials.pixels_render.load_image(
+                &self.res.video,
+                self.res.procedural_source.active_frame(),
+                &output.palette.0,
+                self.res.subtitles.active_text(&self.res.video),
+                output.horizontal_band_limit,
+                output.signal,
+                output.response_blend_factors,
+            ));
+        }
+
+        let [burn_in_blend, burn_in_visibility] = output.burn_in;
+        if burn_in_blend > 0.0 || burn_in_visibility > 0.0 {
+            // The marks follow the source pixels, so they stay in place however the screen is moved around.
+            let (width, height) = materials.pixels_render.source_size();
+            let source = materials.pixels_render.source_texture();
+            materials
+                .burn_in_render
+                .update(source, width as i32, height as i32, burn_in_blend, output.noise_seed)?;
         }
+        materials.pixels_render.set_burn_in(
+            if burn_in_visibility > 0.0 { materials.burn_in_render.texture() } else { None },
+            burn_in_visibility,
+        );
 
         materials.main_buffer_stack.set_depthbuffer(output.pixel_have_depth)?;
         materials.main_buffer_stack.set_resolution(resolution_width, resolution_height)?;
@@ -62,9 +104,13 @@ impl<'a> SimulationDrawer<'a> {
             TextureInterpolationOptions::Linear => glow::LINEAR,
             TextureInterpolationOptions::Nearest => glow::NEAREST,
         })?;
+        materials.pixels_render.set_source_interpolation(match filters.source_interpolation.value {
+            SourceInterpolationOptions::Linear => glow::LINEAR,
+            SourceInterpolationOptions::Nearest => glow::NEAREST,
+        });
 
-        materials.main_buffer_stack.push()?;
-        materials.main_buffer_stack.push()?;
+        materials.main_buffer_stack.push("output")?;
+        materials.main_buffer_stack.push("foreground")?;
         materials.main_buffer_stack.bind_current()?;
 
         gl.clear_color(0.0, 0.0, 0.0, 0.0);
@@ -72,6 +118,8 @@ impl<'a> SimulationDrawer<'a> {
 
         let view = self.res.camera.get_view();
         let position = self.res.camera.get_position();
+        let interlace = interlace_uniform(self.res);
+        let vertical_roll = vertical_roll_uniform(self.res);
 
         let projection = if self.res.screenshot_trigger.is_triggered {
             self.res.camera.get_projection(resolution_width as f32, resolution_height as f32)
@@ -79,50 +127,81 @@ impl<'a> SimulationDrawer<'a> {
             self.res.camera.get_projection(viewport_width as f32, viewport_height as f32)
         };
 
-        for hl_idx in 0..filters.horizontal_lpp.value {
-            for vl_idx in 0..filters.vertical_lpp.value {
+        for hl_idx in 0..filters.horizontal_lpp.line_passes() {
+            for vl_idx in 0..filters.vertical_lpp.line_passes() {
                 for color_idx in 0..output.color_splits {
                     if let ColorChannelsOptions::Overlapping = filters.color_channels.value {
-                        materials.main_buffer_stack.push()?;
+                        materials.main_buffer_stack.push("overlapping channel")?;
                         materials.main_buffer_stack.bind_current()?;
                         if vl_idx == 0 && hl_idx == 0 {
                             gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
                         }
                     }
-                    materials.pixels_render.render(PixelsUniform {
-                        shadow_kind: filters.pixel_shadow_shape_kind.value.value,
-                        geometry_kind: filters.pixels_geometry_kind.value,
-                        view: &matrix_to_16_f32(view),
-                        projection: &matrix_to_16_f32(projection),
-                        ambient_strength: output.ambient_strength,
-                        contrast_factor: filters.extra_contrast.value,
-                        light_color: &output.light_color[color_idx],
-                        extra_light: &output.extra_light,
-                        light_pos: &vec_to_3_f32(position),
-                        screen_curvature: output.screen_curvature_factor,
-                        pixel_spread: &output.pixel_spread,
-                        pixel_scale: &output
-                            .pixel_scale_foreground
-                            .get(vl_idx * filters.horizontal_lpp.value + hl_idx)
-                            .expect("Bad pixel_scale_foreground")[color_idx],
-                        pixel_pulse: output.pixels_pulse,
-                        pixel_offset: &output
-                            .pixel_offset_foreground
-                            .get(vl_idx * filters.horizontal_lpp.value + hl_idx)
-                            .expect("Bad pixel_offset_foreground")[color_idx],
-                        rgb_red: &output.rgb_red,
-                        rgb_green: &output.rgb_green,
-                        rgb_blue: &output.rgb_blue,
-                        color_gamma: output.color_gamma,
-                        color_noise: output.color_noise,
-                        time: output.time as f32,
-                        height_modifier_factor: output.height_modifier_factor,
-                    });
+                    materials.pixels_render.render(with_transition(
+                        self.res,
+                        PixelsUniform {
+                            shadow_kind: filters.pixel_shadow_shape_kind.value.value,
+                            geometry_kind: filters.pixels_geometry_kind.value,
+                            view: &matrix_to_16_f32(view),
+                            projection: &matrix_to_16_f32(projection),
+                            ambient_strength: output.ambient_strength,
+                            contrast_factor: filters.extra_contrast.value,
+                            light_color: &output.light_color[color_idx],
+                            extra_light: &output.extra_light,
+                            light_pos: &vec_to_3_f32(position),
+                            screen_curvature: output.screen_curvature_factor,
+                            screen_curvature_axes: &output.screen_curvature_axes,
+                            pixel_spread: &output.pixel_spread,
+                            pixel_rotation: &output.pixel_rotation,
+                            source_transform: &output.source_transform,
+                            damper_wire_y: &output.damper_wire_y,
+                            damper_wire_opacity: output.damper_wire_opacity,
+                            damper_wire_width: output.damper_wire_width,
+                            rolling_scan: &output.rolling_scan,
+                            screen_matte: &output.screen_matte,
+                            reflection_edge: &[0.0, 0.0, 0.0, 0.0],
+                            pixel_scale: &output
+                                .pixel_scale_foreground
+                                .get(vl_idx * filters.horizontal_lpp.line_passes() + hl_idx)
+                                .expect("Bad pixel_scale_foreground")[color_idx],
+                            pixel_pulse: output.pixels_pulse,
+                            pixel_offset: &output
+                                .pixel_offset_foreground
+                                .get(vl_idx * filters.horizontal_lpp.line_passes() + hl_idx)
+                                .expect("Bad pixel_offset_foreground")[color_idx],
+                            rgb_red: &output.rgb_red,
+                            rgb_green: &output.rgb_green,
+                            rgb_blue: &output.rgb_blue,
+                            color_gamma: output.color_gamma,
+                            black_pedestal: output.black_pedestal,
+                            white_clipping: output.white_clipping,
+                            color_saturation: output.color_saturation,
+                            color_hue: output.color_hue,
+                            channel_gain: &output.channel_gain,
+                            channel_offset: &output.channel_offset,
+                            color_noise: output.color_noise,
+                            convergence: &output.convergence,
+                            interlace: &interlace,
+                            interlace_line_pairing: output.interlace_line_pairing,
+                            hsync_wobble: &output.hsync_wobble,
+                            vertical_roll: &vertical_roll,
+                            row_stagger: output.pixel_row_stagger,
+                            linear_blending: output.linear_blending,
+                            noise_seed: output.noise_seed,
+                            blemish: &output.blemish,
+                            blemish_seed: output.blemish_seed,
+                            blemish_scratches: &output.blemish_scratches,
+                            height_modifier_factor: output.height_modifier_factor,
+                            magnet_center: &output.magnet_center,
+                            magnet_radius: output.magnet_radius,
+                            magnet_strength: output.magnet_strength,
+                        },
+                    ));
                 }
                 if let ColorChannelsOptions::Overlapping = filters.color_channels.value {
-                    materials.main_buffer_stack.pop()?;
-                    materials.main_buffer_stack.pop()?;
-                    materials.main_buffer_stack.pop()?;
+                    materials.main_buffer_stack.pop("overlapping channel")?;
+                    materials.main_buffer_stack.pop("overlapping channel")?;
+                    materials.main_buffer_stack.pop("overlapping channel")?;
                 }
             }
         }
@@ -136,56 +215,55 @@ impl<'a> SimulationDrawer<'a> {
             gl.active_texture(glow::TEXTURE0 + 2);
             gl.bind_texture(glow::TEXTURE_2D, materials.main_buffer_stack.get_nth(3)?.texture());
 
-            materials.rgb_render.render();
+            materials.rgb_render.render(
+                filters.overlapping_blend_mode.value,
+                [
+                    filters.overlapping_gain_red.value,
+                    filters.overlapping_gain_green.value,
+                    filters.overlapping_gain_blue.value,
+                ],
+            );
 
             gl.active_texture(glow::TEXTURE0 + 0);
         }
 
-        materials.main_buffer_stack.push()?;
+        materials.main_buffer_stack.push("background layers")?;
         materials.main_buffer_stack.bind_current()?;
         gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
 
+        let pixels_index = output.layers.iter().position(|layer| layer.kind == LayerKind::SourcePixels);
+        let (layers_below, layers_above) = match pixels_index {
+            Some(index) => (&output.layers[..index], &output.layers[index + 1..]),
+            None => (&output.layers[..], &[][..]),
+        };
+        let foreground_opacity = pixels_index.map_or(0.0, |index| output.layers[index].opacity) * filters.foreground_opacity.value;
+        let layer_camera = LayerCamera {
+            view: matrix_to_16_f32(view),
+            floor_view: matrix_to_16_f32(view * glm::translation(&glm::vec3(0.0, 2.0 * output.floor_y, 0.0)) * glm::scaling(&glm::vec3(1.0, -1.0, 1.0))),
+            projection: matrix_to_16_f32(projection),
+            light_pos: vec_to_3_f32(position),
+        };
+
         if output.showing_background {
-            materials.bg_buffer_stack.set_resolution(1920 / 2, 1080 / 2)?;
-            materials.bg_buffer_stack.set_depthbuffer(false)?;
+            let background_scale = filters.background_resolution_scale.value;
+            materials.bg_buffer_stack.set_resolution(
+                ((viewport_width as f32 * background_scale) as i32).max(1),
+                ((viewport_height as f32 * background_scale) as i32).max(1),
+            )?;
+            materials.bg_buffer_stack.set_depthbuffer(output.background_have_depth)?;
             materials.bg_buffer_stack.set_interpolation(glow::LINEAR)?;
-            materials.bg_buffer_stack.push()?;
-            materials.bg_buffer_stack.bind_current()?;
-            gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
-            for hl_idx in 0..filters.horizontal_lpp.value {
-                for vl_idx in 0..filters.vertical_lpp.value {
-                    materials.pixels_render.render(PixelsUniform {
-                        shadow_kind: 0,
-                        geometry_kind: filters.pixels_geometry_kind.value,
-                        view: &matrix_to_16_f32(view),
-                        projection: &matrix_to_16_f32(projection),
-                        ambient_strength: output.ambient_strength,
-                        contrast_factor: filters.extra_contrast.value,
-                        light_color: &output.light_color_background,
-                        extra_light: &[0.0, 0.0, 0.0],
-                        light_pos: &vec_to_3_f32(position),
-                        pixel_spread: &output.pixel_spread,
-                        pixel_scale: &output.pixel_scale_background[vl_idx * filters.horizontal_lpp.value + hl_idx],
-                        screen_curvature: output.screen_curvature_factor,
-                        pixel_pulse: output.pixels_pulse,
-                        pixel_offset: &output.pixel_offset_background[vl_idx * filters.horizontal_lpp.value + hl_idx],
-                        rgb_red: &output.rgb_red,
-                        rgb_green: &output.rgb_green,
-                        rgb_blue: &output.rgb_blue,
-                        color_gamma: output.color_gamma,
-                        color_noise: output.color_noise,
-                        time: output.time as f32,
-                        height_modifier_factor: 0.0,
-                    });
-                }
-            }
-            let source = (*materials.bg_buffer_stack.get_current()?).clone();
-            let target = materials.main_buffer_stack.get_current()?;
-            materials.blur_render.render(&mut materials.bg_buffer_stack, &source, &target, 6)?;
-            materials.bg_buffer_stack.pop()?;
         }
-        materials.main_buffer_stack.pop()?;
-        materials.main_buffer_stack.pop()?;
+        materials.room_render.load_mesh(output.room_mesh_version, &output.room_mesh);
+        materials.room_render.render(RoomUniform {
+            view: &layer_camera.view,
+            projection: &layer_camera.projection,
+            light_color: &output.room_light,
+        });
+        for layer in layers_below {
+            draw_layer(materials, self.res, layer, &layer_camera)?;
+        }
+        materials.main_buffer_stack.pop("background layers")?;
+        materials.main_buffer_stack.pop("foreground")?;
         materials.main_buffer_stack.bind_current()?;
         gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
 
@@ -193,37 +271,206 @@ impl<'a> SimulationDrawer<'a> {
         gl.bind_texture(glow::TEXTURE_2D, materials.main_buffer_stack.get_nth(1)?.texture());
         gl.active_texture(glow::TEXTURE0 + 1);
         gl.bind_texture(glow::TEXTURE_2D, materials.main_buffer_stack.get_nth(2)?.texture());
-        materials.background_render.render();
+        materials.background_render.render(filters.layering_blend_mode.value, foreground_opacity, None);
         gl.active_texture(glow::TEXTURE0 + 0);
 
-        if filters.blur_passes.value > 0 {
+        if filters.blur_passes.value > 0 && materials.background_render.has_mask() {
+            // The blur goes to a spare buffer above the foreground and background layers,
+            // so they can be composed again mixing in the blurred result where the mask asks for it.
+            let source = materials.main_buffer_stack.get_current()?.clone();
+            materials.main_buffer_stack.push("mask blur")?;
+            materials.main_buffer_stack.push("mask blur")?;
+            materials.main_buffer_stack.push("mask blur")?;
+            let blurred = materials.main_buffer_stack.get_current()?.clone();
+            materials
+                .blur_render
+                .render(&mut materials.main_buffer_stack, &source, &blurred, filters.blur_passes.value)?;
+            materials.main_buffer_stack.pop("mask blur")?;
+            materials.main_buffer_stack.pop("mask blur")?;
+            materials.main_buffer_stack.pop("mask blur")?;
+            materials.main_buffer_stack.bind_current()?;
+            gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
+
+            gl.active_texture(glow::TEXTURE0 + 0);
+            gl.bind_texture(glow::TEXTURE_2D, materials.main_buffer_stack.get_nth(1)?.texture());
+            gl.active_texture(glow::TEXTURE0 + 1);
+            gl.bind_texture(glow::TEXTURE_2D, materials.main_buffer_stack.get_nth(2)?.texture());
+            materials
+                .background_render
+                .render(filters.layering_blend_mode.value, foreground_opacity, blurred.texture());
+            gl.active_texture(glow::TEXTURE0 + 0);
+        } else if filters.blur_passes.value > 0 {
             let target = materials.main_buffer_stack.get_current()?.clone();
             materials
                 .blur_render
                 .render(&mut materials.main_buffer_stack, &target, &target, filters.blur_passes.value)?;
         }
 
-        materials.screenshot_pixels = None;
+        for layer in layers_above {
+            draw_layer(materials, self.res, layer, &layer_camera)?;
+        }
+
+        materials.bezel_render.render(BezelUniform {
+            view: &layer_camera.view,
+            projection: &layer_camera.projection,
+            position: &output.bezel_position,
+            height: output.bezel_height,
+        });
+
+        if output.glass_reflectivity > 0.0 {
+            materials.glass_render.render(GlassUniform {
+                view: &layer_camera.view,
+                projection: &layer_camera.projection,
+                camera_pos: &layer_camera.light_pos,
+                size: &output.glass_size,
+                environment_top: &output.glass_environment[0],
+                environment_bottom: &output.glass_environment[1],
+                reflectivity: output.glass_reflectivity,
+            });
+        }
+
+        if output.bloom[1] > 0.0 {
+            // The bright pass is extracted and blurred in a spare buffer, and then added back over the composition.
+            let source = materials.main_buffer_stack.get_current()?.clone();
+            materials.main_buffer_stack.push("bloom")?;
+            materials.main_buffer_stack.bind_current()?;
+            gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
+            let bright = materials.main_buffer_stack.get_current()?.clone();
+            materials.bloom_render.extract(source.texture(), output.bloom[0]);
+            materials
+                .blur_render
+                .render(&mut materials.main_buffer_stack, &bright, &bright, BLOOM_BLUR_PASSES)?;
+            materials.main_buffer_stack.pop("bloom")?;
+            materials.main_buffer_stack.bind_current()?;
+            materials.bloom_render.combine(bright.texture(), output.bloom[1]);
+        }
+
+        if filters.vignette_strength.value > 0.0 {
+            materials.vignette_render.render(filters.vignette_strength.value, filters.vignette_radius.value);
+        }
+
+        let [static_density, static_seed, static_bars] = output.analog_static;
+        if static_density > 0.0 {
+            materials.analog_static_render.render(static_density, static_seed, static_bars);
+        }
+
+        if output.dithering != DitheringOptions::Off {
+            // The main buffer has the resolution of the emulated panel, so the pattern lands on its pixels.
+            let source = materials.main_buffer_stack.get_current()?.clone();
+            materials.main_buffer_stack.push("dithering")?;
+            materials.main_buffer_stack.bind_current()?;
+            gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
+            let dithered = materials.main_buffer_stack.get_current()?.clone();
+            materials
+                .dither_render
+                .render(source.texture(), output.dithering, output.dithering_levels, output.linear_blending);
+            materials.main_buffer_stack.pop("dithering")?;
+            materials.main_buffer_stack.bind_current()?;
+            materials.internal_resolution_render.copy(dithered.texture());
+        }
 
         if self.res.screenshot_trigger.is_triggered {
-            let pixels: Box<[u8]> = vec![0; (resolution_width * resolution_height * 4) as usize].into_boxed_slice();
-            materials.screenshot_pixels = Some(pixels);
-            match materials.screenshot_pixels {
-                Some(ref mut pixels) => self.ctx.dispatcher().dispatch_screenshot(resolution_width, resolution_height, pixels)?,
-                None => return Err("Screenshot failed because a bad bug right here.".into()),
+            // The flicker is left out, a screenshot is a single frame and it would only come out dimmer.
+            let output_pass = output.linear_blending
+                || output.white_point != [1.0, 1.0, 1.0]
+                || output.output_gamma != 1.0
+                || output.contrast_curve != [0.0, 0.0]
+                || output.phosphor_matrix.is_some()
+                || output.screen_barrel != 0.0;
+            if output_pass {
+                materials.main_buffer_stack.push("screenshot output")?;
+                materials.main_buffer_stack.bind_current()?;
+                gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
+                materials.internal_resolution_render.render(
+                    materials.main_buffer_stack.get_nth(-1)?.texture(),
+                    &OutputUniform {
+                        encode_srgb: output.linear_blending,
+                        flicker: 0.0,
+                        white_point: &output.white_point,
+                        gamma: output.output_gamma,
+                        contrast_curve: &output.contrast_curve,
+                        phosphor_matrix: output.phosphor_matrix.as_ref(),
+                        barrel: output.screen_barrel,
+                        resampling: Resampling::Hardware,
+                    },
+                );
             }
-            materials.main_buffer_stack.pop()?;
+            // The overlays go into the screenshot as well, so it documents the values it was taken with.
+            materials
+                .hud_render
+                .render(&output.hud_lines, resolution_width as u32, resolution_height as u32);
+            materials.screenshot_readback.request(
+                self.ctx.dispatcher(),
+                resolution_width,
+                resolution_height,
+                self.res.screenshot_trigger.tag.clone(),
+            )?;
+            if output_pass {
+                materials.main_buffer_stack.pop("screenshot output")?;
+            }
+            materials.main_buffer_stack.pop("output")?;
             materials.main_buffer_stack.assert_no_stack()?;
+            materials.bg_buffer_stack.assert_no_stack()?;
         } else {
-            materials.main_buffer_stack.pop()?;
+            materials.main_buffer_stack.pop("output")?;
             materials.main_buffer_stack.assert_no_stack()?;
+            materials.bg_buffer_stack.assert_no_stack()?;
 
             gl.bind_framebuffer(glow::FRAMEBUFFER, None);
             gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
 
             gl.viewport(0, 0, viewport_width as i32, viewport_height as i32);
 
-            materials.internal_resolution_render.render(materials.main_buffer_stack.get_nth(1)?.texture());
+            // When sharpening, the upscale goes to a viewport sized buffer first, and the sharpened picture is the output.
+            let sharpen_strength = filters.sharpen_strength.value;
+            if sharpen_strength > 0.0 {
+                materials.sharpen_render.bind_target(viewport_width as i32, viewport_height as i32)?;
+                gl.clear(glow::COLOR_BUFFER_BIT);
+            }
+
+            // The upscale to the viewport has its own filter, and the buffer gets back the one of the stack afterwards.
+            let composition = materials.main_buffer_stack.get_nth(1)?.clone();
+            let (filter, resampling) = match filters.output_interpolation.value {
+                OutputInterpolationOptions::Nearest => (glow::NEAREST, Resampling::Hardware),
+                OutputInterpolationOptions::Linear => (glow::LINEAR, Resampling::Hardware),
+                OutputInterpolationOptions::Bicubic => (glow::NEAREST, Resampling::CatmullRom),
+                OutputInterpolationOptions::Lanczos => (glow::NEAREST, Resampling::Lanczos2),
+            };
+            composition.set_interpolation(&gl, filter);
+            materials.internal_resolution_render.render(
+                composition.texture(),
+                &OutputUniform {
+                    encode_srgb: output.linear_blending,
+                    flicker: output.flicker,
+                    white_point: &output.white_point,
+                    gamma: output.output_gamma,
+                    contrast_curve: &output.contrast_curve,
+                    phosphor_matrix: output.phosphor_matrix.as_ref(),
+                    barrel: output.screen_barrel,
+                    resampling,
+                },
+            );
+            composition.set_interpolation(&gl, materials.main_buffer_stack.interpolation());
+
+            if sharpen_strength > 0.0 {
+                gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+                gl.viewport(0, 0, viewport_width as i32, viewport_height as i32);
+                materials.sharpen_render.render(sharpen_strength, filters.sharpen_halo_suppression.value);
+            }
+
+            if self.res.moire_assistant.capture_requested {
+                materials
+                    .moire_readback
+                    .request(self.ctx.dispatcher(), viewport_width as i32, viewport_height as i32, None)?;
+            }
+
+            materials.hud_render.render(&output.hud_lines, viewport_width, viewport_height);
+
+            if output.light_gun_flash {
+                gl.clear_color(1.0, 1.0, 1.0, 1.0);
+                gl.clear(glow::COLOR_BUFFER_BIT);
+                gl.clear_color(0.0, 0.0, 0.0, 0.0);
+            }
         }
 
         check_error(&gl, line!())?;
@@ -232,6 +479,242 @@ impl<'a> SimulationDrawer<'a> {
     }
 }
 
+// The camera matrices shared by every layer, plus the view mirrored on the floor plane for the floor reflection.
+struct LayerCamera {
+    view: [f32; 16],
+    floor_view: [f32; 16],
+    projection: [f32; 16],
+    light_pos: [f32; 3],
+}
+
+// Every layer but the source pixels is drawn in the background buffers, blurred there, and then laid
+// over what is currently bound in the main stack with its own opacity.
+fn draw_layer(materials: &mut Materials, res: &Resources, layer: &Layer, camera: &LayerCamera) -> AppResult<()> {
+    let filters = &res.controllers;
+    let output = &res.main.render;
+    let interlace = interlace_uniform(res);
+    let vertical_roll = vertical_roll_uniform(res);
+    let gl = materials.gl.clone();
+
+    materials.bg_buffer_stack.push("layer")?;
+    materials.bg_buffer_stack.bind_current()?;
+    gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
+    match layer.kind {
+        LayerKind::SourcePixels => unreachable!("The source pixels are composed as the foreground"),
+        LayerKind::SolidBackdrop => {
+            for hl_idx in 0..filters.horizontal_lpp.line_passes() {
+                for vl_idx in 0..filters.vertical_lpp.line_passes() {
+                    materials.pixels_render.render(with_transition(
+                        res,
+                        PixelsUniform {
+                            shadow_kind: 0,
+                            geometry_kind: filters.pixels_geometry_kind.value,
+                            view: &camera.view,
+                            projection: &camera.projection,
+                            ambient_strength: output.ambient_strength,
+                            contrast_factor: filters.extra_contrast.value,
+                            light_color: &output.light_color_background,
+                            extra_light: &[0.0, 0.0, 0.0],
+                            light_pos: &camera.light_pos,
+                            pixel_spread: &output.pixel_spread,
+                            pixel_rotation: &output.pixel_rotation,
+                            source_transform: &output.source_transform,
+                            damper_wire_y: &output.damper_wire_y,
+                            damper_wire_opacity: output.damper_wire_opacity,
+                            damper_wire_width: output.damper_wire_width,
+                            rolling_scan: &output.rolling_scan,
+                            screen_matte: &output.screen_matte,
+                            reflection_edge: &[0.0, 0.0, 0.0, 0.0],
+                            pixel_scale: &output.pixel_scale_background[vl_idx * filters.horizontal_lpp.line_passes() + hl_idx],
+                            screen_curvature: output.screen_curvature_factor,
+                            screen_curvature_axes: &output.screen_curvature_axes,
+                            pixel_pulse: output.pixels_pulse,
+                            pixel_offset: &output.pixel_offset_background[vl_idx * filters.horizontal_lpp.line_passes() + hl_idx],
+                            rgb_red: &output.rgb_red,
+                            rgb_green: &output.rgb_green,
+                            rgb_blue: &output.rgb_blue,
+                            color_gamma: output.color_gamma,
+                            black_pedestal: output.black_pedestal,
+                            white_clipping: output.white_clipping,
+                            color_saturation: output.color_saturation,
+                            color_hue: output.color_hue,
+                            channel_gain: &output.channel_gain,
+                            channel_offset: &output.channel_offset,
+                            color_noise: output.color_noise,
+                            convergence: &output.convergence,
+                            interlace: &interlace,
+                            interlace_line_pairing: output.interlace_line_pairing,
+                            hsync_wobble: &output.hsync_wobble,
+                            vertical_roll: &vertical_roll,
+                            row_stagger: output.pixel_row_stagger,
+                            linear_blending: output.linear_blending,
+                            noise_seed: output.noise_seed,
+                            blemish: &output.blemish,
+                            blemish_seed: output.blemish_seed,
+                            blemish_scratches: &output.blemish_scratches,
+                            height_modifier_factor: 0.0,
+                            magnet_center: &output.magnet_center,
+                            magnet_radius: output.magnet_radius,
+                            magnet_strength: output.magnet_strength,
+                        },
+                    ));
+                }
+            }
+        }
+        LayerKind::Reflections => {
+            for reflection in output.edge_reflections.iter() {
+                materials.pixels_render.render(with_transition(
+                    res,
+                    PixelsUniform {
+                        shadow_kind: 0,
+                        geometry_kind: filters.pixels_geometry_kind.value,
+                        view: &camera.view,
+                        projection: &camera.projection,
+                        ambient_strength: output.ambient_strength,
+                        contrast_factor: filters.extra_contrast.value,
+                        light_color: &output.light_color_edge_reflection,
+                        extra_light: &[0.0, 0.0, 0.0],
+                        light_pos: &camera.light_pos,
+                        pixel_spread: &output.pixel_spread,
+                        pixel_rotation: &output.pixel_rotation,
+                        source_transform: &reflection.source_transform,
+                        damper_wire_y: &output.damper_wire_y,
+                        damper_wire_opacity: 0.0,
+                        damper_wire_width: output.damper_wire_width,
+                        rolling_scan: &output.rolling_scan,
+                        screen_matte: &output.screen_matte,
+                        reflection_edge: &reflection.edge,
+                        pixel_scale: &reflection.pixel_scale,
+                        screen_curvature: output.screen_curvature_factor,
+                        screen_curvature_axes: &output.screen_curvature_axes,
+                        pixel_pulse: output.pixels_pulse,
+                        pixel_offset: &reflection.pixel_offset,
+                        rgb_red: &output.rgb_red,
+                        rgb_green: &output.rgb_green,
+                        rgb_blue: &output.rgb_blue,
+                        color_gamma: output.color_gamma,
+                        black_pedestal: output.black_pedestal,
+                        white_clipping: output.white_clipping,
+                        color_saturation: output.color_saturation,
+                        color_hue: output.color_hue,
+                        channel_gain: &output.channel_gain,
+                        channel_offset: &output.channel_offset,
+                        color_noise: output.color_noise,
+                        convergence: &output.convergence,
+                        interlace: &interlace,
+                        interlace_line_pairing: output.interlace_line_pairing,
+                        hsync_wobble: &output.hsync_wobble,
+                        vertical_roll: &vertical_roll,
+                        row_stagger: output.pixel_row_stagger,
+                        linear_blending: output.linear_blending,
+                        noise_seed: output.noise_seed,
+                        blemish: &output.blemish,
+                        blemish_seed: output.blemish_seed,
+                        blemish_scratches: &output.blemish_scratches,
+                        height_modifier_factor: 0.0,
+                        magnet_center: &output.magnet_center,
+                        magnet_radius: output.magnet_radius,
+                        magnet_strength: output.magnet_strength,
+                    },
+                ));
+            }
+        }
+        LayerKind::MonitorCase => {
+            materials.case_render.load_mesh(output.case_mesh_version, &output.case_mesh);
+            materials.case_render.render(CaseUniform {
+                view: &camera.view,
+                projection: &camera.projection,
+                light_pos: &camera.light_pos,
+                color: &output.case_color,
+                ambient_strength: output.ambient_strength,
+            });
+        }
+    }
+    let source = materials.bg_buffer_stack.get_current()?.clone();
+    materials
+        .blur_render
+        .render(&mut materials.bg_buffer_stack, &source, &source, filters.background_blur_passes.value)?;
+
+    if layer.kind == LayerKind::Reflections && output.showing_floor_reflection {
+        // The floor reflection has its own blur driven by the roughness, so it goes to a spare buffer first.
+        materials.bg_buffer_stack.push("floor reflection")?;
+        materials.bg_buffer_stack.bind_current()?;
+        gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
+        materials.pixels_render.render(with_transition(
+            res,
+            PixelsUniform {
+                shadow_kind: 0,
+                geometry_kind: filters.pixels_geometry_kind.value,
+                view: &camera.floor_view,
+                projection: &camera.projection,
+                ambient_strength: output.ambient_strength,
+                contrast_factor: filters.extra_contrast.value,
+                light_color: &output.light_color_floor_reflection,
+                extra_light: &[0.0, 0.0, 0.0],
+                light_pos: &camera.light_pos,
+                pixel_spread: &output.pixel_spread,
+                pixel_rotation: &output.pixel_rotation,
+                source_transform: &output.source_transform,
+                damper_wire_y: &output.damper_wire_y,
+                damper_wire_opacity: output.damper_wire_opacity,
+                damper_wire_width: output.damper_wire_width,
+                rolling_scan: &output.rolling_scan,
+                screen_matte: &output.screen_matte,
+                reflection_edge: &[0.0, 0.0, 0.0, 0.0],
+                pixel_scale: &output.pixel_scale_base,
+                screen_curvature: output.screen_curvature_factor,
+                screen_curvature_axes: &output.screen_curvature_axes,
+                pixel_pulse: output.pixels_pulse,
+                pixel_offset: &[0.0, 0.0, 0.0],
+                rgb_red: &output.rgb_red,
+                rgb_green: &output.rgb_green,
+                rgb_blue: &output.rgb_blue,
+                color_gamma: output.color_gamma,
+                black_pedestal: output.black_pedestal,
+                white_clipping: output.white_clipping,
+                color_saturation: output.color_saturation,
+                color_hue: output.color_hue,
+                channel_gain: &output.channel_gain,
+                channel_offset: &output.channel_offset,
+                color_noise: output.color_noise,
+                convergence: &output.convergence,
+                interlace: &interlace,
+                interlace_line_pairing: output.interlace_line_pairing,
+                hsync_wobble: &output.hsync_wobble,
+                vertical_roll: &vertical_roll,
+                row_stagger: output.pixel_row_stagger,
+                linear_blending: output.linear_blending,
+                noise_seed: output.noise_seed,
+                blemish: &output.blemish,
+                blemish_seed: output.blemish_seed,
+                blemish_scratches: &output.blemish_scratches,
+                height_modifier_factor: 0.0,
+                magnet_center: &output.magnet_center,
+                magnet_radius: output.magnet_radius,
+                magnet_strength: output.magnet_strength,
+            },
+        ));
+        let floor = materials.bg_buffer_stack.get_current()?.clone();
+        if output.floor_blur_passes > 0 {
+            materials
+                .blur_render
+                .render(&mut materials.bg_buffer_stack, &floor, &floor, output.floor_blur_passes)?;
+        }
+        materials.bg_buffer_stack.pop("floor reflection")?;
+        materials.bg_buffer_stack.bind_current()?;
+        gl.enable(glow::BLEND);
+        gl.blend_func(glow::ONE, glow::ONE);
+        materials.internal_resolution_render.copy(floor.texture());
+        gl.disable(glow::BLEND);
+    }
+    materials.bg_buffer_stack.pop("layer")?;
+
+    materials.main_buffer_stack.bind_current()?;
+    gl.clear(glow::DEPTH_BUFFER_BIT);
+    materials.layer_render.render(source.texture(), layer.opacity);
+    Ok(())
+}
+
 fn check_error(gl: &GlowSafeAdapter<glow::Context>, line: u32) -> AppResult<()> {
     let error = gl.get_error();
     if error != glow::NO_ERROR {
@@ -240,6 +723,42 @@ fn check_error(gl: &GlowSafeAdapter<glow::Context>, line: u32) -> AppResult<()>
     Ok(())
 }
 
+// Odd and even scanlines take turns every frame.
+fn interlace_uniform(res: &Resources) -> [f32; 2] {
+    [(res.timers.total_frames % 2) as f32, res.main.render.interlace_other_field]
+}
+
+// The picture rolls a couple of times during the roll transition, slowing down until the vertical hold locks it.
+fn vertical_roll_uniform(res: &Resources) -> [f32; 2] {
+    let output = &res.main.render;
+    let rows = res.video.image_size.height as f32;
+    if output.transition != TransitionOptions::Roll {
+        return [0.0, rows];
+    }
+    let fade = 1.0 - output.transition_progress;
+    [TRANSITION_ROLL_TURNS * rows * fade * fade, rows]
+}
+
+// The transition played when the source changes overrides for a moment what the pixels show.
+fn with_transition<'a>(res: &Resources, mut uniforms: PixelsUniform<'a>) -> PixelsUniform<'a> {
+    let output = &res.main.render;
+    match output.transition {
+        TransitionOptions::Static => uniforms.color_noise = uniforms.color_noise.max(1.0 - output.transition_progress),
+        TransitionOptions::BlueScreen => {
+            uniforms.rgb_red = &NO_SIGNAL;
+            uniforms.rgb_green = &NO_SIGNAL;
+            uniforms.rgb_blue = &NO_SIGNAL;
+            uniforms.extra_light = &BLUE_SCREEN;
+        }
+        TransitionOptions::Off | TransitionOptions::Roll => {}
+    }
+    uniforms
+}
+
+const TRANSITION_ROLL_TURNS: f32 = 2.0;
+const NO_SIGNAL: [f32; 3] = [0.0, 0.0, 0.0];
+const BLUE_SCREEN: [f32; 3] = [0.05, 0.1, 0.75];
+
 fn matrix_to_16_f32(matrix: glm::TMat4<f32>) -> [f32; 16] {
     [
         matrix[(0, 0)],
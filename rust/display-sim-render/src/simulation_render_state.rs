@@ -13,13 +13,26 @@
  * You should have received a copy of the GNU Affero General Public License
  * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
 
+use crate::analog_static_render::AnalogStaticRender;
 use crate::background_render::BackgroundRender;
+use crate::bezel_render::BezelRender;
+use crate::bloom_render::BloomRender;
 use crate::blur_render::BlurRender;
+use crate::burn_in_render::BurnInRender;
+use crate::case_render::CaseRender;
+use crate::dither_render::DitherRender;
 use crate::error::AppResult;
+use crate::glass_render::GlassRender;
+use crate::hud_render::HudRender;
 use crate::internal_resolution_render::InternalResolutionRender;
+use crate::layer_render::LayerRender;
 use crate::pixels_render::PixelsRender;
 use crate::render_types::TextureBufferStack;
 use crate::rgb_render::RgbRender;
+use crate::room_render::RoomRender;
+use crate::screenshot_readback::ScreenshotReadback;
+use crate::sharpen_render::SharpenRender;
+use crate::vignette_render::VignetteRender;
 
 use glow::Context;
 use glow::GlowSafeAdapter;
@@ -37,10 +50,25 @@ pub struct Materials {
     pub bg_buffer_stack: TextureBufferStack<Context>,
     pub pixels_render: PixelsRender<Context>,
     pub blur_render: BlurRender<Context>,
+    pub bloom_render: BloomRender<Context>,
     pub background_render: BackgroundRender<Context>,
     pub internal_resolution_render: InternalResolutionRender<Context>,
+    pub layer_render: LayerRender<Context>,
+    pub vignette_render: VignetteRender<Context>,
+    pub analog_static_render: AnalogStaticRender<Context>,
+    pub dither_render: DitherRender<Context>,
+    pub burn_in_render: BurnInRender<Context>,
+    pub glass_render: GlassRender<Context>,
+    pub case_render: CaseRender<Context>,
+    pub bezel_render: BezelRender<Context>,
+    pub room_render: RoomRender<Context>,
+    pub sharpen_render: SharpenRender<Context>,
+    pub hud_render: HudRender<Context>,
     pub rgb_render: RgbRender<Context>,
-    pub screenshot_pixels: Option<Box<[u8]>>,
+    pub screenshot_readback: ScreenshotReadback<Context>,
+    pub moire_readback: ScreenshotReadback<Context>,
+    pub moire_score: Option<f32>,
+    pub frame_luminance: Option<f32>,
 }
 
 impl Materials {
@@ -50,10 +78,25 @@ impl Materials {
             bg_buffer_stack: TextureBufferStack::new(gl.clone()),
             pixels_render: PixelsRender::new(gl.clone(), video)?,
             blur_render: BlurRender::new(gl.clone())?,
+            bloom_render: BloomRender::new(gl.clone())?,
             internal_resolution_render: InternalResolutionRender::new(gl.clone())?,
+            layer_render: LayerRender::new(gl.clone())?,
+            vignette_render: VignetteRender::new(gl.clone())?,
+            analog_static_render: AnalogStaticRender::new(gl.clone())?,
+            dither_render: DitherRender::new(gl.clone())?,
+            burn_in_render: BurnInRender::new(gl.clone())?,
+            glass_render: GlassRender::new(gl.clone())?,
+            case_render: CaseRender::new(gl.clone())?,
+            bezel_render: BezelRender::new(gl.clone())?,
+            room_render: RoomRender::new(gl.clone())?,
+            sharpen_render: SharpenRender::new(gl.clone())?,
+            hud_render: HudRender::new(gl.clone())?,
             rgb_render: RgbRender::new(gl.clone())?,
             background_render: BackgroundRender::new(gl.clone())?,
-            screenshot_pixels: None,
+            screenshot_readback: ScreenshotReadback::new(gl.clone()),
+            moire_readback: ScreenshotReadback::new(gl.clone()),
+            moire_score: None,
+            frame_luminance: None,
             gl,
         })
     }
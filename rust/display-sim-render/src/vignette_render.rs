@@ -0,0 +1,64 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+use crate::error::AppResult;
+use crate::shaders::{make_quad_vao, make_shader, TEXTURE_VERTEX_SHADER};
+
+use glow::GlowSafeAdapter;
+use glow::HasContext;
+use std::rc::Rc;
+
+pub struct VignetteRender<GL: HasContext> {
+    vao: Option<GL::VertexArray>,
+    shader: GL::Program,
+    gl: Rc<GlowSafeAdapter<GL>>,
+}
+
+impl<GL: HasContext> VignetteRender<GL> {
+    pub fn new(gl: Rc<GlowSafeAdapter<GL>>) -> AppResult<VignetteRender<GL>> {
+        let shader = make_shader(&*gl, TEXTURE_VERTEX_SHADER, VIGNETTE_FRAGMENT_SHADER)?;
+        let vao = make_quad_vao(&*gl, &shader)?;
+        Ok(VignetteRender { vao, shader, gl })
+    }
+
+    // Darkens the corners of the bound framebuffer by multiplying it. The radius is the distance from the
+    // center where the darkening starts, 1 being the corners.
+    pub fn render(&self, strength: f32, radius: f32) {
+        self.gl.use_program(Some(self.shader));
+        self.gl.uniform_1_f32(self.gl.get_uniform_location(self.shader, "strength"), strength);
+        self.gl.uniform_1_f32(self.gl.get_uniform_location(self.shader, "radius"), radius);
+        self.gl.bind_vertex_array(self.vao);
+        self.gl.enable(glow::BLEND);
+        self.gl.blend_func(glow::ZERO, glow::SRC_COLOR);
+        self.gl.draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0);
+        self.gl.disable(glow::BLEND);
+    }
+}
+
+pub const VIGNETTE_FRAGMENT_SHADER: &str = r#"#version 300 es
+precision highp float;
+
+out vec4 FragColor;
+in vec2 TexCoord;
+
+uniform float strength;
+uniform float radius;
+
+void main()
+{
+    float distance = length(TexCoord - 0.5) * 2.0 / sqrt(2.0);
+    float factor = 1.0 - strength * smoothstep(radius, radius + 0.5, distance);
+    FragColor = vec4(vec3(factor), 1.0);
+} 
+"#;
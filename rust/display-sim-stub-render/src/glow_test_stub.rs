@@ -37,7 +37,9 @@ pub fn new_glow_stub() -> GlowSafeAdapter<Context> {
 
 impl<GL: HasContext> GlowSafeAdapter<GL> {
     pub fn enable(&self, _: u32) {}
-    pub fn enable_vertex_attrib_array(&self, _: u32) {}
+    pub fn disable(&self, _: u32) {}
+    pub fn blend_func(&self, _: u32, _: u32) {}
+    pub fn enable_vertex_attrib_array(&self, _: Option<u32>) {}
     pub fn create_framebuffer(&self) -> Result<GL::Framebuffer, String> {
         Ok(Default::default())
     }
@@ -83,6 +85,17 @@ impl<GL: HasContext> GlowSafeAdapter<GL> {
         Ok(Default::default())
     }
     pub fn bind_buffer(&self, _: u32, _: Option<GL::Buffer>) {}
+    pub fn delete_buffer(&self, _: GL::Buffer) {}
+    pub fn buffer_data_size(&self, _: u32, _: i32, _: u32) {}
+    pub fn get_buffer_sub_data(&self, _: u32, _: i32, _: &mut [u8]) {}
+    pub fn read_pixels(&self, _: i32, _: i32, _: i32, _: i32, _: u32, _: u32, _: &mut [u8]) {}
+    pub fn fence_sync(&self, _: u32, _: u32) -> Result<GL::Fence, String> {
+        Ok(Default::default())
+    }
+    pub fn get_sync_status(&self, _: GL::Fence) -> u32 {
+        SIGNALED
+    }
+    pub fn delete_sync(&self, _: GL::Fence) {}
     pub fn bind_framebuffer(&self, _: u32, _: Option<GL::Framebuffer>) {}
     pub fn bind_renderbuffer(&self, _: u32, _: Option<GL::Renderbuffer>) {}
     pub fn create_vertex_array(&self) -> Result<GL::VertexArray, String> {
@@ -100,6 +113,7 @@ impl<GL: HasContext> GlowSafeAdapter<GL> {
     pub fn buffer_storage(&self, _: u32, _: i32, _: Option<&mut [u8]>, _: u32) {}
     pub fn delete_framebuffer(&self, _: GL::Framebuffer) {}
     pub fn delete_texture(&self, _: GL::Texture) {}
+    pub fn draw_arrays(&self, _: u32, _: i32, _: i32) {}
     pub fn draw_arrays_instanced(&self, _: u32, _: i32, _: i32, _: i32) {}
     pub fn draw_elements(&self, _: u32, _: i32, _: u32, _: i32) {}
     pub fn flush(&self) {}
@@ -112,8 +126,8 @@ impl<GL: HasContext> GlowSafeAdapter<GL> {
     pub fn get_uniform_location(&self, _: GL::Program, _: &str) -> Option<GL::UniformLocation> {
         Some(Default::default())
     }
-    pub fn get_attrib_location(&self, _: GL::Program, _: &str) -> u32 {
-        0
+    pub fn get_attrib_location(&self, _: GL::Program, _: &str) -> Option<u32> {
+        Some(0)
     }
     pub fn get_active_attributes(&self, _: GL::Program) -> u32 {
         0
@@ -129,14 +143,17 @@ impl<GL: HasContext> GlowSafeAdapter<GL> {
     pub fn uniform_1_f32(&self, _: Option<GL::UniformLocation>, _: f32) {}
     pub fn uniform_2_f32_slice(&self, _: Option<GL::UniformLocation>, _: &[f32; 2]) {}
     pub fn uniform_3_f32_slice(&self, _: Option<GL::UniformLocation>, _: &[f32; 3]) {}
+    pub fn uniform_4_f32_slice(&self, _: Option<GL::UniformLocation>, _: &[f32; 4]) {}
+    pub fn uniform_matrix_2_f32_slice(&self, _: Option<GL::UniformLocation>, _: bool, _: &[f32; 4]) {}
+    pub fn uniform_matrix_3_f32_slice(&self, _: Option<GL::UniformLocation>, _: bool, _: &[f32; 9]) {}
     pub fn uniform_matrix_4_f32_slice(&self, _: Option<GL::UniformLocation>, _: bool, _: &[f32; 16]) {}
     pub fn finish(&self) {}
     pub fn bind_texture(&self, _: u32, _: Option<GL::Texture>) {}
     pub fn active_texture(&self, _: u32) {}
     pub fn tex_parameter_i32(&self, _: u32, _: u32, _: i32) {}
-    pub fn vertex_attrib_divisor(&self, _: u32, _: u32) {}
-    pub fn vertex_attrib_pointer_f32(&self, _: u32, _: i32, _: u32, _: bool, _: i32, _: i32) {}
-    pub fn vertex_attrib_pointer_i32(&self, _: u32, _: i32, _: u32, _: i32, _: i32) {}
+    pub fn vertex_attrib_divisor(&self, _: Option<u32>, _: u32) {}
+    pub fn vertex_attrib_pointer_f32(&self, _: Option<u32>, _: i32, _: u32, _: bool, _: i32, _: i32) {}
+    pub fn vertex_attrib_pointer_i32(&self, _: Option<u32>, _: i32, _: u32, _: i32, _: i32) {}
     pub fn viewport(&self, _: i32, _: i32, _: i32, _: i32) {}
     pub fn pop_debug_group(&self) {}
     pub fn get_uniform_block_index(&self, _: GL::Program, _: &str) -> Option<u32> {
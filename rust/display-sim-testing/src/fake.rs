@@ -19,15 +19,28 @@ use core::input_types::Input;
 use core::simulation_context::{ConcreteSimulationContext, FakeRngGenerator};
 use core::simulation_core_state::{AnimationStep, Resources, VideoInputResources};
 use core::simulation_core_ticker::SimulationCoreTicker;
+use render::analog_static_render::AnalogStaticRender;
 use render::background_render::BackgroundRender;
+use render::bezel_render::BezelRender;
+use render::bloom_render::BloomRender;
 use render::blur_render::BlurRender;
+use render::burn_in_render::BurnInRender;
+use render::case_render::CaseRender;
+use render::dither_render::DitherRender;
 use render::error::AppResult;
+use render::glass_render::GlassRender;
+use render::hud_render::HudRender;
 use render::internal_resolution_render::InternalResolutionRender;
+use render::layer_render::LayerRender;
 use render::pixels_render::PixelsRender;
 use render::render_types::TextureBufferStack;
 use render::rgb_render::RgbRender;
+use render::room_render::RoomRender;
+use render::screenshot_readback::ScreenshotReadback;
+use render::sharpen_render::SharpenRender;
 use render::simulation_draw::SimulationDrawer;
 use render::simulation_render_state::{Materials, VideoInputMaterials};
+use render::vignette_render::VignetteRender;
 
 use render::glow_test_stub::new_glow_stub;
 use std::rc::Rc;
@@ -51,9 +64,12 @@ impl Default for FakeVideoInput {
                 viewport_size: Size2D { width: 256, height: 240 },
                 current_frame: 0,
                 last_frame_change: 0.0,
+                external_clock: false,
+                pending_frame_advances: 0,
                 preset: None,
                 needs_buffer_data_load: true,
                 drawing_activation: true,
+                subtitles: vec![],
             },
             VideoInputMaterials {
                 buffers: vec![Box::new([0; 256 * 224 * 4 * 4])],
@@ -72,10 +88,25 @@ impl FakeVideoInput {
             bg_buffer_stack: TextureBufferStack::new(gl.clone()),
             pixels_render: PixelsRender::new(gl.clone(), self.1)?,
             blur_render: BlurRender::new(gl.clone())?,
+            bloom_render: BloomRender::new(gl.clone())?,
             internal_resolution_render: InternalResolutionRender::new(gl.clone())?,
+            layer_render: LayerRender::new(gl.clone())?,
+            vignette_render: VignetteRender::new(gl.clone())?,
+            analog_static_render: AnalogStaticRender::new(gl.clone())?,
+            dither_render: DitherRender::new(gl.clone())?,
+            burn_in_render: BurnInRender::new(gl.clone())?,
+            glass_render: GlassRender::new(gl.clone())?,
+            case_render: CaseRender::new(gl.clone())?,
+            bezel_render: BezelRender::new(gl.clone())?,
+            room_render: RoomRender::new(gl.clone())?,
+            sharpen_render: SharpenRender::new(gl.clone())?,
+            hud_render: HudRender::new(gl.clone())?,
             rgb_render: RgbRender::new(gl.clone())?,
             background_render: BackgroundRender::new(gl.clone())?,
-            screenshot_pixels: None,
+            screenshot_readback: ScreenshotReadback::new(gl.clone()),
+            moire_readback: ScreenshotReadback::new(gl.clone()),
+            moire_score: None,
+            frame_luminance: None,
             gl,
         };
 
@@ -88,10 +119,13 @@ impl FakeVideoInput {
                 println!("User closed the simulation.");
                 return Ok(());
             }
-            if !res.drawable {
-                continue;
-            }
             SimulationDrawer::new(&ctx, &mut materials, &res).draw()?;
+            if let Some(score) = materials.moire_score.take() {
+                res.moire_assistant.record_score(score);
+            }
+            if let Some(luminance) = materials.frame_luminance.take() {
+                res.blooming.record_luminance(luminance);
+            }
         }
         Ok(())
     }
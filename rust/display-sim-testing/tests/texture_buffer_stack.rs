@@ -0,0 +1,76 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+
+use render::glow_test_stub::new_glow_stub;
+use render::render_types::TextureBufferStack;
+use std::rc::Rc;
+
+fn make_tracked_stack() -> TextureBufferStack<render::glow_test_stub::Context> {
+    let mut stack = TextureBufferStack::new(Rc::new(new_glow_stub()));
+    stack.set_pass_tracking(true);
+    stack
+}
+
+#[test]
+fn test_texture_buffer_stack_balanced_passes_leave_no_stack() {
+    let mut stack = make_tracked_stack();
+    stack.push("output").unwrap();
+    stack.push("bloom").unwrap();
+    stack.push("blur").unwrap();
+    stack.pop("blur").unwrap();
+    stack.pop("bloom").unwrap();
+    stack.pop("output").unwrap();
+    assert!(stack.assert_no_stack().is_ok());
+}
+
+#[test]
+fn test_texture_buffer_stack_pop_from_another_pass_names_both_passes() {
+    let mut stack = make_tracked_stack();
+    stack.push("output").unwrap();
+    stack.push("bloom").unwrap();
+    assert_eq!(
+        stack.pop("output").unwrap_err().to_string(),
+        "Pass 'output' popped the texture buffer pushed by pass 'bloom'."
+    );
+}
+
+#[test]
+fn test_texture_buffer_stack_pop_on_empty_stack_names_the_pass() {
+    let mut stack = make_tracked_stack();
+    assert_eq!(
+        stack.pop("dithering").unwrap_err().to_string(),
+        "Pass 'dithering' popped an empty texture buffer stack."
+    );
+}
+
+#[test]
+fn test_texture_buffer_stack_unbalanced_frame_names_the_passes_still_pushed() {
+    let mut stack = make_tracked_stack();
+    stack.push("output").unwrap();
+    stack.push("mask blur").unwrap();
+    assert_eq!(
+        stack.assert_no_stack().unwrap_err().to_string(),
+        "Texture buffer stack cursor not zero, '2' instead. Still pushed by: 'output', 'mask blur'."
+    );
+}
+
+#[test]
+fn test_texture_buffer_stack_without_tracking_only_checks_the_cursor() {
+    let mut stack = make_tracked_stack();
+    stack.set_pass_tracking(false);
+    stack.push("output").unwrap();
+    assert!(stack.pop("bloom").is_ok());
+    assert!(stack.pop("bloom").is_err());
+}
@@ -19,10 +19,11 @@ use js_sys::Uint8Array;
 use wasm_bindgen::prelude::{wasm_bindgen, JsValue};
 
 use crate::console;
-use crate::web_entrypoint::{print_error, web_load, web_run_frame, web_unload, InputOutput};
+use crate::web_entrypoint::{print_error, web_load, web_run_frame, web_set_bezel_artwork, web_set_filter_mask, web_unload, InputOutput};
 use app_error::AppResult;
 use core::general_types::Size2D;
 use core::simulation_core_state::{AnimationStep, Resources, VideoInputResources};
+use core::subtitles::parse_srt;
 use core::ui_controller::filter_preset::FilterPresetOptions;
 use render::simulation_render_state::VideoInputMaterials;
 use std::str::FromStr;
@@ -72,6 +73,45 @@ impl WasmApp {
         }
     }
 
+    #[wasm_bindgen]
+    pub fn advance_frames(&mut self, frames: u32) {
+        self.res.video.pending_frame_advances += frames;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_filter_mask(&mut self, width: u32, height: u32, buffer: Uint8Array) {
+        if let Some(ref mut io) = self.io {
+            let pixels = buffer.to_vec();
+            handle_result(web_set_filter_mask(io, Some((Size2D { width, height }, &pixels))));
+        } else {
+            console!(error. "State not yet initialized!");
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn clear_filter_mask(&mut self) {
+        if let Some(ref mut io) = self.io {
+            handle_result(web_set_filter_mask(io, None));
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn set_bezel_artwork(&mut self, width: u32, height: u32, buffer: Uint8Array) {
+        if let Some(ref mut io) = self.io {
+            let pixels = buffer.to_vec();
+            handle_result(web_set_bezel_artwork(io, Some((Size2D { width, height }, &pixels))));
+        } else {
+            console!(error. "State not yet initialized!");
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn clear_bezel_artwork(&mut self) {
+        if let Some(ref mut io) = self.io {
+            handle_result(web_set_bezel_artwork(io, None));
+        }
+    }
+
     #[wasm_bindgen]
     pub fn unload(&mut self) {
         if let Some(io) = self.io.take() {
@@ -115,8 +155,11 @@ impl VideoInputConfig {
                 steps: Vec::new(),
                 current_frame: 0,
                 last_frame_change: -1000.0,
+                external_clock: false,
+                pending_frame_advances: 0,
                 needs_buffer_data_load: true,
                 drawing_activation: true,
+                subtitles: vec![],
             },
             materials: VideoInputMaterials::default(),
         }
@@ -155,8 +198,21 @@ impl VideoInputConfig {
         self.resources.max_texture_size = max_texture_size;
     }
 
+    #[wasm_bindgen]
+    pub fn set_external_clock(&mut self, external_clock: bool) {
+        self.resources.external_clock = external_clock;
+    }
+
     #[wasm_bindgen]
     pub fn set_drawing_activation(&mut self, activation: bool) {
         self.resources.drawing_activation = activation;
     }
+
+    #[wasm_bindgen]
+    pub fn set_subtitles(&mut self, srt: String) {
+        match parse_srt(&srt) {
+            Ok(subtitles) => self.resources.subtitles = subtitles,
+            Err(e) => console!(error. "Input subtitles are not valid.", e),
+        }
+    }
 }
@@ -23,6 +23,7 @@ use crate::web_events::WebEventDispatcher;
 use crate::web_utils::now;
 use app_error::{AppError, AppResult};
 use core::camera::CameraChange;
+use core::general_types::Size2D;
 use core::input_types::{Input, InputEventValue, Pressed};
 use core::simulation_context::{ConcreteSimulationContext, RandomGenerator, SimulationContext};
 use core::simulation_core_state::{KeyEventKind, Resources, VideoInputResources};
@@ -83,6 +84,26 @@ pub(crate) fn web_run_frame(res: &mut Resources, io: &mut InputOutput) -> AppRes
     Ok(condition)
 }
 
+pub(crate) fn web_set_filter_mask(io: &mut InputOutput, mask: Option<(Size2D<u32>, &[u8])>) -> AppResult<()> {
+    match mask {
+        Some((size, pixels)) => io.materials.background_render.set_mask(size, pixels),
+        None => {
+            io.materials.background_render.clear_mask();
+            Ok(())
+        }
+    }
+}
+
+pub(crate) fn web_set_bezel_artwork(io: &mut InputOutput, artwork: Option<(Size2D<u32>, &[u8])>) -> AppResult<()> {
+    match artwork {
+        Some((size, pixels)) => io.materials.bezel_render.set_artwork(size, pixels),
+        None => {
+            io.materials.bezel_render.clear_artwork();
+            Ok(())
+        }
+    }
+}
+
 pub(crate) fn print_error(e: AppError) {
     console!(error. "An unexpected error ocurred.", e);
 }
@@ -102,8 +123,12 @@ fn tick(ctx: &dyn SimulationContext, input: &mut Input, res: &mut Resources, mat
     if res.quit {
         return Ok(false);
     }
-    if res.drawable {
-        SimulationDrawer::new(ctx, materials, res).draw()?;
+    SimulationDrawer::new(ctx, materials, res).draw()?;
+    if let Some(score) = materials.moire_score.take() {
+        res.moire_assistant.record_score(score);
+    }
+    if let Some(luminance) = materials.frame_luminance.take() {
+        res.blooming.record_luminance(luminance);
     }
     Ok(true)
 }
@@ -182,10 +207,16 @@ fn read_frontend_event(input: &mut Input, res: &mut Resources, event: JsValue) -
             let y = js_sys::Reflect::get(&value, &"y".into())?.as_f64().ok_or("it should be a number")? as i32;
             InputEventValue::MouseMove { x, y }
         }
+        "front2back:pointer-position" => {
+            let x = js_sys::Reflect::get(&value, &"x".into())?.as_f64().ok_or("it should be a number")? as i32;
+            let y = js_sys::Reflect::get(&value, &"y".into())?.as_f64().ok_or("it should be a number")? as i32;
+            InputEventValue::PointerPosition { x, y }
+        }
         "front2back:mouse-wheel" => InputEventValue::MouseWheel(value.as_f64().ok_or("it should be a number")? as f32),
         "front2back:blurred-window" => InputEventValue::BlurredWindow,
         "front2back:pixel-width" => InputEventValue::PixelWidth(value.as_f64().ok_or("it should be a number")? as f32),
         "front2back:camera_zoom" => InputEventValue::Camera(CameraChange::Zoom(value.as_f64().ok_or("it should be a number")? as f32)),
+        "front2back:camera_focal_length" => InputEventValue::Camera(CameraChange::FocalLength(value.as_f64().ok_or("it should be a number")? as f32)),
         "front2back:camera-pos-x" => InputEventValue::Camera(CameraChange::PosX(value.as_f64().ok_or("it should be a number")? as f32)),
         "front2back:camera-pos-y" => InputEventValue::Camera(CameraChange::PosY(value.as_f64().ok_or("it should be a number")? as f32)),
         "front2back:camera-pos-z" => InputEventValue::Camera(CameraChange::PosZ(value.as_f64().ok_or("it should be a number")? as f32)),
@@ -195,11 +226,15 @@ fn read_frontend_event(input: &mut Input, res: &mut Resources, event: JsValue) -
         "front2back:camera-dir-x" => InputEventValue::Camera(CameraChange::DirectionX(value.as_f64().ok_or("it should be a number")? as f32)),
         "front2back:camera-dir-y" => InputEventValue::Camera(CameraChange::DirectionY(value.as_f64().ok_or("it should be a number")? as f32)),
         "front2back:camera-dir-z" => InputEventValue::Camera(CameraChange::DirectionZ(value.as_f64().ok_or("it should be a number")? as f32)),
+        "front2back:camera-near-plane" => InputEventValue::Camera(CameraChange::NearPlane(value.as_f64().ok_or("it should be a number")? as f32)),
+        "front2back:camera-far-plane" => InputEventValue::Camera(CameraChange::FarPlane(value.as_f64().ok_or("it should be a number")? as f32)),
         "front2back:custom-scaling-resolution-width" => InputEventValue::CustomScalingResolutionWidth(value.as_f64().ok_or("it should be a number")? as f32),
         "front2back:custom-scaling-resolution-height" => InputEventValue::CustomScalingResolutionHeight(value.as_f64().ok_or("it should be a number")? as f32),
         "front2back:custom-scaling-aspect-ratio-x" => InputEventValue::CustomScalingAspectRatioX(value.as_f64().ok_or("it should be a number")? as f32),
         "front2back:custom-scaling-aspect-ratio-y" => InputEventValue::CustomScalingAspectRatioY(value.as_f64().ok_or("it should be a number")? as f32),
         "front2back:custom-scaling-stretch-nearest" => InputEventValue::CustomScalingStretchNearest(value.as_bool().ok_or("it should be a bool")?),
+        "front2back:input-replay" => InputEventValue::InputReplay(value.as_string().ok_or("it should be a string")?),
+        "front2back:input-recording" => InputEventValue::InputRecording(value.as_bool().ok_or("it should be a bool")?),
         "front2back:viewport-resize" => InputEventValue::ViewportResize(
             js_sys::Reflect::get(&value, &"width".into())?.as_f64().ok_or("it should contain width")? as u32,
             js_sys::Reflect::get(&value, &"height".into())?.as_f64().ok_or("it should contain height")? as u32,
@@ -16,18 +16,21 @@
 use crate::console;
 use crate::dispatch_event::{dispatch_event, dispatch_event_with};
 use app_error::{AppError, AppResult};
-use core::app_events::AppEventDispatcher;
+use core::app_events::{AppEventDispatcher, ClampedValue, MessageCategory, SoundEffect};
 use core::camera::CameraLockMode;
+use core::diagnostics::DiagnosticsReadout;
+use core::moire::MoireReport;
 use core::simulation_core_state::ScalingMethod;
+use core::ui_controller::message_verbosity::MessageVerbosityOptions;
 use js_sys::Float32Array;
 use std::cell::RefCell;
-use std::fmt::Display;
 use wasm_bindgen::JsValue;
 use web_sys::WebGl2RenderingContext;
 
 pub struct WebEventDispatcher {
     error: RefCell<Option<AppError>>,
     extra_messages_enabled: RefCell<bool>,
+    message_verbosity: RefCell<MessageVerbosityOptions>,
     gl: WebGl2RenderingContext,
     event_bus: JsValue,
 }
@@ -37,6 +40,7 @@ impl WebEventDispatcher {
         WebEventDispatcher {
             error: Default::default(),
             extra_messages_enabled: RefCell::new(true),
+            message_verbosity: RefCell::new(MessageVerbosityOptions::All),
             gl,
             event_bus,
         }
@@ -48,8 +52,12 @@ impl AppEventDispatcher for WebEventDispatcher {
         *self.extra_messages_enabled.borrow_mut() = extra_messages;
     }
 
-    fn are_extra_messages_enabled(&self) -> bool {
-        *self.extra_messages_enabled.borrow()
+    fn set_message_verbosity(&self, verbosity: MessageVerbosityOptions) {
+        *self.message_verbosity.borrow_mut() = verbosity;
+    }
+
+    fn are_extra_messages_enabled(&self, category: MessageCategory) -> bool {
+        *self.extra_messages_enabled.borrow() && self.message_verbosity.borrow().shows(category)
     }
 
     fn dispatch_log(&self, msg: String) {
@@ -74,6 +82,24 @@ impl AppEventDispatcher for WebEventDispatcher {
         self.catch_error(dispatch_event_with(&self.event_bus, "back2front:camera_update", &values_array.into()));
     }
 
+    fn dispatch_recovery_state(&self, preset: &str, position: &glm::Vec3, direction: &glm::Vec3, axis_up: &glm::Vec3) {
+        let camera: [f32; 9] = [
+            position.x,
+            position.y,
+            position.z,
+            direction.x,
+            direction.y,
+            direction.z,
+            axis_up.x,
+            axis_up.y,
+            axis_up.z,
+        ];
+        let object = js_sys::Object::new();
+        js_sys::Reflect::set(&object, &"preset".into(), &preset.into()).expect("Reflection failed on preset");
+        js_sys::Reflect::set(&object, &"camera".into(), &Float32Array::from(&camera[..]).into()).expect("Reflection failed on camera");
+        self.catch_error(dispatch_event_with(&self.event_bus, "back2front:recovery_state", &object));
+    }
+
     fn dispatch_change_pixel_width(&self, size: f32) {
         self.catch_error(dispatch_event_with(
             &self.event_bus,
@@ -90,8 +116,16 @@ impl AppEventDispatcher for WebEventDispatcher {
         ));
     }
 
+    fn dispatch_change_camera_focal_length(&self, focal_length: f32) {
+        self.catch_error(dispatch_event_with(
+            &self.event_bus,
+            "back2front:change_camera_focal_length",
+            &format!("{:.00}", focal_length).into(),
+        ));
+    }
+
     fn dispatch_scaling_method(&self, method: ScalingMethod) {
-        if self.are_extra_messages_enabled() {
+        if self.are_extra_messages_enabled(MessageCategory::Filters) {
             self.dispatch_top_message(&format!("Scaling method: {}.", method));
         }
         self.catch_error(dispatch_event_with(&self.event_bus, "back2front:scaling_method", &(method.to_string()).into()));
@@ -131,7 +165,7 @@ impl AppEventDispatcher for WebEventDispatcher {
 
     fn dispatch_change_pixel_speed(&self, speed: f32) {
         let speed = self.format_speed(speed);
-        if self.are_extra_messages_enabled() {
+        if self.are_extra_messages_enabled(MessageCategory::Filters) {
             self.dispatch_top_message(&format!("Pixel manipulation speed: {}", speed));
         }
         self.catch_error(dispatch_event_with(&self.event_bus, "back2front:change_pixel_speed", &speed.into()));
@@ -139,7 +173,7 @@ impl AppEventDispatcher for WebEventDispatcher {
 
     fn dispatch_change_turning_speed(&self, speed: f32) {
         let speed = self.format_speed(speed);
-        if self.are_extra_messages_enabled() {
+        if self.are_extra_messages_enabled(MessageCategory::Camera) {
             self.dispatch_top_message(&format!("Turning camera speed: {}", speed));
         }
         self.catch_error(dispatch_event_with(&self.event_bus, "back2front:change_turning_speed", &speed.into()));
@@ -147,7 +181,7 @@ impl AppEventDispatcher for WebEventDispatcher {
 
     fn dispatch_change_movement_speed(&self, speed: f32) {
         let speed = self.format_speed(speed);
-        if self.are_extra_messages_enabled() {
+        if self.are_extra_messages_enabled(MessageCategory::Camera) {
             self.dispatch_top_message(&format!("Translation camera speed: {}", speed));
         }
         self.catch_error(dispatch_event_with(&self.event_bus, "back2front:change_movement_speed", &speed.into()));
@@ -162,6 +196,11 @@ impl AppEventDispatcher for WebEventDispatcher {
         self.catch_error(dispatch_event_with(&self.event_bus, "back2front:fps", &fps.into()));
     }
 
+    fn dispatch_frame_times(&self, frame_times: &[f32]) {
+        let js_frame_times = Float32Array::from(frame_times);
+        self.catch_error(dispatch_event_with(&self.event_bus, "back2front:frame_times", &js_frame_times.into()));
+    }
+
     fn dispatch_request_fullscreen(&self) {
         self.catch_error(dispatch_event(&self.event_bus, "back2front:request_fullscreen"));
     }
@@ -174,19 +213,78 @@ impl AppEventDispatcher for WebEventDispatcher {
         self.catch_error(dispatch_event(&self.event_bus, "back2front:exit_pointer_lock"));
     }
 
+    fn dispatch_diagnostics(&self, readout: &DiagnosticsReadout) {
+        let red: Vec<f32> = readout.convergence_red.iter().flatten().copied().collect();
+        let blue: Vec<f32> = readout.convergence_blue.iter().flatten().copied().collect();
+        let object = js_sys::Object::new();
+        js_sys::Reflect::set(&object, &"scanlinePitch".into(), &readout.scanline_pitch.into()).expect("Reflection failed on scanlinePitch");
+        js_sys::Reflect::set(&object, &"convergenceRed".into(), &Float32Array::from(&red[..]).into()).expect("Reflection failed on convergenceRed");
+        js_sys::Reflect::set(&object, &"convergenceBlue".into(), &Float32Array::from(&blue[..]).into()).expect("Reflection failed on convergenceBlue");
+        self.catch_error(dispatch_event_with(&self.event_bus, "back2front:diagnostics", &object.into()));
+    }
+
+    fn dispatch_moire_report(&self, report: &MoireReport) {
+        let options = js_sys::Array::new();
+        for measurement in report.measurements.iter() {
+            let option = js_sys::Object::new();
+            js_sys::Reflect::set(&option, &"label".into(), &measurement.candidate.to_string().into()).expect("Reflection failed on label");
+            js_sys::Reflect::set(&option, &"score".into(), &measurement.score.into()).expect("Reflection failed on score");
+            js_sys::Reflect::set(&option, &"recommended".into(), &(measurement.candidate == report.recommended).into())
+                .expect("Reflection failed on recommended");
+            options.push(&option.into());
+        }
+        let object = js_sys::Object::new();
+        js_sys::Reflect::set(&object, &"options".into(), &options.into()).expect("Reflection failed on options");
+        js_sys::Reflect::set(&object, &"applied".into(), &report.applied.into()).expect("Reflection failed on applied");
+        self.catch_error(dispatch_event_with(&self.event_bus, "back2front:moire_report", &object.into()));
+    }
+
+    fn dispatch_light_gun_shot(&self, hit: Option<(u32, u32)>) {
+        let value = match hit {
+            Some((x, y)) => {
+                let object = js_sys::Object::new();
+                js_sys::Reflect::set(&object, &"x".into(), &x.into()).expect("Reflection failed on x");
+                js_sys::Reflect::set(&object, &"y".into(), &y.into()).expect("Reflection failed on y");
+                object.into()
+            }
+            None => JsValue::NULL,
+        };
+        self.catch_error(dispatch_event_with(&self.event_bus, "back2front:light_gun_shot", &value));
+    }
+
+    fn dispatch_play_sound(&self, sound: SoundEffect, volume: f32) {
+        let object = js_sys::Object::new();
+        js_sys::Reflect::set(&object, &"sound".into(), &sound.to_string().into()).expect("Reflection failed on sound");
+        js_sys::Reflect::set(&object, &"volume".into(), &volume.into()).expect("Reflection failed on volume");
+        self.catch_error(dispatch_event_with(&self.event_bus, "back2front:play_sound", &object.into()));
+    }
+
     // @TODO no other way to handle this by now, because of glow lacking API, find better way later
-    fn dispatch_screenshot(&self, width: i32, height: i32, pixels: &mut [u8]) -> AppResult<()> {
-        let gl = &self.gl;
-        gl.read_pixels_with_opt_u8_array(0, 0, width, height, glow::RGBA, glow::UNSIGNED_BYTE, Some(&mut *pixels))?;
+    fn request_screenshot_readback(&self, width: i32, height: i32) -> AppResult<()> {
+        self.gl.read_pixels_with_i32(0, 0, width, height, glow::RGBA, glow::UNSIGNED_BYTE, 0)?;
+        Ok(())
+    }
+
+    fn dispatch_screenshot(&self, width: i32, height: i32, pixels: &mut [u8], tag: Option<&str>) -> AppResult<()> {
         let js_pixels = unsafe { js_sys::Uint8Array::view(pixels) };
         let object = js_sys::Object::new();
         js_sys::Reflect::set(&object, &"width".into(), &width.into()).expect("Reflection failed on width");
         js_sys::Reflect::set(&object, &"height".into(), &height.into()).expect("Reflection failed on height");
         js_sys::Reflect::set(&object, &"buffer".into(), &js_pixels.into()).expect("Reflection failed on js_pixels");
+        let js_tag = match tag {
+            Some(tag) => tag.into(),
+            None => JsValue::NULL,
+        };
+        js_sys::Reflect::set(&object, &"tag".into(), &js_tag).expect("Reflection failed on tag");
         self.catch_error(dispatch_event_with(&self.event_bus, "back2front:screenshot", &object));
         Ok(())
     }
 
+    fn dispatch_turntable_recording(&self, recording: bool) -> AppResult<()> {
+        self.catch_error(dispatch_event_with(&self.event_bus, "back2front:turntable_recording", &recording.into()));
+        Ok(())
+    }
+
     fn dispatch_change_camera_movement_mode(&self, locked_mode: CameraLockMode) {
         self.catch_error(dispatch_event_with(
             &self.event_bus,
@@ -199,16 +297,27 @@ impl AppEventDispatcher for WebEventDispatcher {
         self.catch_error(dispatch_event_with(&self.event_bus, "back2front:top_message", &message.into()));
     }
 
-    fn dispatch_minimum_value(&self, value: &dyn Display) {
-        self.dispatch_top_message(&format!("Minimum value is {}", value));
+    fn dispatch_minimum_value(&self, clamped: &ClampedValue) {
+        self.dispatch_top_message(&format!("Minimum value is {}", clamped.bound));
+        self.dispatch_clamped_value(clamped, "minimum");
     }
 
-    fn dispatch_maximum_value(&self, value: &dyn Display) {
-        self.dispatch_top_message(&format!("Maximum value is {}", value));
+    fn dispatch_maximum_value(&self, clamped: &ClampedValue) {
+        self.dispatch_top_message(&format!("Maximum value is {}", clamped.bound));
+        self.dispatch_clamped_value(clamped, "maximum");
     }
 }
 
 impl WebEventDispatcher {
+    fn dispatch_clamped_value(&self, clamped: &ClampedValue, limit: &str) {
+        let object = js_sys::Object::new();
+        js_sys::Reflect::set(&object, &"field".into(), &clamped.field.into()).expect("Reflection failed on field");
+        js_sys::Reflect::set(&object, &"limit".into(), &limit.into()).expect("Reflection failed on limit");
+        js_sys::Reflect::set(&object, &"attempted".into(), &clamped.attempted.to_string().into()).expect("Reflection failed on attempted");
+        js_sys::Reflect::set(&object, &"bound".into(), &clamped.bound.to_string().into()).expect("Reflection failed on bound");
+        self.catch_error(dispatch_event_with(&self.event_bus, "back2front:value_clamped", &object.into()));
+    }
+
     fn format_speed(&self, speed: f32) -> String {
         format!("x{}", (speed * 1000.0).round() / 1000.0)
     }
@@ -29,6 +29,14 @@ impl<GL: HasContext> GlowSafeAdapter<GL> {
         unsafe { self.gl.enable(parameter) }
     }
 
+    pub fn disable(&self, parameter: u32) {
+        unsafe { self.gl.disable(parameter) }
+    }
+
+    pub fn blend_func(&self, src: u32, dst: u32) {
+        unsafe { self.gl.blend_func(src, dst) }
+    }
+
     pub fn enable_vertex_attrib_array(&self, index: Option<u32>) {
         unsafe { self.gl.enable_vertex_attrib_array(index.unwrap()) }
     }
@@ -117,6 +125,34 @@ impl<GL: HasContext> GlowSafeAdapter<GL> {
         unsafe { self.gl.bind_buffer(target, buffer) }
     }
 
+    pub fn delete_buffer(&self, buffer: GL::Buffer) {
+        unsafe { self.gl.delete_buffer(buffer) }
+    }
+
+    pub fn buffer_data_size(&self, target: u32, size: i32, usage: u32) {
+        unsafe { self.gl.buffer_data_size(target, size, usage) }
+    }
+
+    pub fn get_buffer_sub_data(&self, target: u32, offset: i32, dst_data: &mut [u8]) {
+        unsafe { self.gl.get_buffer_sub_data(target, offset, dst_data) }
+    }
+
+    pub fn read_pixels(&self, x: i32, y: i32, width: i32, height: i32, format: u32, gltype: u32, data: &mut [u8]) {
+        unsafe { self.gl.read_pixels(x, y, width, height, format, gltype, data) }
+    }
+
+    pub fn fence_sync(&self, condition: u32, flags: u32) -> Result<GL::Fence, String> {
+        unsafe { self.gl.fence_sync(condition, flags) }
+    }
+
+    pub fn get_sync_status(&self, fence: GL::Fence) -> u32 {
+        unsafe { self.gl.get_sync_status(fence) }
+    }
+
+    pub fn delete_sync(&self, fence: GL::Fence) {
+        unsafe { self.gl.delete_sync(fence) }
+    }
+
     pub fn bind_framebuffer(&self, target: u32, framebuffer: Option<GL::Framebuffer>) {
         unsafe { self.gl.bind_framebuffer(target, framebuffer) }
     }
@@ -177,6 +213,10 @@ impl<GL: HasContext> GlowSafeAdapter<GL> {
         unsafe { self.gl.delete_texture(texture) }
     }
 
+    pub fn draw_arrays(&self, mode: u32, first: i32, count: i32) {
+        unsafe { self.gl.draw_arrays(mode, first, count) }
+    }
+
     pub fn draw_arrays_instanced(&self, mode: u32, first: i32, count: i32, instance_count: i32) {
         unsafe { self.gl.draw_arrays_instanced(mode, first, count, instance_count) }
     }
@@ -264,6 +304,18 @@ impl<GL: HasContext> GlowSafeAdapter<GL> {
         unsafe { self.gl.uniform_3_f32_slice(location, v) }
     }
 
+    pub fn uniform_4_f32_slice(&self, location: Option<GL::UniformLocation>, v: &[f32; 4]) {
+        unsafe { self.gl.uniform_4_f32_slice(location, v) }
+    }
+
+    pub fn uniform_matrix_2_f32_slice(&self, location: Option<GL::UniformLocation>, transpose: bool, v: &[f32; 4]) {
+        unsafe { self.gl.uniform_matrix_2_f32_slice(location, transpose, v) }
+    }
+
+    pub fn uniform_matrix_3_f32_slice(&self, location: Option<GL::UniformLocation>, transpose: bool, v: &[f32; 9]) {
+        unsafe { self.gl.uniform_matrix_3_f32_slice(location, transpose, v) }
+    }
+
     pub fn uniform_matrix_4_f32_slice(&self, location: Option<GL::UniformLocation>, transpose: bool, v: &[f32; 16]) {
         unsafe { self.gl.uniform_matrix_4_f32_slice(location, transpose, v) }
     }
@@ -61,6 +61,8 @@ export async function playQuerystring (querystring: string) {
     const skipControllerUi = searchParams.has('skip-ui');
     const skipDrawing = searchParams.has('skip-drawing');
     const fullscreen = searchParams.has('fullscreen');
+    const subtitles = searchParams.has('subtitles') ? await (await fetch(throwOnNull(searchParams.get('subtitles')))).text() : undefined;
+    const bezelArtwork = searchParams.has('bezel') ? (await animationsGateway.getFromPath(throwOnNull(searchParams.get('bezel')), false))[0].raw : undefined;
 
     const imageWidth = animations[0].raw.width;
     const imageHeight = animations[0].raw.height;
@@ -74,7 +76,9 @@ export async function playQuerystring (querystring: string) {
             backgroundHeight: imageHeight,
             activePreset: selectedPreset,
             animations,
-            skipDrawing
+            skipDrawing,
+            subtitles,
+            bezelArtwork
         },
         skipControllerUi,
         fullscreen
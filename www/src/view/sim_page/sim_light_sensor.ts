@@ -0,0 +1,59 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+
+// Illuminance of a sunny room, where the room brightness saturates.
+const BRIGHT_ROOM_LUX = 10000;
+
+// Follows the light of the real room with the AmbientLightSensor, where the browser exposes it.
+export class SimLightSensor {
+    private _sensor: any = null;
+
+    static make (): SimLightSensor { return new SimLightSensor(); }
+    private constructor () {}
+
+    static isAvailable (): boolean {
+        return 'AmbientLightSensor' in window;
+    }
+
+    // The eye perceives light logarithmically, so the lux are mapped in decades to the 0-1 room brightness.
+    start (onBrightness: (brightness: number) => void): boolean {
+        this.stop();
+        if (!SimLightSensor.isAvailable()) {
+            return false;
+        }
+        let sensor;
+        try {
+            // Throws right away when a permissions policy blocks the sensor.
+            sensor = new (window as any).AmbientLightSensor();
+            sensor.addEventListener('reading', () => {
+                onBrightness(Math.min(1, Math.log10(1 + sensor.illuminance) / Math.log10(1 + BRIGHT_ROOM_LUX)));
+            });
+            sensor.addEventListener('error', (e: any) => console.error(e.error));
+            sensor.start();
+        } catch (e) {
+            console.error(e);
+            return false;
+        }
+        this._sensor = sensor;
+        return true;
+    }
+
+    stop (): void {
+        if (this._sensor) {
+            this._sensor.stop();
+        }
+        this._sensor = null;
+    }
+}
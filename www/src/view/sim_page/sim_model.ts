@@ -24,6 +24,13 @@ const STORE_KEY_WEBGL_POWER_PREFERENCE = 'option-powerPreference';
 const STORE_KEY_WEBGL_ANTIALIAS = 'option-antialias';
 const POWER_PREFERENCE_DEFAULT = 'default';
 const FILTERS_PRESET_STORE_KEY = 'FiltersPreset';
+const RECOVERY_STATE_STORE_KEY = 'RecoveryState';
+const TURNTABLE_CLIP_FRAME_RATE = 30;
+
+export interface RecoveryState {
+    preset: string;
+    camera: number[];
+}
 
 export class SimModel {
     private readonly _eventBus: any;
@@ -39,6 +46,7 @@ export class SimModel {
         this._store = store;
         this._state = {
             canvas,
+            clipRecorder: null,
             msg: null,
             loaded: false,
             storedValues: {
@@ -89,6 +97,32 @@ export class SimModel {
         return Object.assign({ storedValues: this._state.storedValues }, this._state.msg, result);
     }
 
+    saveRecoveryState ({ preset, camera }: { preset: string, camera: ArrayLike<number> }) {
+        const recovery: RecoveryState = { preset, camera: Array.from(camera) };
+        this._store.setItem(RECOVERY_STATE_STORE_KEY, JSON.stringify(recovery));
+    }
+
+    clearRecoveryState () {
+        this._store.removeItem(RECOVERY_STATE_STORE_KEY);
+    }
+
+    takeRecoveryState (): RecoveryState | null {
+        const stored = this._store.getItem(RECOVERY_STATE_STORE_KEY);
+        this.clearRecoveryState();
+        if (!stored) {
+            return null;
+        }
+        try {
+            const recovery = JSON.parse(stored);
+            if (typeof recovery.preset === 'string' && Array.isArray(recovery.camera) && recovery.camera.length === 9) {
+                return recovery;
+            }
+        } catch (e) {
+            Logger.log('Ignoring corrupted recovery state.', e);
+        }
+        return null;
+    }
+
     runFrame () {
         if (!this._state.loaded) {
             return false;
@@ -159,7 +193,81 @@ export class SimModel {
         return { width, height };
     }
 
-    async fireScreenshot ({ buffer, width, height }: { buffer: ArrayLike<number>, width: number, height: number}) {
+    advanceFrames (frames: number) {
+        this._wasmBackend.advanceFrames(frames);
+    }
+
+    // The colors the simulation maps to P3 only come out right if the canvas is tagged as P3 too.
+    setCanvasColorSpace (colorSpace: 'srgb' | 'display-p3') {
+        const gl = this._state.canvas.getContext('webgl2') as any;
+        if (!gl || !('drawingBufferColorSpace' in gl)) {
+            if (colorSpace !== 'srgb') {
+                console.warn('This browser can not tag the canvas color space, the output stays sRGB.');
+            }
+            return;
+        }
+        if (colorSpace !== 'srgb' && !window.matchMedia('(color-gamut: p3)').matches) {
+            console.warn('The display does not cover the P3 gamut, the saturated colors will be clipped.');
+        }
+        gl.drawingBufferColorSpace = colorSpace;
+    }
+
+    async fireInputLog (log: string) {
+        const a = document.createElement('a');
+        document.body.appendChild(a);
+        a.classList.add('no-display');
+        const url = URL.createObjectURL(new Blob([log], { type: 'text/plain' }));
+        a.href = url;
+        a.download = 'Display-Sim_input_' + new Date().toISOString() + '.txt';
+        a.click();
+
+        await new Promise(resolve => setTimeout(resolve, 3000));
+        URL.revokeObjectURL(url);
+        a.remove();
+    }
+
+    // The turntable comes out as a single clip of the canvas, the browser encodes it while the camera orbits.
+    startClipRecording (): boolean {
+        const MediaRecorder = (window as any).MediaRecorder;
+        if (!MediaRecorder || !this._state.canvas.captureStream) {
+            return false;
+        }
+        let recorder;
+        try {
+            recorder = new MediaRecorder(this._state.canvas.captureStream(TURNTABLE_CLIP_FRAME_RATE));
+            recorder.start();
+        } catch (e) {
+            Logger.log('Could not record the canvas.', e);
+            return false;
+        }
+        const chunks: Blob[] = [];
+        recorder.ondataavailable = (e: { data: Blob }) => chunks.push(e.data);
+        recorder.onstop = async () => {
+            const a = document.createElement('a');
+            document.body.appendChild(a);
+            a.classList.add('no-display');
+            const url = URL.createObjectURL(new Blob(chunks, { type: recorder.mimeType }));
+            a.href = url;
+            a.download = 'Display-Sim_turntable_' + new Date().toISOString() + (recorder.mimeType.includes('mp4') ? '.mp4' : '.webm');
+            a.click();
+
+            await new Promise(resolve => setTimeout(resolve, 3000));
+            URL.revokeObjectURL(url);
+            a.remove();
+        };
+        this._state.clipRecorder = recorder;
+        return true;
+    }
+
+    stopClipRecording () {
+        const recorder = this._state.clipRecorder;
+        this._state.clipRecorder = null;
+        if (recorder && recorder.state !== 'inactive') {
+            recorder.stop();
+        }
+    }
+
+    async fireScreenshot ({ buffer, width, height, tag }: { buffer: ArrayLike<number>, width: number, height: number, tag: string | null }) {
         Logger.log('starting screenshot');
         Logger.log('width', width, 'height', height);
 
@@ -184,7 +292,7 @@ export class SimModel {
         const blob = await new Promise(resolve => canvas.toBlob(resolve));
         const url = URL.createObjectURL(blob);
         a.href = url;
-        a.download = 'Display-Sim_' + new Date().toISOString() + '.png';
+        a.download = 'Display-Sim_' + (tag ? tag + '_' : '') + new Date().toISOString() + '.png';
         a.click();
     
         await new Promise(resolve => setTimeout(resolve, 3000));
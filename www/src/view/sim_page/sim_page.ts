@@ -20,6 +20,8 @@ import {PubSub, PubSubImpl} from '../../services/pubsub';
 import {actions, DispatchKeyMessage, SimTemplate, SimTemplateEvents} from './sim_template';
 import {data, SimViewModel, SimViewData} from './sim_view_model';
 import { SimModel } from './sim_model';
+import { SimSounds } from './sim_sounds';
+import { SimLightSensor } from './sim_light_sensor';
 import {throwOnNull} from "../../services/guards";
 import {Observable, ObserverCb} from "../../services/observable";
 import {BackendEvent} from "../../services/event_types";
@@ -66,6 +68,9 @@ const channels: Channels = {
     back: PubSubImpl.make<BackendMessage>()
 };
 
+// Function keys bound by the simulation that the browser would otherwise take for itself.
+const simulationFunctionKeys = new Set(['F1', 'F3', 'F5', 'F6', 'F7', 'F10']);
+
 async function setupPage (root: ShadowRoot, state: SimViewData): Promise<Disposable> {
     const template = SimTemplate.make(root, events);
     const view_model = SimViewModel.make(state, template);
@@ -80,6 +85,8 @@ async function setupPage (root: ShadowRoot, state: SimViewData): Promise<Disposa
 async function show (template: SimTemplate, view_model: SimViewModel, model: SimModel, events: SimTemplateEvents, backendObservable: Observable<BackendMessage>, backendEmitter: Action<BackendMessage>): Promise<Disposable> {
 
     view_model.init(await model.load());
+    const sounds = SimSounds.make();
+    const lightSensor = SimLightSensor.make();
 
     async function fireBackendEvent (kind: string, msg?: any) {
         const event = {
@@ -90,6 +97,20 @@ async function show (template: SimTemplate, view_model: SimViewModel, model: Sim
         console.log('front2back', kind, msg);
     }
 
+    const recovery = model.takeRecoveryState();
+    if (recovery && window.confirm('The previous session did not close properly. Do you want to restore where you left off?')) {
+        view_model.clickPreset(recovery.preset);
+        await fireBackendEvent(Constants.FILTER_PRESETS_SELECTED_EVENT_KIND, recovery.preset);
+        const cameraKinds = [
+            'camera-pos-x', 'camera-pos-y', 'camera-pos-z',
+            'camera-dir-x', 'camera-dir-y', 'camera-dir-z',
+            'camera-axis-up-x', 'camera-axis-up-y', 'camera-axis-up-z'
+        ];
+        for (let i = 0; i < cameraKinds.length; i++) {
+            await fireBackendEvent(cameraKinds[i], recovery.camera[i]);
+        }
+    }
+
     async function fireKeyboardEvent ({ pressed, key, timeout }: {pressed: boolean, key: string, timeout?: number}) {
         await fireBackendEvent('keyboard', { pressed, key });
         if (pressed && timeout) {
@@ -101,8 +122,17 @@ async function show (template: SimTemplate, view_model: SimViewModel, model: Sim
     }
 
     events.toggleControls.subscribe(() => view_model.toggleControls());
+    events.closeMoireReport.subscribe(() => view_model.closeMoireReport());
     events.toggleMenu.subscribe(m => view_model.toggleMenu(m));
     events.changeSyncedInput.subscribe(msg => fireBackendEvent(msg.kind, msg.value));
+    events.changeLayers.subscribe(layers => fireBackendEvent('layers', layers));
+    events.changeCustomPalette.subscribe(palette => fireBackendEvent('custom-palette', palette));
+    events.loadTextFile.subscribe(msg => {
+        if (msg.kind === 'input-replay') {
+            view_model.changeInputReplay(msg.name);
+        }
+        return fireBackendEvent(msg.kind, msg.text);
+    });
     events.clickPreset.subscribe(async preset => {
         view_model.clickPreset(preset);
         model.setPreset(preset);
@@ -113,6 +143,16 @@ async function show (template: SimTemplate, view_model: SimViewModel, model: Sim
             view_model.showLoading();
             await model.changeAntialiasing(msg.value);
             view_model.changeAntialias(msg.value);
+        } else if (msg.kind === 'ambient-light-sensor') {
+            lightSensor.stop();
+            const following = !!msg.value && lightSensor.start(brightness => fireBackendEvent('room-brightness', brightness));
+            if (msg.value && !following) {
+                view_model.openTopMessage('Ambient light sensor not available, set the room brightness by hand.');
+            }
+            view_model.changeAmbientLightSensor(following);
+        } else if (msg.kind === 'input-recording') {
+            view_model.changeInputRecording(msg.value);
+            return fireBackendEvent(msg.kind, msg.value);
         } else {
             return fireBackendEvent(msg.kind, msg.value);
         }
@@ -121,6 +161,13 @@ async function show (template: SimTemplate, view_model: SimViewModel, model: Sim
         if (msg.key.startsWith('webgl:')) {
             return handleWebGLKeys(msg, model, view_model);
         }
+        if (msg.key === 'replay:step') {
+            return model.advanceFrames(1);
+        }
+        if (msg.key === 'replay:stop') {
+            view_model.changeInputReplay('');
+            return fireBackendEvent('input-replay', '');
+        }
         let pressed;
         let timeout;
         switch (msg.action) {
@@ -141,13 +188,25 @@ async function show (template: SimTemplate, view_model: SimViewModel, model: Sim
         case 'back2front:request_pointer_lock': return view_model.requestPointerLock();
         case 'back2front:preset_selected_name': return view_model.presetSelectedName(msg);
         case 'back2front:screenshot': return model.fireScreenshot(msg);
+        case 'back2front:turntable_recording': return recordTurntable(msg, model, view_model);
+        case 'back2front:input_log': return model.fireInputLog(msg);
+        case 'back2front:recovery_state': return model.saveRecoveryState(msg);
         case 'back2front:camera_update': return view_model.updateCameraMatrix(msg);
         case 'back2front:toggle_info_panel': return view_model.toggleInfoPanel();
         case 'back2front:fps': return view_model.changeFps(msg);
+        case 'back2front:frame_times': return view_model.changeFrameTimes(msg);
+        case 'back2front:diagnostics': return view_model.changeDiagnostics(msg);
+        case 'back2front:moire_report': return view_model.changeMoireReport(msg);
+        case 'back2front:value_clamped': return view_model.changeClampedValue(msg);
         case 'back2front:exit_pointer_lock': return view_model.exitPointerLock();
-        case 'back2front:exiting_session': return view_model.exitingSession();
+        case 'back2front:play_sound': return sounds.play(msg);
+        case 'back2front:light_gun_shot': return reportLightGunShot(msg, view_model);
+        case 'back2front:exiting_session':
+            model.clearRecoveryState();
+            return view_model.exitingSession();
         case 'back2front:change_camera_movement_mode': return view_model.changeCameraMovementMode(msg);
         case 'back2front:change_camera_zoom': return view_model.changeCameraZoom(msg);
+        case 'back2front:change_camera_focal_length': return view_model.changeCameraFocalLength(msg);
         case 'back2front:change_pixel_width': return view_model.changePixelWidth(msg);
         case 'back2front:change_pixel_horizontal_gap': return view_model.changePixelHorizontalGap(msg);
         case 'back2front:change_pixel_vertical_gap': return view_model.changePixelVerticalGap(msg);
@@ -178,6 +237,132 @@ async function show (template: SimTemplate, view_model: SimViewModel, model: Sim
         case 'back2front:screen_curvature': return view_model.changeScreenCurvature(msg);
         case 'back2front:color_gamma': return view_model.changeColorGamma(msg);
         case 'back2front:color_noise': return view_model.changeColorNoise(msg);
+        case 'back2front:blending_space': return view_model.changeBlendingSpace(msg);
+        case 'back2front:layering_blend_mode': return view_model.changeLayeringBlendMode(msg);
+        case 'back2front:foreground_opacity': return view_model.changeForegroundOpacity(msg);
+        case 'back2front:background_resolution_scale': return view_model.changeBackgroundResolutionScale(msg);
+        case 'back2front:background_blur_passes': return view_model.changeBackgroundBlurPasses(msg);
+        case 'back2front:pixel_horizontal_spread': return view_model.changePixelHorizontalSpread(msg);
+        case 'back2front:pixel_vertical_spread': return view_model.changePixelVerticalSpread(msg);
+        case 'back2front:physical_calibration': return view_model.changePhysicalCalibration(msg);
+        case 'back2front:display_dpi': return view_model.changeDisplayDpi(msg);
+        case 'back2front:dot_pitch': return view_model.changeDotPitch(msg);
+        case 'back2front:crt_model': return view_model.changeCrtModel(msg);
+        case 'back2front:color_channels_order': return view_model.changeColorChannelsOrder(msg);
+        case 'back2front:channel_offset_red_x': return view_model.changeChannelOffsetRedX(msg);
+        case 'back2front:channel_offset_red_y': return view_model.changeChannelOffsetRedY(msg);
+        case 'back2front:channel_offset_green_x': return view_model.changeChannelOffsetGreenX(msg);
+        case 'back2front:channel_offset_green_y': return view_model.changeChannelOffsetGreenY(msg);
+        case 'back2front:channel_offset_blue_x': return view_model.changeChannelOffsetBlueX(msg);
+        case 'back2front:channel_offset_blue_y': return view_model.changeChannelOffsetBlueY(msg);
+        case 'back2front:overlapping_blend_mode': return view_model.changeOverlappingBlendMode(msg);
+        case 'back2front:overlapping_gain_red': return view_model.changeOverlappingGainRed(msg);
+        case 'back2front:overlapping_gain_green': return view_model.changeOverlappingGainGreen(msg);
+        case 'back2front:overlapping_gain_blue': return view_model.changeOverlappingGainBlue(msg);
+        case 'back2front:crt_sounds': return view_model.changeCrtSounds(msg);
+        case 'back2front:light_gun': return view_model.changeLightGun(msg);
+        case 'back2front:magnet_strength': return view_model.changeMagnetStrength(msg);
+        case 'back2front:magnet_radius': return view_model.changeMagnetRadius(msg);
+        case 'back2front:procedural_source': return view_model.changeProceduralSource(msg);
+        case 'back2front:diagnostics_overlay': return view_model.changeDiagnosticsOverlay(msg);
+        case 'back2front:moire_assistant': return view_model.changeMoireAssistant(msg);
+        case 'back2front:subtitles': return view_model.changeSubtitles(msg);
+        case 'back2front:scanline_phase': return view_model.changeScanlinePhase(msg);
+        case 'back2front:sync_offset': return view_model.changeSyncOffset(msg);
+        case 'back2front:scanline_orientation': return view_model.changeScanlineOrientation(msg);
+        case 'back2front:scanline_angle': return view_model.changeScanlineAngle(msg);
+        case 'back2front:source_rotation': return view_model.changeSourceRotation(msg);
+        case 'back2front:source_mirror': return view_model.changeSourceMirror(msg);
+        case 'back2front:damper_wires': return view_model.changeDamperWires(msg);
+        case 'back2front:damper_wire_position': return view_model.changeDamperWirePosition(msg);
+        case 'back2front:damper_wire_opacity': return view_model.changeDamperWireOpacity(msg);
+        case 'back2front:edge_reflection_intensity': return view_model.changeEdgeReflectionIntensity(msg);
+        case 'back2front:edge_reflection_width': return view_model.changeEdgeReflectionWidth(msg);
+        case 'back2front:blooming_amount': return view_model.changeBloomingAmount(msg);
+        case 'back2front:blooming_time_constant': return view_model.changeBloomingTimeConstant(msg);
+        case 'back2front:tv_lines': return view_model.changeTvLines(msg);
+        case 'back2front:response_rise_time': return view_model.changeResponseRiseTime(msg);
+        case 'back2front:response_fall_time': return view_model.changeResponseFallTime(msg);
+        case 'back2front:floor_reflection': return view_model.changeFloorReflection(msg);
+        case 'back2front:floor_roughness': return view_model.changeFloorRoughness(msg);
+        case 'back2front:foreground_depth_test': return view_model.changeForegroundDepthTest(msg);
+        case 'back2front:background_depth_test': return view_model.changeBackgroundDepthTest(msg);
+        case 'back2front:layers': return view_model.changeLayers(msg);
+        case 'back2front:screenshot_bracket': return view_model.changeScreenshotBracket(msg);
+        case 'back2front:bloom_threshold': return view_model.changeBloomThreshold(msg);
+        case 'back2front:bloom_intensity': return view_model.changeBloomIntensity(msg);
+        case 'back2front:status_bar': return view_model.changeStatusBar(msg);
+        case 'back2front:message_verbosity': return view_model.changeMessageVerbosity(msg);
+        case 'back2front:signal_kind': return view_model.changeSignalKind(msg);
+        case 'back2front:vignette_strength': return view_model.changeVignetteStrength(msg);
+        case 'back2front:vignette_radius': return view_model.changeVignetteRadius(msg);
+        case 'back2front:camera_collision': return view_model.changeCameraCollision(msg);
+        case 'back2front:convergence_red_x': return view_model.changeConvergenceRedX(msg);
+        case 'back2front:convergence_red_y': return view_model.changeConvergenceRedY(msg);
+        case 'back2front:convergence_green_x': return view_model.changeConvergenceGreenX(msg);
+        case 'back2front:convergence_green_y': return view_model.changeConvergenceGreenY(msg);
+        case 'back2front:convergence_blue_x': return view_model.changeConvergenceBlueX(msg);
+        case 'back2front:convergence_blue_y': return view_model.changeConvergenceBlueY(msg);
+        case 'back2front:interlacing': return view_model.changeInterlacing(msg);
+        case 'back2front:rolling_scan': return view_model.changeRollingScan(msg);
+        case 'back2front:rolling_scan_speed': return view_model.changeRollingScanSpeed(msg);
+        case 'back2front:rolling_scan_size': return view_model.changeRollingScanSize(msg);
+        case 'back2front:flicker': return view_model.changeFlicker(msg);
+        case 'back2front:flicker_amplitude': return view_model.changeFlickerAmplitude(msg);
+        case 'back2front:turntable_seconds': return view_model.changeTurntableSeconds(msg);
+        case 'back2front:glass_reflection': return view_model.changeGlassReflection(msg);
+        case 'back2front:glass_reflectivity': return view_model.changeGlassReflectivity(msg);
+        case 'back2front:blemish_strength': return view_model.changeBlemishStrength(msg);
+        case 'back2front:blemish_seed': return view_model.changeBlemishSeed(msg);
+        case 'back2front:dead_subpixels': return view_model.changeDeadSubpixels(msg);
+        case 'back2front:stuck_pixels': return view_model.changeStuckPixels(msg);
+        case 'back2front:dead_scanlines': return view_model.changeDeadScanlines(msg);
+        case 'back2front:bezel_scale': return view_model.changeBezelScale(msg);
+        case 'back2front:bezel_offset_x': return view_model.changeBezelOffsetX(msg);
+        case 'back2front:bezel_offset_y': return view_model.changeBezelOffsetY(msg);
+        case 'back2front:room_environment': return view_model.changeRoomEnvironment(msg);
+        case 'back2front:room_brightness': return view_model.changeRoomBrightness(msg);
+        case 'back2front:color_temperature': return view_model.changeColorTemperature(msg);
+        case 'back2front:output_gamma': return view_model.changeOutputGamma(msg);
+        case 'back2front:black_pedestal': return view_model.changeBlackPedestal(msg);
+        case 'back2front:contrast_knee': return view_model.changeContrastKnee(msg);
+        case 'back2front:contrast_shoulder': return view_model.changeContrastShoulder(msg);
+        case 'back2front:red_gain': return view_model.changeRedGain(msg);
+        case 'back2front:red_offset': return view_model.changeRedOffset(msg);
+        case 'back2front:green_gain': return view_model.changeGreenGain(msg);
+        case 'back2front:green_offset': return view_model.changeGreenOffset(msg);
+        case 'back2front:blue_gain': return view_model.changeBlueGain(msg);
+        case 'back2front:blue_offset': return view_model.changeBlueOffset(msg);
+        case 'back2front:color_saturation': return view_model.changeColorSaturation(msg);
+        case 'back2front:color_hue': return view_model.changeColorHue(msg);
+        case 'back2front:white_clipping': return view_model.changeWhiteClipping(msg);
+        case 'back2front:dithering': return view_model.changeDithering(msg);
+        case 'back2front:dithering_bits': return view_model.changeDitheringBits(msg);
+        case 'back2front:interlace_field_flicker': return view_model.changeInterlaceFieldFlicker(msg);
+        case 'back2front:interlace_line_pairing': return view_model.changeInterlaceLinePairing(msg);
+        case 'back2front:transition': return view_model.changeTransition(msg);
+        case 'back2front:transition_seconds': return view_model.changeTransitionSeconds(msg);
+        case 'back2front:palette': return view_model.changePalette(msg);
+        case 'back2front:custom_palette': return view_model.changeCustomPalette(msg);
+        case 'back2front:burn_in_rate': return view_model.changeBurnInRate(msg);
+        case 'back2front:burn_in_visibility': return view_model.changeBurnInVisibility(msg);
+        case 'back2front:ken_burns_speed': return view_model.changeKenBurnsSpeed(msg);
+        case 'back2front:ken_burns_margin': return view_model.changeKenBurnsMargin(msg);
+        case 'back2front:analog_static': return view_model.changeAnalogStatic(msg);
+        case 'back2front:analog_static_speed': return view_model.changeAnalogStaticSpeed(msg);
+        case 'back2front:hsync_wobble': return view_model.changeHsyncWobble(msg);
+        case 'back2front:hsync_wobble_frequency': return view_model.changeHsyncWobbleFrequency(msg);
+        case 'back2front:source_interpolation': return view_model.changeSourceInterpolation(msg);
+        case 'back2front:output_interpolation': return view_model.changeOutputInterpolation(msg);
+        case 'back2front:sharpen_strength': return view_model.changeSharpenStrength(msg);
+        case 'back2front:sharpen_halo_suppression': return view_model.changeSharpenHaloSuppression(msg);
+        case 'back2front:output_color_space':
+            model.setCanvasColorSpace(msg === 'Display P3' ? 'display-p3' : 'srgb');
+            return view_model.changeOutputColorSpace(msg);
+        case 'back2front:screen_corner_radius': return view_model.changeScreenCornerRadius(msg);
+        case 'back2front:screen_matte': return view_model.changeScreenMatte(msg);
+        case 'back2front:screen_curvature_amount': return view_model.changeScreenCurvatureAmount(msg);
+        case 'back2front:phosphor_primaries': return view_model.changePhosphorPrimaries(msg);
         case 'back2front:rgb_red_r': return view_model.changeColorRgb(msg, 'red', 'r');
         case 'back2front:rgb_red_g': return view_model.changeColorRgb(msg, 'red', 'g');
         case 'back2front:rgb_red_b': return view_model.changeColorRgb(msg, 'red', 'b');
@@ -211,7 +396,12 @@ async function show (template: SimTemplate, view_model: SimViewModel, model: Sim
     }
 
     // Forwarding other events so they can be readed by the backend
-    addDomListener(windowListener, 'keydown', e => fireKeyboardEvent({ pressed: true, key: e.key }));
+    addDomListener(windowListener, 'keydown', e => {
+        if (simulationFunctionKeys.has(e.key)) {
+            e.preventDefault();
+        }
+        fireKeyboardEvent({ pressed: true, key: e.key });
+    });
     addDomListener(windowListener, 'keyup', e => fireKeyboardEvent({ pressed: false, key: e.key }));
     addDomListener(canvasListener, 'mousedown', async e => {
         if (e.buttons === 1) {
@@ -221,6 +411,7 @@ async function show (template: SimTemplate, view_model: SimViewModel, model: Sim
     });
     addDomListener(windowListener, 'mouseup', () => fireBackendEvent('mouse-click', false)); // note this one goes to 'window'. It doesn't work with 'canvas' because of some obscure bug I didn't figure out yet.
     addDomListener(windowListener, 'mousemove', e => fireBackendEvent('mouse-move', { x: e.movementX, y: e.movementY }));
+    addDomListener(canvasListener, 'mousemove', e => fireBackendEvent('pointer-position', { x: e.offsetX * window.devicePixelRatio, y: e.offsetY * window.devicePixelRatio }));
     addDomListener(canvasListener, 'mousewheel', e => fireBackendEvent('mouse-wheel', e.deltaY));
     addDomListener(canvasListener, 'blur', () => fireBackendEvent('blurred-window'));
     addDomListener(canvasListener, 'mouseover', () => fireKeyboardEvent({ pressed: true, key: 'canvas_focused' }));
@@ -230,10 +421,25 @@ async function show (template: SimTemplate, view_model: SimViewModel, model: Sim
     return Disposable.make(() => {
         windowListener.cancelAnimationFrame(newFrameId);
         model.unloadSimulation();
+        sounds.dispose();
+        lightSensor.stop();
         listeners.forEach(({ eventBus, type, callback, options }) => eventBus.removeEventListener(type, callback, options));
     });
 }
 
+function recordTurntable (recording: boolean, model: SimModel, view_model: SimViewModel) {
+    if (!recording) {
+        model.stopClipRecording();
+    } else if (!model.startClipRecording()) {
+        view_model.openTopMessage('This browser can not record the canvas, the turntable will only orbit.');
+    }
+}
+
+function reportLightGunShot (hit: { x: number, y: number } | null, view_model: SimViewModel) {
+    window.dispatchEvent(new CustomEvent('light-gun-shot', { detail: hit }));
+    view_model.openTopMessage(hit ? 'Light gun hit at ' + hit.x + ', ' + hit.y : 'Light gun missed');
+}
+
 async function handleWebGLKeys (msg: DispatchKeyMessage, model: SimModel, view_model: SimViewModel) {
     let direction;
     if (msg.key.endsWith('-dec')) {
@@ -0,0 +1,113 @@
+/* Copyright (c) 2019-2021 José manuel Barroso Galindo <theypsilon@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Affero General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU Affero General Public License for more details.
+ *
+ * You should have received a copy of the GNU Affero General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>. */
+
+import {throwOnNull} from "../../services/guards";
+
+const HORIZONTAL_SCAN_FREQUENCY = 15734;
+const DEGAUSS_SECONDS = 0.35;
+
+export interface PlaySoundMessage {
+    sound: 'hum' | 'degauss' | 'static';
+    volume: number;
+}
+
+export class SimSounds {
+    private _ctx: AudioContext | null = null;
+    private _humGain: GainNode | null = null;
+    private _staticGain: GainNode | null = null;
+
+    static make (): SimSounds { return new SimSounds(); }
+    private constructor () {}
+
+    play (msg: PlaySoundMessage): void {
+        const ctx = this.context();
+        switch (msg.sound) {
+        case 'hum': return setGain(ctx, throwOnNull(this._humGain), msg.volume);
+        case 'static': return setGain(ctx, throwOnNull(this._staticGain), msg.volume);
+        case 'degauss': return playDegauss(ctx, msg.volume);
+        default: throw new Error('Unknown sound: ' + msg.sound);
+        }
+    }
+
+    dispose (): void {
+        if (this._ctx) {
+            this._ctx.close();
+        }
+        this._ctx = null;
+        this._humGain = null;
+        this._staticGain = null;
+    }
+
+    private context (): AudioContext {
+        if (this._ctx) {
+            if (this._ctx.state === 'suspended') {
+                this._ctx.resume();
+            }
+            return this._ctx;
+        }
+        const ctx = new AudioContext();
+
+        const hum = ctx.createOscillator();
+        hum.frequency.value = HORIZONTAL_SCAN_FREQUENCY;
+        this._humGain = ctx.createGain();
+        this._humGain.gain.value = 0;
+        hum.connect(this._humGain).connect(ctx.destination);
+        hum.start();
+
+        const noise = ctx.createBufferSource();
+        noise.buffer = makeNoiseBuffer(ctx, 2);
+        noise.loop = true;
+        this._staticGain = ctx.createGain();
+        this._staticGain.gain.value = 0;
+        noise.connect(this._staticGain).connect(ctx.destination);
+        noise.start();
+
+        this._ctx = ctx;
+        return ctx;
+    }
+}
+
+function setGain (ctx: AudioContext, gain: GainNode, volume: number): void {
+    gain.gain.setTargetAtTime(volume, ctx.currentTime, 0.05);
+}
+
+function playDegauss (ctx: AudioContext, volume: number): void {
+    const gain = ctx.createGain();
+    gain.gain.setValueAtTime(volume, ctx.currentTime);
+    gain.gain.exponentialRampToValueAtTime(0.001, ctx.currentTime + DEGAUSS_SECONDS);
+    gain.connect(ctx.destination);
+
+    const thunk = ctx.createOscillator();
+    thunk.frequency.value = 60;
+    thunk.connect(gain);
+    thunk.start();
+    thunk.stop(ctx.currentTime + DEGAUSS_SECONDS);
+
+    const rattle = ctx.createBufferSource();
+    rattle.buffer = makeNoiseBuffer(ctx, DEGAUSS_SECONDS);
+    const rattleGain = ctx.createGain();
+    rattleGain.gain.value = 0.3;
+    rattle.connect(rattleGain).connect(gain);
+    rattle.start();
+}
+
+function makeNoiseBuffer (ctx: AudioContext, seconds: number): AudioBuffer {
+    const buffer = ctx.createBuffer(1, Math.floor(ctx.sampleRate * seconds), ctx.sampleRate);
+    const data = buffer.getChannelData(0);
+    for (let i = 0; i < data.length; i++) {
+        data[i] = Math.random() * 2 - 1;
+    }
+    return buffer;
+}
@@ -17,7 +17,9 @@ import {html, render, TemplateResult} from 'lit-html';
 import { ifDefined } from 'lit-html/directives/if-defined';
 import {
     ButtonInputEntry, CameraInputEntry,
-    CheckboxInputEntry, ColorInputEntry,
+    DiagnosticsReadout,
+    MoireReport,
+    CheckboxInputEntry, ColorInputEntry, LayersInputEntry, PaletteInputEntry, TextFileInputEntry,
     SimViewEntry,
     HalfPair,
     MenuEntry, NumberInputEntry,
@@ -42,9 +44,13 @@ export function actions() {
         dispatchKey: PubSubImpl.make<DispatchKeyMessage>(),
         toggleCheckbox: PubSubImpl.make<{kind: string, value: boolean}>(),
         changeSyncedInput: PubSubImpl.make<{kind: string, value: number}>(),
+        changeLayers: PubSubImpl.make<string>(),
+        changeCustomPalette: PubSubImpl.make<string>(),
+        loadTextFile: PubSubImpl.make<{kind: string, name: string, text: string}>(),
         toggleControls: PubSubImpl.make<void>(),
         toggleMenu: PubSubImpl.make<MenuEntry>(),
-        clickPreset: PubSubImpl.make<string>()
+        clickPreset: PubSubImpl.make<string>(),
+        closeMoireReport: PubSubImpl.make<void>()
     };
 }
 
@@ -90,6 +96,10 @@ export class SimTemplate
         await this._actions.toggleControls.fire();
     }
 
+    private async closeMoireReport() {
+        await this._actions.closeMoireReport.fire();
+    }
+
     private async toggleMenu(menu: MenuEntry) {
         await this._actions.toggleMenu.fire(menu);
     }
@@ -103,6 +113,18 @@ export class SimTemplate
         await this._actions.changeSyncedInput.fire({kind, value});
     }
 
+    private async changeLayers(layers: string) {
+        await this._actions.changeLayers.fire(layers);
+    }
+
+    private async changeCustomPalette(palette: string) {
+        await this._actions.changeCustomPalette.fire(palette);
+    }
+
+    private async loadTextFile(kind: string, file: File) {
+        await this._actions.loadTextFile.fire({kind, name: file.name, text: await file.text()});
+    }
+
     private async clickPreset(preset: string) {
         await this._actions.clickPreset.fire(preset);
     }
@@ -119,6 +141,8 @@ export class SimTemplate
         <div tabindex=0><canvas id="gl-canvas-id"></canvas></div>
         <div id="simulation-ui">
             <div id="fps-counter">${state.fps}</div>
+            ${state.diagnostics ? this.generateDiagnosticsOverlay(state.diagnostics) : ''}
+            ${state.moireReport ? this.generateMoireReport(state.moireReport) : ''}
             <div id="info-panel" class="${state.menu.visible ? '' : 'display-none'}">
                 <div id="info-panel-content" class="${state.menu.open ? '' : 'display-none'}">
                     ${state.menu.entries.map(entry => this.generateTemplateFromGenericEntry(entry))}
@@ -131,6 +155,30 @@ export class SimTemplate
         `;
     }
 
+    private generateDiagnosticsOverlay (diagnostics: DiagnosticsReadout) {
+        const corners = ['Top left', 'Top right', 'Bottom left', 'Bottom right'];
+        const offset = (values: number[], i: number) => values[i * 2].toFixed(2) + ', ' + values[i * 2 + 1].toFixed(2);
+        return html`
+            <div id="diagnostics-overlay">
+                <div>Scanline pitch: ${diagnostics.scanlinePitch.toFixed(2)} px</div>
+                ${corners.map((corner, i) => html`
+                    <div>${corner}: R ${offset(diagnostics.convergenceRed, i)} / B ${offset(diagnostics.convergenceBlue, i)}</div>
+                `)}
+            </div>
+        `;
+    }
+
+    private generateMoireReport (report: MoireReport) {
+        return html`
+            <div id="moire-report" @click="${() => this.closeMoireReport()}">
+                <div>${report.applied ? 'Applied' : 'Recommended'} configuration (click to close):</div>
+                ${report.options.map(option => html`
+                    <div class="${option.recommended ? 'moire-recommended' : ''}">${option.label}: ${option.score.toFixed(4)}</div>
+                `)}
+            </div>
+        `;
+    }
+
     private generateTemplateFromGenericEntry (entry: SimViewEntry) {
         switch (entry.type) {
             case 'menu': return this.generateTemplateFromMenu(entry);
@@ -144,6 +192,9 @@ export class SimTemplate
             case 'color-input': return this.generateTemplateFromColorInput(entry);
             case 'camera-input': return this.generateTemplateFromCameraInput(entry);
             case 'rgb-input': return this.generateTemplateFromRgbInput(entry);
+            case 'layers-input': return this.generateTemplateFromLayersInput(entry);
+            case 'palette-input': return this.generateTemplateFromPaletteInput(entry);
+            case 'text-file-input': return this.generateTemplateFromTextFileInput(entry);
         }
     }
 
@@ -214,6 +265,82 @@ export class SimTemplate
         `;
     }
 
+    // The layers come encoded as 'kind:on|off:opacity', from the bottom to the top, but they are listed top first.
+    private generateTemplateFromLayersInput (layersInput: LayersInputEntry) {
+        const layers = (layersInput.ref.value || '').split(',').filter(entry => entry.length > 0).map(entry => {
+            const [kind, toggle, opacity] = entry.split(':');
+            return { kind, enabled: toggle === 'on', opacity: +opacity };
+        });
+        const encode = () => layers.map(layer => `${layer.kind}:${layer.enabled ? 'on' : 'off'}:${layer.opacity}`).join(',');
+        const swap = (a: number, b: number) => {
+            if (a < 0 || b < 0 || a >= layers.length || b >= layers.length) return;
+            [layers[a], layers[b]] = [layers[b], layers[a]];
+            this.changeLayers(encode());
+        };
+        return html`
+            <div class="menu-entry ${layersInput.class}">
+                <div class="feature-pack"><div class="feature-name">${layersInput.text}</div></div>
+            </div>
+            ${layers.map((layer, index) => ({ layer, index })).reverse().map(({ layer, index }) => html`
+                <div class="menu-entry ${layersInput.class}">
+                    <div class="feature-pack">
+                        <input type="checkbox" ?checked=${layer.enabled}
+                            @change="${(e: Event) => { layer.enabled = (<HTMLInputElement>e.target).checked; this.changeLayers(encode()); }}">
+                        <div class="feature-name">${layer.kind}</div>
+                    </div>
+                    <div class="feature-value input-holder">
+                        <input class="number-input feature-modificable-input" type="number" step="0.05" min="0" max="1" .value="${layer.opacity}"
+                            @focus="${(e: Event) => this.dispatchKey(e,'keydown', 'input_focused' )}"
+                            @blur="${(e: Event) => this.dispatchKey(e,'keyup', 'input_focused' )}"
+                            @keypress="${(e: KeyboardEvent) => e.charCode === 13 /* ENTER */ && (<HTMLInputElement>e.target).blur()}"
+                            @change="${(e: Event) => { layer.opacity = +(<HTMLInputElement>e.target).value; this.changeLayers(encode()); }}"
+                            >
+                        <button class="button-inc-dec" @click="${() => swap(index, index + 1)}">▲</button>
+                        <button class="button-inc-dec" @click="${() => swap(index, index - 1)}">▼</button>
+                    </div>
+                </div>
+            `)}
+        `;
+    }
+
+    // Palette files are expected to have one hex color per line, like the .hex files most palette sites offer.
+    private generateTemplateFromPaletteInput (paletteInput: PaletteInputEntry) {
+        const upload = async (file: File) => {
+            const colors = (await file.text()).split(/\r?\n/).map(line => line.trim()).filter(line => /^#?[0-9a-fA-F]{6}$/.test(line));
+            if (colors.length > 0) {
+                this.changeCustomPalette(colors.join(','));
+            }
+        };
+        return html`
+            <div class="menu-entry ${paletteInput.class}">
+                <div class="feature-pack"><div class="feature-name">${paletteInput.text}</div></div>
+                <div class="feature-value input-holder">
+                    <input class="number-input feature-modificable-input" type="text" .value="${paletteInput.ref.value || ''}"
+                        title="Comma separated colors, like #000000,#ffffff"
+                        @focus="${(e: Event) => this.dispatchKey(e,'keydown', 'input_focused' )}"
+                        @blur="${(e: Event) => this.dispatchKey(e,'keyup', 'input_focused' )}"
+                        @keypress="${(e: KeyboardEvent) => e.charCode === 13 /* ENTER */ && (<HTMLInputElement>e.target).blur()}"
+                        @change="${(e: Event) => this.changeCustomPalette((<HTMLInputElement>e.target).value)}"
+                        >
+                    <input class="feature-button" type="file" accept=".hex,.txt"
+                        @change="${(e: Event) => { const files = (<HTMLInputElement>e.target).files; if (files && files.length > 0) upload(files[0]); }}">
+                </div>
+            </div>
+        `;
+    }
+
+    private generateTemplateFromTextFileInput (fileInput: TextFileInputEntry) {
+        return html`
+            <div class="menu-entry ${fileInput.class}">
+                <div class="feature-pack"><div class="feature-name">${fileInput.text}</div></div>
+                <div class="feature-value input-holder">
+                    <input class="feature-button" type="file" accept="${fileInput.accept}" title="${fileInput.ref.value || ''}"
+                        @change="${(e: Event) => { const files = (<HTMLInputElement>e.target).files; if (files && files.length > 0) this.loadTextFile(fileInput.ref.eventKind, files[0]); }}">
+                </div>
+            </div>
+        `;
+    }
+
     private generateTemplateFromCheckboxInput (checkboxInput: CheckboxInputEntry) {
         return html`
             <div class="menu-entry menu-button ${checkboxInput.class}"
@@ -273,13 +400,13 @@ export class SimTemplate
             <div class="menu-entry ${numberInput.class}">
                 <div class="feature-pack">
                     <div class="feature-name">${numberInput.text}</div>
-                    <div class="feature-hotkeys">
+                    ${numberInput.hk ? html`<div class="feature-hotkeys">
                         <sup class="hotkey hk-inc" title="Press '${numberInput.hk.inc}' to increse the value of this field">+: ${numberInput.hk.inc}</sup>
                         <sup class="hotkey hk-dec" title="Press '${numberInput.hk.inc}' to decrease the value of this field">-: ${numberInput.hk.dec}</sup>
-                    </div>
+                    </div>` : ''}
                 </div>
                 <div class="feature-value input-holder">
-                    <input class="number-input feature-modificable-input" type="number" 
+                    <input class="number-input feature-modificable-input ${numberInput.ref.clamped ? 'clamped-input' : ''}" type="number" 
                         placeholder="${numberInput.placeholder}" step="${numberInput.step}" min="${numberInput.min}" max="${numberInput.max}" .value="${numberInput.ref.value}"
                         @focus="${(e: Event) => this.dispatchKey(e,'keydown', 'input_focused' )}"
                         @blur="${(e: Event) => this.dispatchKey(e,'keyup', 'input_focused' )}"
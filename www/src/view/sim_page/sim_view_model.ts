@@ -43,6 +43,9 @@ export type SimViewEntry =
     NumericPairEntry |
     NumberInputEntry |
     ColorInputEntry |
+    LayersInputEntry |
+    PaletteInputEntry |
+    TextFileInputEntry |
     CameraInputEntry |
     RgbInputEntry;
 
@@ -57,8 +60,11 @@ export interface CheckboxInputEntry extends CssEntry, RefEntry<number> { type: '
 export interface ButtonInputEntry extends CssEntry, RefEntry<string> { type: 'button-input'; }
 export interface SelectorsInput extends CssEntry, RefEntry<string> { type: 'selectors-input'; hk?: HotKey, }
 export interface NumericPairEntry extends CssEntry { type: 'numeric-pair'; separator: string; pair: [HalfPair, HalfPair]; }
-export interface NumberInputEntry extends CssEntry, RefEntry<number>, PlaceholderEntry { type: 'number-input'; hk: HotKey }
+export interface NumberInputEntry extends CssEntry, RefEntry<number>, PlaceholderEntry { type: 'number-input'; hk?: HotKey }
 export interface ColorInputEntry extends CssEntry, RefEntry<string> { type: 'color-input'; }
+export interface LayersInputEntry extends CssEntry, RefEntry<string> { type: 'layers-input'; }
+export interface PaletteInputEntry extends CssEntry, RefEntry<string> { type: 'palette-input'; }
+export interface TextFileInputEntry extends CssEntry, RefEntry<string> { type: 'text-file-input'; accept: string; }
 
 export interface HotKey {
     inc: string;
@@ -71,6 +77,7 @@ export interface Ref<T> {
     value: T;
     eventKind: string;
     title?: string;
+    clamped?: boolean;
 }
 
 export interface PresetChoice {
@@ -123,25 +130,132 @@ export function data () {
                 { preset: Constants.PRESET_KIND_CUSTOM, text: 'Custom' }
             ]
         },
+        crt_model: { value: null as string | null, eventKind: 'crt-model' },
         internal_resolution: { value: null as number | null, eventKind: 'internal-resolution' },
         screen_curvature: { value: null as number | null, eventKind: 'screen-curvature' },
+        screen_curvature_amount: { value: null as number | null, eventKind: 'screen-curvature-amount' },
         blur_level: { value: null as number | null, eventKind: 'blur-level' },
+        background_resolution_scale: { value: null as number | null, eventKind: 'background-resolution-scale' },
+        background_blur_passes: { value: null as number | null, eventKind: 'background-blur-passes' },
         horizontal_gap: { value: null as number | null, eventKind: 'pixel-horizontal-gap' },
         vertical_gap: { value: null as number | null, eventKind: 'pixel-vertical-gap' },
+        horizontal_spread: { value: null as number | null, eventKind: 'pixel-horizontal-spread' },
+        vertical_spread: { value: null as number | null, eventKind: 'pixel-vertical-spread' },
         pixel_width: { value: null as number | null, eventKind: 'pixel-width' },
         vertical_lpp: { value: null as number | null, eventKind: 'vertical-lpp' },
         horizontal_lpp: { value: null as number | null, eventKind: 'horizontal-lpp' },
         color_gamma: { value: 1.0, eventKind: 'color-gamma' },
+        color_saturation: { value: null as number | null, eventKind: 'color-saturation' },
+        color_hue: { value: null as number | null, eventKind: 'color-hue' },
+        black_pedestal: { value: null as number | null, eventKind: 'black-pedestal' },
+        black_pedestal_setup: { eventKind: 'black-pedestal-setup' },
+        color_temperature: { value: null as number | null, eventKind: 'color-temperature' },
+        red_gain: { value: null as number | null, eventKind: 'red-gain' },
+        red_offset: { value: null as number | null, eventKind: 'red-offset' },
+        green_gain: { value: null as number | null, eventKind: 'green-gain' },
+        green_offset: { value: null as number | null, eventKind: 'green-offset' },
+        blue_gain: { value: null as number | null, eventKind: 'blue-gain' },
+        blue_offset: { value: null as number | null, eventKind: 'blue-offset' },
+        output_gamma: { value: null as number | null, eventKind: 'output-gamma' },
         color_noise: { value: 0.0, eventKind: 'color-noise' },
+        analog_static: { value: null as number | null, eventKind: 'analog-static' },
+        analog_static_speed: { value: null as number | null, eventKind: 'analog-static-speed' },
+        blending_space: { value: null as string | null, eventKind: 'blending-space' },
         light_color: { value: '#FFFFFF', eventKind: 'light-color' },
         pixel_brightness: { value: null as number | null, eventKind: 'pixel-brightness' },
         pixel_contrast: { value: null as number | null, eventKind: 'pixel-contrast' },
+        contrast_knee: { value: null as number | null, eventKind: 'contrast-knee' },
+        contrast_shoulder: { value: null as number | null, eventKind: 'contrast-shoulder' },
         color_representation: { value: null as number | null, eventKind: 'color-representation' },
+        color_channels_order: { value: null as string | null, eventKind: 'color-channels-order' },
+        channel_offset_red_x: { value: null as number | null, eventKind: 'channel-offset-red-x' },
+        channel_offset_red_y: { value: null as number | null, eventKind: 'channel-offset-red-y' },
+        channel_offset_green_x: { value: null as number | null, eventKind: 'channel-offset-green-x' },
+        channel_offset_green_y: { value: null as number | null, eventKind: 'channel-offset-green-y' },
+        channel_offset_blue_x: { value: null as number | null, eventKind: 'channel-offset-blue-x' },
+        channel_offset_blue_y: { value: null as number | null, eventKind: 'channel-offset-blue-y' },
+        convergence_red_x: { value: null as number | null, eventKind: 'convergence-red-x' },
+        convergence_red_y: { value: null as number | null, eventKind: 'convergence-red-y' },
+        convergence_green_x: { value: null as number | null, eventKind: 'convergence-green-x' },
+        convergence_green_y: { value: null as number | null, eventKind: 'convergence-green-y' },
+        convergence_blue_x: { value: null as number | null, eventKind: 'convergence-blue-x' },
+        convergence_blue_y: { value: null as number | null, eventKind: 'convergence-blue-y' },
+        scanline_phase: { value: null as number | null, eventKind: 'scanline-phase' },
+        sync_offset: { value: null as number | null, eventKind: 'sync-offset' },
+        scanline_orientation: { value: null as string | null, eventKind: 'scanline-orientation' },
+        scanline_angle: { value: null as number | null, eventKind: 'scanline-angle' },
+        damper_wires: { value: null as string | null, eventKind: 'damper-wires' },
+        damper_wire_position: { value: null as number | null, eventKind: 'damper-wire-position' },
+        damper_wire_opacity: { value: null as number | null, eventKind: 'damper-wire-opacity' },
+        rolling_scan: { value: null as string | null, eventKind: 'rolling-scan' },
+        rolling_scan_speed: { value: null as number | null, eventKind: 'rolling-scan-speed' },
+        rolling_scan_size: { value: null as number | null, eventKind: 'rolling-scan-size' },
+        screen_corner_radius: { value: null as number | null, eventKind: 'screen-corner-radius' },
+        screen_matte: { value: null as number | null, eventKind: 'screen-matte' },
+        flicker: { value: null as string | null, eventKind: 'flicker' },
+        flicker_amplitude: { value: null as number | null, eventKind: 'flicker-amplitude' },
+        glass_reflection: { value: null as string | null, eventKind: 'glass-reflection' },
+        glass_reflectivity: { value: null as number | null, eventKind: 'glass-reflectivity' },
+        blemish_strength: { value: null as number | null, eventKind: 'blemish-strength' },
+        blemish_seed: { value: null as number | null, eventKind: 'blemish-seed' },
+        dead_subpixels: { value: null as number | null, eventKind: 'dead-subpixels' },
+        stuck_pixels: { value: null as number | null, eventKind: 'stuck-pixels' },
+        dead_scanlines: { value: null as number | null, eventKind: 'dead-scanlines' },
+        bezel_scale: { value: null as number | null, eventKind: 'bezel-scale' },
+        bezel_offset_x: { value: null as number | null, eventKind: 'bezel-offset-x' },
+        bezel_offset_y: { value: null as number | null, eventKind: 'bezel-offset-y' },
+        room_environment: { value: null as string | null, eventKind: 'room-environment' },
+        room_brightness: { value: null as number | null, eventKind: 'room-brightness' },
+        room_lighting_toggle: { eventKind: 'room-lighting-toggle' },
+        ambient_light_sensor: { value: null as boolean | null, eventKind: 'ambient-light-sensor' },
+        edge_reflection_intensity: { value: null as number | null, eventKind: 'edge-reflection-intensity' },
+        edge_reflection_width: { value: null as number | null, eventKind: 'edge-reflection-width' },
+        floor_reflection: { value: null as string | null, eventKind: 'floor-reflection' },
+        floor_roughness: { value: null as number | null, eventKind: 'floor-roughness' },
+        blooming_amount: { value: null as number | null, eventKind: 'blooming-amount' },
+        blooming_time_constant: { value: null as number | null, eventKind: 'blooming-time-constant' },
+        bloom_threshold: { value: null as number | null, eventKind: 'bloom-threshold' },
+        bloom_intensity: { value: null as number | null, eventKind: 'bloom-intensity' },
+        white_clipping: { value: null as string | null, eventKind: 'white-clipping' },
+        vignette_strength: { value: null as number | null, eventKind: 'vignette-strength' },
+        vignette_radius: { value: null as number | null, eventKind: 'vignette-radius' },
+        dithering: { value: null as string | null, eventKind: 'dithering' },
+        dithering_bits: { value: null as number | null, eventKind: 'dithering-bits' },
+        burn_in_rate: { value: null as number | null, eventKind: 'burn-in-rate' },
+        burn_in_visibility: { value: null as number | null, eventKind: 'burn-in-visibility' },
+        tv_lines: { value: null as number | null, eventKind: 'tv-lines' },
+        signal_kind: { value: null as string | null, eventKind: 'signal-kind' },
+        palette: { value: null as string | null, eventKind: 'palette' },
+        custom_palette: { value: null as string | null, eventKind: 'custom-palette' },
+        interlacing: { value: null as string | null, eventKind: 'interlacing' },
+        interlace_field_flicker: { value: null as number | null, eventKind: 'interlace-field-flicker' },
+        interlace_line_pairing: { value: null as number | null, eventKind: 'interlace-line-pairing' },
+        hsync_wobble: { value: null as number | null, eventKind: 'hsync-wobble' },
+        hsync_wobble_frequency: { value: null as number | null, eventKind: 'hsync-wobble-frequency' },
+        transition: { value: null as string | null, eventKind: 'transition' },
+        transition_seconds: { value: null as number | null, eventKind: 'transition-seconds' },
+        response_rise_time: { value: null as number | null, eventKind: 'response-rise-time' },
+        response_fall_time: { value: null as number | null, eventKind: 'response-fall-time' },
+        overlapping_blend_mode: { value: null as string | null, eventKind: 'overlapping-blend-mode' },
+        overlapping_gain_red: { value: null as number | null, eventKind: 'overlapping-gain-red' },
+        overlapping_gain_green: { value: null as number | null, eventKind: 'overlapping-gain-green' },
+        overlapping_gain_blue: { value: null as number | null, eventKind: 'overlapping-gain-blue' },
         pixel_geometry: { value: null as number | null, eventKind: 'pixel-geometry' },
-        pixel_shadow_shape: { value: null as number | null, eventKind: 'pixel-shadow-shape' },
+        foreground_depth_test: { value: null as string | null, eventKind: 'foreground-depth-test' },
+        background_depth_test: { value: null as string | null, eventKind: 'background-depth-test' },
+        pixel_shadow_shape: { value: null as string | number | null, eventKind: 'pixel-shadow-shape' },
         pixel_shadow_height: { value: null as number | null, eventKind: 'pixel-shadow-height' },
         texture_interpolation: { value: null as number | null, eventKind: 'texture-interpolation' },
+        source_interpolation: { value: null as string | null, eventKind: 'source-interpolation' },
+        output_interpolation: { value: null as string | null, eventKind: 'output-interpolation' },
+        sharpen_strength: { value: null as number | null, eventKind: 'sharpen-strength' },
+        sharpen_halo_suppression: { value: null as number | null, eventKind: 'sharpen-halo-suppression' },
+        output_color_space: { value: null as string | null, eventKind: 'output-color-space' },
+        phosphor_primaries: { value: null as string | null, eventKind: 'phosphor-primaries' },
         backlight_percent: { value: null as number | null, eventKind: 'backlight-percent' },
+        layering_blend_mode: { value: null as string | null, eventKind: 'layering-blend-mode' },
+        layers: { value: null as string | null, eventKind: 'layers' },
+        foreground_opacity: { value: null as number | null, eventKind: 'foreground-opacity' },
         pixel_spread: { value: null as number | null, eventKind: 'pixel-spread' },
         brightness_color: { value: '#FFFFFF', eventKind: 'brightness-color' },
         camera_movement_mode: { value: '', title: '', eventKind: 'camera-movement-mode' },
@@ -157,6 +271,33 @@ export function data () {
             blue: { r: { eventKind: 'rgb-blue-r', value: 0 }, g: { eventKind: 'rgb-blue-g', value: 0 }, b: { eventKind: 'rgb-blue-b', value: 1 } }
         },
         camera_zoom: { value: null as number | null, eventKind: 'camera_zoom' },
+        camera_focal_length: { value: null as number | null, eventKind: 'camera_focal_length' },
+        camera_collision: { value: null as string | null, eventKind: 'camera-collision' },
+        camera_near_plane: { value: 0, eventKind: 'camera-near-plane' },
+        camera_far_plane: { value: 0, eventKind: 'camera-far-plane' },
+        physical_calibration: { value: null as string | null, eventKind: 'physical-calibration' },
+        display_dpi: { value: null as number | null, eventKind: 'display-dpi' },
+        dot_pitch: { value: null as number | null, eventKind: 'dot-pitch' },
+        crt_sounds: { value: null as string | null, eventKind: 'crt-sounds' },
+        light_gun: { value: null as string | null, eventKind: 'light-gun' },
+        magnet_strength: { value: null as number | null, eventKind: 'magnet-strength' },
+        magnet_radius: { value: null as number | null, eventKind: 'magnet-radius' },
+        procedural_source: { value: null as string | null, eventKind: 'procedural-source' },
+        subtitles: { value: null as string | null, eventKind: 'subtitles' },
+        source_rotation: { value: null as string | null, eventKind: 'source-rotation' },
+        source_mirror: { value: null as string | null, eventKind: 'source-mirror' },
+        diagnostics_overlay: { value: null as string | null, eventKind: 'diagnostics-overlay' },
+        status_bar: { value: null as string | null, eventKind: 'status-bar' },
+        message_verbosity: { value: null as string | null, eventKind: 'message-verbosity' },
+        moire_assistant: { value: null as string | null, eventKind: 'moire-assistant' },
+        screenshot_bracket: { value: null as string | null, eventKind: 'screenshot-bracket' },
+        turntable_seconds: { value: null as number | null, eventKind: 'turntable-seconds' },
+        ken_burns_speed: { value: null as number | null, eventKind: 'ken-burns-speed' },
+        ken_burns_margin: { value: null as number | null, eventKind: 'ken-burns-margin' },
+        input_recording: { value: null as boolean | null, eventKind: 'input-recording' },
+        input_replay: { value: null as string | null, eventKind: 'input-replay' },
+        input_replay_step: { eventKind: 'replay:step' },
+        input_replay_stop: { eventKind: 'replay:stop' },
         move_speed: { value: null as number | null, eventKind: 'move-speed' },
         pixel_speed: { value: null as number | null, eventKind: 'pixel-speed' },
         turn_speed: { value: null as number | null, eventKind: 'turn-speed' },
@@ -164,6 +305,12 @@ export function data () {
         reset_camera: { eventKind: 'reset-camera' },
         reset_speeds: { eventKind: 'reset-speeds' },
         capture_framebuffer: { eventKind: 'capture-framebuffer' },
+        moire_assistant_run: { eventKind: 'moire-assistant-run' },
+        screenshot_bracket_run: { eventKind: 'screenshot-bracket-run' },
+        turntable_run: { eventKind: 'turntable-run' },
+        blind_test_run: { eventKind: 'blind-test-run' },
+        blind_test_a: { eventKind: 'blind-test-a' },
+        blind_test_b: { eventKind: 'blind-test-b' },
         webgl_performance: { value: null as string | null, eventKind: 'webgl:performance' },
         webgl_antialias: { value: null as boolean | null, eventKind: 'webgl:antialias' },
         scaling_method: { value: null as string | null, eventKind: 'scaling-method' },
@@ -178,6 +325,9 @@ export function data () {
     return {
         initStoredValues: false,
         fps: 60,
+        frameTimes: [] as number[],
+        diagnostics: null as DiagnosticsReadout | null,
+        moireReport: null as MoireReport | null,
         options,
         menu: {
             open: true,
@@ -189,7 +339,11 @@ export function data () {
                     text: 'Presets',
                     open: true,
                     entries: [
-                        { type: 'preset-buttons', class: 'menu-2 menu-blc-grey', ref: options.presets } as PresetButtonsEntry
+                        { type: 'preset-buttons', class: 'menu-2 menu-blc-grey', ref: options.presets } as PresetButtonsEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-white', text: 'CRT model', ref: options.crt_model } as SelectorsInput,
+                        { type: 'button-input', class: 'menu-2 menu-blc-white', text: 'Blind A/B Test (start / vote)', ref: options.blind_test_run } as ButtonInputEntry,
+                        { type: 'button-input', class: 'menu-2 menu-blc-white', text: 'Blind Test: Show 1', ref: options.blind_test_a } as ButtonInputEntry,
+                        { type: 'button-input', class: 'menu-2 menu-blc-white', text: 'Blind Test: Show 2', ref: options.blind_test_b } as ButtonInputEntry
                     ]
                 } as MenuEntry,
                 {
@@ -228,7 +382,22 @@ export function data () {
                     open: true,
                     entries: [
                         { type: 'selectors-input', class: 'menu-2 menu-blc-white', text: 'Internal Resolution', hk: { inc: 'Y', dec: 'Shift + Y' }, ref: options.internal_resolution },
-                        { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'Blur passes', hk: { inc: 'J', dec: 'Shift + J' }, step: 1, min: 0, max: 100, value: 0, placeholder: 0, ref: options.blur_level } as NumberInputEntry
+                        { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'Blur passes', hk: { inc: 'J', dec: 'Shift + J' }, step: 1, min: 0, max: 100, value: 0, placeholder: 0, ref: options.blur_level } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'TV lines (0 = unlimited)', step: 10, min: 0, max: 1200, value: 0, placeholder: 0, ref: options.tv_lines } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-blue', text: 'Video signal', ref: options.signal_kind },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-blue', text: 'Palette', ref: options.palette },
+                        { type: 'palette-input', class: 'menu-2 menu-blc-blue', text: 'Custom palette', ref: options.custom_palette } as PaletteInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-blue', text: 'Interlacing', hk: { inc: '0', dec: 'Shift + 0' }, ref: options.interlacing },
+                        { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'Field brightness flicker', step: 0.01, min: 0, max: 0.25, value: 0, placeholder: 0, ref: options.interlace_field_flicker } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'Line pairing', step: 0.05, min: 0, max: 1, value: 0, placeholder: 0, ref: options.interlace_line_pairing } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'Sync wobble', step: 0.25, min: 0, max: 8, value: 0, placeholder: 0, ref: options.hsync_wobble } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'Sync wobble frequency', step: 0.5, min: 0.5, max: 20, value: 3, placeholder: 3, ref: options.hsync_wobble_frequency } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-blue', text: 'Source change transition', ref: options.transition },
+                        { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'Transition seconds', step: 0.1, min: 0.1, max: 3, value: 0.5, placeholder: 0.5, ref: options.transition_seconds } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'Response rise time (ms)', step: 1, min: 0, max: 1000, value: 0, placeholder: 0, ref: options.response_rise_time } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'Response fall time (ms)', step: 1, min: 0, max: 1000, value: 0, placeholder: 0, ref: options.response_fall_time } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'Background resolution scale', step: 0.01, min: 0.05, max: 1, value: 0.5, placeholder: 0.5, ref: options.background_resolution_scale } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'Background blur passes', step: 1, min: 1, max: 100, value: 6, placeholder: 6, ref: options.background_blur_passes } as NumberInputEntry
                     ]
                 } as MenuEntry,
                 {
@@ -238,10 +407,27 @@ export function data () {
                     entries: [
                         { type: 'rgb-input', class: 'menu-blc-red', ref: options.rgb_values },
                         { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Gamma correction', hk: { inc: '????', dec: 'Shift + ????' }, step: 0.1, min: 0, max: 1, value: 0, placeholder: 0, ref: options.color_gamma },
+                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Saturation', hk: { inc: 'Insert', dec: 'Delete' }, step: 0.01, min: 0, max: 2, value: 1, placeholder: 1, ref: options.color_saturation } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Hue (degrees)', hk: { inc: 'F10', dec: 'Shift + F10' }, step: 1, min: -180, max: 180, value: 0, placeholder: 0, ref: options.color_hue } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Black pedestal (IRE)', step: 0.5, min: -20, max: 20, value: 0, placeholder: 0, ref: options.black_pedestal } as NumberInputEntry,
+                        { type: 'button-input', class: 'menu-2 menu-blc-lila', text: 'Toggle 7.5 IRE Setup', ref: options.black_pedestal_setup } as ButtonInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Color temperature (K)', step: 100, min: 5000, max: 9300, value: 6500, placeholder: 6500, ref: options.color_temperature } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Red gain', step: 0.01, min: 0, max: 2, value: 1, placeholder: 1, ref: options.red_gain } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Red offset', step: 0.01, min: -0.5, max: 0.5, value: 0, placeholder: 0, ref: options.red_offset } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Green gain', step: 0.01, min: 0, max: 2, value: 1, placeholder: 1, ref: options.green_gain } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Green offset', step: 0.01, min: -0.5, max: 0.5, value: 0, placeholder: 0, ref: options.green_offset } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Blue gain', step: 0.01, min: 0, max: 2, value: 1, placeholder: 1, ref: options.blue_gain } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Blue offset', step: 0.01, min: -0.5, max: 0.5, value: 0, placeholder: 0, ref: options.blue_offset } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Output gamma', hk: { inc: 'F8', dec: 'Shift + F8' }, step: 0.1, min: 0.5, max: 3, value: 1, placeholder: 1, ref: options.output_gamma } as NumberInputEntry,
                         { type: 'number-input', class: 'menu-2 menu-blc-yellow', text: 'Color noise', hk: { inc: '????', dec: 'Shift + ????' }, step: 0.1, min: 0, max: 1, value: 0, placeholder: 0, ref: options.color_noise },
+                        { type: 'number-input', class: 'menu-2 menu-blc-yellow', text: 'Analog static', step: 0.05, min: 0, max: 1, value: 0, placeholder: 0, ref: options.analog_static } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-yellow', text: 'Analog static speed', step: 0.1, min: 0, max: 4, value: 1, placeholder: 1, ref: options.analog_static_speed } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-white', text: 'Blending space', ref: options.blending_space },
                         { type: 'color-input', class: 'menu-2 menu-blc-blue', text: 'Source light color', value: '#ffffff', ref: options.light_color },
                         { type: 'number-input', class: 'menu-2 menu-blc-white', text: 'Brightness', hk: { inc: 'X', dec: 'Shift + X' }, step: 0.001, min: -1, max: 1, value: 0, placeholder: 0, ref: options.pixel_brightness },
                         { type: 'number-input', class: 'menu-2 menu-blc-white', text: 'Contrast', hk: { inc: 'Z', dec: 'Shift + Z' }, step: 0.001, min: 0, max: 20, value: 1, placeholder: 0, ref: options.pixel_contrast },
+                        { type: 'number-input', class: 'menu-2 menu-blc-white', text: 'Contrast knee', step: 0.01, min: 0, max: 1, value: 0, placeholder: 0, ref: options.contrast_knee } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-white', text: 'Contrast shoulder', step: 0.01, min: 0, max: 1, value: 0, placeholder: 0, ref: options.contrast_shoulder } as NumberInputEntry,
                         { type: 'color-input', class: 'display-none', text: 'Brightness color', value: '#ffffff', ref: options.brightness_color }
                     ]
                 } as MenuEntry,
@@ -251,16 +437,90 @@ export function data () {
                     open: false,
                     entries: [
                         { type: 'selectors-input', class: 'menu-2 menu-blc-white', text: 'Screen curvature type', hk: { inc: 'B', dec: 'Shift + B' }, ref: options.screen_curvature },
+                        { type: 'number-input', class: 'menu-2 menu-blc-white', text: 'Curvature amount', step: 0.01, min: -0.5, max: 0.5, value: 0, placeholder: 0, ref: options.screen_curvature_amount } as NumberInputEntry,
                         { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Horizontal gap', hk: { inc: 'U', dec: 'Shift + U' }, step: 0.001, min: 0, max: 10, value: 0, placeholder: 0, ref: options.horizontal_gap },
                         { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Vertical gap', hk: { inc: 'I', dec: 'Shift + I' }, step: 0.001, min: 0, max: 10, value: 0, placeholder: 0, ref: options.vertical_gap },
-                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Vertical lines per pixel', hk: { inc: 'K', dec: 'Shift + K' }, step: 1, min: 0, max: 100, value: 0, placeholder: 0, ref: options.vertical_lpp },
-                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Horizontal lines per pixel', hk: { inc: 'L', dec: 'Shift + L' }, step: 1, min: 0, max: 100, value: 0, placeholder: 0, ref: options.horizontal_lpp },
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Horizontal spread', step: 0.001, min: 0, max: 10, value: 0, placeholder: 0, ref: options.horizontal_spread } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Vertical spread', step: 0.001, min: 0, max: 10, value: 0, placeholder: 0, ref: options.vertical_spread } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Vertical lines per pixel', hk: { inc: 'K', dec: 'Shift + K' }, step: 0.5, min: 0, max: 100, value: 0, placeholder: 0, ref: options.vertical_lpp },
+                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Horizontal lines per pixel', hk: { inc: 'L', dec: 'Shift + L' }, step: 0.5, min: 0, max: 100, value: 0, placeholder: 0, ref: options.horizontal_lpp },
                         { type: 'selectors-input', class: 'menu-2 menu-blc-red', text: 'Color channels type', hk: { inc: 'C', dec: 'Shift + C' }, ref: options.color_representation },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-red', text: 'Color channels order', ref: options.color_channels_order },
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Red channel offset X', step: 0.01, min: -10, max: 10, value: 0, placeholder: 0, ref: options.channel_offset_red_x } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Red channel offset Y', step: 0.01, min: -10, max: 10, value: 0, placeholder: 0, ref: options.channel_offset_red_y } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Green channel offset X', step: 0.01, min: -10, max: 10, value: 0, placeholder: 0, ref: options.channel_offset_green_x } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Green channel offset Y', step: 0.01, min: -10, max: 10, value: 0, placeholder: 0, ref: options.channel_offset_green_y } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Blue channel offset X', step: 0.01, min: -10, max: 10, value: 0, placeholder: 0, ref: options.channel_offset_blue_x } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Blue channel offset Y', step: 0.01, min: -10, max: 10, value: 0, placeholder: 0, ref: options.channel_offset_blue_y } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Red convergence X', step: 0.01, min: -3, max: 3, value: 0, placeholder: 0, ref: options.convergence_red_x } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Red convergence Y', step: 0.01, min: -3, max: 3, value: 0, placeholder: 0, ref: options.convergence_red_y } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Green convergence X', step: 0.01, min: -3, max: 3, value: 0, placeholder: 0, ref: options.convergence_green_x } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Green convergence Y', step: 0.01, min: -3, max: 3, value: 0, placeholder: 0, ref: options.convergence_green_y } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Blue convergence X', step: 0.01, min: -3, max: 3, value: 0, placeholder: 0, ref: options.convergence_blue_x } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Blue convergence Y', step: 0.01, min: -3, max: 3, value: 0, placeholder: 0, ref: options.convergence_blue_y } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Scanline phase', step: 0.01, min: -0.5, max: 0.5, value: 0, placeholder: 0, ref: options.scanline_phase } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Sync offset', step: 0.01, min: -2, max: 2, value: 0, placeholder: 0, ref: options.sync_offset } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-red', text: 'Scanline orientation', hk: { inc: '4', dec: 'Shift + 4' }, ref: options.scanline_orientation },
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Scanline angle', step: 1, min: -180, max: 180, value: 0, placeholder: 0, ref: options.scanline_angle } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-red', text: 'Damper wires', ref: options.damper_wires },
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Damper wire position', step: 0.01, min: 0, max: 0.5, value: 0.333, placeholder: 0.333, ref: options.damper_wire_position } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Damper wire opacity', step: 0.01, min: 0, max: 1, value: 0.25, placeholder: 0.25, ref: options.damper_wire_opacity } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-red', text: 'Rolling scan', ref: options.rolling_scan },
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Rolling scan speed', step: 0.01, min: 0, max: 10, value: 0.5, placeholder: 0.5, ref: options.rolling_scan_speed } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Rolling scan size', step: 0.01, min: 0.01, max: 1, value: 0.25, placeholder: 0.25, ref: options.rolling_scan_size } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Corner radius', step: 0.01, min: 0, max: 0.5, value: 0, placeholder: 0, ref: options.screen_corner_radius } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Black matte', step: 0.005, min: 0, max: 0.25, value: 0, placeholder: 0, ref: options.screen_matte } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-red', text: 'Flicker', ref: options.flicker },
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Flicker amplitude', step: 0.01, min: 0, max: 0.5, value: 0.05, placeholder: 0.05, ref: options.flicker_amplitude } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-red', text: 'Glass reflection', ref: options.glass_reflection },
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Glass reflectivity', step: 0.01, min: 0, max: 1, value: 0.04, placeholder: 0.04, ref: options.glass_reflectivity } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Aging and blemishes', step: 0.01, min: 0, max: 1, value: 0, placeholder: 0, ref: options.blemish_strength } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Monitor seed', step: 1, min: 0, max: 9999, value: 1, placeholder: 1, ref: options.blemish_seed } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Dead subpixels', step: 1, min: 0, max: 5000, value: 0, placeholder: 0, ref: options.dead_subpixels } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Stuck pixels', step: 1, min: 0, max: 5000, value: 0, placeholder: 0, ref: options.stuck_pixels } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Dead scanlines', step: 1, min: 0, max: 100, value: 0, placeholder: 0, ref: options.dead_scanlines } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Bezel artwork scale', step: 0.01, min: 0.5, max: 4, value: 1.5, placeholder: 1.5, ref: options.bezel_scale } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Bezel artwork offset X', step: 0.005, min: -1, max: 1, value: 0, placeholder: 0, ref: options.bezel_offset_x } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Bezel artwork offset Y', step: 0.005, min: -1, max: 1, value: 0, placeholder: 0, ref: options.bezel_offset_y } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-red', text: 'Room environment', ref: options.room_environment },
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Room brightness', step: 0.01, min: 0, max: 1, value: 0, placeholder: 0, ref: options.room_brightness } as NumberInputEntry,
+                        { type: 'button-input', class: 'menu-2 menu-blc-red', text: 'Toggle Day/Night (F7)', ref: options.room_lighting_toggle } as ButtonInputEntry,
+                        { type: 'checkbox-input', class: 'menu-2 menu-blc-red', text: 'Follow the ambient light sensor', ref: options.ambient_light_sensor },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-red', text: 'Overlapping blend mode', ref: options.overlapping_blend_mode },
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Overlapping red gain', step: 0.01, min: 0, max: 10, value: 1, placeholder: 1, ref: options.overlapping_gain_red } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Overlapping green gain', step: 0.01, min: 0, max: 10, value: 1, placeholder: 1, ref: options.overlapping_gain_green } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-red', text: 'Overlapping blue gain', step: 0.01, min: 0, max: 10, value: 1, placeholder: 1, ref: options.overlapping_gain_blue } as NumberInputEntry,
                         { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Pixel geometry type', hk: { inc: 'V', dec: 'Shift + V' }, ref: options.pixel_geometry },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Foreground depth test', ref: options.foreground_depth_test },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Background depth test', ref: options.background_depth_test },
                         { type: 'selectors-input', class: 'menu-2 menu-blc-blue', text: 'Pixel texture', hk: { inc: 'N', dec: 'Shift + N' }, ref: options.pixel_shadow_shape },
                         { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Pixel variable height', hk: { inc: 'M', dec: 'Shift + M' }, step: 0.001, min: 0, max: 1, value: 0, placeholder: 0, ref: options.pixel_shadow_height },
-                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Texture interpolation', hk: { inc: 'H', dec: 'Shift + H' }, ref: options.texture_interpolation },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Buffer interpolation', hk: { inc: 'H', dec: 'Shift + H' }, ref: options.texture_interpolation },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Source interpolation', ref: options.source_interpolation },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Output interpolation', ref: options.output_interpolation },
+                        { type: 'number-input', class: 'menu-2 menu-blc-yellow', text: 'Sharpen strength', step: 0.05, min: 0, max: 1, value: 0, placeholder: 0, ref: options.sharpen_strength } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-yellow', text: 'Sharpen halo suppression', step: 0.05, min: 0, max: 1, value: 0.5, placeholder: 0.5, ref: options.sharpen_halo_suppression } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Output color space', ref: options.output_color_space },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Phosphor primaries', hk: { inc: ']', dec: '[' }, ref: options.phosphor_primaries },
                         { type: 'number-input', class: 'menu-2 menu-blc-green', text: 'Backlight', hk: { inc: 'dot', dec: 'Shift + dot' }, step: 0.001, min: 0, max: 1, value: 0.5, placeholder: 0.5, ref: options.backlight_percent },
+                        { type: 'number-input', class: 'menu-2 menu-blc-green', text: 'Edge reflection', step: 0.01, min: 0, max: 1, value: 0, placeholder: 0, ref: options.edge_reflection_intensity } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-green', text: 'Edge reflection width', step: 1, min: 0, max: 64, value: 16, placeholder: 16, ref: options.edge_reflection_width } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-green', text: 'Floor reflection', ref: options.floor_reflection },
+                        { type: 'number-input', class: 'menu-2 menu-blc-green', text: 'Floor roughness', step: 0.01, min: 0, max: 1, value: 0.5, placeholder: 0.5, ref: options.floor_roughness } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-green', text: 'Raster blooming %', step: 0.1, min: 0, max: 10, value: 0, placeholder: 0, ref: options.blooming_amount } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-green', text: 'Raster blooming lag (s)', step: 0.05, min: 0, max: 5, value: 0.5, placeholder: 0.5, ref: options.blooming_time_constant } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-green', text: 'Bloom threshold', step: 0.05, min: 0, max: 1, value: 0.7, placeholder: 0.7, ref: options.bloom_threshold } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-green', text: 'Bloom intensity', step: 0.1, min: 0, max: 4, value: 0, placeholder: 0, ref: options.bloom_intensity } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-green', text: 'White clipping', ref: options.white_clipping },
+                        { type: 'number-input', class: 'menu-2 menu-blc-green', text: 'Vignette strength', step: 0.05, min: 0, max: 1, value: 0, placeholder: 0, ref: options.vignette_strength } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-green', text: 'Vignette radius', step: 0.05, min: 0, max: 1.5, value: 0.75, placeholder: 0.75, ref: options.vignette_radius } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-green', text: 'Dithering', ref: options.dithering },
+                        { type: 'number-input', class: 'menu-2 menu-blc-green', text: 'Dithering bits per channel', step: 1, min: 1, max: 8, value: 6, placeholder: 6, ref: options.dithering_bits } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-green', text: 'Burn-in rate per minute', step: 0.5, min: 0, max: 60, value: 0, placeholder: 0, ref: options.burn_in_rate } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-green', text: 'Burn-in visibility', step: 0.05, min: 0, max: 1, value: 0.25, placeholder: 0.25, ref: options.burn_in_visibility } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-green', text: 'Layering blend mode', ref: options.layering_blend_mode },
+                        { type: 'layers-input', class: 'menu-2 menu-blc-green', text: 'Layers', ref: options.layers } as LayersInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-green', text: 'Foreground opacity', step: 0.001, min: 0, max: 1, value: 1, placeholder: 1, ref: options.foreground_opacity } as NumberInputEntry,
                         { type: 'number-input', class: 'display-none', text: 'Pixel spread', hk: { inc: 'P', dec: 'Shift + P' }, step: 0.001, min: 0, max: 10, value: 0, placeholder: 0, ref: options.pixel_spread },
                         { type: 'button-input', class: 'menu-2 menu-blc-grey', text: 'Reset Filter Values', ref: options.reset_filters }
                     ]
@@ -271,8 +531,15 @@ export function data () {
                     open: false,
                     entries: [
                         { type: 'selectors-input', class: 'menu-2 menu-blc-lila', text: 'Movement Type', hk: { inc: 'G', dec: 'Shift + G' }, ref: options.camera_movement_mode },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-lila', text: 'Collision', hk: { inc: '9', dec: 'Shift + 9' }, ref: options.camera_collision },
                         { type: 'camera-input', class: 'menu-blc-red', ref: options.camera_matrix },
                         { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'Zoom', hk: { inc: 'Mouse Wheel Up', dec: 'Mouse Wheel Down' }, step: 1, min: 1, max: 45, value: 0, placeholder: 0, ref: options.camera_zoom },
+                        { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'Focal length (mm)', hk: { inc: 'F5', dec: 'Shift + F5' }, step: 1, min: 1, max: 2000, value: 0, placeholder: 0, ref: options.camera_focal_length },
+                        { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'Near plane (0 = auto)', step: 0.01, min: 0, max: 10000, value: 0, placeholder: 0, ref: options.camera_near_plane } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-blue', text: 'Far plane (0 = auto)', step: 1, min: 0, max: 100000, value: 0, placeholder: 0, ref: options.camera_far_plane } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-lila', text: 'Physical calibration', ref: options.physical_calibration },
+                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Display DPI', step: 1, min: 30, max: 1000, value: 96, placeholder: 96, ref: options.display_dpi } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-lila', text: 'Dot pitch (mm)', step: 0.01, min: 0.05, max: 2, value: 0.25, placeholder: 0.25, ref: options.dot_pitch } as NumberInputEntry,
                         { type: 'button-input', class: 'menu-2 menu-blc-grey', text: 'Reset Position', ref: options.reset_camera }
                     ]
                 } as MenuEntry,
@@ -301,7 +568,30 @@ export function data () {
                     text: 'Extra',
                     open: false,
                     entries: [
-                        { type: 'button-input', class: 'menu-2 menu-blc-yellow', text: 'Take Screenshot', ref: options.capture_framebuffer }
+                        { type: 'button-input', class: 'menu-2 menu-blc-yellow', text: 'Take Screenshot', ref: options.capture_framebuffer },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Screenshot bracket', ref: options.screenshot_bracket },
+                        { type: 'button-input', class: 'menu-2 menu-blc-yellow', text: 'Capture Screenshot Bracket', ref: options.screenshot_bracket_run } as ButtonInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-yellow', text: 'Turntable seconds', step: 1, min: 1, max: 60, value: 8, placeholder: 8, ref: options.turntable_seconds } as NumberInputEntry,
+                        { type: 'button-input', class: 'menu-2 menu-blc-yellow', text: 'Record Turntable (F6)', ref: options.turntable_run } as ButtonInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-yellow', text: 'Still picture drift per minute', step: 0.5, min: 0, max: 10, value: 0, placeholder: 0, ref: options.ken_burns_speed } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-yellow', text: 'Still picture drift margin', step: 0.01, min: 0, max: 0.5, value: 0.1, placeholder: 0.1, ref: options.ken_burns_margin } as NumberInputEntry,
+                        { type: 'checkbox-input', class: 'menu-2 menu-blc-yellow', text: 'Record input', ref: options.input_recording },
+                        { type: 'text-file-input', class: 'menu-2 menu-blc-yellow', text: 'Replay input log', accept: '.txt,.log', ref: options.input_replay } as TextFileInputEntry,
+                        { type: 'button-input', class: 'menu-2 menu-blc-yellow', text: 'Step one replay frame', ref: options.input_replay_step } as ButtonInputEntry,
+                        { type: 'button-input', class: 'menu-2 menu-blc-yellow', text: 'Stop replay', ref: options.input_replay_stop } as ButtonInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'CRT sounds', ref: options.crt_sounds },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Light gun', ref: options.light_gun },
+                        { type: 'number-input', class: 'menu-2 menu-blc-yellow', text: 'Magnet strength (hold 1)', step: 0.01, min: 0, max: 10, value: 1, placeholder: 1, ref: options.magnet_strength } as NumberInputEntry,
+                        { type: 'number-input', class: 'menu-2 menu-blc-yellow', text: 'Magnet radius', step: 0.5, min: 1, max: 500, value: 40, placeholder: 40, ref: options.magnet_radius } as NumberInputEntry,
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Procedural source', hk: { inc: '2', dec: 'Shift + 2' }, ref: options.procedural_source },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Subtitles', hk: { inc: '3', dec: 'Shift + 3' }, ref: options.subtitles },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Source rotation', hk: { inc: '5', dec: 'Shift + 5' }, ref: options.source_rotation },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Source mirroring', hk: { inc: '6', dec: 'Shift + 6' }, ref: options.source_mirror },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Diagnostics overlay', ref: options.diagnostics_overlay },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Status bar (F2)', ref: options.status_bar },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Top messages (F3)', ref: options.message_verbosity },
+                        { type: 'selectors-input', class: 'menu-2 menu-blc-yellow', text: 'Moiré assistant', ref: options.moire_assistant },
+                        { type: 'button-input', class: 'menu-2 menu-blc-yellow', text: 'Find Moiré-free Setting', ref: options.moire_assistant_run } as ButtonInputEntry
                     ]
                 } as MenuEntry,
                 { type: 'button-input', class: 'menu-1 menu-blc-grey favicon', text: 'Go to Landing Page', ref: options.quit_simulation } as ButtonInputEntry
@@ -312,6 +602,24 @@ export function data () {
 
 export type SimViewData = ReturnType<typeof data>;
 
+export interface DiagnosticsReadout {
+    scanlinePitch: number;
+    convergenceRed: number[];
+    convergenceBlue: number[];
+}
+
+export interface ClampedValue {
+    field: string;
+    limit: 'minimum' | 'maximum';
+    attempted: string;
+    bound: string;
+}
+
+export interface MoireReport {
+    options: Array<{ label: string, score: number, recommended: boolean }>;
+    applied: boolean;
+}
+
 export interface SimViewInitDto {
     glError: boolean;
     skipControllerUi: boolean;
@@ -439,6 +747,34 @@ export class SimViewModel {
         this._state.fps = Math.round(msg);
         this._isDirty = true;
     }
+    changeFrameTimes (msg: Float32Array) {
+        this._state.frameTimes = Array.from(msg);
+    }
+    changeDiagnostics (msg: { scanlinePitch: number, convergenceRed: Float32Array, convergenceBlue: Float32Array }) {
+        this._state.diagnostics = {
+            scanlinePitch: msg.scanlinePitch,
+            convergenceRed: Array.from(msg.convergenceRed),
+            convergenceBlue: Array.from(msg.convergenceBlue)
+        };
+        this._isDirty = true;
+    }
+    changeMoireReport (msg: MoireReport) {
+        this._state.moireReport = msg;
+        this._isDirty = true;
+    }
+    changeClampedValue (msg: ClampedValue) {
+        const refs = Object.values(this._state.options).filter(option => (option as Ref<unknown>).eventKind === msg.field) as Array<Ref<unknown>>;
+        refs.forEach(ref => { ref.clamped = true; });
+        this._isDirty = true;
+        setTimeout(() => {
+            refs.forEach(ref => { ref.clamped = false; });
+            this._isDirty = true;
+        }, 400);
+    }
+    closeMoireReport () {
+        this._state.moireReport = null;
+        this._isDirty = true;
+    }
     exitingSession () {
         Logger.log('User closed the simulation.');
         window.location.hash = '';
@@ -463,6 +799,10 @@ export class SimViewModel {
         this._state.options.camera_zoom.value = msg;
         this._isDirty = true;
     }
+    changeCameraFocalLength (msg: number) {
+        this._state.options.camera_focal_length.value = msg;
+        this._isDirty = true;
+    }
     changePixelWidth (msg: number) {
         this._state.options.pixel_width.value = msg;
         this._isDirty = true;
@@ -527,7 +867,7 @@ export class SimViewModel {
         this._state.options.pixel_geometry.value = msg;
         this._isDirty = true;
     }
-    changePixelShadowShape (msg: number) {
+    changePixelShadowShape (msg: string) {
         this._state.options.pixel_shadow_shape.value = msg;
         this._isDirty = true;
     }
@@ -580,6 +920,10 @@ export class SimViewModel {
         this._isDirty = true;
         this._visibility.hideLoading();
     }
+    changeAmbientLightSensor (enabled: boolean) {
+        this._state.options.ambient_light_sensor.value = enabled;
+        this._isDirty = true;
+    }
     changeAntialias (antialias: boolean) {
         this._state.options.webgl_antialias.value = antialias;
         this._isDirty = true;
@@ -593,6 +937,513 @@ export class SimViewModel {
         this._state.options.color_noise.value = noise;
         this._isDirty = true;
     }
+    changeBlendingSpace (msg: string) {
+        this._state.options.blending_space.value = msg;
+        this._isDirty = true;
+    }
+    changeLayeringBlendMode (msg: string) {
+        this._state.options.layering_blend_mode.value = msg;
+        this._isDirty = true;
+    }
+    changeForegroundOpacity (msg: number) {
+        this._state.options.foreground_opacity.value = msg;
+        this._isDirty = true;
+    }
+    changeBackgroundResolutionScale (msg: number) {
+        this._state.options.background_resolution_scale.value = msg;
+        this._isDirty = true;
+    }
+    changeBackgroundBlurPasses (msg: number) {
+        this._state.options.background_blur_passes.value = msg;
+        this._isDirty = true;
+    }
+    changePixelHorizontalSpread (msg: number) {
+        this._state.options.horizontal_spread.value = msg;
+        this._isDirty = true;
+    }
+    changePixelVerticalSpread (msg: number) {
+        this._state.options.vertical_spread.value = msg;
+        this._isDirty = true;
+    }
+    changePhysicalCalibration (msg: string) {
+        this._state.options.physical_calibration.value = msg;
+        this._isDirty = true;
+    }
+    changeDisplayDpi (msg: number) {
+        this._state.options.display_dpi.value = msg;
+        this._isDirty = true;
+    }
+    changeDotPitch (msg: number) {
+        this._state.options.dot_pitch.value = msg;
+        this._isDirty = true;
+    }
+    changeCrtModel (msg: string) {
+        this._state.options.crt_model.value = msg;
+        this._isDirty = true;
+    }
+    changeColorChannelsOrder (msg: string) {
+        this._state.options.color_channels_order.value = msg;
+        this._isDirty = true;
+    }
+    changeChannelOffsetRedX (msg: number) {
+        this._state.options.channel_offset_red_x.value = msg;
+        this._isDirty = true;
+    }
+    changeChannelOffsetRedY (msg: number) {
+        this._state.options.channel_offset_red_y.value = msg;
+        this._isDirty = true;
+    }
+    changeChannelOffsetGreenX (msg: number) {
+        this._state.options.channel_offset_green_x.value = msg;
+        this._isDirty = true;
+    }
+    changeChannelOffsetGreenY (msg: number) {
+        this._state.options.channel_offset_green_y.value = msg;
+        this._isDirty = true;
+    }
+    changeChannelOffsetBlueX (msg: number) {
+        this._state.options.channel_offset_blue_x.value = msg;
+        this._isDirty = true;
+    }
+    changeChannelOffsetBlueY (msg: number) {
+        this._state.options.channel_offset_blue_y.value = msg;
+        this._isDirty = true;
+    }
+    changeOverlappingBlendMode (msg: string) {
+        this._state.options.overlapping_blend_mode.value = msg;
+        this._isDirty = true;
+    }
+    changeOverlappingGainRed (msg: number) {
+        this._state.options.overlapping_gain_red.value = msg;
+        this._isDirty = true;
+    }
+    changeOverlappingGainGreen (msg: number) {
+        this._state.options.overlapping_gain_green.value = msg;
+        this._isDirty = true;
+    }
+    changeOverlappingGainBlue (msg: number) {
+        this._state.options.overlapping_gain_blue.value = msg;
+        this._isDirty = true;
+    }
+    changeCrtSounds (msg: string) {
+        this._state.options.crt_sounds.value = msg;
+        this._isDirty = true;
+    }
+    changeLightGun (msg: string) {
+        this._state.options.light_gun.value = msg;
+        this._isDirty = true;
+    }
+    changeMagnetStrength (msg: number) {
+        this._state.options.magnet_strength.value = msg;
+        this._isDirty = true;
+    }
+    changeMagnetRadius (msg: number) {
+        this._state.options.magnet_radius.value = msg;
+        this._isDirty = true;
+    }
+    changeProceduralSource (msg: string) {
+        this._state.options.procedural_source.value = msg;
+        this._isDirty = true;
+    }
+    changeDiagnosticsOverlay (msg: string) {
+        this._state.options.diagnostics_overlay.value = msg;
+        if (msg === 'Off') {
+            this._state.diagnostics = null;
+        }
+        this._isDirty = true;
+    }
+    changeMoireAssistant (msg: string) {
+        this._state.options.moire_assistant.value = msg;
+        this._isDirty = true;
+    }
+    changeSubtitles (msg: string) {
+        this._state.options.subtitles.value = msg;
+        this._isDirty = true;
+    }
+    changeScanlinePhase (msg: number) {
+        this._state.options.scanline_phase.value = msg;
+        this._isDirty = true;
+    }
+    changeSyncOffset (msg: number) {
+        this._state.options.sync_offset.value = msg;
+        this._isDirty = true;
+    }
+    changeScanlineOrientation (msg: string) {
+        this._state.options.scanline_orientation.value = msg;
+        this._isDirty = true;
+    }
+    changeScanlineAngle (msg: number) {
+        this._state.options.scanline_angle.value = msg;
+        this._isDirty = true;
+    }
+    changeSourceRotation (msg: string) {
+        this._state.options.source_rotation.value = msg;
+        this._isDirty = true;
+    }
+    changeSourceMirror (msg: string) {
+        this._state.options.source_mirror.value = msg;
+        this._isDirty = true;
+    }
+    changeDamperWires (msg: string) {
+        this._state.options.damper_wires.value = msg;
+        this._isDirty = true;
+    }
+    changeDamperWirePosition (msg: number) {
+        this._state.options.damper_wire_position.value = msg;
+        this._isDirty = true;
+    }
+    changeDamperWireOpacity (msg: number) {
+        this._state.options.damper_wire_opacity.value = msg;
+        this._isDirty = true;
+    }
+    changeEdgeReflectionIntensity (msg: number) {
+        this._state.options.edge_reflection_intensity.value = msg;
+        this._isDirty = true;
+    }
+    changeEdgeReflectionWidth (msg: number) {
+        this._state.options.edge_reflection_width.value = msg;
+        this._isDirty = true;
+    }
+    changeBloomingAmount (msg: number) {
+        this._state.options.blooming_amount.value = msg;
+        this._isDirty = true;
+    }
+    changeBloomingTimeConstant (msg: number) {
+        this._state.options.blooming_time_constant.value = msg;
+        this._isDirty = true;
+    }
+    changeTvLines (msg: number) {
+        this._state.options.tv_lines.value = msg;
+        this._isDirty = true;
+    }
+    changeResponseRiseTime (msg: number) {
+        this._state.options.response_rise_time.value = msg;
+        this._isDirty = true;
+    }
+    changeResponseFallTime (msg: number) {
+        this._state.options.response_fall_time.value = msg;
+        this._isDirty = true;
+    }
+    changeFloorReflection (msg: string) {
+        this._state.options.floor_reflection.value = msg;
+        this._isDirty = true;
+    }
+    changeFloorRoughness (msg: number) {
+        this._state.options.floor_roughness.value = msg;
+        this._isDirty = true;
+    }
+    changeForegroundDepthTest (msg: string) {
+        this._state.options.foreground_depth_test.value = msg;
+        this._isDirty = true;
+    }
+    changeBackgroundDepthTest (msg: string) {
+        this._state.options.background_depth_test.value = msg;
+        this._isDirty = true;
+    }
+    changeLayers (msg: string) {
+        this._state.options.layers.value = msg;
+        this._isDirty = true;
+    }
+    changeScreenshotBracket (msg: string) {
+        this._state.options.screenshot_bracket.value = msg;
+        this._isDirty = true;
+    }
+    changeBloomThreshold (msg: number) {
+        this._state.options.bloom_threshold.value = msg;
+        this._isDirty = true;
+    }
+    changeBloomIntensity (msg: number) {
+        this._state.options.bloom_intensity.value = msg;
+        this._isDirty = true;
+    }
+    changeStatusBar (msg: string) {
+        this._state.options.status_bar.value = msg;
+        this._isDirty = true;
+    }
+    changeMessageVerbosity (msg: string) {
+        this._state.options.message_verbosity.value = msg;
+        this._isDirty = true;
+    }
+    changeSignalKind (msg: string) {
+        this._state.options.signal_kind.value = msg;
+        this._isDirty = true;
+    }
+    changeVignetteStrength (msg: number) {
+        this._state.options.vignette_strength.value = msg;
+        this._isDirty = true;
+    }
+    changeVignetteRadius (msg: number) {
+        this._state.options.vignette_radius.value = msg;
+        this._isDirty = true;
+    }
+    changeCameraCollision (msg: string) {
+        this._state.options.camera_collision.value = msg;
+        this._isDirty = true;
+    }
+    changeConvergenceRedX (msg: number) {
+        this._state.options.convergence_red_x.value = msg;
+        this._isDirty = true;
+    }
+    changeConvergenceRedY (msg: number) {
+        this._state.options.convergence_red_y.value = msg;
+        this._isDirty = true;
+    }
+    changeConvergenceGreenX (msg: number) {
+        this._state.options.convergence_green_x.value = msg;
+        this._isDirty = true;
+    }
+    changeConvergenceGreenY (msg: number) {
+        this._state.options.convergence_green_y.value = msg;
+        this._isDirty = true;
+    }
+    changeConvergenceBlueX (msg: number) {
+        this._state.options.convergence_blue_x.value = msg;
+        this._isDirty = true;
+    }
+    changeConvergenceBlueY (msg: number) {
+        this._state.options.convergence_blue_y.value = msg;
+        this._isDirty = true;
+    }
+    changeInterlacing (msg: string) {
+        this._state.options.interlacing.value = msg;
+        this._isDirty = true;
+    }
+    changeRollingScan (msg: string) {
+        this._state.options.rolling_scan.value = msg;
+        this._isDirty = true;
+    }
+    changeRollingScanSpeed (msg: number) {
+        this._state.options.rolling_scan_speed.value = msg;
+        this._isDirty = true;
+    }
+    changeRollingScanSize (msg: number) {
+        this._state.options.rolling_scan_size.value = msg;
+        this._isDirty = true;
+    }
+    changeFlicker (msg: string) {
+        this._state.options.flicker.value = msg;
+        this._isDirty = true;
+    }
+    changeFlickerAmplitude (msg: number) {
+        this._state.options.flicker_amplitude.value = msg;
+        this._isDirty = true;
+    }
+    changeTurntableSeconds (msg: number) {
+        this._state.options.turntable_seconds.value = msg;
+        this._isDirty = true;
+    }
+    changeGlassReflection (msg: string) {
+        this._state.options.glass_reflection.value = msg;
+        this._isDirty = true;
+    }
+    changeGlassReflectivity (msg: number) {
+        this._state.options.glass_reflectivity.value = msg;
+        this._isDirty = true;
+    }
+    changeBlemishStrength (msg: number) {
+        this._state.options.blemish_strength.value = msg;
+        this._isDirty = true;
+    }
+    changeBlemishSeed (msg: number) {
+        this._state.options.blemish_seed.value = msg;
+        this._isDirty = true;
+    }
+    changeDeadSubpixels (msg: number) {
+        this._state.options.dead_subpixels.value = msg;
+        this._isDirty = true;
+    }
+    changeStuckPixels (msg: number) {
+        this._state.options.stuck_pixels.value = msg;
+        this._isDirty = true;
+    }
+    changeDeadScanlines (msg: number) {
+        this._state.options.dead_scanlines.value = msg;
+        this._isDirty = true;
+    }
+    changeBezelScale (msg: number) {
+        this._state.options.bezel_scale.value = msg;
+        this._isDirty = true;
+    }
+    changeBezelOffsetX (msg: number) {
+        this._state.options.bezel_offset_x.value = msg;
+        this._isDirty = true;
+    }
+    changeBezelOffsetY (msg: number) {
+        this._state.options.bezel_offset_y.value = msg;
+        this._isDirty = true;
+    }
+    changeRoomEnvironment (msg: string) {
+        this._state.options.room_environment.value = msg;
+        this._isDirty = true;
+    }
+    changeRoomBrightness (msg: number) {
+        this._state.options.room_brightness.value = msg;
+        this._isDirty = true;
+    }
+    changeColorTemperature (msg: number) {
+        this._state.options.color_temperature.value = msg;
+        this._isDirty = true;
+    }
+    changeOutputGamma (msg: number) {
+        this._state.options.output_gamma.value = msg;
+        this._isDirty = true;
+    }
+    changeBlackPedestal (msg: number) {
+        this._state.options.black_pedestal.value = msg;
+        this._isDirty = true;
+    }
+    changeContrastKnee (msg: number) {
+        this._state.options.contrast_knee.value = msg;
+        this._isDirty = true;
+    }
+    changeContrastShoulder (msg: number) {
+        this._state.options.contrast_shoulder.value = msg;
+        this._isDirty = true;
+    }
+    changeRedGain (msg: number) {
+        this._state.options.red_gain.value = msg;
+        this._isDirty = true;
+    }
+    changeRedOffset (msg: number) {
+        this._state.options.red_offset.value = msg;
+        this._isDirty = true;
+    }
+    changeGreenGain (msg: number) {
+        this._state.options.green_gain.value = msg;
+        this._isDirty = true;
+    }
+    changeGreenOffset (msg: number) {
+        this._state.options.green_offset.value = msg;
+        this._isDirty = true;
+    }
+    changeBlueGain (msg: number) {
+        this._state.options.blue_gain.value = msg;
+        this._isDirty = true;
+    }
+    changeBlueOffset (msg: number) {
+        this._state.options.blue_offset.value = msg;
+        this._isDirty = true;
+    }
+    changeColorSaturation (msg: number) {
+        this._state.options.color_saturation.value = msg;
+        this._isDirty = true;
+    }
+    changeColorHue (msg: number) {
+        this._state.options.color_hue.value = msg;
+        this._isDirty = true;
+    }
+    changeWhiteClipping (msg: string) {
+        this._state.options.white_clipping.value = msg;
+        this._isDirty = true;
+    }
+    changeDithering (msg: string) {
+        this._state.options.dithering.value = msg;
+        this._isDirty = true;
+    }
+    changeDitheringBits (msg: number) {
+        this._state.options.dithering_bits.value = msg;
+        this._isDirty = true;
+    }
+    changeInterlaceFieldFlicker (msg: number) {
+        this._state.options.interlace_field_flicker.value = msg;
+        this._isDirty = true;
+    }
+    changeInterlaceLinePairing (msg: number) {
+        this._state.options.interlace_line_pairing.value = msg;
+        this._isDirty = true;
+    }
+    changeTransition (msg: string) {
+        this._state.options.transition.value = msg;
+        this._isDirty = true;
+    }
+    changeTransitionSeconds (msg: number) {
+        this._state.options.transition_seconds.value = msg;
+        this._isDirty = true;
+    }
+    changePalette (msg: string) {
+        this._state.options.palette.value = msg;
+        this._isDirty = true;
+    }
+    changeCustomPalette (msg: string) {
+        this._state.options.custom_palette.value = msg;
+        this._isDirty = true;
+    }
+    changeBurnInRate (msg: number) {
+        this._state.options.burn_in_rate.value = msg;
+        this._isDirty = true;
+    }
+    changeBurnInVisibility (msg: number) {
+        this._state.options.burn_in_visibility.value = msg;
+        this._isDirty = true;
+    }
+    changeKenBurnsSpeed (msg: number) {
+        this._state.options.ken_burns_speed.value = msg;
+        this._isDirty = true;
+    }
+    changeKenBurnsMargin (msg: number) {
+        this._state.options.ken_burns_margin.value = msg;
+        this._isDirty = true;
+    }
+    changeInputRecording (recording: boolean) {
+        this._state.options.input_recording.value = recording;
+        this._isDirty = true;
+    }
+    changeInputReplay (name: string) {
+        this._state.options.input_replay.value = name;
+        this._isDirty = true;
+    }
+    changeAnalogStatic (msg: number) {
+        this._state.options.analog_static.value = msg;
+        this._isDirty = true;
+    }
+    changeAnalogStaticSpeed (msg: number) {
+        this._state.options.analog_static_speed.value = msg;
+        this._isDirty = true;
+    }
+    changeHsyncWobble (msg: number) {
+        this._state.options.hsync_wobble.value = msg;
+        this._isDirty = true;
+    }
+    changeHsyncWobbleFrequency (msg: number) {
+        this._state.options.hsync_wobble_frequency.value = msg;
+        this._isDirty = true;
+    }
+    changeSourceInterpolation (msg: string) {
+        this._state.options.source_interpolation.value = msg;
+        this._isDirty = true;
+    }
+    changeOutputInterpolation (msg: string) {
+        this._state.options.output_interpolation.value = msg;
+        this._isDirty = true;
+    }
+    changeSharpenStrength (msg: number) {
+        this._state.options.sharpen_strength.value = msg;
+        this._isDirty = true;
+    }
+    changeSharpenHaloSuppression (msg: number) {
+        this._state.options.sharpen_halo_suppression.value = msg;
+        this._isDirty = true;
+    }
+    changeOutputColorSpace (msg: string) {
+        this._state.options.output_color_space.value = msg;
+        this._isDirty = true;
+    }
+    changeScreenCornerRadius (msg: number) {
+        this._state.options.screen_corner_radius.value = msg;
+        this._isDirty = true;
+    }
+    changeScreenMatte (msg: number) {
+        this._state.options.screen_matte.value = msg;
+        this._isDirty = true;
+    }
+    changeScreenCurvatureAmount (msg: number) {
+        this._state.options.screen_curvature_amount.value = msg;
+        this._isDirty = true;
+    }
+    changePhosphorPrimaries (msg: string) {
+        this._state.options.phosphor_primaries.value = msg;
+        this._isDirty = true;
+    }
     changeColorRgb (value: number, rgbRow: 'red' | 'green' | 'blue', rgbColumn: 'r' | 'g' | 'b') {
         this._state.options.rgb_values[rgbRow][rgbColumn].value = value;
         this._isDirty = true;
@@ -59,6 +59,14 @@ export class SimWasmBackend {
             config.set_drawing_activation(false);
         }
 
+        if (params.subtitles) {
+            config.set_subtitles(params.subtitles); // SRT text, burnt into the frames
+        }
+
+        if (params.externalClock) {
+            config.set_external_clock(true); // frames only advance through advanceFrames
+        }
+
         Logger.log('gl context form', params.ctxOptions);
         const gl = canvas.getContext('webgl2', params.ctxOptions) as WebGL2RenderingContext | null;
 
@@ -68,6 +76,10 @@ export class SimWasmBackend {
             Logger.log('calling wasmApp.load');
             this._app.load(gl, eventBus, config);
             Logger.log('wasmApp.load done');
+
+            if (params.bezelArtwork) {
+                this.setBezelArtwork(params.bezelArtwork);
+            }
     
             return { success: true };   
         } else {
@@ -80,6 +92,26 @@ export class SimWasmBackend {
         return this._app.run_frame();
     }
 
+    advanceFrames (frames: number) {
+        return this._app.advance_frames(frames);
+    }
+
+    // Channels modulate foreground opacity (r), brightness (g), background (b) and blur (a) per screen region.
+    setFilterMask (mask: ImageData | null) {
+        if (mask) {
+            return this._app.set_filter_mask(mask.width, mask.height, new Uint8Array(mask.data.buffer));
+        }
+        return this._app.clear_filter_mask();
+    }
+
+    // Transparent artwork framing the screen, like the overlays of the arcade frontends.
+    setBezelArtwork (artwork: ImageData | null) {
+        if (artwork) {
+            return this._app.set_bezel_artwork(artwork.width, artwork.height, new Uint8Array(artwork.data.buffer));
+        }
+        return this._app.clear_bezel_artwork();
+    }
+
     unload () {
         return this._app.unload();
     }